@@ -1,19 +1,23 @@
 use std::collections::HashMap;
 use std::fmt::Debug;
-use std::{any::Any, cell::RefCell};
+use std::time::Duration;
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+};
 
 use anyhow::Result;
 #[cfg(test)]
 use mockall::automock;
 use platforms::windows::{
-    self, BitBltCapture, Frame, Handle, KeyInputKind, KeyKind, Keys, WgcCapture, WindowBoxCapture,
+    self, BitBltCapture, Frame, Handle, KeyInputKind, KeyKind, Keys, MonitorRegionCapture,
+    WgcCapture, WindowBoxCapture,
 };
 
 use crate::context::MS_PER_TICK_F32;
-use crate::database::Seeds;
-use crate::rng::Rng;
+use crate::rng::{Rng, RngSeed};
 use crate::rpc;
-use crate::{CaptureMode, context::MS_PER_TICK, rpc::KeysService};
+use crate::{CaptureMode, Settings, context::MS_PER_TICK, rpc::KeysService};
 
 /// Base mean in milliseconds to generate a pair from.
 const BASE_MEAN_MS_DELAY: f32 = 100.0;
@@ -28,11 +32,15 @@ const MEAN_STD_REVERSION_RATE: f32 = 0.2;
 /// The rate at which generated mean will revert to the base [`BASE_MEAN_MS_DELAY`] over time.
 const MEAN_STD_VOLATILITY: f32 = 3.0;
 
+/// The number of pixels to skip between each sampled pixel when checking for a blank frame.
+const BLANK_FRAME_SAMPLE_STEP: usize = 31;
+
 /// The input method to use for the key sender.
 ///
 /// This is a bridge enum between platform-specific and gRPC input options.
 pub enum KeySenderMethod {
-    Rpc(Handle, String),
+    /// Sends keys through the gRPC server at the provided URL with a per-request timeout.
+    Rpc(Handle, String, Duration),
     Default(Handle, KeyInputKind),
 }
 
@@ -58,6 +66,9 @@ pub enum MouseAction {
 pub trait KeySender: Debug {
     fn set_method(&mut self, method: KeySenderMethod);
 
+    /// Reseeds the random delays between key presses with `seed`.
+    fn set_seed(&mut self, seed: RngSeed);
+
     fn send(&self, kind: KeyKind) -> Result<()>;
 
     /// Sends mouse to `(x, y)` relative to the client coordinate (e.g. capture area) and
@@ -72,6 +83,16 @@ pub trait KeySender: Debug {
 
     fn send_down(&self, kind: KeyKind) -> Result<()>;
 
+    /// Whether the key sender can currently reach its input destination.
+    ///
+    /// This is always `true` for non-RPC key sender.
+    fn is_connected(&self) -> bool;
+
+    /// Whether the input destination window is currently focused.
+    ///
+    /// This is always `true` for RPC key sender.
+    fn is_focused(&self) -> bool;
+
     fn as_any_mut(&mut self) -> &mut dyn Any;
 }
 
@@ -81,6 +102,9 @@ pub struct DefaultKeySender {
     delay_rng: Rng,
     delay_mean_std_pair: (f32, f32),
     delay_map: RefCell<HashMap<KeyKind, u32>>,
+    /// The number of keys sent through [`KeySender::send`], [`KeySender::send_down`] and
+    /// [`KeySender::send_up`] since the last [`Self::take_sent_count`].
+    sent_count: Cell<u32>,
 }
 
 #[derive(Debug)]
@@ -91,12 +115,13 @@ enum InputDelay {
 }
 
 impl DefaultKeySender {
-    pub fn new(method: KeySenderMethod, seeds: Seeds) -> Self {
+    pub fn new(method: KeySenderMethod, seed: RngSeed) -> Self {
         Self {
-            kind: to_key_sender_kind_from(method, &seeds.seed),
-            delay_rng: Rng::new(seeds.seed),
+            kind: to_key_sender_kind_from(method, &seed),
+            delay_rng: Rng::new(seed),
             delay_mean_std_pair: (BASE_MEAN_MS_DELAY, BASE_STD_MS_DELAY),
             delay_map: RefCell::new(HashMap::new()),
+            sent_count: Cell::new(0),
         }
     }
 
@@ -216,6 +241,12 @@ impl DefaultKeySender {
         });
     }
 
+    /// Takes the number of keys sent since the last call.
+    #[inline]
+    pub fn take_sent_count(&self) -> u32 {
+        self.sent_count.take()
+    }
+
     fn random_input_delay_tick_count(&self) -> (f32, u32) {
         let (mean, std) = self.delay_mean_std_pair;
         self.delay_rng
@@ -226,7 +257,7 @@ impl DefaultKeySender {
 impl KeySender for DefaultKeySender {
     fn set_method(&mut self, method: KeySenderMethod) {
         match &method {
-            KeySenderMethod::Rpc(handle, url) => {
+            KeySenderMethod::Rpc(handle, url, timeout) => {
                 if let KeySenderKind::Rpc(ref cur_handle, ref option) = self.kind {
                     let service = option.as_ref();
                     let service_borrow = service.map(|service| service.borrow_mut());
@@ -234,6 +265,7 @@ impl KeySender for DefaultKeySender {
                         && borrow.url() == url
                         && handle == cur_handle
                     {
+                        borrow.set_timeout(*timeout);
                         let _ = borrow.init(self.delay_rng.seed());
                         borrow.reset();
                         return;
@@ -245,7 +277,12 @@ impl KeySender for DefaultKeySender {
         self.kind = to_key_sender_kind_from(method, self.delay_rng.seed());
     }
 
+    fn set_seed(&mut self, seed: RngSeed) {
+        self.delay_rng = Rng::new(seed);
+    }
+
     fn send(&self, kind: KeyKind) -> Result<()> {
+        self.sent_count.set(self.sent_count.get() + 1);
         self.send_inner(kind)
     }
 
@@ -289,13 +326,31 @@ impl KeySender for DefaultKeySender {
     }
 
     fn send_up(&self, kind: KeyKind) -> Result<()> {
+        self.sent_count.set(self.sent_count.get() + 1);
         self.send_up_inner(kind, false)
     }
 
     fn send_down(&self, kind: KeyKind) -> Result<()> {
+        self.sent_count.set(self.sent_count.get() + 1);
         self.send_down_inner(kind)
     }
 
+    fn is_connected(&self) -> bool {
+        match &self.kind {
+            KeySenderKind::Rpc(_, service) => service
+                .as_ref()
+                .is_some_and(|cell| cell.borrow().is_connected()),
+            KeySenderKind::Default(_) => true,
+        }
+    }
+
+    fn is_focused(&self) -> bool {
+        match &self.kind {
+            KeySenderKind::Rpc(_, _) => true,
+            KeySenderKind::Default(keys) => keys.is_foreground(),
+        }
+    }
+
     #[inline]
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
@@ -308,18 +363,22 @@ pub enum ImageCaptureKind {
     BitBlt(BitBltCapture),
     Wgc(Option<WgcCapture>),
     BitBltArea(WindowBoxCapture),
+    MonitorRegion(MonitorRegionCapture),
 }
 
 /// A struct for managing different capture modes.
 #[derive(Debug)]
 pub struct ImageCapture {
     kind: ImageCaptureKind,
+    /// Whether the last grabbed frame was blank.
+    blank: bool,
 }
 
 impl ImageCapture {
-    pub fn new(handle: Handle, mode: CaptureMode) -> Self {
+    pub fn new(handle: Handle, settings: &Settings) -> Self {
         Self {
-            kind: to_image_capture_kind_from(handle, mode),
+            kind: to_image_capture_kind_from(handle, settings),
+            blank: false,
         }
     }
 
@@ -327,26 +386,38 @@ impl ImageCapture {
         &self.kind
     }
 
+    /// Grabs a frame from the current capture mode.
+    ///
+    /// A blank frame (e.g. the game has left the capture area) is discarded and [`None`] is
+    /// returned instead.
     pub fn grab(&mut self) -> Option<Frame> {
-        match &mut self.kind {
+        let frame = match &mut self.kind {
             ImageCaptureKind::BitBlt(capture) => capture.grab().ok(),
             ImageCaptureKind::Wgc(capture) => {
                 capture.as_mut().and_then(|capture| capture.grab().ok())
             }
             ImageCaptureKind::BitBltArea(capture) => capture.grab().ok(),
-        }
+            ImageCaptureKind::MonitorRegion(capture) => capture.grab().ok(),
+        }?;
+        self.blank = is_blank_frame(&frame);
+        (!self.blank).then_some(frame)
     }
 
-    pub fn set_mode(&mut self, handle: Handle, mode: CaptureMode) {
-        self.kind = to_image_capture_kind_from(handle, mode);
+    /// Whether the last grabbed frame was blank.
+    pub fn is_blank(&self) -> bool {
+        self.blank
+    }
+
+    pub fn set_mode(&mut self, handle: Handle, settings: &Settings) {
+        self.kind = to_image_capture_kind_from(handle, settings);
     }
 }
 
 #[inline]
 fn to_key_sender_kind_from(method: KeySenderMethod, seed: &[u8]) -> KeySenderKind {
     match method {
-        KeySenderMethod::Rpc(handle, url) => {
-            let mut service = KeysService::connect(url);
+        KeySenderMethod::Rpc(handle, url, timeout) => {
+            let mut service = KeysService::connect(url, timeout);
             if let Ok(ref mut service) = service {
                 let _ = service.init(seed);
             }
@@ -356,14 +427,34 @@ fn to_key_sender_kind_from(method: KeySenderMethod, seed: &[u8]) -> KeySenderKin
     }
 }
 
+/// Checks whether `frame` is a single solid color by sampling its pixels.
 #[inline]
-fn to_image_capture_kind_from(handle: Handle, mode: CaptureMode) -> ImageCaptureKind {
-    match mode {
+fn is_blank_frame(frame: &Frame) -> bool {
+    let mut pixels = frame.data.chunks_exact(4).step_by(BLANK_FRAME_SAMPLE_STEP);
+    let Some(first) = pixels.next() else {
+        return true;
+    };
+    pixels.all(|pixel| pixel[..3] == first[..3])
+}
+
+#[inline]
+fn to_image_capture_kind_from(handle: Handle, settings: &Settings) -> ImageCaptureKind {
+    match settings.capture_mode {
         CaptureMode::BitBlt => ImageCaptureKind::BitBlt(BitBltCapture::new(handle, false)),
         CaptureMode::WindowsGraphicsCapture => {
             ImageCaptureKind::Wgc(WgcCapture::new(handle, MS_PER_TICK).ok())
         }
         CaptureMode::BitBltArea => ImageCaptureKind::BitBltArea(WindowBoxCapture::default()),
+        CaptureMode::MonitorRegion => {
+            let region = settings.capture_monitor_region;
+            ImageCaptureKind::MonitorRegion(MonitorRegionCapture::new(
+                settings.capture_monitor_index as usize,
+                region.x,
+                region.y,
+                region.width,
+                region.height,
+            ))
+        }
     }
 }
 
@@ -379,13 +470,9 @@ mod tests {
     ];
 
     fn test_key_sender() -> DefaultKeySender {
-        let seeds = Seeds {
-            id: None,
-            seed: SEED,
-        };
         DefaultKeySender::new(
             KeySenderMethod::Default(Handle::new("Handle"), KeyInputKind::Fixed),
-            seeds,
+            SEED,
         )
     }
 
@@ -434,4 +521,17 @@ mod tests {
         sender.update_input_delay(200);
         assert_ne!(sender.delay_mean_std_pair, original_pair);
     }
+
+    #[test]
+    fn is_blank_frame_solid_color() {
+        let mut frame = Frame {
+            width: 64,
+            height: 64,
+            data: [0, 0, 0, 255].repeat(64 * 64),
+        };
+        assert!(is_blank_frame(&frame));
+
+        frame.data[BLANK_FRAME_SAMPLE_STEP * 4 * 3] = 100;
+        assert!(!is_blank_frame(&frame));
+    }
 }
@@ -7,14 +7,18 @@ use anyhow::Result;
 use strum::EnumIter;
 
 use crate::{
-    Configuration, Settings,
+    BuffsRegion, Configuration, Settings,
     context::{Context, Contextual, ControlFlow},
+    database::BuffKind as BuffKindData,
     player::Player,
     task::{Task, Update, update_detection_task},
 };
 
 const BUFF_FAIL_MAX_COUNT: u32 = 3;
 
+/// The default minimum milliseconds between each buff detection.
+pub const BUFF_DETECTION_MILLIS: u64 = 5000;
+
 /// Stores persistent state of a buff.
 #[derive(Debug)]
 pub struct BuffState {
@@ -29,6 +33,14 @@ pub struct BuffState {
     max_fail_count: u32,
     /// Whether a buff is enabled.
     enabled: bool,
+    /// Whether a buff is recasted on a timer without detection.
+    ///
+    /// The buff is assumed to always be active when this is `true`.
+    timer_only: bool,
+    /// The region of the buffs bar to detect the buff in.
+    region: BuffsRegion,
+    /// The minimum milliseconds between each detection.
+    detection_millis: u64,
 }
 
 impl BuffState {
@@ -54,11 +66,19 @@ impl BuffState {
                 | BuffKind::ExtremeGoldPotion => BUFF_FAIL_MAX_COUNT,
             },
             enabled: true,
+            timer_only: false,
+            region: BuffsRegion::default(),
+            detection_millis: BUFF_DETECTION_MILLIS,
         }
     }
 
     /// Updates the enabled states of each buff to only detect if enabled.
+    ///
+    /// This also updates the buffs region and interval used for detection from `settings` and
+    /// whether the buff is recasted on a timer only from `config`.
     pub fn update_enabled_state(&mut self, config: &Configuration, settings: &Settings) {
+        self.region = settings.buffs_region;
+        self.detection_millis = settings.buffs_detection_millis;
         self.enabled = match self.kind {
             BuffKind::Rune => settings.enable_rune_solving,
             BuffKind::Familiar => config.familiar_buff_key.enabled,
@@ -75,7 +95,11 @@ impl BuffState {
             BuffKind::ExtremeGreenPotion => config.extreme_green_potion_key.enabled,
             BuffKind::ExtremeGoldPotion => config.extreme_gold_potion_key.enabled,
         };
-        if !self.enabled {
+        self.timer_only = config
+            .timer_only_buffs
+            .iter()
+            .any(|kind| BuffKind::from(*kind) == self.kind);
+        if !self.enabled || self.timer_only {
             self.fail_count = 0;
             self.task = None;
         }
@@ -93,8 +117,7 @@ pub enum Buff {
     Volatile,
 }
 
-#[derive(Clone, Copy, Debug, EnumIter)]
-#[cfg_attr(test, derive(PartialEq))]
+#[derive(Clone, Copy, PartialEq, Debug, EnumIter)]
 #[repr(usize)]
 pub enum BuffKind {
     // NOTE: Upon failing to solving rune, there is a cooldown
@@ -119,6 +142,26 @@ impl BuffKind {
     pub const COUNT: usize = mem::variant_count::<BuffKind>();
 }
 
+impl From<BuffKindData> for BuffKind {
+    fn from(value: BuffKindData) -> Self {
+        match value {
+            BuffKindData::Familiar => BuffKind::Familiar,
+            BuffKindData::SayramElixir => BuffKind::SayramElixir,
+            BuffKindData::AureliaElixir => BuffKind::AureliaElixir,
+            BuffKindData::ExpCouponX3 => BuffKind::ExpCouponX3,
+            BuffKindData::BonusExpCoupon => BuffKind::BonusExpCoupon,
+            BuffKindData::LegionWealth => BuffKind::LegionWealth,
+            BuffKindData::LegionLuck => BuffKind::LegionLuck,
+            BuffKindData::WealthAcquisitionPotion => BuffKind::WealthAcquisitionPotion,
+            BuffKindData::ExpAccumulationPotion => BuffKind::ExpAccumulationPotion,
+            BuffKindData::ExtremeRedPotion => BuffKind::ExtremeRedPotion,
+            BuffKindData::ExtremeBluePotion => BuffKind::ExtremeBluePotion,
+            BuffKindData::ExtremeGreenPotion => BuffKind::ExtremeGreenPotion,
+            BuffKindData::ExtremeGoldPotion => BuffKind::ExtremeGoldPotion,
+        }
+    }
+}
+
 impl Index<BuffKind> for [Buff; BuffKind::COUNT] {
     type Output = Buff;
 
@@ -140,6 +183,9 @@ impl Contextual for Buff {
         if !state.enabled {
             return ControlFlow::Next(Buff::No);
         }
+        if state.timer_only {
+            return ControlFlow::Next(Buff::Yes);
+        }
         let next = if matches!(context.player, Player::CashShopThenExit(_, _)) {
             self
         } else {
@@ -152,11 +198,13 @@ impl Contextual for Buff {
 #[inline]
 fn update_context(contextual: Buff, context: &Context, state: &mut BuffState) -> Buff {
     let kind = state.kind;
-    let Update::Ok(has_buff) =
-        update_detection_task(context, 5000, &mut state.task, move |detector| {
-            Ok(detector.detect_player_buff(kind))
-        })
-    else {
+    let region = state.region;
+    let Update::Ok(has_buff) = update_detection_task(
+        context,
+        state.detection_millis,
+        &mut state.task,
+        move |detector| Ok(detector.detect_player_buff(kind, region)),
+    ) else {
         return contextual;
     };
     state.fail_count = if matches!(contextual, Buff::Volatile) && !has_buff {
@@ -193,13 +241,13 @@ mod tests {
     use tokio::time::advance;
 
     use super::*;
-    use crate::detect::MockDetector;
+    use crate::{KeyBindingConfiguration, detect::MockDetector};
 
     fn detector_with_kind(kind: BuffKind, result: bool) -> MockDetector {
         let mut detector = MockDetector::new();
         detector
             .expect_detect_player_buff()
-            .with(eq(kind))
+            .with(eq(kind), eq(BuffsRegion::default()))
             .return_const(result);
         detector
             .expect_clone()
@@ -290,6 +338,61 @@ mod tests {
         assert!(state.task.is_none());
     }
 
+    #[test]
+    fn buff_timer_only_skip_detection() {
+        let kind = BuffKind::SayramElixir;
+        let mut state = BuffState::new(kind);
+        state.fail_count = 2;
+
+        let config = Configuration {
+            sayram_elixir_key: KeyBindingConfiguration {
+                enabled: true,
+                ..KeyBindingConfiguration::default()
+            },
+            timer_only_buffs: vec![BuffKindData::SayramElixir],
+            ..Configuration::default()
+        };
+        state.update_enabled_state(&config, &Settings::default());
+        assert!(state.timer_only);
+        assert_eq!(state.fail_count, 0);
+
+        let context = Context::new(None, Some(MockDetector::new()));
+        assert!(matches!(
+            Buff::No.update(&context, &mut state),
+            ControlFlow::Next(Buff::Yes)
+        ));
+        assert!(state.task.is_none());
+    }
+
+    #[test]
+    fn buff_update_region() {
+        let mut state = BuffState::new(BuffKind::Rune);
+        let settings = Settings {
+            buffs_region: BuffsRegion {
+                offset_x: 10,
+                offset_y: 20,
+                scale: 1.5,
+            },
+            ..Settings::default()
+        };
+
+        state.update_enabled_state(&Configuration::default(), &settings);
+        assert_eq!(state.region, settings.buffs_region);
+    }
+
+    #[test]
+    fn buff_update_detection_millis() {
+        let mut state = BuffState::new(BuffKind::Familiar);
+        assert_eq!(state.detection_millis, BUFF_DETECTION_MILLIS);
+
+        let settings = Settings {
+            buffs_detection_millis: 2000,
+            ..Settings::default()
+        };
+        state.update_enabled_state(&Configuration::default(), &settings);
+        assert_eq!(state.detection_millis, 2000);
+    }
+
     #[tokio::test(start_paused = true)]
     async fn buff_volatile_stay_before_threshold() {
         for kind in BuffKind::iter() {
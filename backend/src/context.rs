@@ -1,5 +1,6 @@
 use std::{
     cell::RefCell,
+    collections::VecDeque,
     env,
     rc::Rc,
     sync::atomic::{AtomicBool, Ordering},
@@ -9,36 +10,65 @@ use std::{
 
 use dyn_clone::clone_box;
 use opencv::{
-    core::{Vector, VectorToVec},
+    core::{Point, Rect, Vector, VectorToVec},
     imgcodecs::imencode_def,
 };
 use platforms::windows::{self, Handle, KeyInputKind, KeyReceiver};
 use strum::IntoEnumIterator;
 use tokio::sync::broadcast;
 
+#[cfg(debug_assertions)]
+use crate::debug::{DebugAudioCue, play_debug_audio_cue};
 use crate::{
-    Action, RequestHandler,
+    Action, RequestHandler, Settings,
     bridge::{DefaultKeySender, ImageCapture, ImageCaptureKind, KeySender, KeySenderMethod},
     buff::{Buff, BuffKind, BuffState},
-    database::{CaptureMode, InputMethod, KeyBinding, query_seeds},
-    detect::{CachedDetector, Detector},
+    database::{
+        Bound, CaptureMode, InputMethod, KeyBinding, Minimap as MinimapData, MinimapPositions,
+        Seeds, query_minimap_positions, query_seeds, upsert_minimap_positions,
+    },
+    detect::{CachedDetector, Detector, load_grayscale_templates},
     mat::OwnedMat,
     minimap::{Minimap, MinimapState},
     network::{DiscordNotification, NotificationKind},
     player::{PanicTo, Panicking, Player, PlayerState},
-    query_configs, query_settings,
-    request_handler::{DefaultRequestHandler, config_buffs},
-    rng::Rng,
+    query_configs, query_maps, query_settings,
+    request_handler::{DefaultRequestHandler, apply_settings, config_buffs},
+    rng::{Rng, RngSeed, seed_from_u64},
     rotator::Rotator,
     skill::{Skill, SkillKind, SkillState},
 };
 #[cfg(test)]
-use crate::{Settings, bridge::MockKeySender, detect::MockDetector};
+use crate::{bridge::MockKeySender, detect::MockDetector};
 
 const FPS: u32 = 30;
 pub const MS_PER_TICK: u64 = MS_PER_TICK_F32 as u64;
 pub const MS_PER_TICK_F32: f32 = 1000.0 / FPS as f32;
 
+/// Maximum duration the key sender can stay disconnected while rotating actions before halting.
+const INPUT_DISCONNECTED_MAX_DURATION: Duration = Duration::from_secs(5);
+
+/// Number of consecutive ticks the captured frame can stay invalid before notifying.
+const CAPTURE_INVALID_NOTIFY_TICKS: u32 = FPS * 10;
+
+/// Minimum x or y distance the player must move to be considered as making progress.
+const STUCK_PROGRESS_DISTANCE: i32 = 5;
+
+/// Duration of uninterrupted farming after the last channel change to reset
+/// [`Context::channel_change_count`].
+const CHANNEL_CHANGE_RESET_DURATION: Duration = Duration::from_secs(300);
+
+/// The result of matching the detected minimap against the saved maps after a map change.
+#[derive(Debug)]
+enum SavedMapMatch {
+    /// The detected minimap matches the currently selected map.
+    Selected,
+    /// The detected minimap matches another saved map.
+    Other(MinimapData),
+    /// The detected minimap does not match any saved map.
+    None,
+}
+
 /// A control flow to use after a contextual state update.
 pub enum ControlFlow<T> {
     /// The contextual state is updated immediately.
@@ -88,6 +118,40 @@ pub struct Context {
     pub buffs: [Buff; BuffKind::COUNT],
     /// Whether the bot is halting.
     pub halting: bool,
+    /// Whether the bot is waiting for the minimap to be stably detected after a map or channel
+    /// transition.
+    ///
+    /// While this is `true`, [`Rotator`] does not rotate any action but the bot is not halted.
+    pub transitioning: bool,
+    /// Whether the bot is waiting for [`crate::Settings::initial_map_delay_millis`] to elapse
+    /// after the minimap is first detected.
+    ///
+    /// While this is `true`, [`Rotator`] does not rotate any action.
+    pub map_loading: bool,
+    /// Whether the last captured frame is invalid (e.g. blank).
+    ///
+    /// While this is `true`, detection is paused and [`Rotator`] does not rotate any action.
+    pub capture_invalid: bool,
+    /// Whether the game window has lost focus and [`Rotator`] should pause.
+    ///
+    /// This is only set when [`crate::Settings::input_method_pause_on_focus_loss`] is enabled.
+    pub focus_lost: bool,
+    /// The [`Instant`]s of keys sent within the last minute.
+    pub key_sends: VecDeque<Instant>,
+    /// Whether the number of keys sent within the last minute has reached
+    /// [`crate::Settings::max_apm`] and [`Rotator`] should stall.
+    pub apm_throttled: bool,
+    /// The last [`Instant`] the player has moved or completed an action while rotating actions.
+    ///
+    /// This is [`None`] when halting or [`crate::Settings::stuck_timeout_millis`] is zero.
+    pub last_progress_instant: Option<Instant>,
+    /// The number of consecutive channel changes without a period of uninterrupted farming in
+    /// between.
+    pub channel_change_count: u32,
+    /// The last [`Instant`] the player started changing channel.
+    ///
+    /// This is [`None`] when halting or [`Self::channel_change_count`] has been reset.
+    pub last_channel_change_instant: Option<Instant>,
     /// The game current tick.
     ///
     /// This is increased on each update tick.
@@ -108,6 +172,15 @@ impl Context {
             skills: [Skill::Detecting; SkillKind::COUNT],
             buffs: [Buff::No; BuffKind::COUNT],
             halting: false,
+            transitioning: false,
+            map_loading: false,
+            capture_invalid: false,
+            focus_lost: false,
+            key_sends: VecDeque::new(),
+            apm_throttled: false,
+            last_progress_instant: None,
+            channel_change_count: 0,
+            last_channel_change_instant: None,
             tick: 0,
         }
     }
@@ -165,27 +238,31 @@ fn update_loop() {
     let mut config = query_configs().unwrap().into_iter().next().unwrap(); // Override by UI
     let mut buffs = config_buffs(&config);
     let settings = query_settings(); // Override by UI
-    let seeds = query_seeds(); // Fixed, unchanged
-    let rng = Rng::new(seeds.seed); // Create one for Context
+    let seed = context_rng_seed(&settings, &query_seeds());
+    let rng = Rng::new(seed); // Create one for Context
 
     let key_sender_method = if let InputMethod::Rpc = settings.input_method {
-        KeySenderMethod::Rpc(handle, settings.input_method_rpc_server_url.clone())
+        KeySenderMethod::Rpc(
+            handle,
+            settings.input_method_rpc_server_url.clone(),
+            Duration::from_millis(settings.input_method_rpc_timeout_millis),
+        )
     } else {
         match settings.capture_mode {
-            CaptureMode::BitBlt | CaptureMode::WindowsGraphicsCapture => {
-                KeySenderMethod::Default(handle, KeyInputKind::Fixed)
-            }
+            CaptureMode::BitBlt
+            | CaptureMode::WindowsGraphicsCapture
+            | CaptureMode::MonitorRegion => KeySenderMethod::Default(handle, KeyInputKind::Fixed),
             // This shouldn't matter because we have to get the Handle from the box capture anyway
             CaptureMode::BitBltArea => KeySenderMethod::Default(handle, KeyInputKind::Foreground),
         }
     };
-    let mut keys = DefaultKeySender::new(key_sender_method, seeds);
+    let mut keys = DefaultKeySender::new(key_sender_method, seed);
     let key_sender = broadcast::channel::<KeyBinding>(1).0; // Callback to UI
     let mut key_receiver = KeyReceiver::new(handle, KeyInputKind::Fixed);
 
     let mut capture_handles = Vec::<(String, Handle)>::new();
-    let mut selected_capture_handle = None;
-    let mut image_capture = ImageCapture::new(handle, settings.capture_mode);
+    let mut selected_capture_handle = None::<(String, Handle)>;
+    let mut image_capture = ImageCapture::new(handle, &settings);
     if let ImageCaptureKind::BitBltArea(capture) = image_capture.kind() {
         key_receiver = KeyReceiver::new(capture.handle(), KeyInputKind::Foreground);
         keys.set_method(KeySenderMethod::Default(
@@ -206,10 +283,37 @@ fn update_loop() {
         skills: [Skill::Detecting],
         buffs: [Buff::No; BuffKind::COUNT],
         halting: true,
+        transitioning: false,
+        map_loading: false,
+        capture_invalid: false,
+        focus_lost: false,
+        key_sends: VecDeque::new(),
+        apm_throttled: false,
+        last_progress_instant: None,
+        channel_change_count: 0,
+        last_channel_change_instant: None,
         tick: 0,
     };
     let mut player_state = PlayerState::default();
+    apply_settings(&mut player_state.config, &settings.borrow());
+    player_state.set_death_templates(load_grayscale_templates(
+        &settings.borrow().death_template_paths,
+    ));
+    rotator.set_loot_object_templates(load_grayscale_templates(
+        &settings.borrow().loot_object_template_paths,
+    ));
     let mut minimap_state = MinimapState::default();
+    minimap_state
+        .set_border_whiteness_threshold(settings.borrow().minimap_border_whiteness_threshold);
+    minimap_state.set_elite_boss_detection_millis(settings.borrow().elite_boss_detection_millis);
+    minimap_state.set_rune_stable_detection_count(settings.borrow().rune_stable_detection_count);
+    let mut minimap_positions = query_minimap_positions();
+    if settings.borrow().remember_minimap_position {
+        minimap_state.set_seed_bbox(remembered_minimap_bbox(
+            &minimap_positions,
+            &selected_capture_handle,
+        ));
+    }
     let mut skill_states = SkillKind::iter()
         .map(SkillState::new)
         .collect::<Vec<SkillState>>();
@@ -220,22 +324,70 @@ fn update_loop() {
         state.update_enabled_state(&config, &settings.borrow());
     });
 
+    let mut transition_stable_ticks = 0;
+    let mut map_detected_instant = None;
+    let mut input_disconnected_instant = None;
+    let mut last_progress_pos = None;
+    let mut capture_invalid_ticks = 0;
+    let mut matching_saved_maps = false;
+
     #[cfg(debug_assertions)]
     let mut recording_images_id = None;
     #[cfg(debug_assertions)]
+    let mut recording_macro = None;
+    #[cfg(debug_assertions)]
     let mut infering_rune = None;
 
     loop_with_fps(FPS, || {
+        // Re-detects before tracking minimap change so it is not treated as a map change
+        if player_state.take_redetect_minimap() {
+            context.minimap = Minimap::Detecting;
+        }
+
         let mat = image_capture.grab().map(OwnedMat::new);
+        context.capture_invalid = image_capture.is_blank();
+        if context.capture_invalid {
+            capture_invalid_ticks += 1;
+        } else {
+            capture_invalid_ticks = 0;
+        }
         let was_player_alive = !player_state.is_dead;
         let was_minimap_idle = matches!(context.minimap, Minimap::Idle(_));
+        let was_changing_channel = is_changing_channel(context.player);
+        let prev_capture_handle = selected_capture_handle.as_ref().map(|(_, handle)| *handle);
         let detector = mat.map(CachedDetector::new);
 
         context.tick += 1;
+        context.focus_lost =
+            settings.borrow().input_method_pause_on_focus_loss && !context.keys.is_focused();
         if let Some(detector) = detector {
             context.detector = Some(Box::new(detector));
             context.minimap = fold_context(&context, context.minimap, &mut minimap_state);
+            update_transitioning_state(
+                &mut context,
+                was_minimap_idle,
+                &mut transition_stable_ticks,
+                settings.borrow().transition_grace_ticks,
+            );
+            update_map_loading_state(
+                &mut context,
+                Instant::now(),
+                &mut map_detected_instant,
+                settings.borrow().initial_map_delay_millis,
+            );
+            #[cfg(debug_assertions)]
+            let prev_player = context.player;
             context.player = fold_context(&context, context.player, &mut player_state);
+            #[cfg(debug_assertions)]
+            if settings.borrow().enable_debug_audio_cues
+                && let Some(cue) = debug_audio_cue(
+                    prev_player,
+                    context.player,
+                    was_player_alive && player_state.is_dead,
+                )
+            {
+                play_debug_audio_cue(cue);
+            }
             for (i, state) in skill_states
                 .iter_mut()
                 .enumerate()
@@ -251,12 +403,19 @@ fn update_loop() {
         }
         // TODO: Maybe should not downcast but really don't want to public update_input_delay
         // method
-        context
+        let default_keys = context
             .keys
             .as_any_mut()
             .downcast_mut::<DefaultKeySender>()
-            .unwrap()
-            .update_input_delay(context.tick);
+            .unwrap();
+        default_keys.update_input_delay(context.tick);
+        let sent_count = default_keys.take_sent_count();
+        update_apm_state(
+            &mut context,
+            Instant::now(),
+            sent_count,
+            settings.borrow().max_apm,
+        );
 
         // Poll requests, keys and update scheduled notifications frames
         let mut settings_borrow_mut = settings.borrow_mut();
@@ -279,10 +438,13 @@ fn update_loop() {
             #[cfg(debug_assertions)]
             recording_images_id: &mut recording_images_id,
             #[cfg(debug_assertions)]
+            recording_macro: &mut recording_macro,
+            #[cfg(debug_assertions)]
             infering_rune: &mut infering_rune,
         };
         handler.poll_request();
         handler.poll_key();
+        let capture_handle_reselected = handler.poll_capture_handle();
         #[cfg(debug_assertions)]
         handler.poll_debug();
         handler.context.notification.update_scheduled_frames(|| {
@@ -297,10 +459,12 @@ fn update_loop() {
 
         // Upon accidental or white roomed causing map to change,
         // abort actions and send notification
+        let mut notify_fail_or_map_change = false;
+        let health_depleted = handler.player.take_health_depleted();
         if handler.minimap.data().is_some() && !handler.context.halting {
             let minimap_changed =
                 was_minimap_idle && matches!(handler.context.minimap, Minimap::Detecting);
-            let player_died = was_player_alive && handler.player.is_dead;
+            let player_died = (was_player_alive && handler.player.is_dead) || health_depleted;
             let can_halt_or_notify = minimap_changed
                 && !matches!(
                     handler.context.player,
@@ -310,19 +474,381 @@ fn update_loop() {
                     })
                 );
 
-            if player_died || (can_halt_or_notify && handler.settings.stop_on_fail_or_change_map) {
-                handler.on_rotate_actions(true);
+            if can_halt_or_notify && handler.settings.enable_match_saved_maps {
+                matching_saved_maps = true;
             }
-            if can_halt_or_notify {
-                drop(settings_borrow_mut); // For notification to borrow immutably
-                let _ = context
-                    .notification
-                    .schedule_notification(NotificationKind::FailOrMapChange);
+            let is_wrong_map = if !handler.settings.enable_match_saved_maps {
+                can_halt_or_notify
+            } else if matching_saved_maps && let Minimap::Idle(idle) = handler.context.minimap {
+                matching_saved_maps = false;
+                match match_saved_maps(handler.minimap.data().unwrap(), idle.bbox, || {
+                    query_maps().unwrap_or_default()
+                }) {
+                    SavedMapMatch::Selected => false,
+                    SavedMapMatch::Other(minimap) => {
+                        if handler.settings.switch_to_matched_map {
+                            handler.on_update_minimap(minimap.initial_preset(), minimap);
+                        }
+                        false
+                    }
+                    SavedMapMatch::None => true,
+                }
+            } else {
+                false
+            };
+
+            if player_died || (is_wrong_map && handler.settings.stop_on_fail_or_change_map) {
+                handler.on_rotate_actions(true);
             }
+            notify_fail_or_map_change = is_wrong_map;
+        } else {
+            matching_saved_maps = false;
+        }
+
+        // Halts when the key sender (e.g. RPC server) is unreachable for too long
+        let mut notify_input_disconnected = false;
+        if handler.context.halting || handler.context.keys.is_connected() {
+            input_disconnected_instant = None;
+        } else if input_disconnected_instant
+            .get_or_insert_with(Instant::now)
+            .elapsed()
+            >= INPUT_DISCONNECTED_MAX_DURATION
+        {
+            input_disconnected_instant = None;
+            notify_input_disconnected = true;
+            handler.on_rotate_actions(true);
+        }
+
+        // Halts when the player has not made any progress for too long
+        let action_completed = handler.player.take_action_completed();
+        let notify_stuck = update_stuck_state(
+            handler.context,
+            Instant::now(),
+            handler.player.last_known_pos,
+            &mut last_progress_pos,
+            action_completed,
+            handler.settings.stuck_timeout_millis,
+        );
+        if notify_stuck {
+            handler.on_rotate_actions(true);
+        }
+
+        // Halts when the player keeps changing channel without farming in between
+        let notify_channel_change_limit = update_channel_change_state(
+            handler.context,
+            Instant::now(),
+            !was_changing_channel && is_changing_channel(handler.context.player),
+            handler.settings.max_consecutive_channel_changes,
+        );
+        if notify_channel_change_limit {
+            handler.on_rotate_actions(true);
+        }
+
+        drop(settings_borrow_mut); // For notification to borrow immutably
+        if settings.borrow().remember_minimap_position
+            && prev_capture_handle != selected_capture_handle.as_ref().map(|(_, handle)| *handle)
+        {
+            minimap_state.set_seed_bbox(remembered_minimap_bbox(
+                &minimap_positions,
+                &selected_capture_handle,
+            ));
+        }
+        if settings.borrow().remember_minimap_position
+            && !was_minimap_idle
+            && let Minimap::Idle(idle) = context.minimap
+        {
+            remember_minimap_position(
+                &mut minimap_positions,
+                capture_handle_key(&selected_capture_handle),
+                idle.bbox,
+            );
+        }
+        if notify_fail_or_map_change {
+            let _ = context
+                .notification
+                .schedule_notification(NotificationKind::FailOrMapChange);
+        }
+        if capture_handle_reselected {
+            let _ = context
+                .notification
+                .schedule_notification(NotificationKind::CaptureHandleReselected);
+        }
+        if capture_invalid_ticks == CAPTURE_INVALID_NOTIFY_TICKS {
+            let _ = context
+                .notification
+                .schedule_notification(NotificationKind::CaptureInvalid);
+        }
+        if notify_input_disconnected {
+            let _ = context
+                .notification
+                .schedule_notification(NotificationKind::InputDisconnected);
+        }
+        if notify_stuck {
+            let _ = context
+                .notification
+                .schedule_notification(NotificationKind::Stuck);
+        }
+        if notify_channel_change_limit {
+            let _ = context
+                .notification
+                .schedule_notification(NotificationKind::ChannelChangeLimit);
         }
     });
 }
 
+/// Matches the detected minimap `bbox` against the `current` map and then the saved `maps`.
+///
+/// The minimap size is used as the signature of a map.
+#[inline]
+fn match_saved_maps(
+    current: &MinimapData,
+    bbox: Rect,
+    maps: impl FnOnce() -> Vec<MinimapData>,
+) -> SavedMapMatch {
+    let is_matched =
+        |minimap: &MinimapData| minimap.width == bbox.width && minimap.height == bbox.height;
+    if is_matched(current) {
+        return SavedMapMatch::Selected;
+    }
+    maps()
+        .into_iter()
+        .find(|minimap| minimap.id != current.id && is_matched(minimap))
+        .map(SavedMapMatch::Other)
+        .unwrap_or(SavedMapMatch::None)
+}
+
+/// Gets the seed of [`Context::rng`].
+///
+/// The fixed seed from `settings` is preferred over the stored `seeds` when enabled.
+#[inline]
+pub fn context_rng_seed(settings: &Settings, seeds: &Seeds) -> RngSeed {
+    if settings.enable_fixed_rng_seed {
+        seed_from_u64(settings.fixed_rng_seed as u64)
+    } else {
+        seeds.seed
+    }
+}
+
+/// Updates [`Context::key_sends`] sliding window and [`Context::apm_throttled`].
+///
+/// `sent_count` keys are recorded as sent at `now`. A `max_apm` of zero never throttles.
+#[inline]
+fn update_apm_state(context: &mut Context, now: Instant, sent_count: u32, max_apm: u32) {
+    const WINDOW: Duration = Duration::from_secs(60);
+
+    if max_apm == 0 {
+        context.key_sends.clear();
+        context.apm_throttled = false;
+        return;
+    }
+    for _ in 0..sent_count {
+        context.key_sends.push_back(now);
+    }
+    while context
+        .key_sends
+        .front()
+        .is_some_and(|instant| now.duration_since(*instant) >= WINDOW)
+    {
+        context.key_sends.pop_front();
+    }
+    context.apm_throttled = context.key_sends.len() >= max_apm as usize;
+}
+
+/// Updates [`Context::last_progress_instant`] and returns whether the player is stuck.
+///
+/// The player makes progress when its position `pos` has moved at least
+/// [`STUCK_PROGRESS_DISTANCE`] from `last_progress_pos` or an action has `completed`. The player is
+/// stuck when there is no progress for `timeout_millis`. Tracking is reset while halting or
+/// paused because of focus loss and is disabled when `timeout_millis` is zero.
+#[inline]
+fn update_stuck_state(
+    context: &mut Context,
+    now: Instant,
+    pos: Option<Point>,
+    last_progress_pos: &mut Option<Point>,
+    completed: bool,
+    timeout_millis: u64,
+) -> bool {
+    if timeout_millis == 0 || context.halting || context.focus_lost {
+        context.last_progress_instant = None;
+        *last_progress_pos = None;
+        return false;
+    }
+    let moved = match (pos, *last_progress_pos) {
+        (Some(pos), Some(last_pos)) => {
+            (pos.x - last_pos.x).abs() >= STUCK_PROGRESS_DISTANCE
+                || (pos.y - last_pos.y).abs() >= STUCK_PROGRESS_DISTANCE
+        }
+        (Some(_), None) => true,
+        (None, _) => false,
+    };
+    let Some(instant) = context
+        .last_progress_instant
+        .filter(|_| !moved && !completed)
+    else {
+        context.last_progress_instant = Some(now);
+        *last_progress_pos = pos.or(*last_progress_pos);
+        return false;
+    };
+    if now.duration_since(instant) < Duration::from_millis(timeout_millis) {
+        return false;
+    }
+    context.last_progress_instant = None;
+    *last_progress_pos = None;
+    true
+}
+
+/// Updates [`Context::channel_change_count`] and returns whether the count exceeds `max_count`.
+///
+/// The count is increased when the player has `started` changing channel and is reset after
+/// [`CHANNEL_CHANGE_RESET_DURATION`] without any channel change. Tracking is reset while halting
+/// and is disabled when `max_count` is zero.
+#[inline]
+fn update_channel_change_state(
+    context: &mut Context,
+    now: Instant,
+    started: bool,
+    max_count: u32,
+) -> bool {
+    if max_count == 0 || context.halting {
+        context.channel_change_count = 0;
+        context.last_channel_change_instant = None;
+        return false;
+    }
+    if started {
+        context.channel_change_count += 1;
+        context.last_channel_change_instant = Some(now);
+    } else if context
+        .last_channel_change_instant
+        .is_some_and(|instant| now.duration_since(instant) >= CHANNEL_CHANGE_RESET_DURATION)
+    {
+        context.channel_change_count = 0;
+        context.last_channel_change_instant = None;
+    }
+    if context.channel_change_count <= max_count {
+        return false;
+    }
+    context.channel_change_count = 0;
+    context.last_channel_change_instant = None;
+    true
+}
+
+/// Gets the key of the selected capture handle used for remembering the minimap position.
+#[inline]
+fn capture_handle_key(selected: &Option<(String, Handle)>) -> &str {
+    selected
+        .as_ref()
+        .map(|(name, _)| name.as_str())
+        .unwrap_or_default()
+}
+
+/// Gets the remembered minimap bounding box of the `selected` capture handle.
+#[inline]
+fn remembered_minimap_bbox(
+    positions: &MinimapPositions,
+    selected: &Option<(String, Handle)>,
+) -> Option<Rect> {
+    positions
+        .positions
+        .get(capture_handle_key(selected))
+        .copied()
+        .map(Rect::from)
+}
+
+/// Persists the minimap `bbox` of capture handle `key` if it differs from the remembered one.
+#[inline]
+fn remember_minimap_position(positions: &mut MinimapPositions, key: &str, bbox: Rect) {
+    let bound = Bound::from(bbox);
+    if positions.positions.get(key) == Some(&bound) {
+        return;
+    }
+    positions.positions.insert(key.to_string(), bound);
+    let _ = upsert_minimap_positions(positions);
+}
+
+/// Whether `player` is changing channel.
+#[inline]
+fn is_changing_channel(player: Player) -> bool {
+    matches!(
+        player,
+        Player::Panicking(Panicking {
+            to: PanicTo::Channel,
+            ..
+        })
+    )
+}
+
+/// Updates [`Context::transitioning`] after a map or channel transition.
+///
+/// The flag is set when the minimap changes from idle to detecting and is cleared once the minimap
+/// has been idle for `grace_ticks` consecutive ticks.
+#[inline]
+fn update_transitioning_state(
+    context: &mut Context,
+    was_minimap_idle: bool,
+    stable_ticks: &mut u32,
+    grace_ticks: u32,
+) {
+    match context.minimap {
+        Minimap::Detecting => {
+            if was_minimap_idle && grace_ticks > 0 {
+                context.transitioning = true;
+            }
+            *stable_ticks = 0;
+        }
+        Minimap::Idle(_) => {
+            if context.transitioning {
+                *stable_ticks += 1;
+                if *stable_ticks >= grace_ticks {
+                    context.transitioning = false;
+                    *stable_ticks = 0;
+                }
+            }
+        }
+    }
+}
+
+/// Updates [`Context::map_loading`] after the minimap is first detected.
+///
+/// `detected_instant` is the [`Instant`] the minimap became idle and is cleared when the minimap
+/// is detecting again. The flag stays set until `delay_millis` has elapsed since that instant.
+#[inline]
+fn update_map_loading_state(
+    context: &mut Context,
+    now: Instant,
+    detected_instant: &mut Option<Instant>,
+    delay_millis: u64,
+) {
+    match context.minimap {
+        Minimap::Detecting => {
+            *detected_instant = None;
+            context.map_loading = false;
+        }
+        Minimap::Idle(_) => {
+            let instant = *detected_instant.get_or_insert(now);
+            context.map_loading = now.duration_since(instant) < Duration::from_millis(delay_millis);
+        }
+    }
+}
+
+/// Gets the [`DebugAudioCue`] to play when the player transitions from `prev` to `next`.
+#[cfg(debug_assertions)]
+#[inline]
+fn debug_audio_cue(prev: Player, next: Player, player_died: bool) -> Option<DebugAudioCue> {
+    if player_died {
+        return Some(DebugAudioCue::PlayerDied);
+    }
+    if std::mem::discriminant(&prev) == std::mem::discriminant(&next) {
+        return None;
+    }
+    match next {
+        Player::SolvingRune(_) => Some(DebugAudioCue::SolvingRune),
+        Player::Panicking(_) => Some(DebugAudioCue::Panicking),
+        Player::FamiliarsSwapping(_) => Some(DebugAudioCue::FamiliarsSwapping),
+        _ => None,
+    }
+}
+
 #[inline]
 fn fold_context<C>(
     context: &Context,
@@ -367,3 +893,70 @@ fn to_png(frame: Option<&OwnedMat>) -> Option<Vec<u8>> {
         Some(bytes.to_vec())
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn update_apm_state_sliding_window() {
+        let mut context = Context::new(None, None);
+        let now = Instant::now();
+
+        update_apm_state(&mut context, now, 2, 3);
+        assert_eq!(context.key_sends.len(), 2);
+        assert!(!context.apm_throttled);
+
+        update_apm_state(&mut context, now + Duration::from_secs(30), 1, 3);
+        assert_eq!(context.key_sends.len(), 3);
+        assert!(context.apm_throttled);
+
+        // keys sent at least a minute ago are dropped from the window
+        update_apm_state(&mut context, now + Duration::from_secs(60), 0, 3);
+        assert_eq!(context.key_sends.len(), 1);
+        assert!(!context.apm_throttled);
+
+        // zero max apm never throttles
+        update_apm_state(&mut context, now + Duration::from_secs(60), 10, 0);
+        assert!(context.key_sends.is_empty());
+        assert!(!context.apm_throttled);
+    }
+
+    #[test]
+    fn remembered_minimap_bbox_of_selected_capture_handle() {
+        let bound = Bound {
+            x: 1,
+            y: 2,
+            width: 3,
+            height: 4,
+        };
+        let other_bound = Bound {
+            x: 5,
+            y: 6,
+            width: 7,
+            height: 8,
+        };
+        let positions = MinimapPositions {
+            positions: HashMap::from([
+                ("".to_string(), bound),
+                ("MapleStory".to_string(), other_bound),
+            ]),
+            ..MinimapPositions::default()
+        };
+
+        assert_eq!(
+            remembered_minimap_bbox(&positions, &None),
+            Some(Rect::from(bound))
+        );
+        // selecting or reselecting a capture handle seeds from its own remembered position
+        let selected = Some(("MapleStory".to_string(), Handle::new("MapleStory")));
+        assert_eq!(
+            remembered_minimap_bbox(&positions, &selected),
+            Some(Rect::from(other_bound))
+        );
+        let selected = Some(("Unknown".to_string(), Handle::new("Unknown")));
+        assert_eq!(remembered_minimap_bbox(&positions, &selected), None);
+    }
+}
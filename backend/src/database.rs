@@ -12,7 +12,14 @@ use rusqlite::{Connection, Params, Statement, types::Null};
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use strum::{Display, EnumIter, EnumString};
 
-use crate::pathing;
+use crate::{
+    buff::BUFF_DETECTION_MILLIS,
+    minimap::{
+        ELITE_BOSS_DETECTION_MILLIS, MINIMAP_BORDER_WHITENESS_THRESHOLD,
+        RUNE_STABLE_DETECTION_COUNT,
+    },
+    pathing,
+};
 
 static CONNECTION: LazyLock<Mutex<Connection>> = LazyLock::new(|| {
     let path = env::current_exe()
@@ -40,6 +47,10 @@ static CONNECTION: LazyLock<Mutex<Connection>> = LazyLock::new(|| {
             id INTEGER PRIMARY KEY,
             data TEXT NOT NULL
         );
+        CREATE TABLE IF NOT EXISTS minimap_positions (
+            id INTEGER PRIMARY KEY,
+            data TEXT NOT NULL
+        );
         "#,
     )
     .unwrap();
@@ -83,6 +94,18 @@ impl Default for Seeds {
 
 impl_identifiable!(Seeds);
 
+/// The last successfully detected minimap bounding boxes.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MinimapPositions {
+    pub id: Option<i64>,
+    /// Bounding boxes keyed by the capture handle name.
+    ///
+    /// The empty name is used for the default capture handle.
+    pub positions: HashMap<String, Bound>,
+}
+
+impl_identifiable!(MinimapPositions);
+
 #[derive(
     Clone, Copy, PartialEq, Default, Debug, Serialize, Deserialize, EnumIter, Display, EnumString,
 )]
@@ -102,6 +125,20 @@ pub enum SwappableFamiliars {
     SecondAndLast,
 }
 
+/// The outcome when no swappable familiar card is found during familiars swapping.
+#[derive(
+    Clone, Copy, PartialEq, Default, Debug, Serialize, Deserialize, EnumIter, Display, EnumString,
+)]
+pub enum NoSwappableCardsOutcome {
+    /// Completes the swapping silently.
+    #[default]
+    Complete,
+    /// Completes the swapping and sends a notification.
+    Notify,
+    /// Waits for the cards list to populate and searches for the cards again.
+    Retry,
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Default, Hash, Serialize, Deserialize)]
 pub enum FamiliarRarity {
     #[default]
@@ -114,8 +151,18 @@ pub struct Familiars {
     pub enable_familiars_swapping: bool,
     #[serde(default = "familiars_swap_check_millis")]
     pub swap_check_millis: u64,
+    #[serde(default = "familiars_essence_check_millis")]
+    pub essence_check_millis: u64,
     pub swappable_familiars: SwappableFamiliars,
     pub swappable_rarities: HashSet<FamiliarRarity>,
+    /// Whether to move to the map [`Minimap::home_position`] before swapping familiars.
+    #[serde(default)]
+    pub move_to_safe_spot: bool,
+    /// Number of ticks to wait after hovering a familiar before detecting its level.
+    #[serde(default = "familiars_hover_settle_ticks")]
+    pub hover_settle_ticks: u32,
+    #[serde(default)]
+    pub no_swappable_cards_outcome: NoSwappableCardsOutcome,
 }
 
 impl Default for Familiars {
@@ -123,8 +170,12 @@ impl Default for Familiars {
         Self {
             enable_familiars_swapping: false,
             swap_check_millis: familiars_swap_check_millis(),
+            essence_check_millis: familiars_essence_check_millis(),
             swappable_familiars: SwappableFamiliars::default(),
             swappable_rarities: HashSet::default(),
+            move_to_safe_spot: false,
+            hover_settle_ticks: familiars_hover_settle_ticks(),
+            no_swappable_cards_outcome: NoSwappableCardsOutcome::default(),
         }
     }
 }
@@ -133,6 +184,14 @@ fn familiars_swap_check_millis() -> u64 {
     300000
 }
 
+fn familiars_essence_check_millis() -> u64 {
+    20000
+}
+
+fn familiars_hover_settle_ticks() -> u32 {
+    5
+}
+
 #[derive(
     Clone, Copy, PartialEq, Default, Debug, Serialize, Deserialize, EnumIter, Display, EnumString,
 )]
@@ -140,6 +199,49 @@ pub enum PanicMode {
     #[default]
     CycleChannel,
     GoToTown,
+    Defensive,
+}
+
+/// How each rune arrow key is pressed while solving rune.
+#[derive(
+    Clone, Copy, PartialEq, Default, Debug, Serialize, Deserialize, EnumIter, Display, EnumString,
+)]
+pub enum RuneArrowPressStyle {
+    #[default]
+    Tap,
+    Hold,
+}
+
+/// The side to approach the rune from before interacting while solving rune.
+#[derive(
+    Clone, Copy, PartialEq, Default, Debug, Serialize, Deserialize, EnumIter, Display, EnumString,
+)]
+pub enum RuneApproachSide {
+    #[default]
+    Either,
+    Left,
+    Right,
+}
+
+/// The recovery to perform when the player position has been lost for a while.
+#[derive(
+    Clone, Copy, PartialEq, Default, Debug, Serialize, Deserialize, EnumIter, Display, EnumString,
+)]
+pub enum LostPositionRecovery {
+    #[default]
+    Jump,
+    MoveToCenter,
+    RedetectMinimap,
+}
+
+/// The fallback to perform when changing channel is blocked in the current map.
+#[derive(
+    Clone, Copy, PartialEq, Default, Debug, Serialize, Deserialize, EnumIter, Display, EnumString,
+)]
+pub enum ChangeChannelBlockedFallback {
+    #[default]
+    Town,
+    None,
 }
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
@@ -153,6 +255,50 @@ pub struct Notifications {
     pub notify_on_player_guildie_appear: bool,
     pub notify_on_player_stranger_appear: bool,
     pub notify_on_player_friend_appear: bool,
+    #[serde(default)]
+    pub notify_on_rune_abandoned: bool,
+    #[serde(default)]
+    pub notify_on_capture_handle_reselected: bool,
+    #[serde(default)]
+    pub notify_on_panic_to_town_failed: bool,
+    #[serde(default)]
+    pub notify_on_input_disconnected: bool,
+    #[serde(default)]
+    pub notify_on_capture_invalid: bool,
+    #[serde(default)]
+    pub notify_on_lost_position_recovery_failed: bool,
+    #[serde(default)]
+    pub notify_on_stuck: bool,
+    #[serde(default)]
+    pub notify_on_rune_solved: bool,
+    #[serde(default)]
+    pub notify_on_channel_change_limit: bool,
+    /// The minimum milliseconds between two notifications of the same kind.
+    #[serde(default)]
+    pub notification_cooldown_millis: u64,
+}
+
+/// The region used for detecting buffs.
+///
+/// The region is relative to the default buffs region at the top right of the game.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BuffsRegion {
+    /// The number of pixels to shift the region to the left.
+    pub offset_x: i32,
+    /// The number of pixels to shift the region down.
+    pub offset_y: i32,
+    /// The scale applied to the region width and height.
+    pub scale: f32,
+}
+
+impl Default for BuffsRegion {
+    fn default() -> Self {
+        Self {
+            offset_x: 0,
+            offset_y: 0,
+            scale: 1.0,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -161,32 +307,187 @@ pub struct Settings {
     pub id: Option<i64>,
     #[serde(default)]
     pub capture_mode: CaptureMode,
+    #[serde(default)]
+    pub capture_monitor_index: u32,
+    #[serde(default = "capture_monitor_region_default")]
+    pub capture_monitor_region: Bound,
     #[serde(default = "enable_rune_solving_default")]
     pub enable_rune_solving: bool,
     #[serde(default)]
     pub enable_change_channel_on_elite_boss_appear: bool,
     #[serde(default)]
+    pub enable_panic_on_elite_boss_low_health: bool,
+    #[serde(default = "elite_boss_low_health_percent_default")]
+    pub elite_boss_low_health_percent: f32,
+    #[serde(default)]
+    pub elite_boss_consumable_keys: Vec<KeyBinding>,
+    #[serde(default)]
     pub enable_panic_mode: bool,
     #[serde(default)]
     pub panic_mode: PanicMode,
     #[serde(default)]
+    pub panic_defensive_key: KeyBindingConfiguration,
+    #[serde(default = "panic_react_to_player_default")]
+    pub panic_react_to_strangers: bool,
+    #[serde(default = "panic_react_to_player_default")]
+    pub panic_react_to_guildies: bool,
+    #[serde(default = "panic_react_to_player_default")]
+    pub panic_react_to_friends: bool,
+    #[serde(default = "change_channel_menu_wait_millis_default")]
+    pub change_channel_menu_wait_millis: u64,
+    #[serde(default = "change_channel_menu_max_retries_default")]
+    pub change_channel_menu_max_retries: u32,
+    #[serde(default)]
+    pub change_channel_blocked_fallback: ChangeChannelBlockedFallback,
+    /// Whether to seed the random number generator with [`Self::fixed_rng_seed`] for
+    /// reproducible behavior.
+    #[serde(default)]
+    pub enable_fixed_rng_seed: bool,
+    #[serde(default)]
+    pub fixed_rng_seed: u32,
+    /// Whether to play a short tone when rune solving, panicking, familiars swapping or death
+    /// happens.
+    #[serde(default)]
+    pub enable_debug_audio_cues: bool,
+    #[serde(default)]
     pub stop_on_fail_or_change_map: bool,
+    /// Whether a map change is only treated as wrong map when the detected minimap does not
+    /// match any of the saved maps.
+    #[serde(default)]
+    pub enable_match_saved_maps: bool,
+    /// Whether to switch to the matched saved map after a map change.
+    #[serde(default)]
+    pub switch_to_matched_map: bool,
+    #[serde(default = "transition_grace_ticks_default")]
+    pub transition_grace_ticks: u32,
+    /// Milliseconds to wait after a map is first detected before rotating any action.
+    #[serde(default)]
+    pub initial_map_delay_millis: u64,
+    #[serde(default)]
+    pub arrival_tolerance: i32,
+    #[serde(default)]
+    pub edge_safety_margin: i32,
     #[serde(default)]
     pub input_method: InputMethod,
     #[serde(default)]
     pub input_method_rpc_server_url: String,
+    #[serde(default = "input_method_rpc_timeout_millis_default")]
+    pub input_method_rpc_timeout_millis: u64,
+    #[serde(default)]
+    pub input_method_pause_on_focus_loss: bool,
+    /// The maximum number of keys sent per minute with zero indicating unlimited.
+    #[serde(default)]
+    pub max_apm: u32,
     #[serde(default)]
     pub notifications: Notifications,
     #[serde(default)]
     pub familiars: Familiars,
     #[serde(default = "toggle_actions_key_default")]
     pub toggle_actions_key: KeyBindingConfiguration,
+    #[serde(default = "emergency_town_key_default")]
+    pub emergency_town_key: KeyBindingConfiguration,
+    /// The key to switch the current map between its auto-mobbing preset and other preset.
+    #[serde(default = "toggle_mode_key_default")]
+    pub toggle_mode_key: KeyBindingConfiguration,
     #[serde(default = "platform_start_key_default")]
     pub platform_start_key: KeyBindingConfiguration,
     #[serde(default = "platform_end_key_default")]
     pub platform_end_key: KeyBindingConfiguration,
     #[serde(default = "platform_add_key_default")]
     pub platform_add_key: KeyBindingConfiguration,
+    #[serde(default = "rune_calibration_timeout_millis_default")]
+    pub rune_calibration_timeout_millis: u64,
+    /// The maximum milliseconds to wait for the rune buff after solving a rune before retrying.
+    #[serde(default = "rune_validate_millis_default")]
+    pub rune_validate_millis: u64,
+    #[serde(default = "rune_calibration_max_attempts_default")]
+    pub rune_calibration_max_attempts: u32,
+    #[serde(default)]
+    pub rune_arrow_press_style: RuneArrowPressStyle,
+    #[serde(default = "rune_arrow_hold_millis_default")]
+    pub rune_arrow_hold_millis: u64,
+    #[serde(default)]
+    pub rune_approach_side: RuneApproachSide,
+    #[serde(default)]
+    pub enable_rune_clear_mobs: bool,
+    #[serde(default)]
+    pub rune_clear_mobs_key: KeyBindingConfiguration,
+    #[serde(default = "rune_clear_mobs_millis_default")]
+    pub rune_clear_mobs_millis: u64,
+    #[serde(default)]
+    pub max_runes_solved_per_minute: u32,
+    #[serde(default)]
+    pub enable_collect_drops: bool,
+    #[serde(default = "collect_drops_millis_default")]
+    pub collect_drops_millis: u64,
+    #[serde(default = "cash_shop_dwell_millis_default")]
+    pub cash_shop_dwell_millis: u64,
+    #[serde(default)]
+    pub cash_shop_exit_wait_millis: u64,
+    #[serde(default)]
+    pub enable_redetect_minimap_on_unstuck: bool,
+    #[serde(default = "redetect_minimap_unstuck_count_default")]
+    pub redetect_minimap_unstuck_count: u32,
+    #[serde(default)]
+    pub enable_lost_position_recovery: bool,
+    #[serde(default = "lost_position_recovery_millis_default")]
+    pub lost_position_recovery_millis: u64,
+    #[serde(default)]
+    pub lost_position_recovery: LostPositionRecovery,
+    #[serde(default = "minimap_border_whiteness_threshold_default")]
+    pub minimap_border_whiteness_threshold: u8,
+    /// Whether to remember the last detected minimap position and try it first on startup.
+    #[serde(default)]
+    pub remember_minimap_position: bool,
+    #[serde(default)]
+    pub enable_focus_rune_solving: bool,
+    #[serde(default)]
+    pub auto_mob_priority_grace_millis: u64,
+    #[serde(default)]
+    pub enable_rune_solving_ignore_buff: bool,
+    #[serde(default)]
+    pub enable_capture_handle_reselection: bool,
+    #[serde(default)]
+    pub buffs_region: BuffsRegion,
+    #[serde(default = "buffs_detection_millis_default")]
+    pub buffs_detection_millis: u64,
+    #[serde(default = "elite_boss_detection_millis_default")]
+    pub elite_boss_detection_millis: u64,
+    /// The number of consecutive rune detections at the same position required before the rune
+    /// is considered present.
+    #[serde(default = "rune_stable_detection_count_default")]
+    pub rune_stable_detection_count: u32,
+    #[serde(default)]
+    pub abort_strict_position_on_timeout: bool,
+    #[serde(default)]
+    pub stop_on_health_depleted: bool,
+    #[serde(default)]
+    pub enable_idle_fidget: bool,
+    #[serde(default)]
+    pub wait_for_ground: bool,
+    #[serde(default)]
+    pub enable_clean_ui_on_start: bool,
+    /// Paths to the additional images used to detect the player is dead (e.g. event maps).
+    #[serde(default)]
+    pub death_template_paths: Vec<String>,
+    /// Milliseconds without any player progress before stopping the actions.
+    ///
+    /// Zero disables stopping.
+    #[serde(default)]
+    pub stuck_timeout_millis: u64,
+    /// Maximum number of consecutive channel changes without farming in between before stopping
+    /// the actions.
+    ///
+    /// Zero disables stopping.
+    #[serde(default)]
+    pub max_consecutive_channel_changes: u32,
+    #[serde(default)]
+    pub enable_loot_objects: bool,
+    /// Paths to the images used to detect the lootable objects (e.g. bags, chests).
+    #[serde(default)]
+    pub loot_object_template_paths: Vec<String>,
+    #[serde(default = "loot_object_check_millis_default")]
+    pub loot_object_check_millis: u64,
 }
 
 impl Default for Settings {
@@ -194,29 +495,172 @@ impl Default for Settings {
         Self {
             id: None,
             capture_mode: CaptureMode::default(),
+            capture_monitor_index: 0,
+            capture_monitor_region: capture_monitor_region_default(),
             enable_rune_solving: enable_rune_solving_default(),
             enable_change_channel_on_elite_boss_appear: false,
+            enable_panic_on_elite_boss_low_health: false,
+            elite_boss_low_health_percent: elite_boss_low_health_percent_default(),
+            elite_boss_consumable_keys: vec![],
             enable_panic_mode: false,
             panic_mode: PanicMode::default(),
+            panic_defensive_key: KeyBindingConfiguration::default(),
+            panic_react_to_strangers: panic_react_to_player_default(),
+            panic_react_to_guildies: panic_react_to_player_default(),
+            panic_react_to_friends: panic_react_to_player_default(),
+            change_channel_menu_wait_millis: change_channel_menu_wait_millis_default(),
+            change_channel_menu_max_retries: change_channel_menu_max_retries_default(),
+            change_channel_blocked_fallback: ChangeChannelBlockedFallback::default(),
             input_method: InputMethod::default(),
             input_method_rpc_server_url: String::default(),
+            input_method_rpc_timeout_millis: input_method_rpc_timeout_millis_default(),
+            input_method_pause_on_focus_loss: false,
+            max_apm: 0,
+            enable_fixed_rng_seed: false,
+            fixed_rng_seed: 0,
+            enable_debug_audio_cues: false,
             stop_on_fail_or_change_map: false,
+            enable_match_saved_maps: false,
+            switch_to_matched_map: false,
+            transition_grace_ticks: transition_grace_ticks_default(),
+            initial_map_delay_millis: 0,
+            arrival_tolerance: 0,
+            edge_safety_margin: 0,
             notifications: Notifications::default(),
             familiars: Familiars::default(),
             toggle_actions_key: toggle_actions_key_default(),
+            emergency_town_key: emergency_town_key_default(),
+            toggle_mode_key: toggle_mode_key_default(),
             platform_start_key: platform_start_key_default(),
             platform_end_key: platform_end_key_default(),
             platform_add_key: platform_add_key_default(),
+            rune_calibration_timeout_millis: rune_calibration_timeout_millis_default(),
+            rune_validate_millis: rune_validate_millis_default(),
+            rune_calibration_max_attempts: rune_calibration_max_attempts_default(),
+            rune_arrow_press_style: RuneArrowPressStyle::default(),
+            rune_arrow_hold_millis: rune_arrow_hold_millis_default(),
+            rune_approach_side: RuneApproachSide::default(),
+            enable_rune_clear_mobs: false,
+            rune_clear_mobs_key: KeyBindingConfiguration::default(),
+            rune_clear_mobs_millis: rune_clear_mobs_millis_default(),
+            max_runes_solved_per_minute: 0,
+            enable_collect_drops: false,
+            collect_drops_millis: collect_drops_millis_default(),
+            cash_shop_dwell_millis: cash_shop_dwell_millis_default(),
+            cash_shop_exit_wait_millis: 0,
+            enable_redetect_minimap_on_unstuck: false,
+            redetect_minimap_unstuck_count: redetect_minimap_unstuck_count_default(),
+            enable_lost_position_recovery: false,
+            lost_position_recovery_millis: lost_position_recovery_millis_default(),
+            lost_position_recovery: LostPositionRecovery::default(),
+            minimap_border_whiteness_threshold: minimap_border_whiteness_threshold_default(),
+            remember_minimap_position: false,
+            enable_focus_rune_solving: false,
+            auto_mob_priority_grace_millis: 0,
+            enable_rune_solving_ignore_buff: false,
+            enable_capture_handle_reselection: false,
+            buffs_region: BuffsRegion::default(),
+            buffs_detection_millis: buffs_detection_millis_default(),
+            elite_boss_detection_millis: elite_boss_detection_millis_default(),
+            rune_stable_detection_count: rune_stable_detection_count_default(),
+            abort_strict_position_on_timeout: false,
+            stop_on_health_depleted: false,
+            enable_idle_fidget: false,
+            wait_for_ground: false,
+            enable_clean_ui_on_start: false,
+            death_template_paths: vec![],
+            stuck_timeout_millis: 0,
+            max_consecutive_channel_changes: 0,
+            enable_loot_objects: false,
+            loot_object_template_paths: vec![],
+            loot_object_check_millis: loot_object_check_millis_default(),
         }
     }
 }
 
 impl_identifiable!(Settings);
 
+fn capture_monitor_region_default() -> Bound {
+    Bound {
+        x: 0,
+        y: 0,
+        width: 1366,
+        height: 768,
+    }
+}
+
 fn enable_rune_solving_default() -> bool {
     true
 }
 
+fn panic_react_to_player_default() -> bool {
+    true
+}
+
+fn change_channel_menu_wait_millis_default() -> u64 {
+    500
+}
+
+fn change_channel_menu_max_retries_default() -> u32 {
+    3
+}
+
+fn transition_grace_ticks_default() -> u32 {
+    30
+}
+
+fn elite_boss_low_health_percent_default() -> f32 {
+    20.0
+}
+
+fn input_method_rpc_timeout_millis_default() -> u64 {
+    500
+}
+
+fn rune_calibration_timeout_millis_default() -> u64 {
+    5000
+}
+
+fn rune_validate_millis_default() -> u64 {
+    12500
+}
+
+fn rune_calibration_max_attempts_default() -> u32 {
+    3
+}
+
+fn rune_arrow_hold_millis_default() -> u64 {
+    100
+}
+
+fn rune_clear_mobs_millis_default() -> u64 {
+    1000
+}
+
+fn redetect_minimap_unstuck_count_default() -> u32 {
+    3
+}
+
+fn lost_position_recovery_millis_default() -> u64 {
+    5000
+}
+
+fn minimap_border_whiteness_threshold_default() -> u8 {
+    MINIMAP_BORDER_WHITENESS_THRESHOLD
+}
+
+fn buffs_detection_millis_default() -> u64 {
+    BUFF_DETECTION_MILLIS
+}
+
+fn elite_boss_detection_millis_default() -> u64 {
+    ELITE_BOSS_DETECTION_MILLIS
+}
+
+fn rune_stable_detection_count_default() -> u32 {
+    RUNE_STABLE_DETECTION_COUNT
+}
+
 fn toggle_actions_key_default() -> KeyBindingConfiguration {
     KeyBindingConfiguration {
         key: KeyBinding::Comma,
@@ -224,6 +668,20 @@ fn toggle_actions_key_default() -> KeyBindingConfiguration {
     }
 }
 
+fn emergency_town_key_default() -> KeyBindingConfiguration {
+    KeyBindingConfiguration {
+        key: KeyBinding::Period,
+        enabled: false,
+    }
+}
+
+fn toggle_mode_key_default() -> KeyBindingConfiguration {
+    KeyBindingConfiguration {
+        key: KeyBinding::Slash,
+        enabled: false,
+    }
+}
+
 fn platform_start_key_default() -> KeyBindingConfiguration {
     KeyBindingConfiguration {
         key: KeyBinding::J,
@@ -245,6 +703,18 @@ fn platform_add_key_default() -> KeyBindingConfiguration {
     }
 }
 
+fn collect_drops_millis_default() -> u64 {
+    60000
+}
+
+fn loot_object_check_millis_default() -> u64 {
+    5000
+}
+
+fn cash_shop_dwell_millis_default() -> u64 {
+    10000
+}
+
 #[derive(
     Clone, Copy, PartialEq, Default, Debug, Serialize, Deserialize, EnumIter, Display, EnumString,
 )]
@@ -254,6 +724,7 @@ pub enum CaptureMode {
     #[strum(to_string = "Windows 10 (1903 and up)")] // Thanks OBS
     WindowsGraphicsCapture,
     BitBltArea,
+    MonitorRegion,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -266,8 +737,12 @@ pub struct Configuration {
     pub teleport_key: Option<KeyBindingConfiguration>,
     #[serde(default = "jump_key_default")]
     pub jump_key: KeyBindingConfiguration,
+    #[serde(default)]
+    pub flash_jump_key: Option<KeyBindingConfiguration>,
     pub up_jump_key: Option<KeyBindingConfiguration>,
     pub interact_key: KeyBindingConfiguration,
+    #[serde(default)]
+    pub pickup_key: KeyBindingConfiguration,
     pub cash_shop_key: KeyBindingConfiguration,
     #[serde(default)]
     pub familiar_menu_key: KeyBindingConfiguration,
@@ -277,6 +752,10 @@ pub struct Configuration {
     pub change_channel_key: KeyBindingConfiguration,
     pub feed_pet_key: KeyBindingConfiguration,
     pub feed_pet_millis: u64,
+    #[serde(default = "feed_pet_count_default")]
+    pub feed_pet_count: u32,
+    #[serde(default = "feed_pet_spacing_millis_default")]
+    pub feed_pet_spacing_millis: u64,
     pub potion_key: KeyBindingConfiguration,
     pub potion_mode: PotionMode,
     pub health_update_millis: u64,
@@ -303,10 +782,53 @@ pub struct Configuration {
     #[serde(default)]
     pub extreme_gold_potion_key: KeyBindingConfiguration,
     #[serde(default)]
+    pub enable_buff_all: bool,
+    #[serde(default)]
+    pub buff_all_keys: Vec<KeyBinding>,
+    #[serde(default = "buff_all_spacing_millis_default")]
+    pub buff_all_spacing_millis: u64,
+    /// The buffs to recast on a timer instead of detecting visually.
+    #[serde(default)]
+    pub timer_only_buffs: Vec<BuffKind>,
+    /// Milliseconds between each recast of the buffs in [`Self::timer_only_buffs`].
+    #[serde(default = "timer_only_buff_recast_millis_default")]
+    pub timer_only_buff_recast_millis: u64,
+    /// Whether to defer casting buffs while the player is attacking.
+    #[serde(default)]
+    pub skip_buff_in_combat: bool,
+    /// Milliseconds without attacking required before casting buffs if
+    /// [`Self::skip_buff_in_combat`] is enabled.
+    ///
+    /// A missing buff is still cast if it has been deferred for too long under continuous combat.
+    #[serde(default = "buff_combat_lull_millis_default")]
+    pub buff_combat_lull_millis: u64,
+    #[serde(default)]
     pub class: Class,
     #[serde(default)]
     pub disable_adjusting: bool,
     #[serde(default)]
+    pub adjust_unstuck_jump: bool,
+    /// Whether to use key action while walking near its destination instead of stopping first.
+    #[serde(default)]
+    pub adjust_attack_while_walking: bool,
+    /// The percentage to shorten small adjusting movement as the player nears the destination.
+    #[serde(default)]
+    pub adjust_damping_percent: f32,
+    /// The maximum number of adjusting direction reversals before accepting the position with
+    /// `0` indicating no limit.
+    #[serde(default)]
+    pub adjust_max_correction_cycles: u32,
+    #[serde(default = "near_stationary_x_velocity_threshold_default")]
+    pub near_stationary_x_velocity_threshold: f32,
+    #[serde(default = "near_stationary_y_velocity_threshold_default")]
+    pub near_stationary_y_velocity_threshold: f32,
+    #[serde(default)]
+    pub stationary_cast_velocity_threshold: f32,
+    #[serde(default)]
+    pub jump_hold_millis: u64,
+    #[serde(default = "movement_speed_factor_default")]
+    pub movement_speed_factor: f32,
+    #[serde(default)]
     pub actions: Vec<ActionConfiguration>,
 }
 
@@ -326,6 +848,80 @@ fn familiar_essence_key_default() -> KeyBindingConfiguration {
     }
 }
 
+fn near_stationary_x_velocity_threshold_default() -> f32 {
+    0.75
+}
+
+fn near_stationary_y_velocity_threshold_default() -> f32 {
+    0.4
+}
+
+fn movement_speed_factor_default() -> f32 {
+    1.0
+}
+
+fn feed_pet_count_default() -> u32 {
+    3
+}
+
+fn feed_pet_spacing_millis_default() -> u64 {
+    350
+}
+
+fn buff_all_spacing_millis_default() -> u64 {
+    100
+}
+
+fn buff_combat_lull_millis_default() -> u64 {
+    2000
+}
+
+fn timer_only_buff_recast_millis_default() -> u64 {
+    1_800_000
+}
+
+impl Configuration {
+    /// Creates a starter [`Configuration`] for `class`.
+    ///
+    /// The preset only tunes the near stationary velocity thresholds used by double jump and
+    /// stationary link keys to the class link key timing. Key bindings, including the up jump
+    /// key, are left for the user to configure.
+    pub fn preset_for(class: Class) -> Configuration {
+        let default = Configuration {
+            class,
+            ..Configuration::default()
+        };
+        match class {
+            // Short link key timing and chained skills that keep the player moving, relax the
+            // stationary check so link keys are not held back
+            Class::Cadena | Class::Blaster => Configuration {
+                near_stationary_x_velocity_threshold: 1.0,
+                near_stationary_y_velocity_threshold: 0.6,
+                ..default
+            },
+            // Longest link key timing, requires the player to be more stationary
+            Class::Ark => Configuration {
+                near_stationary_x_velocity_threshold: 0.5,
+                near_stationary_y_velocity_threshold: 0.3,
+                ..default
+            },
+            Class::Generic => default,
+        }
+    }
+
+    /// Applies the class-specific fields of [`Self::preset_for`] to this [`Configuration`].
+    ///
+    /// Key bindings, buffs, actions and other user settings are kept as-is.
+    pub fn with_class_preset(self) -> Configuration {
+        let preset = Configuration::preset_for(self.class);
+        Configuration {
+            near_stationary_x_velocity_threshold: preset.near_stationary_x_velocity_threshold,
+            near_stationary_y_velocity_threshold: preset.near_stationary_y_velocity_threshold,
+            ..self
+        }
+    }
+}
+
 impl Default for Configuration {
     fn default() -> Self {
         Self {
@@ -334,14 +930,18 @@ impl Default for Configuration {
             ropelift_key: None,
             teleport_key: None,
             jump_key: jump_key_default(),
+            flash_jump_key: None,
             up_jump_key: None,
             interact_key: KeyBindingConfiguration::default(),
+            pickup_key: KeyBindingConfiguration::default(),
             cash_shop_key: KeyBindingConfiguration::default(),
             familiar_menu_key: KeyBindingConfiguration::default(),
             maple_guide_key: KeyBindingConfiguration::default(),
             change_channel_key: KeyBindingConfiguration::default(),
             feed_pet_key: KeyBindingConfiguration::default(),
             feed_pet_millis: 320000,
+            feed_pet_count: feed_pet_count_default(),
+            feed_pet_spacing_millis: feed_pet_spacing_millis_default(),
             potion_key: KeyBindingConfiguration::default(),
             potion_mode: PotionMode::EveryMillis(180000),
             health_update_millis: 1000,
@@ -359,13 +959,52 @@ impl Default for Configuration {
             extreme_blue_potion_key: KeyBindingConfiguration::default(),
             extreme_green_potion_key: KeyBindingConfiguration::default(),
             extreme_gold_potion_key: KeyBindingConfiguration::default(),
+            enable_buff_all: false,
+            buff_all_keys: vec![],
+            buff_all_spacing_millis: buff_all_spacing_millis_default(),
+            timer_only_buffs: vec![],
+            timer_only_buff_recast_millis: timer_only_buff_recast_millis_default(),
+            skip_buff_in_combat: false,
+            buff_combat_lull_millis: buff_combat_lull_millis_default(),
             class: Class::default(),
             disable_adjusting: false,
+            adjust_unstuck_jump: false,
+            adjust_attack_while_walking: false,
+            adjust_damping_percent: 0.0,
+            adjust_max_correction_cycles: 0,
+            near_stationary_x_velocity_threshold: near_stationary_x_velocity_threshold_default(),
+            near_stationary_y_velocity_threshold: near_stationary_y_velocity_threshold_default(),
+            stationary_cast_velocity_threshold: 0.0,
+            jump_hold_millis: 0,
+            movement_speed_factor: movement_speed_factor_default(),
             actions: vec![],
         }
     }
 }
 
+/// A buff that can be selected by the user.
+///
+/// Unlike the internal buff kind, this excludes the rune buff.
+#[derive(
+    Clone, Copy, PartialEq, Default, Debug, Serialize, Deserialize, EnumIter, Display, EnumString,
+)]
+pub enum BuffKind {
+    #[default]
+    Familiar,
+    SayramElixir,
+    AureliaElixir,
+    ExpCouponX3,
+    BonusExpCoupon,
+    LegionWealth,
+    LegionLuck,
+    WealthAcquisitionPotion,
+    ExpAccumulationPotion,
+    ExtremeRedPotion,
+    ExtremeBluePotion,
+    ExtremeGreenPotion,
+    ExtremeGoldPotion,
+}
+
 #[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize, EnumIter, Display, EnumString)]
 pub enum PotionMode {
     EveryMillis(u64),
@@ -409,18 +1048,25 @@ impl From<ActionConfiguration> for Action {
             link_key: None,
             count: key_count_default(),
             position: None,
+            strict_position: false,
             condition: ActionCondition::EveryMillis(value.every_millis),
             direction: ActionKeyDirection::Any,
+            require_direction: None,
+            require_buff: None,
+            probability: None,
             with: if value.require_stationary {
                 ActionKeyWith::Stationary
             } else {
                 Default::default()
             },
+            use_while_falling: false,
             queue_to_front: Some(true),
             wait_before_use_millis: value.wait_before_use_millis,
             wait_before_use_millis_random_range: 0,
             wait_after_use_millis: value.wait_after_use_millis,
             wait_after_use_millis_random_range: 0,
+            linked_wait_millis: 0,
+            fire_during_move: false,
         })
     }
 }
@@ -465,6 +1111,11 @@ pub struct PingPong {
     pub key_count: u32,
     pub key_wait_before_millis: u64,
     pub key_wait_after_millis: u64,
+    #[serde(default)]
+    pub edge_inset: i32,
+    /// The key to use instead of [`Self::key`] when moving to the left.
+    #[serde(default)]
+    pub alternate_key: Option<KeyBinding>,
 }
 
 impl Default for PingPong {
@@ -475,6 +1126,8 @@ impl Default for PingPong {
             key_count: key_count_default(),
             key_wait_before_millis: 0,
             key_wait_after_millis: 0,
+            edge_inset: 0,
+            alternate_key: None,
         }
     }
 }
@@ -487,6 +1140,25 @@ pub struct AutoMobbing {
     pub key_count: u32,
     pub key_wait_before_millis: u64,
     pub key_wait_after_millis: u64,
+    #[serde(default)]
+    pub empty_map_action: EmptyMapAction,
+    #[serde(default = "empty_map_timeout_millis_default")]
+    pub empty_map_timeout_millis: u64,
+    #[serde(default)]
+    pub scale_count_with_mobs: bool,
+    /// The minimum milliseconds to wait after the previous auto-mob action before choosing a new
+    /// target.
+    #[serde(default)]
+    pub retarget_interval_millis: u64,
+    /// Whether to prefer mobs near the center of the minimap when choosing a new target.
+    #[serde(default)]
+    pub prefer_center: bool,
+    /// The milliseconds the preferred platform must stay empty before moving to another
+    /// platform.
+    ///
+    /// Zero disables the platform preference.
+    #[serde(default)]
+    pub platform_dwell_millis: u64,
 }
 
 impl Default for AutoMobbing {
@@ -497,14 +1169,68 @@ impl Default for AutoMobbing {
             key_count: key_count_default(),
             key_wait_before_millis: 0,
             key_wait_after_millis: 0,
+            empty_map_action: EmptyMapAction::default(),
+            empty_map_timeout_millis: empty_map_timeout_millis_default(),
+            scale_count_with_mobs: false,
+            retarget_interval_millis: 0,
+            prefer_center: false,
+            platform_dwell_millis: 0,
         }
     }
 }
 
+/// Holds near a spot, using a key in place and repositioning only when knocked away.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct HoldSpot {
+    pub position: Position,
+    pub key: KeyBinding,
+    #[serde(default = "key_count_default")]
+    pub key_count: u32,
+    pub key_wait_before_millis: u64,
+    pub key_wait_after_millis: u64,
+    /// The maximum x or y distance from [`Self::position`] before repositioning.
+    #[serde(default = "reposition_threshold_default")]
+    pub reposition_threshold: i32,
+    /// Whether to alternate facing left and right each time the key is used.
+    #[serde(default)]
+    pub turn_and_attack: bool,
+}
+
+impl Default for HoldSpot {
+    fn default() -> Self {
+        Self {
+            position: Position::default(),
+            key: KeyBinding::default(),
+            key_count: key_count_default(),
+            key_wait_before_millis: 0,
+            key_wait_after_millis: 0,
+            reposition_threshold: reposition_threshold_default(),
+            turn_and_attack: false,
+        }
+    }
+}
+
+#[derive(
+    Clone, Copy, PartialEq, Default, Debug, Serialize, Deserialize, EnumIter, Display, EnumString,
+)]
+pub enum EmptyMapAction {
+    #[default]
+    Wait,
+    ChangeChannel,
+}
+
 fn key_count_default() -> u32 {
     1
 }
 
+fn empty_map_timeout_millis_default() -> u64 {
+    60000
+}
+
+fn reposition_threshold_default() -> i32 {
+    5
+}
+
 #[derive(
     Clone, Copy, PartialEq, Default, Debug, Serialize, Deserialize, EnumIter, Display, EnumString,
 )]
@@ -512,8 +1238,10 @@ pub enum RotationMode {
     StartToEnd,
     #[default]
     StartToEndThenReverse,
+    ShuffledCycle,
     AutoMobbing(AutoMobbing),
     PingPong(PingPong),
+    HoldSpot(HoldSpot),
 }
 
 impl_identifiable!(Configuration);
@@ -528,14 +1256,81 @@ pub struct Minimap {
     pub width: i32,
     pub height: i32,
     pub rotation_mode: RotationMode,
+    /// The rotation modes of action presets overriding [`Self::rotation_mode`].
+    pub preset_rotation_modes: HashMap<String, RotationMode>,
     pub platforms: Vec<Platform>,
     pub rune_platforms_pathing: bool,
     pub rune_platforms_pathing_up_jump_only: bool,
     pub auto_mob_platforms_pathing: bool,
     pub auto_mob_platforms_pathing_up_jump_only: bool,
     pub auto_mob_platforms_bound: bool,
+    pub platforms_pathing_walk_off_double_jump: bool,
+    /// The polygon vertices used as the auto-mobbing and ping-pong bound instead of the rectangle.
+    ///
+    /// The vertices are in minimap coordinate with y-axis pointing down, same as [`Bound`]. The
+    /// rectangle bound is used when there are fewer than three vertices.
+    pub bound_polygon: Vec<(i32, i32)>,
     pub actions_any_reset_on_erda_condition: bool,
+    /// The distance from the player within which a [`RotationMode::StartToEndThenReverse`] action
+    /// is merged into the current position instead of moving.
+    ///
+    /// Zero disables merging.
+    pub actions_merge_distance: i32,
+    /// The key to use whenever a [`RotationMode::StartToEndThenReverse`] rotation reverses.
+    pub actions_turn_around_key: Option<KeyBinding>,
+    /// The position to move back to after the player has been idle for
+    /// [`Self::home_idle_millis`].
+    pub home_position: Position,
+    /// Milliseconds the player has been idle without any action before moving back to
+    /// [`Self::home_position`].
+    ///
+    /// Zero disables moving back.
+    pub home_idle_millis: u64,
+    /// The x offset added to every action position when the actions are built.
+    ///
+    /// Used to calibrate actions recorded at a different resolution without editing each one.
+    pub actions_offset_x: i32,
+    /// The y offset added to every action position when the actions are built.
+    pub actions_offset_y: i32,
     pub actions: HashMap<String, Vec<Action>>,
+    /// The last selected preset of [`Self::actions`] restored when this map is selected again.
+    pub last_preset: Option<String>,
+}
+
+impl Minimap {
+    /// Gets the preset to select when this map is selected.
+    ///
+    /// Returns [`Self::last_preset`] if it still exists or the first preset otherwise.
+    pub fn initial_preset(&self) -> Option<String> {
+        self.last_preset
+            .clone()
+            .filter(|preset| self.actions.contains_key(preset))
+            .or_else(|| self.actions.keys().next().cloned())
+    }
+
+    /// Gets the [`RotationMode`] of `preset`.
+    ///
+    /// Falls back to [`Self::rotation_mode`] if `preset` is [`None`] or has no rotation mode of
+    /// its own.
+    pub fn rotation_mode_of(&self, preset: Option<&str>) -> RotationMode {
+        preset
+            .and_then(|preset| self.preset_rotation_modes.get(preset).copied())
+            .unwrap_or(self.rotation_mode)
+    }
+
+    /// Gets the mutable [`RotationMode`] of `preset`.
+    ///
+    /// If `preset` has no rotation mode of its own, it is initialized from
+    /// [`Self::rotation_mode`].
+    pub fn rotation_mode_of_mut(&mut self, preset: Option<&str>) -> &mut RotationMode {
+        match preset {
+            Some(preset) => self
+                .preset_rotation_modes
+                .entry(preset.to_string())
+                .or_insert(self.rotation_mode),
+            None => &mut self.rotation_mode,
+        }
+    }
 }
 
 impl_identifiable!(Minimap);
@@ -567,7 +1362,19 @@ pub struct Position {
 pub struct ActionMove {
     pub position: Position,
     pub condition: ActionCondition,
+    /// The probability in the range `0..=1` for this action to be used when its turn comes.
+    ///
+    /// Only applies to normal actions. `None` means the action is always used.
+    #[serde(default)]
+    pub probability: Option<f32>,
     pub wait_after_move_millis: u64,
+    #[serde(default)]
+    pub wait_after_move_millis_random_range: u64,
+    #[serde(default)]
+    pub linked_wait_millis: u64,
+    /// The key to use once when the player arrives at [`Self::position`] before waiting.
+    #[serde(default)]
+    pub attack_on_arrival: Option<KeyBinding>,
 }
 
 #[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
@@ -578,15 +1385,35 @@ pub struct ActionKey {
     #[serde(default = "count_default")]
     pub count: u32,
     pub position: Option<Position>,
+    #[serde(default)]
+    pub strict_position: bool,
     pub condition: ActionCondition,
     pub direction: ActionKeyDirection,
+    #[serde(default)]
+    pub require_direction: Option<ActionKeyDirection>,
+    #[serde(default)]
+    pub require_buff: Option<BuffKind>,
+    /// The probability in the range `0..=1` for this action to be used when its turn comes.
+    ///
+    /// Only applies to normal actions. `None` means the action is always used.
+    #[serde(default)]
+    pub probability: Option<f32>,
     pub with: ActionKeyWith,
+    #[serde(default)]
+    pub use_while_falling: bool,
     pub wait_before_use_millis: u64,
     #[serde(default)]
     pub wait_before_use_millis_random_range: u64,
     pub wait_after_use_millis: u64,
     #[serde(default)]
     pub wait_after_use_millis_random_range: u64,
+    #[serde(default)]
+    pub linked_wait_millis: u64,
+    /// Whether to send this key while the next linked move is in progress instead of before it.
+    ///
+    /// Only applies when the next action in the linked chain is a move.
+    #[serde(default)]
+    pub fire_during_move: bool,
     pub queue_to_front: Option<bool>,
 }
 
@@ -597,13 +1424,20 @@ impl Default for ActionKey {
             link_key: None,
             count: count_default(),
             position: None,
+            strict_position: false,
             condition: ActionCondition::default(),
             direction: ActionKeyDirection::default(),
+            require_direction: None,
+            require_buff: None,
+            probability: None,
             with: ActionKeyWith::default(),
+            use_while_falling: false,
             wait_before_use_millis: 0,
             wait_before_use_millis_random_range: 0,
             wait_after_use_millis: 0,
             wait_after_use_millis_random_range: 0,
+            linked_wait_millis: 0,
+            fire_during_move: false,
             queue_to_front: None,
         }
     }
@@ -683,6 +1517,8 @@ pub enum ActionKeyWith {
     Any,
     Stationary,
     DoubleJump,
+    UpJump,
+    Airborne,
 }
 
 #[derive(
@@ -770,6 +1606,29 @@ pub enum KeyBinding {
     Shift,
     Ctrl,
     Alt,
+    Numpad0,
+    Numpad1,
+    Numpad2,
+    Numpad3,
+    Numpad4,
+    Numpad5,
+    Numpad6,
+    Numpad7,
+    Numpad8,
+    Numpad9,
+    NumpadMultiply,
+    NumpadAdd,
+    NumpadSubtract,
+    NumpadDecimal,
+    NumpadDivide,
+    Minus,
+    Equal,
+    LeftBracket,
+    RightBracket,
+    Backslash,
+    Tab,
+    CapsLock,
+    Backspace,
 }
 
 impl From<KeyBinding> for KeyKind {
@@ -845,6 +1704,29 @@ impl From<KeyBinding> for KeyKind {
             KeyBinding::Shift => KeyKind::Shift,
             KeyBinding::Ctrl => KeyKind::Ctrl,
             KeyBinding::Alt => KeyKind::Alt,
+            KeyBinding::Numpad0 => KeyKind::Numpad0,
+            KeyBinding::Numpad1 => KeyKind::Numpad1,
+            KeyBinding::Numpad2 => KeyKind::Numpad2,
+            KeyBinding::Numpad3 => KeyKind::Numpad3,
+            KeyBinding::Numpad4 => KeyKind::Numpad4,
+            KeyBinding::Numpad5 => KeyKind::Numpad5,
+            KeyBinding::Numpad6 => KeyKind::Numpad6,
+            KeyBinding::Numpad7 => KeyKind::Numpad7,
+            KeyBinding::Numpad8 => KeyKind::Numpad8,
+            KeyBinding::Numpad9 => KeyKind::Numpad9,
+            KeyBinding::NumpadMultiply => KeyKind::NumpadMultiply,
+            KeyBinding::NumpadAdd => KeyKind::NumpadAdd,
+            KeyBinding::NumpadSubtract => KeyKind::NumpadSubtract,
+            KeyBinding::NumpadDecimal => KeyKind::NumpadDecimal,
+            KeyBinding::NumpadDivide => KeyKind::NumpadDivide,
+            KeyBinding::Minus => KeyKind::Minus,
+            KeyBinding::Equal => KeyKind::Equal,
+            KeyBinding::LeftBracket => KeyKind::LeftBracket,
+            KeyBinding::RightBracket => KeyKind::RightBracket,
+            KeyBinding::Backslash => KeyKind::Backslash,
+            KeyBinding::Tab => KeyKind::Tab,
+            KeyBinding::CapsLock => KeyKind::CapsLock,
+            KeyBinding::Backspace => KeyKind::Backspace,
         }
     }
 }
@@ -922,6 +1804,29 @@ impl From<KeyKind> for KeyBinding {
             KeyKind::Shift => KeyBinding::Shift,
             KeyKind::Ctrl => KeyBinding::Ctrl,
             KeyKind::Alt => KeyBinding::Alt,
+            KeyKind::Numpad0 => KeyBinding::Numpad0,
+            KeyKind::Numpad1 => KeyBinding::Numpad1,
+            KeyKind::Numpad2 => KeyBinding::Numpad2,
+            KeyKind::Numpad3 => KeyBinding::Numpad3,
+            KeyKind::Numpad4 => KeyBinding::Numpad4,
+            KeyKind::Numpad5 => KeyBinding::Numpad5,
+            KeyKind::Numpad6 => KeyBinding::Numpad6,
+            KeyKind::Numpad7 => KeyBinding::Numpad7,
+            KeyKind::Numpad8 => KeyBinding::Numpad8,
+            KeyKind::Numpad9 => KeyBinding::Numpad9,
+            KeyKind::NumpadMultiply => KeyBinding::NumpadMultiply,
+            KeyKind::NumpadAdd => KeyBinding::NumpadAdd,
+            KeyKind::NumpadSubtract => KeyBinding::NumpadSubtract,
+            KeyKind::NumpadDecimal => KeyBinding::NumpadDecimal,
+            KeyKind::NumpadDivide => KeyBinding::NumpadDivide,
+            KeyKind::Minus => KeyBinding::Minus,
+            KeyKind::Equal => KeyBinding::Equal,
+            KeyKind::LeftBracket => KeyBinding::LeftBracket,
+            KeyKind::RightBracket => KeyBinding::RightBracket,
+            KeyKind::Backslash => KeyBinding::Backslash,
+            KeyKind::Tab => KeyBinding::Tab,
+            KeyKind::CapsLock => KeyBinding::CapsLock,
+            KeyKind::Backspace => KeyBinding::Backspace,
         }
     }
 }
@@ -938,6 +1843,18 @@ pub fn query_seeds() -> Seeds {
     seeds
 }
 
+pub fn query_minimap_positions() -> MinimapPositions {
+    query_from_table::<MinimapPositions>("minimap_positions")
+        .unwrap()
+        .into_iter()
+        .next()
+        .unwrap_or_default()
+}
+
+pub fn upsert_minimap_positions(positions: &mut MinimapPositions) -> Result<()> {
+    upsert_to_table("minimap_positions", positions)
+}
+
 pub fn query_settings() -> Settings {
     let mut settings = query_from_table::<Settings>("settings")
         .unwrap()
@@ -1051,3 +1968,57 @@ fn delete_from_table<T: Identifiable>(table: &str, data: &T) -> Result<()> {
     }
     inner(table, data.id())
 }
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn configuration_preset_for_generic_is_default() {
+        assert_eq!(
+            Configuration::preset_for(Class::Generic),
+            Configuration::default()
+        );
+    }
+
+    #[test]
+    fn configuration_preset_for_class_sets_class() {
+        for class in Class::iter() {
+            assert_eq!(Configuration::preset_for(class).class, class);
+        }
+    }
+
+    #[test]
+    fn configuration_with_class_preset_keeps_user_settings() {
+        let config = Configuration {
+            id: Some(1),
+            name: "Ark".to_string(),
+            class: Class::Ark,
+            jump_key: KeyBindingConfiguration {
+                key: KeyBinding::C,
+                enabled: true,
+            },
+            up_jump_key: Some(KeyBindingConfiguration {
+                key: KeyBinding::V,
+                enabled: true,
+            }),
+            potion_key: KeyBindingConfiguration {
+                key: KeyBinding::Delete,
+                enabled: true,
+            },
+            ..Configuration::default()
+        };
+        let preset = Configuration::preset_for(Class::Ark);
+
+        assert_eq!(
+            config.clone().with_class_preset(),
+            Configuration {
+                near_stationary_x_velocity_threshold: preset.near_stationary_x_velocity_threshold,
+                near_stationary_y_velocity_threshold: preset.near_stationary_y_velocity_threshold,
+                ..config
+            }
+        );
+    }
+}
@@ -22,7 +22,7 @@ use opencv::{
     highgui::{imshow, wait_key},
     imgcodecs::imwrite_def,
 };
-use platforms::windows::KeyKind;
+use platforms::windows::{KeyKind, beep};
 use rand::distr::{Alphanumeric, SampleString};
 
 static DATASET_DIR: LazyLock<PathBuf> = LazyLock::new(|| {
@@ -317,6 +317,11 @@ pub fn save_mobs_for_training(mat: &Mat, mobs: &[Rect]) {
     }
 }
 
+#[allow(unused)]
+pub fn debug_buffs_region(mat: &impl MatTraitConst, region: Rect) {
+    debug_mat("Buffs Region", mat, 0, &[(region, "Buffs")]);
+}
+
 #[allow(unused)]
 pub fn save_minimap_for_training<T: MatTraitConst + ToInputArray>(mat: &T, minimap: Rect) {
     let name = Alphanumeric.sample_string(&mut rand::rng(), 8);
@@ -331,6 +336,25 @@ pub fn save_minimap_for_training<T: MatTraitConst + ToInputArray>(mat: &T, minim
     }
 }
 
+/// Kinds of audio cue played when a player action of interest starts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DebugAudioCue {
+    SolvingRune,
+    Panicking,
+    FamiliarsSwapping,
+    PlayerDied,
+}
+
+pub fn play_debug_audio_cue(cue: DebugAudioCue) {
+    let (frequency, duration_millis) = match cue {
+        DebugAudioCue::SolvingRune => (880, 150),
+        DebugAudioCue::Panicking => (440, 300),
+        DebugAudioCue::FamiliarsSwapping => (660, 150),
+        DebugAudioCue::PlayerDied => (220, 500),
+    };
+    beep(frequency, duration_millis);
+}
+
 fn map_bbox_from_prediction(pred: &[f32], size: Size, w_ratio: f32, h_ratio: f32) -> Rect {
     let tl_x = (pred[0] / w_ratio).max(0.0).min(size.width as f32) as i32;
     let tl_y = (pred[1] / h_ratio).max(0.0).min(size.height as f32) as i32;
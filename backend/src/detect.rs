@@ -46,11 +46,14 @@ use platforms::windows::KeyKind;
 
 #[cfg(debug_assertions)]
 use crate::debug::{debug_mat, debug_spinning_arrows};
-use crate::{array::Array, buff::BuffKind, mat::OwnedMat};
+use crate::{BuffsRegion, array::Array, buff::BuffKind, mat::OwnedMat};
 
 const MAX_ARROWS: usize = 4;
 const MAX_SPIN_ARROWS: usize = 2; // PRAY
 
+/// Maximum number of runes detected on the minimap.
+pub const MAX_RUNES_COUNT: usize = 4;
+
 /// Struct for storing information about the spinning arrows
 #[derive(Debug, Copy, Clone)]
 struct SpinArrow {
@@ -128,6 +131,12 @@ pub trait Detector: 'static + Send + DynClone + Debug {
     /// Detects the ESC confirm button.
     fn detect_esc_confirm_button(&self) -> Result<Rect>;
 
+    /// Detects the ESC ok button.
+    fn detect_esc_ok_button(&self) -> Result<Rect>;
+
+    /// Detects the ESC cancel button.
+    fn detect_esc_cancel_button(&self) -> Result<Rect>;
+
     /// Detects the Tomb ok button.
     fn detect_tomb_ok_button(&self) -> Result<Rect>;
 
@@ -145,21 +154,28 @@ pub trait Detector: 'static + Send + DynClone + Debug {
     /// Returns `Rect` relative to `minimap` coordinate.
     fn detect_minimap_portals(&self, minimap: Rect) -> Vec<Rect>;
 
-    /// Detects the rune from the given `minimap` rectangle.
+    /// Detects the runes from the given `minimap` rectangle.
     ///
-    /// Returns `Rect` relative to `minimap` coordinate.
-    fn detect_minimap_rune(&self, minimap: Rect) -> Result<Rect>;
+    /// Returns `Rect`s relative to `minimap` coordinate or error if there is no rune.
+    fn detect_minimap_runes(&self, minimap: Rect) -> Result<Vec<Rect>>;
 
     /// Detects the player in the provided `minimap` rectangle.
     ///
     /// Returns `Rect` relative to `minimap` coordinate.
     fn detect_player(&self, minimap: Rect) -> Result<Rect>;
 
-    /// Detects whether a player of `kind` is in the minimap.
-    fn detect_player_kind(&self, minimap: Rect, kind: OtherPlayerKind) -> bool;
+    /// Detects the number of players of `kind` in the minimap.
+    fn detect_player_kind(&self, minimap: Rect, kind: OtherPlayerKind) -> usize;
 
     /// Detects whether the player is dead.
-    fn detect_player_is_dead(&self) -> bool;
+    ///
+    /// The additional `templates` are checked alongside the built-in tomb template.
+    fn detect_player_is_dead(&self, templates: &[Mat]) -> bool;
+
+    /// Detects the lootable objects (e.g. bags, chests) matching any of the `templates`.
+    ///
+    /// Returns a list of objects coordinate relative to minimap coordinate.
+    fn detect_loot_objects(&self, minimap: Rect, player: Point, templates: &[Mat]) -> Vec<Point>;
 
     /// Detects whether the player is in cash shop.
     fn detect_player_in_cash_shop(&self) -> bool;
@@ -173,8 +189,8 @@ pub trait Detector: 'static + Send + DynClone + Debug {
     /// Detects the player current health and max health.
     fn detect_player_health(&self, current_bar: Rect, max_bar: Rect) -> Result<(u32, u32)>;
 
-    /// Detects whether the player has a buff specified by `kind`.
-    fn detect_player_buff(&self, kind: BuffKind) -> bool;
+    /// Detects whether the player has a buff specified by `kind` inside the buffs `region`.
+    fn detect_player_buff(&self, kind: BuffKind, region: BuffsRegion) -> bool;
 
     /// Detects arrows from the given RGBA `Mat` image.
     ///
@@ -233,19 +249,27 @@ mock! {
         fn detect_mobs(&self, minimap: Rect, bound: Rect, player: Point) -> Result<Vec<Point>>;
         fn detect_esc_settings(&self) -> bool;
         fn detect_esc_confirm_button(&self) -> Result<Rect>;
+        fn detect_esc_ok_button(&self) -> Result<Rect>;
+        fn detect_esc_cancel_button(&self) -> Result<Rect>;
         fn detect_tomb_ok_button(&self) -> Result<Rect>;
         fn detect_elite_boss_bar(&self) -> bool;
         fn detect_minimap(&self, border_threshold: u8) -> Result<Rect>;
         fn detect_minimap_portals(&self, minimap: Rect) -> Vec<Rect>;
-        fn detect_minimap_rune(&self, minimap: Rect) -> Result<Rect>;
+        fn detect_minimap_runes(&self, minimap: Rect) -> Result<Vec<Rect>>;
         fn detect_player(&self, minimap: Rect) -> Result<Rect>;
-        fn detect_player_kind(&self, minimap: Rect, kind: OtherPlayerKind) -> bool;
-        fn detect_player_is_dead(&self) -> bool;
+        fn detect_player_kind(&self, minimap: Rect, kind: OtherPlayerKind) -> usize;
+        fn detect_player_is_dead(&self, templates: &[Mat]) -> bool;
+        fn detect_loot_objects(
+            &self,
+            minimap: Rect,
+            player: Point,
+            templates: &[Mat],
+        ) -> Vec<Point>;
         fn detect_player_in_cash_shop(&self) -> bool;
         fn detect_player_health_bar(&self) -> Result<Rect>;
         fn detect_player_current_max_health_bars(&self, health_bar: Rect) -> Result<(Rect, Rect)>;
         fn detect_player_health(&self, current_bar: Rect, max_bar: Rect) -> Result<(u32, u32)>;
-        fn detect_player_buff(&self, kind: BuffKind) -> bool;
+        fn detect_player_buff(&self, kind: BuffKind, region: BuffsRegion) -> bool;
         fn detect_rune_arrows<'a>(
             &self,
             calibrating: ArrowsCalibrating,
@@ -298,7 +322,7 @@ impl CachedDetector {
         })));
         let buffs_grayscale = grayscale.clone();
         let buffs_grayscale = Arc::new(LazyLock::<Mat, MatFn>::new(Box::new(move || {
-            crop_to_buffs_region(&**buffs_grayscale).clone_pointee()
+            crop_to_buffs_region(&**buffs_grayscale, BuffsRegion::default()).clone_pointee()
         })));
         Self {
             mat,
@@ -325,6 +349,14 @@ impl Detector for CachedDetector {
         detect_esc_confirm_button(&**self.grayscale)
     }
 
+    fn detect_esc_ok_button(&self) -> Result<Rect> {
+        detect_esc_ok_button(&**self.grayscale)
+    }
+
+    fn detect_esc_cancel_button(&self) -> Result<Rect> {
+        detect_esc_cancel_button(&**self.grayscale)
+    }
+
     fn detect_tomb_ok_button(&self) -> Result<Rect> {
         detect_tomb_ok_button(&**self.grayscale)
     }
@@ -342,9 +374,9 @@ impl Detector for CachedDetector {
         detect_minimap_portals(minimap_color)
     }
 
-    fn detect_minimap_rune(&self, minimap: Rect) -> Result<Rect> {
+    fn detect_minimap_runes(&self, minimap: Rect) -> Result<Vec<Rect>> {
         let minimap_color = to_bgr(&self.mat.roi(minimap)?);
-        detect_minimap_rune(&minimap_color)
+        detect_minimap_runes(&minimap_color)
     }
 
     fn detect_player(&self, minimap: Rect) -> Result<Rect> {
@@ -352,13 +384,17 @@ impl Detector for CachedDetector {
         detect_player(&minimap_color)
     }
 
-    fn detect_player_kind(&self, minimap: Rect, kind: OtherPlayerKind) -> bool {
+    fn detect_player_kind(&self, minimap: Rect, kind: OtherPlayerKind) -> usize {
         let minimap_color = to_bgr(&self.mat.roi(minimap).unwrap());
         detect_player_kind(&minimap_color, kind)
     }
 
-    fn detect_player_is_dead(&self) -> bool {
-        detect_player_is_dead(&**self.grayscale)
+    fn detect_player_is_dead(&self, templates: &[Mat]) -> bool {
+        detect_player_is_dead(&**self.grayscale, templates)
+    }
+
+    fn detect_loot_objects(&self, minimap: Rect, player: Point, templates: &[Mat]) -> Vec<Point> {
+        detect_loot_objects(&**self.grayscale, minimap, player, templates)
     }
 
     fn detect_player_in_cash_shop(&self) -> bool {
@@ -377,14 +413,20 @@ impl Detector for CachedDetector {
         detect_player_health(&*self.mat, current_bar, max_bar)
     }
 
-    fn detect_player_buff(&self, kind: BuffKind) -> bool {
+    fn detect_player_buff(&self, kind: BuffKind, region: BuffsRegion) -> bool {
         let mat = match kind {
             BuffKind::Rune
             | BuffKind::Familiar
             | BuffKind::SayramElixir
             | BuffKind::AureliaElixir
             | BuffKind::ExpCouponX3
-            | BuffKind::BonusExpCoupon => &**self.buffs_grayscale,
+            | BuffKind::BonusExpCoupon => {
+                if region == BuffsRegion::default() {
+                    &**self.buffs_grayscale
+                } else {
+                    &crop_to_buffs_region(&**self.grayscale, region).clone_pointee()
+                }
+            }
             BuffKind::LegionWealth
             | BuffKind::LegionLuck
             | BuffKind::WealthAcquisitionPotion
@@ -392,7 +434,7 @@ impl Detector for CachedDetector {
             | BuffKind::ExtremeRedPotion
             | BuffKind::ExtremeBluePotion
             | BuffKind::ExtremeGreenPotion
-            | BuffKind::ExtremeGoldPotion => &to_bgr(&crop_to_buffs_region(&*self.mat)),
+            | BuffKind::ExtremeGoldPotion => &to_bgr(&crop_to_buffs_region(&*self.mat, region)),
         };
         detect_player_buff(mat, kind)
     }
@@ -454,13 +496,91 @@ impl Detector for CachedDetector {
     }
 }
 
-fn crop_to_buffs_region(mat: &impl MatTraitConst) -> BoxedRef<'_, Mat> {
-    let size = mat.size().unwrap();
-    // crop to top right of the image for buffs region
-    let crop_x = size.width / 3;
-    let crop_y = size.height / 4;
-    let crop_bbox = Rect::new(size.width - crop_x, 0, crop_x, crop_y);
-    mat.roi(crop_bbox).unwrap()
+fn crop_to_buffs_region(mat: &impl MatTraitConst, region: BuffsRegion) -> BoxedRef<'_, Mat> {
+    mat.roi(buffs_region_bbox(mat.size().unwrap(), region))
+        .unwrap()
+}
+
+/// Computes the buffs region bounding box for an image of `size`.
+///
+/// The default region is the top right of the image and is then shifted and scaled by `region`.
+/// The returned bounding box is always inside the image.
+pub fn buffs_region_bbox(size: Size, region: BuffsRegion) -> Rect {
+    let width = (((size.width / 3) as f32 * region.scale) as i32).clamp(1, size.width);
+    let height = (((size.height / 4) as f32 * region.scale) as i32).clamp(1, size.height);
+    let x = (size.width - width - region.offset_x).clamp(0, size.width - width);
+    let y = region.offset_y.clamp(0, size.height - height);
+    Rect::new(x, y, width, height)
+}
+
+/// Approximates the mob coordinate on screen to mob coordinate on minimap.
+///
+/// This function tries to approximate the delta (dx, dy) that the player needs to move
+/// in relative to the minimap coordinate in order to reach the mob. Returns the mob
+/// coordinate on the minimap by adding the delta to the player position.
+///
+/// Note: It is not that accurate but that is that and this is this. Hey it seems better than
+/// the previous alchemy.
+#[inline]
+fn to_minimap_coordinate(
+    mob_bbox: Rect,
+    minimap_bbox: Rect,
+    mobbing_bound: Rect,
+    player: Point,
+    mat_size: Size,
+) -> Option<Point> {
+    // These numbers are for scaling dx/dy on the screen to dx/dy on the minimap.
+    // They are approximated in 1280x720 resolution by going from one point to another point
+    // from the middle of the screen with both points visible on screen before traveling. Take
+    // the distance traveled on the minimap and divide it by half of the resolution
+    // (e.g. tralveled minimap x / 640). Whether it is correct or not, time will tell.
+    const X_SCALE: f32 = 0.059_375;
+    const Y_SCALE: f32 = 0.036_111;
+
+    // The main idea is to calculate the offset of the detected mob from the middle of screen
+    // and use that distance as dx/dy to move the player. This assumes the player will
+    // most of the time be near or very close to the middle of the screen. This is already
+    // not accurate in the sense that the camera will have a bit of lag before
+    // it is centered again on the player. And when the player is near edges of the map,
+    // this function is just plain wrong. For better accuracy, detecting where the player is
+    // on the screen and use that as the basis is required.
+    let x_screen_mid = mat_size.width / 2;
+    let x_mob_mid = mob_bbox.x + mob_bbox.width / 2;
+    let x_screen_delta = x_screen_mid - x_mob_mid;
+    let x_minimap_delta = (x_screen_delta as f32 * X_SCALE) as i32;
+
+    // For dy, if the whole mob bounding box is above the screen mid point, then the
+    // box top edge is used to increase the dy distance as to help the player move up. The same
+    // goes for moving down. If the bounding box overlaps with the screen mid point, the box
+    // mid point is used as to to help the player stay in place.
+    let y_screen_mid = mat_size.height / 2;
+    let y_mob = if mob_bbox.y + mob_bbox.height < y_screen_mid {
+        mob_bbox.y
+    } else if mob_bbox.y > y_screen_mid {
+        mob_bbox.y + mob_bbox.height
+    } else {
+        mob_bbox.y + mob_bbox.height / 2
+    };
+    let y_screen_delta = y_screen_mid - y_mob;
+    let y_minimap_delta = (y_screen_delta as f32 * Y_SCALE) as i32;
+
+    let point_x = if x_minimap_delta > 0 {
+        (player.x - x_minimap_delta).max(0)
+    } else {
+        (player.x - x_minimap_delta).min(minimap_bbox.width)
+    };
+    let point_y = (player.y + y_minimap_delta).max(0).min(minimap_bbox.height);
+    // Minus the y by minimap height to make it relative to the minimap top edge
+    let point = Point::new(point_x, minimap_bbox.height - point_y);
+    if point.x < mobbing_bound.x
+        || point.x > mobbing_bound.x + mobbing_bound.width
+        || point.y < mobbing_bound.y
+        || point.y > mobbing_bound.y + mobbing_bound.height
+    {
+        None
+    } else {
+        Some(point)
+    }
 }
 
 fn detect_mobs(
@@ -476,76 +596,6 @@ fn detect_mobs(
         )
     });
 
-    /// Approximates the mob coordinate on screen to mob coordinate on minimap.
-    ///
-    /// This function tries to approximate the delta (dx, dy) that the player needs to move
-    /// in relative to the minimap coordinate in order to reach the mob. Returns the mob
-    /// coordinate on the minimap by adding the delta to the player position.
-    ///
-    /// Note: It is not that accurate but that is that and this is this. Hey it seems better than
-    /// the previous alchemy.
-    #[inline]
-    fn to_minimap_coordinate(
-        mob_bbox: Rect,
-        minimap_bbox: Rect,
-        mobbing_bound: Rect,
-        player: Point,
-        mat_size: Size,
-    ) -> Option<Point> {
-        // These numbers are for scaling dx/dy on the screen to dx/dy on the minimap.
-        // They are approximated in 1280x720 resolution by going from one point to another point
-        // from the middle of the screen with both points visible on screen before traveling. Take
-        // the distance traveled on the minimap and divide it by half of the resolution
-        // (e.g. tralveled minimap x / 640). Whether it is correct or not, time will tell.
-        const X_SCALE: f32 = 0.059_375;
-        const Y_SCALE: f32 = 0.036_111;
-
-        // The main idea is to calculate the offset of the detected mob from the middle of screen
-        // and use that distance as dx/dy to move the player. This assumes the player will
-        // most of the time be near or very close to the middle of the screen. This is already
-        // not accurate in the sense that the camera will have a bit of lag before
-        // it is centered again on the player. And when the player is near edges of the map,
-        // this function is just plain wrong. For better accuracy, detecting where the player is
-        // on the screen and use that as the basis is required.
-        let x_screen_mid = mat_size.width / 2;
-        let x_mob_mid = mob_bbox.x + mob_bbox.width / 2;
-        let x_screen_delta = x_screen_mid - x_mob_mid;
-        let x_minimap_delta = (x_screen_delta as f32 * X_SCALE) as i32;
-
-        // For dy, if the whole mob bounding box is above the screen mid point, then the
-        // box top edge is used to increase the dy distance as to help the player move up. The same
-        // goes for moving down. If the bounding box overlaps with the screen mid point, the box
-        // mid point is used as to to help the player stay in place.
-        let y_screen_mid = mat_size.height / 2;
-        let y_mob = if mob_bbox.y + mob_bbox.height < y_screen_mid {
-            mob_bbox.y
-        } else if mob_bbox.y > y_screen_mid {
-            mob_bbox.y + mob_bbox.height
-        } else {
-            mob_bbox.y + mob_bbox.height / 2
-        };
-        let y_screen_delta = y_screen_mid - y_mob;
-        let y_minimap_delta = (y_screen_delta as f32 * Y_SCALE) as i32;
-
-        let point_x = if x_minimap_delta > 0 {
-            (player.x - x_minimap_delta).max(0)
-        } else {
-            (player.x - x_minimap_delta).min(minimap_bbox.width)
-        };
-        let point_y = (player.y + y_minimap_delta).max(0).min(minimap_bbox.height);
-        // Minus the y by minimap height to make it relative to the minimap top edge
-        let point = Point::new(point_x, minimap_bbox.height - point_y);
-        if point.x < mobbing_bound.x
-            || point.x > mobbing_bound.x + mobbing_bound.width
-            || point.y < mobbing_bound.y
-            || point.y > mobbing_bound.y + mobbing_bound.height
-        {
-            None
-        } else {
-            Some(point)
-        }
-    }
-
     let size = mat.size().unwrap();
     let (mat_in, w_ratio, h_ratio, left, top) = preprocess_for_yolo(mat);
     let mut model = MOB_MODEL.lock().unwrap();
@@ -619,6 +669,15 @@ fn detect_esc_confirm_button(mat: &impl ToInputArray) -> Result<Rect> {
     detect_template(mat, &ESC_SETTINGS[6], Point::default(), 0.75)
 }
 
+fn detect_esc_ok_button(mat: &impl ToInputArray) -> Result<Rect> {
+    detect_template(mat, &ESC_SETTINGS[5], Point::default(), 0.75)
+}
+
+fn detect_esc_cancel_button(mat: &impl ToInputArray) -> Result<Rect> {
+    detect_template(mat, &ESC_SETTINGS[7], Point::default(), 0.75)
+        .or_else(|_| detect_template(mat, &ESC_SETTINGS[8], Point::default(), 0.75))
+}
+
 fn detect_tomb_ok_button(mat: &impl ToInputArray) -> Result<Rect> {
     static TEMPLATE: LazyLock<Mat> = LazyLock::new(|| {
         imgcodecs::imdecode(
@@ -819,7 +878,7 @@ fn detect_minimap_portals<T: MatTraitConst + ToInputArray>(minimap: T) -> Vec<Re
         .collect::<Vec<_>>()
 }
 
-fn detect_minimap_rune(minimap: &impl ToInputArray) -> Result<Rect> {
+fn detect_minimap_runes(minimap: &impl ToInputArray) -> Result<Vec<Rect>> {
     /// TODO: Support default ratio
     static TEMPLATE: LazyLock<Mat> = LazyLock::new(|| {
         imgcodecs::imdecode(include_bytes!(env!("RUNE_TEMPLATE")), IMREAD_COLOR).unwrap()
@@ -830,8 +889,22 @@ fn detect_minimap_rune(minimap: &impl ToInputArray) -> Result<Rect> {
 
     // Expands by 2 pixels to preserve previous position calculation. Previous template is 11x11
     // while the current template is 9x9
-    detect_template_single(minimap, &*TEMPLATE, &*TEMPLATE_MASK, Point::default(), 0.75)
-        .map(|(rect, _)| Rect::new(rect.x - 1, rect.y - 1, rect.width + 2, rect.height + 2))
+    let runes = detect_template_multiple(
+        minimap,
+        &*TEMPLATE,
+        &*TEMPLATE_MASK,
+        Point::default(),
+        MAX_RUNES_COUNT,
+        0.75,
+    )
+    .into_iter()
+    .filter_map(|result| result.ok())
+    .map(|(rect, _)| Rect::new(rect.x - 1, rect.y - 1, rect.width + 2, rect.height + 2))
+    .collect::<Vec<_>>();
+    if runes.is_empty() {
+        bail!("rune not found");
+    }
+    Ok(runes)
 }
 
 fn detect_player(mat: &impl ToInputArray) -> Result<Rect> {
@@ -846,7 +919,10 @@ fn detect_player(mat: &impl ToInputArray) -> Result<Rect> {
         .map(|(rect, _)| Rect::new(rect.x - 1, rect.y - 1, rect.width + 2, rect.height + 2))
 }
 
-fn detect_player_kind(mat: &impl ToInputArray, kind: OtherPlayerKind) -> bool {
+fn detect_player_kind(mat: &impl ToInputArray, kind: OtherPlayerKind) -> usize {
+    /// The maximum number of players of a kind to count.
+    const MAX_PLAYERS_COUNT: usize = 10;
+
     /// TODO: Support default ratio
     static STRANGER_TEMPLATE: LazyLock<Mat> = LazyLock::new(|| {
         imgcodecs::imdecode(
@@ -866,26 +942,70 @@ fn detect_player_kind(mat: &impl ToInputArray, kind: OtherPlayerKind) -> bool {
         imgcodecs::imdecode(include_bytes!(env!("PLAYER_FRIEND_TEMPLATE")), IMREAD_COLOR).unwrap()
     });
 
-    match kind {
-        OtherPlayerKind::Stranger => {
-            detect_template(mat, &*STRANGER_TEMPLATE, Point::default(), 0.85).is_ok()
-        }
-        OtherPlayerKind::Guildie => {
-            detect_template(mat, &*GUILDIE_TEMPLATE, Point::default(), 0.85).is_ok()
-        }
-        OtherPlayerKind::Friend => {
-            detect_template(mat, &*FRIEND_TEMPLATE, Point::default(), 0.85).is_ok()
-        }
-    }
+    let template = match kind {
+        OtherPlayerKind::Stranger => &*STRANGER_TEMPLATE,
+        OtherPlayerKind::Guildie => &*GUILDIE_TEMPLATE,
+        OtherPlayerKind::Friend => &*FRIEND_TEMPLATE,
+    };
+    detect_template_multiple(
+        mat,
+        template,
+        no_array(),
+        Point::default(),
+        MAX_PLAYERS_COUNT,
+        0.85,
+    )
+    .into_iter()
+    .filter(|result| result.is_ok())
+    .count()
+}
+
+/// Loads the grayscale templates from image files at `paths`.
+///
+/// Paths that fail to load are skipped.
+pub fn load_grayscale_templates(paths: &[String]) -> Vec<Mat> {
+    paths
+        .iter()
+        .map(|path| path.trim())
+        .filter(|path| !path.is_empty())
+        .filter_map(|path| match imgcodecs::imread(path, IMREAD_GRAYSCALE) {
+            Ok(mat) if !mat.empty() => Some(mat),
+            _ => {
+                error!(target: "detect", "failed to load template {path}");
+                None
+            }
+        })
+        .collect()
 }
 
-fn detect_player_is_dead(mat: &impl ToInputArray) -> bool {
+fn detect_loot_objects(
+    mat: &(impl MatTraitConst + ToInputArray),
+    minimap: Rect,
+    player: Point,
+    templates: &[Mat],
+) -> Vec<Point> {
+    let size = mat.size().unwrap();
+    let bound = Rect::new(0, 0, minimap.width, minimap.height);
+    templates
+        .iter()
+        .flat_map(|template| {
+            detect_template_multiple(mat, template, no_array(), Point::default(), 8, 0.8)
+        })
+        .filter_map(|result| result.ok())
+        .filter_map(|(bbox, _)| to_minimap_coordinate(bbox, minimap, bound, player, size))
+        .collect()
+}
+
+fn detect_player_is_dead(mat: &impl ToInputArray, templates: &[Mat]) -> bool {
     /// TODO: Support default ratio
     static TEMPLATE: LazyLock<Mat> = LazyLock::new(|| {
         imgcodecs::imdecode(include_bytes!(env!("TOMB_TEMPLATE")), IMREAD_GRAYSCALE).unwrap()
     });
 
     detect_template(mat, &*TEMPLATE, Point::default(), 0.8).is_ok()
+        || templates
+            .iter()
+            .any(|template| detect_template(mat, template, Point::default(), 0.8).is_ok())
 }
 
 fn detect_player_in_cash_shop(mat: &impl ToInputArray) -> bool {
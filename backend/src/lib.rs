@@ -39,11 +39,13 @@ pub use {
     context::init,
     database::{
         Action, ActionCondition, ActionConfiguration, ActionKey, ActionKeyDirection, ActionKeyWith,
-        ActionMove, AutoMobbing, Bound, CaptureMode, Class, Configuration, FamiliarRarity,
-        Familiars, InputMethod, KeyBinding, KeyBindingConfiguration, LinkKeyBinding, Minimap,
-        Notifications, PanicMode, PingPong, Platform, Position, PotionMode, RotationMode, Settings,
-        SwappableFamiliars, delete_map, query_configs, query_maps, query_settings, upsert_config,
-        upsert_map, upsert_settings,
+        ActionMove, AutoMobbing, Bound, BuffKind, BuffsRegion, CaptureMode,
+        ChangeChannelBlockedFallback, Class, Configuration, EmptyMapAction, FamiliarRarity,
+        Familiars, HoldSpot, InputMethod, KeyBinding, KeyBindingConfiguration, LinkKeyBinding,
+        LostPositionRecovery, Minimap, NoSwappableCardsOutcome, Notifications, PanicMode, PingPong,
+        Platform, Position, PotionMode, RotationMode, RuneApproachSide, RuneArrowPressStyle,
+        Settings, SwappableFamiliars, delete_map, query_configs, query_maps, query_settings,
+        upsert_config, upsert_map, upsert_settings,
     },
     pathing::MAX_PLATFORMS_COUNT,
     rotator::RotatorMode,
@@ -85,6 +87,7 @@ enum Request {
     RotateActionsHalting,
     CreateMinimap(String),
     UpdateMinimap(Option<String>, Minimap),
+    UpdateAutoMobBound(Bound),
     UpdateConfiguration(Configuration),
     UpdateSettings(Settings),
     RedetectMinimap,
@@ -103,7 +106,11 @@ enum Request {
     #[cfg(debug_assertions)]
     RecordImages(bool),
     #[cfg(debug_assertions)]
+    RecordMacro(bool),
+    #[cfg(debug_assertions)]
     TestSpinRune,
+    #[cfg(debug_assertions)]
+    ViewBuffsRegion,
 }
 
 /// Represents response to UI [`Request`]
@@ -116,6 +123,7 @@ enum Response {
     RotateActionsHalting(bool),
     CreateMinimap(Option<Minimap>),
     UpdateMinimap,
+    UpdateAutoMobBound,
     UpdateConfiguration,
     UpdateSettings,
     RedetectMinimap,
@@ -134,7 +142,11 @@ enum Response {
     #[cfg(debug_assertions)]
     RecordImages,
     #[cfg(debug_assertions)]
+    RecordMacro(Option<Vec<Action>>),
+    #[cfg(debug_assertions)]
     TestSpinRune,
+    #[cfg(debug_assertions)]
+    ViewBuffsRegion,
 }
 
 pub(crate) trait RequestHandler {
@@ -146,6 +158,8 @@ pub(crate) trait RequestHandler {
 
     fn on_update_minimap(&mut self, preset: Option<String>, minimap: Minimap);
 
+    fn on_update_auto_mob_bound(&mut self, bound: Bound);
+
     fn on_update_configuration(&mut self, config: Configuration);
 
     fn on_update_settings(&mut self, settings: Settings);
@@ -176,8 +190,14 @@ pub(crate) trait RequestHandler {
     #[cfg(debug_assertions)]
     fn on_record_images(&mut self, start: bool);
 
+    #[cfg(debug_assertions)]
+    fn on_record_macro(&mut self, start: bool) -> Option<Vec<Action>>;
+
     #[cfg(debug_assertions)]
     fn on_test_spin_rune(&self);
+
+    #[cfg(debug_assertions)]
+    fn on_view_buffs_region(&self);
 }
 
 #[derive(Debug, Clone)]
@@ -189,6 +209,11 @@ pub struct GameState {
     pub priority_action: Option<String>,
     pub erda_shower_state: String,
     pub destinations: Vec<(i32, i32)>,
+    pub input_connected: bool,
+    /// The id of the map currently used by the backend.
+    pub minimap_id: Option<i64>,
+    /// The action preset of the map currently used by the backend.
+    pub minimap_preset: Option<String>,
 }
 
 pub async fn rotate_actions(halting: bool) {
@@ -219,6 +244,13 @@ pub async fn update_minimap(preset: Option<String>, minimap: Minimap) {
     )
 }
 
+pub async fn update_auto_mob_bound(bound: Bound) {
+    expect_unit_variant!(
+        request(Request::UpdateAutoMobBound(bound)).await,
+        Response::UpdateAutoMobBound
+    )
+}
+
 pub async fn update_configuration(config: Configuration) {
     expect_unit_variant!(
         request(Request::UpdateConfiguration(config)).await,
@@ -300,11 +332,30 @@ pub async fn record_images(start: bool) {
     )
 }
 
+/// Starts or stops recording the user key inputs as a macro.
+///
+/// When stopping, returns the recorded keys as a chain of linked [`Action::Key`]s.
+#[cfg(debug_assertions)]
+pub async fn record_macro(start: bool) -> Option<Vec<Action>> {
+    expect_value_variant!(
+        request(Request::RecordMacro(start)).await,
+        Response::RecordMacro
+    )
+}
+
 #[cfg(debug_assertions)]
 pub async fn test_spin_rune() {
     expect_unit_variant!(request(Request::TestSpinRune).await, Response::TestSpinRune)
 }
 
+#[cfg(debug_assertions)]
+pub async fn view_buffs_region() {
+    expect_unit_variant!(
+        request(Request::ViewBuffsRegion).await,
+        Response::ViewBuffsRegion
+    )
+}
+
 pub(crate) fn poll_request(handler: &mut dyn RequestHandler) {
     if let Ok((request, sender)) = LazyLock::force(&REQUESTS).1.lock().unwrap().try_recv() {
         let result = match request {
@@ -322,6 +373,10 @@ pub(crate) fn poll_request(handler: &mut dyn RequestHandler) {
                 handler.on_update_minimap(preset, minimap);
                 Response::UpdateMinimap
             }
+            Request::UpdateAutoMobBound(bound) => {
+                handler.on_update_auto_mob_bound(bound);
+                Response::UpdateAutoMobBound
+            }
             Request::UpdateConfiguration(config) => {
                 handler.on_update_configuration(config);
                 Response::UpdateConfiguration
@@ -368,10 +423,17 @@ pub(crate) fn poll_request(handler: &mut dyn RequestHandler) {
                 Response::RecordImages
             }
             #[cfg(debug_assertions)]
+            Request::RecordMacro(start) => Response::RecordMacro(handler.on_record_macro(start)),
+            #[cfg(debug_assertions)]
             Request::TestSpinRune => {
                 handler.on_test_spin_rune();
                 Response::TestSpinRune
             }
+            #[cfg(debug_assertions)]
+            Request::ViewBuffsRegion => {
+                handler.on_view_buffs_region();
+                Response::ViewBuffsRegion
+            }
         };
         let _ = sender.send(result);
     }
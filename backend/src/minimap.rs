@@ -5,10 +5,11 @@ use log::debug;
 use opencv::core::{MatTraitConst, Point, Rect, Vec4b};
 
 use crate::{
+    RotationMode,
     array::Array,
     context::{Context, Contextual, ControlFlow},
     database::Minimap as MinimapData,
-    detect::{Detector, OtherPlayerKind},
+    detect::{Detector, MAX_RUNES_COUNT, OtherPlayerKind},
     network::NotificationKind,
     pathing::{
         MAX_PLATFORMS_COUNT, Platform, PlatformWithNeighbors, find_neighbors, find_platforms_bound,
@@ -17,19 +18,47 @@ use crate::{
     task::{Task, Update, update_detection_task},
 };
 
-const MINIMAP_BORDER_WHITENESS_THRESHOLD: u8 = 160;
+/// The default whiteness threshold of the minimap border used for detecting the minimap.
+pub const MINIMAP_BORDER_WHITENESS_THRESHOLD: u8 = 160;
+
+/// The default minimum milliseconds between each elite boss detection.
+pub const ELITE_BOSS_DETECTION_MILLIS: u64 = 10000;
+
+/// The default number of consecutive rune detections at the same position required before the
+/// rune is considered present.
+pub const RUNE_STABLE_DETECTION_COUNT: u32 = 2;
+
+/// The milliseconds between each rune detection while a rune candidate is not yet stable.
+const RUNE_CANDIDATE_DETECTION_MILLIS: u64 = 500;
+
+/// The maximum x or y distance between two detections for the rune to be at the same position.
+///
+/// The rune icon can shift slightly when it overlaps with the player icon.
+const RUNE_STABLE_POSITION_TOLERANCE: i32 = 2;
 
 #[derive(Debug, Default)]
 pub struct MinimapState {
     data: Option<MinimapData>,
+    /// The currently selected action preset of [`Self::data`].
+    preset: Option<String>,
     minimap_task: Option<Task<Result<(Anchors, Rect)>>>,
-    rune_task: Option<Task<Result<Point>>>,
+    runes_task: Option<Task<Result<Array<Point, MAX_RUNES_COUNT>>>>,
     portals_task: Option<Task<Result<Vec<Rect>>>>,
     has_elite_boss_task: Option<Task<Result<bool>>>,
-    has_guildie_player_task: Option<Task<Result<bool>>>,
-    has_stranger_player_task: Option<Task<Result<bool>>>,
-    has_friend_player_task: Option<Task<Result<bool>>>,
+    guildie_player_count_task: Option<Task<Result<usize>>>,
+    stranger_player_count_task: Option<Task<Result<usize>>>,
+    friend_player_count_task: Option<Task<Result<usize>>>,
     update_platforms: bool,
+    /// The minimap border whiteness threshold overriding [`MINIMAP_BORDER_WHITENESS_THRESHOLD`].
+    border_whiteness_threshold: Option<u8>,
+    /// The elite boss detection interval overriding [`ELITE_BOSS_DETECTION_MILLIS`].
+    elite_boss_detection_millis: Option<u64>,
+    /// The rune stable detection count overriding [`RUNE_STABLE_DETECTION_COUNT`].
+    rune_stable_detection_count: Option<u32>,
+    /// The previously remembered minimap bounding box to try before a full detection.
+    ///
+    /// It is consumed on the first [`Minimap::Detecting`] update.
+    seed_bbox: Option<Rect>,
 }
 
 impl MinimapState {
@@ -37,10 +66,92 @@ impl MinimapState {
         self.data.as_ref()
     }
 
-    pub fn set_data(&mut self, data: MinimapData) {
+    pub fn data_mut(&mut self) -> Option<&mut MinimapData> {
+        self.data.as_mut()
+    }
+
+    pub fn set_data(&mut self, data: MinimapData, preset: Option<String>) {
         self.data = Some(data);
+        self.preset = preset;
         self.update_platforms = true;
     }
+
+    /// Gets the [`RotationMode`] of the current data and preset.
+    pub fn rotation_mode(&self) -> Option<RotationMode> {
+        self.data
+            .as_ref()
+            .map(|data| data.rotation_mode_of(self.preset.as_deref()))
+    }
+
+    /// Gets the currently selected action preset.
+    pub fn preset(&self) -> Option<&str> {
+        self.preset.as_deref()
+    }
+
+    /// Gets the preset to switch to when toggling between auto-mobbing and other rotation modes.
+    ///
+    /// If the current preset uses [`RotationMode::AutoMobbing`], returns the first preset by name
+    /// that does not and vice versa. Returns [`None`] if there is no such preset.
+    pub fn toggle_mode_preset(&self) -> Option<String> {
+        let data = self.data.as_ref()?;
+        let is_auto_mobbing = |preset: Option<&str>| {
+            matches!(data.rotation_mode_of(preset), RotationMode::AutoMobbing(_))
+        };
+        let current = self.preset.as_deref();
+        let current_auto_mobbing = is_auto_mobbing(current);
+
+        data.actions
+            .keys()
+            .filter(|preset| {
+                Some(preset.as_str()) != current
+                    && is_auto_mobbing(Some(preset)) != current_auto_mobbing
+            })
+            .min()
+            .cloned()
+    }
+
+    /// Gets the mutable [`RotationMode`] of the current data and preset.
+    pub fn rotation_mode_mut(&mut self) -> Option<&mut RotationMode> {
+        let preset = self.preset.as_deref();
+        self.data
+            .as_mut()
+            .map(|data| data.rotation_mode_of_mut(preset))
+    }
+
+    /// Gets the whiteness threshold of the minimap border used for detecting the minimap.
+    pub fn border_whiteness_threshold(&self) -> u8 {
+        self.border_whiteness_threshold
+            .unwrap_or(MINIMAP_BORDER_WHITENESS_THRESHOLD)
+    }
+
+    pub fn set_border_whiteness_threshold(&mut self, threshold: u8) {
+        self.border_whiteness_threshold = Some(threshold);
+    }
+
+    /// Gets the minimum milliseconds between each elite boss detection.
+    pub fn elite_boss_detection_millis(&self) -> u64 {
+        self.elite_boss_detection_millis
+            .unwrap_or(ELITE_BOSS_DETECTION_MILLIS)
+    }
+
+    pub fn set_elite_boss_detection_millis(&mut self, millis: u64) {
+        self.elite_boss_detection_millis = Some(millis);
+    }
+
+    /// Gets the number of consecutive rune detections at the same position required before the
+    /// rune is considered present.
+    pub fn rune_stable_detection_count(&self) -> u32 {
+        self.rune_stable_detection_count
+            .unwrap_or(RUNE_STABLE_DETECTION_COUNT)
+    }
+
+    pub fn set_rune_stable_detection_count(&mut self, count: u32) {
+        self.rune_stable_detection_count = Some(count);
+    }
+
+    pub fn set_seed_bbox(&mut self, bbox: Option<Rect>) {
+        self.seed_bbox = bbox;
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -75,6 +186,24 @@ impl<T> Threshold<T> {
     pub fn value(&self) -> Option<&T> {
         self.value.as_ref()
     }
+
+    #[inline]
+    fn update_ok(&mut self, value: T) {
+        self.value = Some(value);
+        self.fail_count = 0;
+    }
+
+    #[inline]
+    fn update_err(&mut self) {
+        if self.value.is_some() {
+            if self.fail_count >= self.max_fail_count {
+                self.value = None;
+                self.fail_count = 0;
+            } else {
+                self.fail_count += 1;
+            }
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -91,18 +220,24 @@ pub struct MinimapIdle {
     ///
     /// It is partially overlapped by other UIs if one of the anchor mismatches.
     pub partially_overlapping: bool,
-    /// The rune position.
-    pub rune: Threshold<Point>,
+    /// The rune positions.
+    pub runes: Threshold<Array<Point, MAX_RUNES_COUNT>>,
+    /// The detected rune positions not yet stable and the number of consecutive detections.
+    ///
+    /// The rune icon can flicker or shift when it overlaps with the player icon, so the positions
+    /// only replace [`Self::runes`] after being detected at the same place for
+    /// [`MinimapState::rune_stable_detection_count`] times.
+    rune_candidate: Option<(Array<Point, MAX_RUNES_COUNT>, u32)>,
     /// Whether there is an elite boss.
     ///
     /// This does not belong to minimap though...
     pub has_elite_boss: bool,
-    /// Whether there is a guildie.
-    has_guildie_player: Threshold<bool>,
-    /// Whether there is a stranger.
-    has_stranger_player: Threshold<bool>,
-    /// Whether there is a friend.
-    has_friend_player: Threshold<bool>,
+    /// The number of guildies.
+    guildie_player_count: Threshold<usize>,
+    /// The number of strangers.
+    stranger_player_count: Threshold<usize>,
+    /// The number of friends.
+    friend_player_count: Threshold<usize>,
     /// The portal positions.
     ///
     /// Praying each night that there won't be more than 16 portals...
@@ -115,10 +250,66 @@ pub struct MinimapIdle {
 }
 
 impl MinimapIdle {
-    pub fn has_any_other_player(&self) -> bool {
-        self.has_guildie_player.value.unwrap_or_default()
-            || self.has_stranger_player.value.unwrap_or_default()
-            || self.has_friend_player.value.unwrap_or_default()
+    /// Whether there is any rune on the minimap.
+    #[inline]
+    pub fn has_rune(&self) -> bool {
+        self.runes.value().is_some_and(|runes| !runes.is_empty())
+    }
+
+    /// Retrieves the runes sorted by the distance from `pos` in ascending order.
+    pub fn runes_nearest_to(&self, pos: Point) -> Vec<Point> {
+        let mut runes = self
+            .runes
+            .value()
+            .copied()
+            .map(|runes| runes.into_iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+        runes.sort_by_key(|rune| (rune.x - pos.x).pow(2) + (rune.y - pos.y).pow(2));
+        runes
+    }
+
+    /// Retrieves the number of other players of `kind`.
+    pub fn other_player_count(&self, kind: OtherPlayerKind) -> usize {
+        let count = match kind {
+            OtherPlayerKind::Guildie => self.guildie_player_count,
+            OtherPlayerKind::Stranger => self.stranger_player_count,
+            OtherPlayerKind::Friend => self.friend_player_count,
+        };
+        count.value.unwrap_or_default()
+    }
+
+    #[cfg(test)]
+    pub fn set_other_player_count(&mut self, kind: OtherPlayerKind, count: usize) {
+        match kind {
+            OtherPlayerKind::Guildie => self.guildie_player_count.set_value(count),
+            OtherPlayerKind::Stranger => self.stranger_player_count.set_value(count),
+            OtherPlayerKind::Friend => self.friend_player_count.set_value(count),
+        }
+    }
+
+    /// Whether there is any other player of the kinds allowed by `filter`.
+    pub fn has_other_player(&self, filter: OtherPlayerFilter) -> bool {
+        (filter.guildie && self.other_player_count(OtherPlayerKind::Guildie) > 0)
+            || (filter.stranger && self.other_player_count(OtherPlayerKind::Stranger) > 0)
+            || (filter.friend && self.other_player_count(OtherPlayerKind::Friend) > 0)
+    }
+}
+
+/// The kinds of other players to consider when checking for their presence.
+#[derive(Clone, Copy, Debug)]
+pub struct OtherPlayerFilter {
+    pub guildie: bool,
+    pub stranger: bool,
+    pub friend: bool,
+}
+
+impl Default for OtherPlayerFilter {
+    fn default() -> Self {
+        Self {
+            guildie: true,
+            stranger: true,
+            friend: true,
+        }
     }
 }
 
@@ -148,9 +339,24 @@ fn update_context(contextual: Minimap, context: &Context, state: &mut MinimapSta
 }
 
 fn update_detecting_context(context: &Context, state: &mut MinimapState) -> Minimap {
+    if let Some(bbox) = state.seed_bbox.take() {
+        // Validates the remembered bounding box by finding its anchors, falls back to a full
+        // detection if the minimap is no longer there
+        let mat = context.detector_unwrap().mat();
+        let size = bbox.width.min(bbox.height).max(0) as usize;
+        if let Ok(tl) = anchor_at(mat, bbox.tl(), size, 1)
+            && let Ok(br) = anchor_at(mat, bbox.br(), size, -1)
+        {
+            debug!(target: "minimap", "using remembered minimap bbox {bbox:?}");
+            return minimap_idle(state, Anchors { tl, br }, bbox);
+        }
+        debug!(target: "minimap", "remembered minimap bbox {bbox:?} is invalid");
+    }
+
+    let threshold = state.border_whiteness_threshold();
     let Update::Ok((anchors, bbox)) =
         update_detection_task(context, 2000, &mut state.minimap_task, move |detector| {
-            let bbox = detector.detect_minimap(MINIMAP_BORDER_WHITENESS_THRESHOLD)?;
+            let bbox = detector.detect_minimap(threshold)?;
             let size = bbox.width.min(bbox.height) as usize;
             let tl = anchor_at(detector.mat(), bbox.tl(), size, 1)?;
             let br = anchor_at(detector.mat(), bbox.br(), size, -1)?;
@@ -162,24 +368,30 @@ fn update_detecting_context(context: &Context, state: &mut MinimapState) -> Mini
         return Minimap::Detecting;
     };
 
+    minimap_idle(state, anchors, bbox)
+}
+
+#[inline]
+fn minimap_idle(state: &mut MinimapState, anchors: Anchors, bbox: Rect) -> Minimap {
     let (platforms, platforms_bound) = state
         .data
         .as_ref()
         .map(|data| platforms_from_data(bbox, data))
         .unwrap_or_default();
     state.update_platforms = false;
-    state.rune_task = None;
+    state.runes_task = None;
     state.has_elite_boss_task = None;
 
     Minimap::Idle(MinimapIdle {
         anchors,
         bbox,
         partially_overlapping: false,
-        rune: Threshold::new(3),
+        runes: Threshold::new(3),
+        rune_candidate: None,
         has_elite_boss: false,
-        has_guildie_player: Threshold::new(2),
-        has_stranger_player: Threshold::new(2),
-        has_friend_player: Threshold::new(2),
+        guildie_player_count: Threshold::new(2),
+        stranger_player_count: Threshold::new(2),
+        friend_player_count: Threshold::new(2),
         portals: Array::new(),
         platforms,
         platforms_bound,
@@ -198,11 +410,12 @@ fn update_idle_context(
     let MinimapIdle {
         anchors,
         bbox,
-        rune,
+        runes,
+        rune_candidate,
         has_elite_boss,
-        has_guildie_player,
-        has_stranger_player,
-        has_friend_player,
+        guildie_player_count,
+        stranger_player_count,
+        friend_player_count,
         portals,
         mut platforms,
         mut platforms_bound,
@@ -223,28 +436,39 @@ fn update_idle_context(
     }
 
     let partially_overlapping = (tl_match && !br_match) || (!tl_match && br_match);
-    let rune = update_rune_task(context, &mut state.rune_task, bbox, rune);
-    let has_elite_boss =
-        update_elite_boss_task(context, &mut state.has_elite_boss_task, has_elite_boss);
-    let has_guildie_player = update_other_player_task(
+    let (runes, rune_candidate) = update_runes_task(
+        context,
+        &mut state.runes_task,
+        bbox,
+        runes,
+        rune_candidate,
+        state.rune_stable_detection_count(),
+    );
+    let has_elite_boss = update_elite_boss_task(
+        context,
+        &mut state.has_elite_boss_task,
+        state.elite_boss_detection_millis(),
+        has_elite_boss,
+    );
+    let guildie_player_count = update_other_player_task(
         context,
-        &mut state.has_guildie_player_task,
+        &mut state.guildie_player_count_task,
         bbox,
-        has_guildie_player,
+        guildie_player_count,
         OtherPlayerKind::Guildie,
     );
-    let has_stranger_player = update_other_player_task(
+    let stranger_player_count = update_other_player_task(
         context,
-        &mut state.has_stranger_player_task,
+        &mut state.stranger_player_count_task,
         bbox,
-        has_stranger_player,
+        stranger_player_count,
         OtherPlayerKind::Stranger,
     );
-    let has_friend_player = update_other_player_task(
+    let friend_player_count = update_other_player_task(
         context,
-        &mut state.has_friend_player_task,
+        &mut state.friend_player_count_task,
         bbox,
-        has_friend_player,
+        friend_player_count,
         OtherPlayerKind::Friend,
     );
     let portals = update_portals_task(context, &mut state.portals_task, portals, bbox);
@@ -260,11 +484,12 @@ fn update_idle_context(
 
     Some(Minimap::Idle(MinimapIdle {
         partially_overlapping,
-        rune,
+        runes,
+        rune_candidate,
         has_elite_boss,
-        has_guildie_player,
-        has_stranger_player,
-        has_friend_player,
+        guildie_player_count,
+        stranger_player_count,
+        friend_player_count,
         portals,
         platforms,
         platforms_bound,
@@ -283,38 +508,91 @@ fn anchor_match(anchor: Vec4b, pixel: Vec4b) -> bool {
     avg <= ANCHOR_ACCEPTABLE_ERROR_RANGE
 }
 
+/// Updates the rune detection task.
+///
+/// Newly detected or moved runes are kept as the candidate until they are detected at the same
+/// positions for `stable_count` consecutive times, and are re-detected more frequently meanwhile.
 #[inline]
-fn update_rune_task(
+fn update_runes_task(
     context: &Context,
-    task: &mut Option<Task<Result<Point>>>,
+    task: &mut Option<Task<Result<Array<Point, MAX_RUNES_COUNT>>>>,
     minimap: Rect,
-    rune: Threshold<Point>,
-) -> Threshold<Point> {
-    let was_none = rune.value.is_none();
+    mut runes: Threshold<Array<Point, MAX_RUNES_COUNT>>,
+    mut candidate: Option<(Array<Point, MAX_RUNES_COUNT>, u32)>,
+    stable_count: u32,
+) -> (
+    Threshold<Array<Point, MAX_RUNES_COUNT>>,
+    Option<(Array<Point, MAX_RUNES_COUNT>, u32)>,
+) {
+    let was_none = runes.value.is_none();
     if matches!(context.player, Player::SolvingRune(_)) && !was_none {
-        return rune;
+        return (runes, candidate);
     }
 
-    let rune = update_threshold_detection(context, 10000, rune, task, move |detector| {
-        detector
-            .detect_minimap_rune(minimap)
-            .map(|rune| center_of_bbox(rune, minimap))
+    let repeat_delay_millis = if candidate.is_some() {
+        RUNE_CANDIDATE_DETECTION_MILLIS
+    } else {
+        10000
+    };
+    let update = update_detection_task(context, repeat_delay_millis, task, move |detector| {
+        detector.detect_minimap_runes(minimap).map(|runes| {
+            Array::from_iter(runes.into_iter().map(|rune| center_of_bbox(rune, minimap)))
+        })
     });
-    if was_none && rune.value.is_some() && !context.halting {
+    match update {
+        Update::Ok(detected) => {
+            let count = match candidate {
+                Some((positions, count)) if is_same_runes_positions(&positions, &detected) => {
+                    count + 1
+                }
+                _ => 1,
+            };
+            let unchanged = runes
+                .value
+                .as_ref()
+                .is_some_and(|positions| is_same_runes_positions(positions, &detected));
+            if unchanged || count >= stable_count {
+                runes.update_ok(detected);
+                candidate = None;
+            } else {
+                debug!(target: "minimap", "rune candidate {detected:?} detected {count} time(s)");
+                candidate = Some((detected, count));
+            }
+        }
+        Update::Err(_) => {
+            runes.update_err();
+            candidate = None;
+        }
+        Update::Pending => (),
+    }
+    if was_none && runes.value.is_some() && !context.halting {
         let _ = context
             .notification
             .schedule_notification(NotificationKind::RuneAppear);
     }
-    rune
+    (runes, candidate)
+}
+
+#[inline]
+fn is_same_runes_positions(
+    positions: &Array<Point, MAX_RUNES_COUNT>,
+    other: &Array<Point, MAX_RUNES_COUNT>,
+) -> bool {
+    positions.len() == other.len()
+        && positions.iter().zip(other.iter()).all(|(pos, other)| {
+            (pos.x - other.x).abs() <= RUNE_STABLE_POSITION_TOLERANCE
+                && (pos.y - other.y).abs() <= RUNE_STABLE_POSITION_TOLERANCE
+        })
 }
 
 #[inline]
 fn update_elite_boss_task(
     context: &Context,
     task: &mut Option<Task<Result<bool>>>,
+    repeat_delay_millis: u64,
     has_elite_boss: bool,
 ) -> bool {
-    let update = update_detection_task(context, 10000, task, move |detector| {
+    let update = update_detection_task(context, repeat_delay_millis, task, move |detector| {
         Ok(detector.detect_elite_boss_bar())
     });
     match update {
@@ -334,16 +612,16 @@ fn update_elite_boss_task(
 #[inline]
 fn update_other_player_task(
     context: &Context,
-    task: &mut Option<Task<Result<bool>>>,
+    task: &mut Option<Task<Result<usize>>>,
     minimap: Rect,
-    threshold: Threshold<bool>,
+    threshold: Threshold<usize>,
     kind: OtherPlayerKind,
-) -> Threshold<bool> {
-    let has_player = threshold.value.unwrap_or_default();
+) -> Threshold<usize> {
+    let has_player = threshold.value.unwrap_or_default() > 0;
     let threshold = update_threshold_detection(context, 2000, threshold, task, move |detector| {
         Ok(detector.detect_player_kind(minimap, kind))
     });
-    if !context.halting && !has_player && threshold.value.unwrap_or_default() {
+    if !context.halting && !has_player && threshold.value.unwrap_or_default() > 0 {
         let notification = match kind {
             OtherPlayerKind::Guildie => NotificationKind::PlayerGuildieAppear,
             OtherPlayerKind::Stranger => NotificationKind::PlayerStrangerAppear,
@@ -418,20 +696,8 @@ where
     );
 
     match update {
-        Update::Ok(value) => {
-            threshold.value = Some(value);
-            threshold.fail_count = 0;
-        }
-        Update::Err(_) => {
-            if threshold.value.is_some() {
-                if threshold.fail_count >= threshold.max_fail_count {
-                    threshold.value = None;
-                    threshold.fail_count = 0;
-                } else {
-                    threshold.fail_count += 1;
-                }
-            }
-        }
+        Update::Ok(value) => threshold.update_ok(value),
+        Update::Err(_) => threshold.update_err(),
         Update::Pending => (),
     }
 
@@ -485,7 +751,57 @@ mod tests {
     use tokio::time;
 
     use super::*;
-    use crate::detect::MockDetector;
+    use crate::{AutoMobbing, PingPong, detect::MockDetector};
+
+    #[test]
+    fn minimap_state_rotation_mode_of_preset() {
+        let mut data = MinimapData {
+            rotation_mode: RotationMode::StartToEnd,
+            ..MinimapData::default()
+        };
+        data.preset_rotation_modes.insert(
+            "ping pong".to_string(),
+            RotationMode::PingPong(PingPong::default()),
+        );
+        let mut state = MinimapState::default();
+
+        state.set_data(data.clone(), Some("route".to_string()));
+        assert_matches!(state.rotation_mode(), Some(RotationMode::StartToEnd));
+
+        state.set_data(data.clone(), Some("ping pong".to_string()));
+        assert_matches!(state.rotation_mode(), Some(RotationMode::PingPong(_)));
+
+        state.set_data(data, Some("route".to_string()));
+        *state.rotation_mode_mut().unwrap() = RotationMode::ShuffledCycle;
+        assert_matches!(state.rotation_mode(), Some(RotationMode::ShuffledCycle));
+        assert_matches!(
+            state.data().unwrap().rotation_mode,
+            RotationMode::StartToEnd
+        );
+    }
+
+    #[test]
+    fn minimap_state_toggle_mode_preset() {
+        let mut data = MinimapData::default();
+        for preset in ["route b", "route a", "mob"] {
+            data.actions.insert(preset.to_string(), vec![]);
+        }
+        data.preset_rotation_modes.insert(
+            "mob".to_string(),
+            RotationMode::AutoMobbing(AutoMobbing::default()),
+        );
+        let mut state = MinimapState::default();
+
+        state.set_data(data.clone(), Some("route b".to_string()));
+        assert_eq!(state.toggle_mode_preset(), Some("mob".to_string()));
+
+        state.set_data(data.clone(), Some("mob".to_string()));
+        assert_eq!(state.toggle_mode_preset(), Some("route a".to_string()));
+
+        data.actions.remove("mob");
+        state.set_data(data, Some("route a".to_string()));
+        assert_eq!(state.toggle_mode_preset(), None);
+    }
 
     fn create_test_mat() -> (Mat, Anchors) {
         let mut mat = Mat::zeros(100, 100, opencv::core::CV_8UC4)
@@ -512,9 +828,9 @@ mod tests {
         let bbox = Rect::new(0, 0, 100, 100);
         let rune_bbox = Rect::new(40, 40, 20, 20);
         detector
-            .expect_detect_minimap_rune()
+            .expect_detect_minimap_runes()
             .withf(move |b| *b == bbox)
-            .returning(move |_| Ok(rune_bbox));
+            .returning(move |_| Ok(vec![rune_bbox]));
         detector
             .expect_clone()
             .returning(|| create_mock_detector().0);
@@ -534,7 +850,7 @@ mod tests {
         let context = Context::new(None, Some(detector));
         let completed = |state: &MinimapState| {
             if matches!(contextual, Minimap::Idle(_)) {
-                state.rune_task.as_ref().unwrap().completed()
+                state.runes_task.as_ref().unwrap().completed()
             } else {
                 state.minimap_task.as_ref().unwrap().completed()
             }
@@ -547,6 +863,44 @@ mod tests {
         minimap
     }
 
+    #[test]
+    fn minimap_detecting_to_idle_with_seed_bbox() {
+        let mut state = MinimapState::default();
+        let (mat, anchors) = create_test_mat();
+        let bbox = Rect::new(0, 0, 100, 100);
+        let mut detector = MockDetector::new();
+        detector.expect_detect_minimap().never();
+        detector.expect_mat().return_const(mat.into());
+        let context = Context::new(None, Some(detector));
+        state.set_seed_bbox(Some(bbox));
+
+        let minimap = update_context(Minimap::Detecting, &context, &mut state);
+        assert_matches!(minimap, Minimap::Idle(_));
+        match minimap {
+            Minimap::Idle(idle) => {
+                assert_eq!(idle.anchors, anchors);
+                assert_eq!(idle.bbox, bbox);
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(state.seed_bbox, None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn minimap_detecting_to_idle_with_invalid_seed_bbox() {
+        let mut state = MinimapState::default();
+        let (detector, bbox, _, _) = create_mock_detector();
+        state.set_seed_bbox(Some(Rect::new(0, 0, 5, 5)));
+
+        let minimap = advance_task(Minimap::Detecting, detector, &mut state).await;
+        assert_matches!(minimap, Minimap::Idle(_));
+        match minimap {
+            Minimap::Idle(idle) => assert_eq!(idle.bbox, bbox),
+            _ => unreachable!(),
+        }
+        assert_eq!(state.seed_bbox, None);
+    }
+
     #[tokio::test(start_paused = true)]
     async fn minimap_detecting_to_idle() {
         let mut state = MinimapState::default();
@@ -560,27 +914,47 @@ mod tests {
                 assert_eq!(idle.bbox, bbox);
                 assert!(!idle.partially_overlapping);
                 assert_eq!(state.data, None);
-                assert_eq!(idle.rune.value, None);
+                assert_eq!(idle.runes.value, None);
                 assert!(!idle.has_elite_boss);
             }
             _ => unreachable!(),
         }
     }
 
+    #[test]
+    fn minimap_idle_runes_nearest_to() {
+        let mut idle = MinimapIdle::default();
+        assert!(!idle.has_rune());
+        assert!(idle.runes_nearest_to(Point::default()).is_empty());
+
+        idle.runes.set_value(Array::from_iter([
+            Point::new(50, 10),
+            Point::new(5, 5),
+            Point::new(20, 0),
+        ]));
+        assert!(idle.has_rune());
+        assert_eq!(
+            idle.runes_nearest_to(Point::new(0, 0)),
+            vec![Point::new(5, 5), Point::new(20, 0), Point::new(50, 10)]
+        );
+    }
+
     #[tokio::test(start_paused = true)]
     async fn minimap_idle_rune_detection() {
         let mut state = MinimapState::default();
+        state.set_rune_stable_detection_count(1);
         let (detector, bbox, anchors, rune_bbox) = create_mock_detector();
 
         let idle = MinimapIdle {
             anchors,
             bbox,
             partially_overlapping: false,
-            rune: Threshold::new(3),
+            runes: Threshold::new(3),
+            rune_candidate: None,
             has_elite_boss: false,
-            has_guildie_player: Threshold::default(),
-            has_stranger_player: Threshold::default(),
-            has_friend_player: Threshold::default(),
+            guildie_player_count: Threshold::default(),
+            stranger_player_count: Threshold::default(),
+            friend_player_count: Threshold::default(),
             portals: Array::new(),
             platforms: Array::new(),
             platforms_bound: None,
@@ -590,9 +964,60 @@ mod tests {
         assert_matches!(minimap, Minimap::Idle(_));
         match minimap {
             Minimap::Idle(idle) => {
-                assert_eq!(idle.rune.value, Some(center_of_bbox(rune_bbox, bbox)));
+                assert_eq!(
+                    idle.runes.value,
+                    Some(Array::from_iter([center_of_bbox(rune_bbox, bbox)]))
+                );
             }
             _ => unreachable!(),
         }
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn minimap_idle_rune_detection_requires_stable_positions() {
+        let mut state = MinimapState::default();
+        let (detector, bbox, anchors, rune_bbox) = create_mock_detector();
+        let rune = center_of_bbox(rune_bbox, bbox);
+        let idle = MinimapIdle {
+            anchors,
+            bbox,
+            runes: Threshold::new(3),
+            ..MinimapIdle::default()
+        };
+
+        // (1) First detection is kept as candidate
+        let minimap = advance_task(Minimap::Idle(idle), detector, &mut state).await;
+        let Minimap::Idle(idle) = minimap else {
+            unreachable!()
+        };
+        assert_eq!(idle.runes.value, None);
+        assert_eq!(idle.rune_candidate, Some((Array::from_iter([rune]), 1)));
+
+        // (2) Second detection at the same position is accepted
+        let (detector, _, _, _) = create_mock_detector();
+        let minimap = advance_task(Minimap::Idle(idle), detector, &mut state).await;
+        let Minimap::Idle(idle) = minimap else {
+            unreachable!()
+        };
+        assert_eq!(idle.runes.value, Some(Array::from_iter([rune])));
+        assert_eq!(idle.rune_candidate, None);
+    }
+
+    #[test]
+    fn minimap_is_same_runes_positions() {
+        let positions = Array::from_iter([Point::new(10, 10)]);
+
+        assert!(is_same_runes_positions(
+            &positions,
+            &Array::from_iter([Point::new(12, 8)])
+        ));
+        assert!(!is_same_runes_positions(
+            &positions,
+            &Array::from_iter([Point::new(13, 10)])
+        ));
+        assert!(!is_same_runes_positions(
+            &positions,
+            &Array::from_iter([Point::new(10, 10), Point::new(50, 10)])
+        ));
+    }
 }
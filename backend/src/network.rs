@@ -1,5 +1,6 @@
 use std::{
     cell::RefCell,
+    collections::HashMap,
     mem,
     ops::{Index, Not},
     rc::Rc,
@@ -20,12 +21,12 @@ use tokio::{
     time::{Instant, sleep},
 };
 
-use crate::Settings;
+use crate::{NoSwappableCardsOutcome, Settings};
 
 static TRUE: bool = true;
 static FALSE: bool = false;
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 #[repr(usize)]
 pub enum NotificationKind {
     FailOrMapChange,
@@ -35,6 +36,16 @@ pub enum NotificationKind {
     PlayerStrangerAppear,
     PlayerFriendAppear,
     PlayerIsDead,
+    RuneAbandoned,
+    CaptureHandleReselected,
+    PanicToTownFailed,
+    InputDisconnected,
+    CaptureInvalid,
+    LostPositionRecoveryFailed,
+    Stuck,
+    RuneSolved,
+    ChannelChangeLimit,
+    FamiliarsNoSwappableCards,
 }
 
 impl From<NotificationKind> for usize {
@@ -80,6 +91,12 @@ pub struct DiscordNotification {
     ///
     /// There can only be one unique [`NotificationKind`] scheduled at a time.
     pending: Arc<Mutex<BitVec>>,
+    /// Storing the last scheduled instant and the number of suppressed notifications since then
+    /// for each [`NotificationKind`]
+    ///
+    /// Used to collapse identical notifications within
+    /// [`crate::Notifications::notification_cooldown_millis`].
+    last_scheduled: RefCell<HashMap<NotificationKind, (Instant, u32)>>,
 }
 
 impl DiscordNotification {
@@ -92,6 +109,7 @@ impl DiscordNotification {
                 mem::variant_count::<NotificationKind>(),
                 false,
             ))),
+            last_scheduled: RefCell::new(HashMap::new()),
         }
     }
 
@@ -113,6 +131,31 @@ impl DiscordNotification {
             NotificationKind::PlayerFriendAppear => {
                 settings.notifications.notify_on_player_friend_appear
             }
+            NotificationKind::RuneAbandoned => settings.notifications.notify_on_rune_abandoned,
+            NotificationKind::CaptureHandleReselected => {
+                settings.notifications.notify_on_capture_handle_reselected
+            }
+            NotificationKind::PanicToTownFailed => {
+                settings.notifications.notify_on_panic_to_town_failed
+            }
+            NotificationKind::InputDisconnected => {
+                settings.notifications.notify_on_input_disconnected
+            }
+            NotificationKind::CaptureInvalid => settings.notifications.notify_on_capture_invalid,
+            NotificationKind::LostPositionRecoveryFailed => {
+                settings
+                    .notifications
+                    .notify_on_lost_position_recovery_failed
+            }
+            NotificationKind::Stuck => settings.notifications.notify_on_stuck,
+            NotificationKind::RuneSolved => settings.notifications.notify_on_rune_solved,
+            NotificationKind::ChannelChangeLimit => {
+                settings.notifications.notify_on_channel_change_limit
+            }
+            NotificationKind::FamiliarsNoSwappableCards => matches!(
+                settings.familiars.no_swappable_cards_outcome,
+                NoSwappableCardsOutcome::Notify
+            ),
         };
         if !is_enabled {
             bail!("notification not enabled");
@@ -131,6 +174,17 @@ impl DiscordNotification {
             bail!("failed to parse webhook url");
         }
 
+        let cooldown = Duration::from_millis(settings.notifications.notification_cooldown_millis);
+        let mut last_scheduled = self.last_scheduled.borrow_mut();
+        let suppressed_count = match last_scheduled.get_mut(&kind) {
+            Some((instant, count)) if instant.elapsed() < cooldown => {
+                *count += 1;
+                bail!("notification is on cooldown");
+            }
+            Some((_, count)) => mem::take(count),
+            None => 0,
+        };
+
         let user_id = settings
             .notifications
             .discord_user_id
@@ -166,6 +220,41 @@ impl DiscordNotification {
             NotificationKind::PlayerFriendAppear => {
                 format!("{user_id}Bot has detected friend player(s)")
             }
+            NotificationKind::RuneAbandoned => {
+                format!("{user_id}Bot has given up solving rune after failing to detect arrows")
+            }
+            NotificationKind::CaptureHandleReselected => {
+                format!("{user_id}Bot has re-selected the capture window after it was closed")
+            }
+            NotificationKind::PanicToTownFailed => {
+                format!("{user_id}Bot has failed to go to town using the Maple Guide")
+            }
+            NotificationKind::InputDisconnected => {
+                format!("{user_id}Bot stopped because the input server has been disconnected")
+            }
+            NotificationKind::CaptureInvalid => {
+                format!("{user_id}Bot has paused because the captured frame is blank")
+            }
+            NotificationKind::LostPositionRecoveryFailed => {
+                format!("{user_id}Bot failed to recover the lost player position on the minimap")
+            }
+            NotificationKind::Stuck => {
+                format!("{user_id}Bot stopped because the player has not made any progress")
+            }
+            NotificationKind::RuneSolved => {
+                format!("{user_id}Bot has solved a rune")
+            }
+            NotificationKind::ChannelChangeLimit => {
+                format!("{user_id}Bot stopped because it has changed channel too many times")
+            }
+            NotificationKind::FamiliarsNoSwappableCards => {
+                format!("{user_id}Bot could not find any swappable familiar card")
+            }
+        };
+        let content = if suppressed_count > 0 {
+            format!("{content} (repeated {suppressed_count} more time(s) since last notification)")
+        } else {
+            content
         };
         let body = DiscordWebhookBody {
             content,
@@ -179,6 +268,16 @@ impl DiscordNotification {
             | NotificationKind::PlayerGuildieAppear
             | NotificationKind::PlayerStrangerAppear
             | NotificationKind::PlayerFriendAppear
+            | NotificationKind::RuneAbandoned
+            | NotificationKind::CaptureHandleReselected
+            | NotificationKind::PanicToTownFailed
+            | NotificationKind::InputDisconnected
+            | NotificationKind::CaptureInvalid
+            | NotificationKind::LostPositionRecoveryFailed
+            | NotificationKind::Stuck
+            | NotificationKind::RuneSolved
+            | NotificationKind::ChannelChangeLimit
+            | NotificationKind::FamiliarsNoSwappableCards
             | NotificationKind::RuneAppear => vec![(None, 2)],
         };
         let delay = match kind {
@@ -188,6 +287,16 @@ impl DiscordNotification {
             | NotificationKind::PlayerGuildieAppear
             | NotificationKind::PlayerStrangerAppear
             | NotificationKind::PlayerFriendAppear
+            | NotificationKind::RuneAbandoned
+            | NotificationKind::CaptureHandleReselected
+            | NotificationKind::PanicToTownFailed
+            | NotificationKind::InputDisconnected
+            | NotificationKind::CaptureInvalid
+            | NotificationKind::LostPositionRecoveryFailed
+            | NotificationKind::Stuck
+            | NotificationKind::RuneSolved
+            | NotificationKind::ChannelChangeLimit
+            | NotificationKind::FamiliarsNoSwappableCards
             | NotificationKind::RuneAppear => 3,
         };
 
@@ -200,6 +309,7 @@ impl DiscordNotification {
             body,
         });
         pending.set(kind.into(), true);
+        last_scheduled.insert(kind, (Instant::now(), 0));
 
         let client = self.client.clone();
         let pending = self.pending.clone();
@@ -328,6 +438,47 @@ mod test {
     use super::{DiscordNotification, DiscordWebhookBody, NotificationKind, ScheduledNotification};
     use crate::{Notifications, Settings};
 
+    #[tokio::test(start_paused = true)]
+    async fn schedule_kind_cooldown() {
+        let noti = DiscordNotification::new(Rc::new(RefCell::new(Settings {
+            notifications: Notifications {
+                discord_webhook_url: "https://discord.com/api/webhooks/foo/bar".to_string(),
+                notify_on_rune_appear: true,
+                notification_cooldown_millis: 10000,
+                ..Default::default()
+            },
+            ..Default::default()
+        })));
+        noti.last_scheduled
+            .borrow_mut()
+            .insert(NotificationKind::RuneAppear, (Instant::now(), 0));
+
+        // Suppressed within cooldown
+        assert!(
+            noti.schedule_notification(NotificationKind::RuneAppear)
+                .is_err()
+        );
+        assert!(noti.scheduled.lock().unwrap().is_empty());
+
+        // Scheduled with the suppressed count after cooldown
+        advance(Duration::from_secs(11)).await;
+        assert!(
+            noti.schedule_notification(NotificationKind::RuneAppear)
+                .is_ok()
+        );
+        let scheduled = noti.scheduled.lock().unwrap();
+        assert!(
+            scheduled[0]
+                .body
+                .content
+                .contains("repeated 1 more time(s)")
+        );
+        assert_eq!(
+            noti.last_scheduled.borrow()[&NotificationKind::RuneAppear].1,
+            0
+        );
+    }
+
     #[tokio::test(start_paused = true)]
     async fn schedule_kind_unique() {
         let noti = DiscordNotification::new(Rc::new(RefCell::new(Settings {
@@ -6,7 +6,7 @@ use std::{
 
 use opencv::core::{Point, Rect};
 
-use crate::array::Array;
+use crate::{array::Array, rng::Rng};
 
 pub const MAX_PLATFORMS_COUNT: usize = 24;
 
@@ -18,6 +18,8 @@ pub enum MovementHint {
     Infer,
     /// Performs a walk and then jump.
     WalkAndJump,
+    /// Walks off the platform edge and then double jumps while falling.
+    WalkOffAndDoubleJump,
 }
 
 /// A platform where player can stand on.
@@ -137,11 +139,16 @@ pub fn find_neighbors(
 /// `vertical_threshold` represents maximum y distance between two connected platforms to perform
 /// a grappling. This is used as weight score to help prioritize vertical movement over
 /// horizontal movement. If `enable_hint` is true, provides movement hints like `WalkAndJump`.
+/// If `enable_walk_off_hint` is true, provides `WalkOffAndDoubleJump` hint for crossing wide gaps
+/// to a lower platform.
+#[allow(clippy::too_many_arguments)]
 pub fn find_points_with(
+    rng: &Rng,
     platforms: &Array<PlatformWithNeighbors, MAX_PLATFORMS_COUNT>,
     from: Point,
     to: Point,
     enable_hint: bool,
+    enable_walk_off_hint: bool,
     double_jump_threshold: i32,
     jump_threshold: i32,
     vertical_threshold: i32,
@@ -167,12 +174,14 @@ pub fn find_points_with(
         let current_score = score.get(&current.platform).copied().unwrap_or(u32::MAX);
         if current.platform == to_platform {
             return points_from(
+                rng,
                 &came_from,
                 from,
                 from_platform,
                 to_platform,
                 to,
                 enable_hint,
+                enable_walk_off_hint,
                 double_jump_threshold,
                 jump_threshold,
             );
@@ -210,12 +219,14 @@ pub fn find_points_with(
 /// Adds offsets to handle jump and landing safety margins.
 #[allow(clippy::too_many_arguments)]
 fn points_from(
+    rng: &Rng,
     came_from: &HashMap<Platform, Platform>,
     from: Point,
     from_platform: Platform,
     to_platform: Platform,
     to: Point,
     enable_hint: bool,
+    enable_walk_off_hint: bool,
     double_jump_threshold: i32,
     jump_threshold: i32,
 ) -> Option<Vec<(Point, MovementHint)>> {
@@ -258,8 +269,7 @@ fn points_from(
                 }
             } else {
                 // Outside intersection range, add 2 points to move inside and then up or down
-                // TODO: Replace rand with Rng
-                let x = rand::random_range(start_max..end_min);
+                let x = rng.random_range(start_max..end_min);
                 points.push((Point::new(x, current.y), MovementHint::Infer));
                 points.push((Point::new(x, next.y), MovementHint::Infer));
             }
@@ -274,13 +284,21 @@ fn points_from(
             // Ignore initial point as it has the same platform as the current
             let can_double_jump_last_point = can_double_jump_last_point && points.len() > 1;
 
+            let gap = start_max - end_min - 1;
             // Check if the two platforms are close enough to just do a walk and jump
             let (offset, hint) = if enable_hint
                 && !can_double_jump_last_point
-                && start_max - end_min - 1 < WALK_AND_JUMP_THRESHOLD
+                && gap < WALK_AND_JUMP_THRESHOLD
                 && (current.y - next.y).abs() < jump_threshold
             {
                 (JUMP_OFFSET, MovementHint::WalkAndJump)
+            } else if enable_walk_off_hint
+                && !can_double_jump_last_point
+                && gap >= WALK_AND_JUMP_THRESHOLD
+                && current.y - next.y >= jump_threshold
+            {
+                // Wide gap to a lower platform, walks off the edge and double jumps mid-air
+                (JUMP_OFFSET, MovementHint::WalkOffAndDoubleJump)
             } else {
                 (double_jump_offset, MovementHint::Infer)
             };
@@ -384,6 +402,7 @@ mod tests {
     use crate::{
         array::Array,
         pathing::{find_points_with, ranges_overlap},
+        rng::Rng,
     };
 
     fn make_platforms_with_neighbors(
@@ -419,7 +438,8 @@ mod tests {
         let from = Point::new(10, 50);
         let to = Point::new(20, 60);
 
-        let points = find_points_with(&platforms, from, to, true, 25, 7, 41).unwrap();
+        let rng = Rng::new([0; 32]);
+        let points = find_points_with(&rng, &platforms, from, to, true, false, 25, 7, 41).unwrap();
 
         let expected = vec![
             (Point::new(10, 60), MovementHint::Infer),
@@ -440,7 +460,8 @@ mod tests {
         let from = Point::new(25, 50);
         let to = Point::new(65, 55);
 
-        let points = find_points_with(&platforms, from, to, true, 25, 7, 41).unwrap();
+        let rng = Rng::new([0; 32]);
+        let points = find_points_with(&rng, &platforms, from, to, true, false, 25, 7, 41).unwrap();
 
         assert_eq!(points.first().unwrap().0.y, 50);
         assert_eq!(points.last().unwrap().0.y, 55);
@@ -459,7 +480,8 @@ mod tests {
         let from = Point::new(10, 50);
         let to = Point::new(20, 131);
 
-        let points = find_points_with(&platforms, from, to, true, 25, 7, 41).unwrap();
+        let rng = Rng::new([0; 32]);
+        let points = find_points_with(&rng, &platforms, from, to, true, false, 25, 7, 41).unwrap();
 
         // Check that y-values ascend (multi-hop upward movement)
         let ys: Vec<_> = points.iter().map(|(p, _)| p.y).collect();
@@ -483,7 +505,8 @@ mod tests {
         let from = Point::new(25, 50);
         let to = Point::new(125, 55);
 
-        let points = find_points_with(&platforms, from, to, true, 25, 7, 41);
+        let rng = Rng::new([0; 32]);
+        let points = find_points_with(&rng, &platforms, from, to, true, false, 25, 7, 41);
         assert!(points.is_none());
     }
 
@@ -498,7 +521,8 @@ mod tests {
         let from = Point::new(45, 50); // Near right edge of first platform
         let to = Point::new(60, 52); // Near left edge of second platform
 
-        let points = find_points_with(&platforms, from, to, true, 25, 7, 41).unwrap();
+        let rng = Rng::new([0; 32]);
+        let points = find_points_with(&rng, &platforms, from, to, true, false, 25, 7, 41).unwrap();
 
         let has_walk_and_jump = points
             .iter()
@@ -511,4 +535,33 @@ mod tests {
         assert_eq!(points.first().unwrap().0.y, 50);
         assert_eq!(points.last().unwrap().0.y, 52);
     }
+
+    #[test]
+    fn find_points_with_walk_off_and_double_jump_hint() {
+        let platforms = [
+            Platform::new(0..50, 60),
+            Platform::new(70..120, 40), // 19 units of horizontal gap and 20 units lower
+        ];
+        let platforms = make_platforms_with_neighbors(&platforms);
+
+        let from = Point::new(25, 60);
+        let to = Point::new(100, 40);
+
+        let rng = Rng::new([0; 32]);
+        let points = find_points_with(&rng, &platforms, from, to, true, true, 25, 7, 41).unwrap();
+        let expected = vec![
+            (Point::new(47, 60), MovementHint::WalkOffAndDoubleJump),
+            (Point::new(100, 40), MovementHint::Infer),
+        ];
+        assert_eq!(points, expected);
+
+        let rng = Rng::new([0; 32]);
+        let points = find_points_with(&rng, &platforms, from, to, true, false, 25, 7, 41).unwrap();
+        assert!(
+            points
+                .iter()
+                .all(|(_, hint)| *hint != MovementHint::WalkOffAndDoubleJump),
+            "Expected no WalkOffAndDoubleJump movement hint, got: {points:?}",
+        );
+    }
 }
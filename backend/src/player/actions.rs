@@ -5,8 +5,9 @@ use strum::Display;
 use super::{Player, PlayerState, use_key::UseKey};
 use crate::{
     Action, ActionKey, ActionKeyDirection, ActionKeyWith, ActionMove, FamiliarRarity, KeyBinding,
-    Position, SwappableFamiliars,
+    NoSwappableCardsOutcome, Position, SwappableFamiliars,
     array::Array,
+    buff::BuffKind,
     context::{Context, MS_PER_TICK},
     database::LinkKeyBinding,
     minimap::Minimap,
@@ -27,12 +28,25 @@ pub struct PlayerActionKey {
     pub link_key: Option<LinkKeyBinding>,
     pub count: u32,
     pub position: Option<Position>,
+    /// Whether to wait for the player to be at [`Self::position`] before using the key.
+    pub strict_position: bool,
     pub direction: ActionKeyDirection,
+    /// The direction the player must be facing for [`Rotator`] to rotate this action.
+    pub require_direction: Option<ActionKeyDirection>,
+    /// The buff that must be active for [`Rotator`] to rotate this action.
+    pub require_buff: Option<BuffKind>,
+    /// The probability for [`Rotator`] to rotate this action when its turn comes.
+    pub probability: Option<f32>,
     pub with: ActionKeyWith,
+    /// Whether the key can be used during [`Player::Falling`] before the fall completes.
+    pub use_while_falling: bool,
     pub wait_before_use_ticks: u32,
     pub wait_before_use_ticks_random_range: u32,
     pub wait_after_use_ticks: u32,
     pub wait_after_use_ticks_random_range: u32,
+    /// The ticks to wait once after the last of [`Self::count`] uses before the next linked
+    /// action.
+    pub linked_wait_ticks: u32,
 }
 
 impl From<ActionKey> for PlayerActionKey {
@@ -42,8 +56,13 @@ impl From<ActionKey> for PlayerActionKey {
             link_key,
             count,
             position,
+            strict_position,
             direction,
+            require_direction,
+            require_buff,
+            probability,
             with,
+            use_while_falling,
             wait_before_use_millis,
             wait_before_use_millis_random_range,
             wait_after_use_millis,
@@ -56,14 +75,20 @@ impl From<ActionKey> for PlayerActionKey {
             link_key,
             count: count.max(1),
             position,
+            strict_position,
             direction,
+            require_direction,
+            require_buff: require_buff.map(BuffKind::from),
+            probability,
             with,
+            use_while_falling,
             wait_before_use_ticks: (wait_before_use_millis / MS_PER_TICK) as u32,
             wait_before_use_ticks_random_range: (wait_before_use_millis_random_range / MS_PER_TICK)
                 as u32,
             wait_after_use_ticks: (wait_after_use_millis / MS_PER_TICK) as u32,
             wait_after_use_ticks_random_range: (wait_after_use_millis_random_range / MS_PER_TICK)
                 as u32,
+            linked_wait_ticks: 0,
         }
     }
 }
@@ -74,20 +99,32 @@ impl From<ActionKey> for PlayerActionKey {
 #[derive(Clone, Copy, Debug)]
 pub struct PlayerActionMove {
     pub position: Position,
+    /// The probability for [`Rotator`] to rotate this action when its turn comes.
+    pub probability: Option<f32>,
     pub wait_after_move_ticks: u32,
+    pub wait_after_move_ticks_random_range: u32,
+    /// The key to use once on arrival before waiting.
+    pub attack_on_arrival: Option<KeyBinding>,
 }
 
 impl From<ActionMove> for PlayerActionMove {
     fn from(
         ActionMove {
             position,
+            probability,
             wait_after_move_millis,
+            wait_after_move_millis_random_range,
+            attack_on_arrival,
             ..
         }: ActionMove,
     ) -> Self {
         Self {
             position,
+            probability,
             wait_after_move_ticks: (wait_after_move_millis / MS_PER_TICK) as u32,
+            wait_after_move_ticks_random_range: (wait_after_move_millis_random_range / MS_PER_TICK)
+                as u32,
+            attack_on_arrival,
         }
     }
 }
@@ -127,6 +164,9 @@ pub struct PlayerActionPingPong {
     /// This bound is in player relative coordinate.
     pub bound: Rect,
     pub direction: PingPongDirection,
+    /// The key to use instead of [`Self::key`] when [`Self::direction`] is
+    /// [`PingPongDirection::Left`].
+    pub alternate_key: Option<KeyBinding>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -146,6 +186,18 @@ impl Default for PingPongDirection {
 pub struct PlayerActionFamiliarsSwapping {
     pub swappable_slots: SwappableFamiliars,
     pub swappable_rarities: Array<FamiliarRarity, 2>,
+    /// Number of ticks to wait after hovering a familiar before detecting its level.
+    pub hover_settle_ticks: u32,
+    pub no_swappable_cards_outcome: NoSwappableCardsOutcome,
+}
+
+/// Represents a collect drops action.
+///
+/// This is a type of action that walks across the current platform while spamming the pick up
+/// key to collect nearby drops.
+#[derive(Clone, Copy, Debug)]
+pub struct PlayerActionCollectDrops {
+    pub key: KeyBinding,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -177,6 +229,8 @@ pub enum PlayerAction {
     FamiliarsSwapping(PlayerActionFamiliarsSwapping),
     /// Panicking to town or another channel action.
     Panic(PlayerActionPanic),
+    /// Collecting drops on the current platform action.
+    CollectDrops(PlayerActionCollectDrops),
 }
 
 impl From<Action> for PlayerAction {
@@ -310,6 +364,7 @@ pub fn on_action_state_mut(
                 }
                 PlayerAction::Panic(_)
                 | PlayerAction::FamiliarsSwapping(_)
+                | PlayerAction::CollectDrops(_)
                 | PlayerAction::AutoMob(_)
                 | PlayerAction::Key(PlayerActionKey { position: None, .. }) => (),
             }
@@ -24,6 +24,15 @@ pub const ADJUSTING_MEDIUM_THRESHOLD: i32 = 3;
 
 const ADJUSTING_SHORT_TIMEOUT: u32 = 3;
 
+/// Number of ticks without position change while walking before jumping to clear an obstacle.
+///
+/// Must be less than [`MOVE_TIMEOUT`] so the jump happens before timing out.
+const ADJUSTING_UNSTUCK_JUMP_TICKS: u32 = 3;
+
+/// Maximum x distance from the destination allowed to use key while walking when
+/// [`PlayerConfiguration::adjust_attack_while_walking`] is enabled.
+const ADJUSTING_ATTACK_X_THRESHOLD: i32 = 5;
+
 /// Minimium y distance required to perform a fall and then walk.
 const FALLING_THRESHOLD: i32 = 8;
 
@@ -87,17 +96,37 @@ pub fn update_adjusting_context(
                     (true, _, Some((down_key, up_key, dir))) => {
                         let _ = context.keys.send_up(up_key);
                         let _ = context.keys.send_down(down_key);
+                        // Walking against walls or slopes does not change the position
+                        if state.config.adjust_unstuck_jump
+                            && moving.timeout.current == ADJUSTING_UNSTUCK_JUMP_TICKS
+                        {
+                            let _ = context.keys.send(state.config.jump_key);
+                        }
                         state.last_known_direction = dir;
                     }
                     (false, true, Some((down_key, up_key, dir))) => {
-                        let _ = context.keys.send_up(up_key);
-                        let _ = context.keys.send_down(down_key);
-
-                        if moving.timeout.current >= ADJUSTING_SHORT_TIMEOUT {
-                            let _ = context.keys.send_up(down_key);
+                        // Overshot the destination and has to go back
+                        if !matches!(state.last_known_direction, ActionKeyDirection::Any)
+                            && state.last_known_direction != dir
+                        {
+                            state.track_adjust_correction(moving.dest);
                         }
+                        if state.is_adjust_corrections_exhausted(moving.dest) {
+                            let _ = context.keys.send_up(KeyKind::Left);
+                            let _ = context.keys.send_up(KeyKind::Right);
+                            moving = moving.completed(true);
+                        } else {
+                            let _ = context.keys.send_up(up_key);
+                            let _ = context.keys.send_down(down_key);
 
-                        state.last_known_direction = dir;
+                            let hold_ticks =
+                                adjusting_short_hold_ticks(x_distance, state.config.adjust_damping);
+                            if moving.timeout.current >= hold_ticks {
+                                let _ = context.keys.send_up(down_key);
+                            }
+
+                            state.last_known_direction = dir;
+                        }
                     }
                     _ => {
                         let _ = context.keys.send_up(KeyKind::Left);
@@ -123,6 +152,18 @@ pub fn update_adjusting_context(
     )
 }
 
+/// Gets the number of ticks to hold the direction key for small movement.
+///
+/// With `damping` of `0.0`, the key is held for [`ADJUSTING_SHORT_TIMEOUT`] ticks. As `damping`
+/// approaches `1.0`, the ticks are scaled down toward `x_distance` so that the player commits
+/// less to each movement when nearing the destination.
+#[inline]
+fn adjusting_short_hold_ticks(x_distance: i32, damping: f32) -> u32 {
+    let full = ADJUSTING_SHORT_TIMEOUT as f32;
+    let scaled = x_distance.clamp(1, ADJUSTING_SHORT_TIMEOUT as i32) as f32;
+    (full + (scaled - full) * damping.clamp(0.0, 1.0)).round() as u32
+}
+
 fn on_player_action(
     context: &Context,
     state: &PlayerState,
@@ -165,6 +206,14 @@ fn on_player_action(
         }) => {
             if moving.completed && y_distance <= USE_KEY_Y_THRESHOLD {
                 Some((Player::UseKey(UseKey::from_action(action)), false))
+            } else if state.config.adjust_attack_while_walking
+                && !moving.exact
+                && x_distance <= ADJUSTING_ATTACK_X_THRESHOLD
+                && y_distance <= USE_KEY_Y_THRESHOLD
+            {
+                let _ = context.keys.send_up(KeyKind::Left);
+                let _ = context.keys.send_up(KeyKind::Right);
+                Some((Player::UseKey(UseKey::from_action(action)), false))
             } else {
                 None
             }
@@ -173,13 +222,170 @@ fn on_player_action(
             on_auto_mob_use_key_action(context, action, moving.pos, x_distance, y_distance)
         }
         PlayerAction::Key(PlayerActionKey {
-            with: ActionKeyWith::Stationary,
+            with: ActionKeyWith::Stationary | ActionKeyWith::UpJump | ActionKeyWith::Airborne,
             ..
         })
         | PlayerAction::SolveRune
         | PlayerAction::Move(_) => None,
-        PlayerAction::PingPong(_) | PlayerAction::Panic(_) | PlayerAction::FamiliarsSwapping(_) => {
+        PlayerAction::PingPong(_)
+        | PlayerAction::Panic(_)
+        | PlayerAction::FamiliarsSwapping(_)
+        | PlayerAction::CollectDrops(_) => {
             unreachable!()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches::assert_matches;
+
+    use opencv::core::Point;
+
+    use super::*;
+    use crate::{KeyBinding, bridge::MockKeySender};
+
+    #[test]
+    fn update_adjusting_context_unstuck_jump() {
+        let pos = Point::new(5, 5);
+        let moving = Moving {
+            pos,
+            dest: Point::new(pos.x + ADJUSTING_MEDIUM_THRESHOLD + 2, pos.y),
+            timeout: Timeout {
+                started: true,
+                current: ADJUSTING_UNSTUCK_JUMP_TICKS - 1,
+                total: ADJUSTING_UNSTUCK_JUMP_TICKS - 1,
+            },
+            ..Default::default()
+        };
+        let mut state = PlayerState::default();
+        state.config.jump_key = KeyKind::Space;
+        state.config.adjust_unstuck_jump = true;
+        state.last_known_pos = Some(pos);
+
+        let mut keys = MockKeySender::new();
+        keys.expect_send_up()
+            .withf(|key| matches!(key, KeyKind::Left))
+            .returning(|_| Ok(()));
+        keys.expect_send_down()
+            .withf(|key| matches!(key, KeyKind::Right))
+            .returning(|_| Ok(()));
+        keys.expect_send()
+            .withf(|key| matches!(key, KeyKind::Space))
+            .once()
+            .returning(|_| Ok(()));
+        let context = Context::new(Some(keys), None);
+
+        let player = update_adjusting_context(&context, &mut state, moving);
+        assert_matches!(player, Player::Adjusting(_));
+        let _ = context.keys; // Drop for test checkpoint
+
+        // Does not jump when disabled
+        state.config.adjust_unstuck_jump = false;
+        let mut keys = MockKeySender::new();
+        keys.expect_send_up().returning(|_| Ok(()));
+        keys.expect_send_down().returning(|_| Ok(()));
+        keys.expect_send().never();
+        let context = Context::new(Some(keys), None);
+
+        update_adjusting_context(&context, &mut state, moving);
+    }
+
+    #[test]
+    fn adjusting_short_hold_ticks_damping() {
+        assert_eq!(adjusting_short_hold_ticks(1, 0.0), ADJUSTING_SHORT_TIMEOUT);
+        assert_eq!(adjusting_short_hold_ticks(2, 0.0), ADJUSTING_SHORT_TIMEOUT);
+        assert_eq!(adjusting_short_hold_ticks(1, 0.5), 2);
+        assert_eq!(adjusting_short_hold_ticks(1, 1.0), 1);
+        assert_eq!(adjusting_short_hold_ticks(2, 1.0), 2);
+    }
+
+    #[test]
+    fn update_adjusting_context_max_correction_cycles() {
+        let pos = Point::new(5, 5);
+        let moving = Moving {
+            pos,
+            dest: Point::new(pos.x + 1, pos.y),
+            exact: true,
+            timeout: Timeout {
+                started: true,
+                current: 1,
+                total: 1,
+            },
+            ..Default::default()
+        };
+        let mut state = PlayerState::default();
+        state.config.adjust_max_correction_cycles = Some(1);
+        state.last_known_pos = Some(pos);
+        state.last_known_direction = ActionKeyDirection::Left;
+
+        let mut keys = MockKeySender::new();
+        keys.expect_send_up().returning(|_| Ok(()));
+        keys.expect_send_down().never();
+        let context = Context::new(Some(keys), None);
+
+        // Accepts the position after reversing direction once
+        let player = update_adjusting_context(&context, &mut state, moving);
+        assert_matches!(
+            player,
+            Player::Adjusting(Moving {
+                completed: true,
+                ..
+            })
+        );
+        assert!(state.is_adjust_corrections_exhausted(moving.dest));
+    }
+
+    #[test]
+    fn update_adjusting_context_attack_while_walking() {
+        let pos = Point::new(5, 5);
+        let moving = Moving {
+            pos,
+            dest: Point::new(pos.x + ADJUSTING_ATTACK_X_THRESHOLD - 1, pos.y),
+            timeout: Timeout {
+                started: true,
+                current: 1,
+                total: 1,
+            },
+            ..Default::default()
+        };
+        let action = PlayerActionKey {
+            key: KeyBinding::A,
+            link_key: None,
+            count: 1,
+            position: None,
+            strict_position: false,
+            direction: ActionKeyDirection::Any,
+            require_direction: None,
+            require_buff: None,
+            probability: None,
+            with: ActionKeyWith::Any,
+            use_while_falling: false,
+            wait_before_use_ticks: 0,
+            wait_before_use_ticks_random_range: 0,
+            wait_after_use_ticks: 0,
+            wait_after_use_ticks_random_range: 0,
+            linked_wait_ticks: 0,
+        };
+        let mut state = PlayerState::default();
+        state.config.adjust_attack_while_walking = true;
+        state.last_known_pos = Some(pos);
+        state.set_normal_action(0, PlayerAction::Key(action));
+
+        let mut keys = MockKeySender::new();
+        keys.expect_send_up().returning(|_| Ok(()));
+        keys.expect_send_down()
+            .withf(|key| matches!(key, KeyKind::Right))
+            .returning(|_| Ok(()));
+        let context = Context::new(Some(keys), None);
+
+        // Uses key while still walking when enabled
+        let player = update_adjusting_context(&context, &mut state, moving);
+        assert_matches!(player, Player::UseKey(_));
+
+        // Keeps walking when disabled
+        state.config.adjust_attack_while_walking = false;
+        let player = update_adjusting_context(&context, &mut state, moving);
+        assert_matches!(player, Player::Adjusting(_));
+    }
+}
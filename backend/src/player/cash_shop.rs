@@ -11,6 +11,7 @@ use crate::{bridge::MouseAction, context::Context};
 pub enum CashShop {
     Entering,
     Entered,
+    WaitingToExit,
     Exitting,
     Exitted,
     Stalling,
@@ -34,15 +35,20 @@ pub fn update_cash_shop_context(
             };
             Player::CashShopThenExit(timeout, next)
         }
-        CashShop::Entered => {
-            update_with_timeout(
-                timeout,
-                305, // exits after 10 secs
-                |timeout| Player::CashShopThenExit(timeout, cash_shop),
-                || Player::CashShopThenExit(timeout, CashShop::Exitting),
-                |timeout| Player::CashShopThenExit(timeout, cash_shop),
-            )
-        }
+        CashShop::Entered => update_with_timeout(
+            timeout,
+            state.config.cash_shop_dwell_ticks.max(1),
+            |timeout| Player::CashShopThenExit(timeout, cash_shop),
+            || Player::CashShopThenExit(Timeout::default(), CashShop::WaitingToExit),
+            |timeout| Player::CashShopThenExit(timeout, cash_shop),
+        ),
+        CashShop::WaitingToExit => update_with_timeout(
+            timeout,
+            state.config.cash_shop_exit_wait_ticks.max(1),
+            |timeout| Player::CashShopThenExit(timeout, cash_shop),
+            || Player::CashShopThenExit(Timeout::default(), CashShop::Exitting),
+            |timeout| Player::CashShopThenExit(timeout, cash_shop),
+        ),
         CashShop::Exitting => {
             let next = if context.detector_unwrap().detect_player_in_cash_shop() {
                 CashShop::Exitting
@@ -75,3 +81,51 @@ pub fn update_cash_shop_context(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn update_cash_shop_context_entered_dwell() {
+        let context = Context::new(None, None);
+        let mut state = PlayerState::default();
+        state.config.cash_shop_dwell_ticks = 5;
+        let timeout = Timeout {
+            started: true,
+            current: 4,
+            ..Timeout::default()
+        };
+
+        let next = update_cash_shop_context(&context, &state, timeout, CashShop::Entered, false);
+        assert_matches!(next, Player::CashShopThenExit(_, CashShop::Entered));
+
+        let timeout = Timeout {
+            current: 5,
+            ..timeout
+        };
+        let next = update_cash_shop_context(&context, &state, timeout, CashShop::Entered, false);
+        assert_matches!(
+            next,
+            Player::CashShopThenExit(Timeout { started: false, .. }, CashShop::WaitingToExit)
+        );
+    }
+
+    #[test]
+    fn update_cash_shop_context_waiting_to_exit() {
+        let context = Context::new(None, None);
+        let mut state = PlayerState::default();
+        state.config.cash_shop_exit_wait_ticks = 3;
+        let timeout = Timeout {
+            started: true,
+            current: 3,
+            ..Timeout::default()
+        };
+
+        let next =
+            update_cash_shop_context(&context, &state, timeout, CashShop::WaitingToExit, false);
+        assert_matches!(next, Player::CashShopThenExit(_, CashShop::Exitting));
+    }
+}
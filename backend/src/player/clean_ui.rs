@@ -0,0 +1,129 @@
+use platforms::windows::KeyKind;
+
+use super::{
+    Player,
+    timeout::{Timeout, update_with_timeout},
+};
+use crate::{context::Context, detect::Detector};
+
+/// Maximum number of ESC key presses before giving up.
+const MAX_RETRY: u32 = 3;
+
+/// Number of ticks to wait after pressing ESC key before checking the menus again.
+const TIMEOUT: u32 = 10;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CleaningUi {
+    timeout: Timeout,
+    retry_count: u32,
+}
+
+/// Updates [`Player::CleaningUi`] contextual state.
+///
+/// This state is transitioned to when the actions start rotating. It checks whether any known
+/// menu is opened and presses ESC key to close it, then waits for [`TIMEOUT`] ticks before
+/// checking again. It completes when no known menu is opened or after [`MAX_RETRY`] presses.
+pub fn update_cleaning_ui_context(context: &Context, cleaning: CleaningUi) -> Player {
+    update_with_timeout(
+        cleaning.timeout,
+        TIMEOUT,
+        |timeout| {
+            if cleaning.retry_count >= MAX_RETRY || !has_menu_opened(context.detector_unwrap()) {
+                return Player::Detecting;
+            }
+            let _ = context.keys.send(KeyKind::Esc);
+            Player::CleaningUi(CleaningUi {
+                timeout,
+                ..cleaning
+            })
+        },
+        || {
+            Player::CleaningUi(CleaningUi {
+                timeout: Timeout::default(),
+                retry_count: cleaning.retry_count + 1,
+            })
+        },
+        |timeout| {
+            Player::CleaningUi(CleaningUi {
+                timeout,
+                ..cleaning
+            })
+        },
+    )
+}
+
+#[inline]
+fn has_menu_opened(detector: &dyn Detector) -> bool {
+    detector.detect_esc_settings()
+        || detector.detect_familiar_menu_opened()
+        || detector.detect_change_channel_menu_opened()
+        || detector.detect_maple_guide_menu_opened()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches::assert_matches;
+
+    use super::*;
+    use crate::{bridge::MockKeySender, detect::MockDetector};
+
+    fn detector(menu_opened: bool) -> MockDetector {
+        let mut detector = MockDetector::default();
+        detector.expect_detect_esc_settings().return_const(false);
+        detector
+            .expect_detect_familiar_menu_opened()
+            .return_const(menu_opened);
+        detector
+            .expect_detect_change_channel_menu_opened()
+            .return_const(false);
+        detector
+            .expect_detect_maple_guide_menu_opened()
+            .return_const(false);
+        detector
+    }
+
+    #[test]
+    fn update_cleaning_ui_context_no_menu_opened() {
+        let mut keys = MockKeySender::default();
+        keys.expect_send().never();
+        let context = Context::new(Some(keys), Some(detector(false)));
+
+        let next = update_cleaning_ui_context(&context, CleaningUi::default());
+        assert_matches!(next, Player::Detecting);
+    }
+
+    #[test]
+    fn update_cleaning_ui_context_menu_opened() {
+        let mut keys = MockKeySender::default();
+        keys.expect_send()
+            .withf(|key| matches!(key, KeyKind::Esc))
+            .once()
+            .returning(|_| Ok(()));
+        let context = Context::new(Some(keys), Some(detector(true)));
+
+        let next = update_cleaning_ui_context(&context, CleaningUi::default());
+        assert_matches!(
+            next,
+            Player::CleaningUi(CleaningUi {
+                timeout: Timeout { started: true, .. },
+                retry_count: 0
+            })
+        );
+    }
+
+    #[test]
+    fn update_cleaning_ui_context_max_retry() {
+        let mut keys = MockKeySender::default();
+        keys.expect_send().never();
+        let context = Context::new(Some(keys), Some(detector(true)));
+
+        let next = update_cleaning_ui_context(
+            &context,
+            CleaningUi {
+                timeout: Timeout::default(),
+                retry_count: MAX_RETRY,
+            },
+        );
+        assert_matches!(next, Player::Detecting);
+    }
+}
@@ -0,0 +1,230 @@
+use opencv::core::Point;
+use platforms::windows::KeyKind;
+
+use super::{
+    Player, PlayerState,
+    actions::on_action,
+    timeout::{Timeout, update_with_timeout},
+};
+use crate::{context::Context, minimap::Minimap};
+
+/// Maximum number of ticks for walking across the platform before timing out.
+const TIMEOUT: u32 = 150;
+
+/// Number of ticks between each pick up key press.
+const PICK_UP_KEY_INTERVAL: u32 = 4;
+
+/// Maximum y distance from a platform for the player to be considered standing on it.
+const PLATFORM_Y_THRESHOLD: i32 = 2;
+
+#[derive(Clone, Copy, Debug)]
+pub struct CollectingDrops {
+    /// The pick up key.
+    key: KeyKind,
+    /// The direction key to hold while walking.
+    direction: KeyKind,
+    /// The x position to stop walking at.
+    end_x: i32,
+    timeout: Timeout,
+}
+
+impl CollectingDrops {
+    /// Creates a new [`CollectingDrops`] walking toward the farther end of the current platform.
+    ///
+    /// Returns [`None`] when the player is not standing on any known platform.
+    pub fn new(context: &Context, cur_pos: Point, key: KeyKind) -> Option<Self> {
+        let Minimap::Idle(idle) = context.minimap else {
+            unreachable!();
+        };
+        let platform = idle.platforms.iter().find(|platform| {
+            (platform.y() - cur_pos.y).abs() <= PLATFORM_Y_THRESHOLD
+                && platform.xs().contains(&cur_pos.x)
+        })?;
+        // The platform xs end is exclusive
+        let (start_x, end_x) = (platform.xs().start, platform.xs().end - 1);
+        let (direction, end_x) = if cur_pos.x - start_x > end_x - cur_pos.x {
+            (KeyKind::Left, start_x)
+        } else {
+            (KeyKind::Right, end_x)
+        };
+
+        Some(Self {
+            key,
+            direction,
+            end_x,
+            timeout: Timeout::default(),
+        })
+    }
+}
+
+/// Updates [`Player::CollectingDrops`] contextual state.
+///
+/// This state holds the direction key toward [`CollectingDrops::end_x`] while pressing the pick up
+/// key every [`PICK_UP_KEY_INTERVAL`] ticks. It completes when the player reaches the end of the
+/// platform or times out.
+pub fn update_collecting_drops_context(
+    context: &Context,
+    state: &mut PlayerState,
+    collecting: CollectingDrops,
+) -> Player {
+    let cur_pos = state.last_known_pos.unwrap();
+    let reached_end = match collecting.direction {
+        KeyKind::Left => cur_pos.x <= collecting.end_x,
+        KeyKind::Right => cur_pos.x >= collecting.end_x,
+        _ => unreachable!(),
+    };
+    let next = update_with_timeout(
+        collecting.timeout,
+        TIMEOUT,
+        |timeout| {
+            let _ = context.keys.send_down(collecting.direction);
+            let _ = context.keys.send(collecting.key);
+            Player::CollectingDrops(CollectingDrops {
+                timeout,
+                ..collecting
+            })
+        },
+        || {
+            let _ = context.keys.send_up(collecting.direction);
+            Player::Idle
+        },
+        |timeout| {
+            if reached_end {
+                let _ = context.keys.send_up(collecting.direction);
+                return Player::Idle;
+            }
+            if timeout.current % PICK_UP_KEY_INTERVAL == 0 {
+                let _ = context.keys.send(collecting.key);
+            }
+            Player::CollectingDrops(CollectingDrops {
+                timeout,
+                ..collecting
+            })
+        },
+    );
+
+    on_action(
+        state,
+        |_| Some((next, matches!(next, Player::Idle))),
+        || {
+            // Force cancel if it is not initiated from an action
+            let _ = context.keys.send_up(collecting.direction);
+            Player::Idle
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches::assert_matches;
+
+    use super::*;
+    use crate::{
+        array::Array,
+        bridge::MockKeySender,
+        minimap::MinimapIdle,
+        pathing::{Platform, find_neighbors},
+        player::{PlayerAction, PlayerActionCollectDrops},
+    };
+
+    fn collecting(direction: KeyKind, end_x: i32, timeout: Timeout) -> CollectingDrops {
+        CollectingDrops {
+            key: KeyKind::Z,
+            direction,
+            end_x,
+            timeout,
+        }
+    }
+
+    fn context_with_platform(platform: Platform) -> Context {
+        let mut idle = MinimapIdle::default();
+        idle.platforms = Array::from_iter(find_neighbors(&[platform], 25, 7, 41));
+        let mut context = Context::new(None, None);
+        context.minimap = Minimap::Idle(idle);
+        context
+    }
+
+    #[test]
+    fn collecting_drops_new_stops_at_platform_last_x() {
+        let context = context_with_platform(Platform::new(10..50, 20));
+
+        let collecting = CollectingDrops::new(&context, Point::new(20, 20), KeyKind::Z).unwrap();
+        assert_eq!(collecting.direction, KeyKind::Right);
+        assert_eq!(collecting.end_x, 49);
+
+        let collecting = CollectingDrops::new(&context, Point::new(40, 20), KeyKind::Z).unwrap();
+        assert_eq!(collecting.direction, KeyKind::Left);
+        assert_eq!(collecting.end_x, 10);
+    }
+
+    #[test]
+    fn collecting_drops_new_none_without_platform() {
+        let context = context_with_platform(Platform::new(10..50, 20));
+
+        assert!(CollectingDrops::new(&context, Point::new(20, 40), KeyKind::Z).is_none());
+        assert!(CollectingDrops::new(&context, Point::new(60, 20), KeyKind::Z).is_none());
+    }
+
+    #[test]
+    fn update_collecting_drops_context_started() {
+        let mut keys = MockKeySender::default();
+        keys.expect_send_down()
+            .withf(|key| matches!(key, KeyKind::Right))
+            .once()
+            .returning(|_| Ok(()));
+        keys.expect_send()
+            .withf(|key| matches!(key, KeyKind::Z))
+            .once()
+            .returning(|_| Ok(()));
+        let context = Context::new(Some(keys), None);
+        let mut state = PlayerState::default();
+        state.last_known_pos = Some(Point::new(10, 10));
+        state.set_priority_action(
+            0,
+            PlayerAction::CollectDrops(PlayerActionCollectDrops {
+                key: Default::default(),
+            }),
+        );
+
+        let next = update_collecting_drops_context(
+            &context,
+            &mut state,
+            collecting(KeyKind::Right, 50, Timeout::default()),
+        );
+        assert_matches!(next, Player::CollectingDrops(_));
+        assert!(state.has_priority_action());
+    }
+
+    #[test]
+    fn update_collecting_drops_context_reached_end() {
+        let mut keys = MockKeySender::default();
+        keys.expect_send_up()
+            .withf(|key| matches!(key, KeyKind::Left))
+            .once()
+            .returning(|_| Ok(()));
+        let context = Context::new(Some(keys), None);
+        let mut state = PlayerState::default();
+        state.last_known_pos = Some(Point::new(5, 10));
+        state.set_priority_action(
+            0,
+            PlayerAction::CollectDrops(PlayerActionCollectDrops {
+                key: Default::default(),
+            }),
+        );
+
+        let next = update_collecting_drops_context(
+            &context,
+            &mut state,
+            collecting(
+                KeyKind::Left,
+                5,
+                Timeout {
+                    started: true,
+                    ..Timeout::default()
+                },
+            ),
+        );
+        assert_matches!(next, Player::Idle);
+        assert!(!state.has_priority_action());
+    }
+}
@@ -10,11 +10,12 @@ use super::{
     moving::Moving,
     timeout::update_with_timeout,
     up_jump::UpJumping,
-    use_key::UseKey,
+    use_key::{UseKey, is_airborne},
 };
 use crate::{
     ActionKeyDirection, ActionKeyWith,
     context::Context,
+    minimap::Minimap,
     player::{
         moving::MOVE_TIMEOUT,
         state::LastMovement,
@@ -51,12 +52,6 @@ const GRAPPLING_THRESHOLD: i32 = 4;
 /// Minimum x velocity to be considered as double jumped.
 const X_VELOCITY_THRESHOLD: f32 = 1.0;
 
-/// Maximum x velocity allowed to be considered as near stationary.
-const X_NEAR_STATIONARY_VELOCITY_THRESHOLD: f32 = 0.75;
-
-/// Maximum y velocity allowed to be considered as near stationary.
-const Y_NEAR_STATIONARY_VELOCITY_THRESHOLD: f32 = 0.4;
-
 /// Minimium y distance required to perform a fall and then double jump.
 const FALLING_THRESHOLD: i32 = 8;
 
@@ -141,8 +136,8 @@ pub fn update_double_jumping_context(
         }
         // Stalls until near stationary
         if double_jumping.require_near_stationary
-            && (state.velocity.0 > X_NEAR_STATIONARY_VELOCITY_THRESHOLD
-                || state.velocity.1 > Y_NEAR_STATIONARY_VELOCITY_THRESHOLD)
+            && (state.velocity.0 > state.config.near_stationary_x_velocity_threshold
+                || state.velocity.1 > state.config.near_stationary_y_velocity_threshold)
         {
             return Player::DoubleJumping(double_jumping.moving(moving.pos(cur_pos)));
         }
@@ -194,13 +189,26 @@ pub fn update_double_jumping_context(
                 let can_continue = !double_jumping.forced
                     && x_distance >= state.double_jump_threshold(is_intermediate);
                 let can_press = double_jumping.forced && state.velocity.0 <= X_VELOCITY_THRESHOLD;
-                if can_continue || can_press {
+                if (can_continue || can_press)
+                    && is_near_minimap_edge(context, state, cur_pos, x_direction)
+                {
+                    // Prefers walking near the edges to avoid double jumping off the map
+                    if double_jumping.forced {
+                        moving = moving.completed(true);
+                    }
+                } else if can_continue || can_press {
                     if !double_jumping.cooldown_timeout.started
                         && state.velocity.0 <= X_VELOCITY_THRESHOLD
                     {
-                        let _ = context
-                            .keys
-                            .send(state.config.teleport_key.unwrap_or(state.config.jump_key));
+                        // Flash jump only works in the air so the first press from the ground
+                        // must be a normal jump
+                        let key = state.config.teleport_key.unwrap_or(
+                            match state.config.flash_jump_key {
+                                Some(key) if is_airborne(state) => key,
+                                _ => state.config.jump_key,
+                            },
+                        );
+                        let _ = context.keys.send(key);
                     } else {
                         double_jumping.update_jump_cooldown();
                     }
@@ -251,11 +259,46 @@ pub fn update_double_jumping_context(
     )
 }
 
+/// Checks whether the player is within [`PlayerConfiguration::edge_safety_margin`] of the
+/// minimap edge it is heading toward.
+///
+/// The heading is `x_direction` or [`PlayerState::last_known_direction`] if `x_direction` is zero.
+///
+/// [`PlayerConfiguration::edge_safety_margin`]: super::state::PlayerConfiguration::edge_safety_margin
+#[inline]
+fn is_near_minimap_edge(
+    context: &Context,
+    state: &PlayerState,
+    cur_pos: Point,
+    x_direction: i32,
+) -> bool {
+    let margin = state.config.edge_safety_margin;
+    if margin <= 0 {
+        return false;
+    }
+    let Minimap::Idle(idle) = context.minimap else {
+        return false;
+    };
+    let direction = match x_direction.cmp(&0) {
+        Ordering::Greater => ActionKeyDirection::Right,
+        Ordering::Less => ActionKeyDirection::Left,
+        Ordering::Equal => state.last_known_direction,
+    };
+    let near_left = cur_pos.x <= margin;
+    let near_right = cur_pos.x >= idle.bbox.width - margin;
+    match direction {
+        ActionKeyDirection::Any => near_left || near_right,
+        ActionKeyDirection::Left => near_left,
+        ActionKeyDirection::Right => near_right,
+    }
+}
+
 /// Handles [`PlayerAction`] during double jump.
 ///
-/// It currently handles action for auto mob and a key action with [`ActionKeyWith::Any`] or
-/// [`ActionKeyWith::DoubleJump`]. For auto mob, the same handling logics is reused. For the other,
-/// it will try to transition to [`Player::UseKey`] when the player is close enough.
+/// It currently handles action for auto mob and a key action with [`ActionKeyWith::Any`],
+/// [`ActionKeyWith::DoubleJump`] or [`ActionKeyWith::Airborne`]. For auto mob, the same handling
+/// logics is reused. For the other, it will try to transition to [`Player::UseKey`] when the
+/// player is close enough.
 fn on_player_action(
     context: &Context,
     state: &PlayerState,
@@ -284,7 +327,7 @@ fn on_player_action(
             on_auto_mob_use_key_action(context, action, moving.pos, x_distance, y_distance)
         }
         PlayerAction::Key(PlayerActionKey {
-            with: ActionKeyWith::DoubleJump | ActionKeyWith::Any,
+            with: ActionKeyWith::DoubleJump | ActionKeyWith::Airborne | ActionKeyWith::Any,
             ..
         }) => {
             if !moving.completed {
@@ -303,12 +346,14 @@ fn on_player_action(
             }
         }
         PlayerAction::Key(PlayerActionKey {
-            with: ActionKeyWith::Stationary,
+            with: ActionKeyWith::Stationary | ActionKeyWith::UpJump,
             ..
         })
         | PlayerAction::SolveRune
         | PlayerAction::Move { .. } => None,
-        PlayerAction::Panic(_) | PlayerAction::FamiliarsSwapping(_) => unreachable!(),
+        PlayerAction::Panic(_)
+        | PlayerAction::FamiliarsSwapping(_)
+        | PlayerAction::CollectDrops(_) => unreachable!(),
     }
 }
 
@@ -424,6 +469,7 @@ mod tests {
         ActionKeyDirection,
         bridge::MockKeySender,
         context::Context,
+        minimap::{Minimap, MinimapIdle},
         player::{
             PingPongDirection, Player, PlayerAction, PlayerActionPingPong,
             double_jump::DoubleJumping, moving::Moving, state::PlayerState, timeout::Timeout,
@@ -467,6 +513,93 @@ mod tests {
         update_double_jumping_context(&context, &mut state, jumping);
     }
 
+    #[test]
+    fn double_jumping_uses_flash_jump_key() {
+        let pos = Point::new(100, 50);
+        let dest = Point::new(50, 50);
+        let moving = Moving {
+            pos,
+            dest,
+            timeout: Timeout {
+                started: true,
+                ..Timeout::default()
+            },
+            ..Default::default()
+        };
+        let jumping = DoubleJumping::new(moving, false, false);
+
+        let mut state = PlayerState::default();
+        state.last_known_pos = Some(pos);
+        state.config.jump_key = KeyKind::Space;
+        state.config.flash_jump_key = Some(KeyKind::Shift);
+
+        // (1) Jumps with the jump key from the ground
+        let mut keys = MockKeySender::new();
+        keys.expect_send_down().returning(|_| Ok(()));
+        keys.expect_send_up().returning(|_| Ok(()));
+        keys.expect_send()
+            .withf(|k| matches!(k, KeyKind::Space))
+            .once()
+            .returning(|_| Ok(()));
+        let context = Context::new(Some(keys), None);
+
+        update_double_jumping_context(&context, &mut state, jumping);
+        let _ = context.keys; // Drop for test checkpoint
+
+        // (2) Flash jumps with the flash jump key when airborne
+        state.velocity = (0.0, 1.0);
+        let mut keys = MockKeySender::new();
+        keys.expect_send_down().returning(|_| Ok(()));
+        keys.expect_send_up().returning(|_| Ok(()));
+        keys.expect_send()
+            .withf(|k| matches!(k, KeyKind::Shift))
+            .once()
+            .returning(|_| Ok(()));
+        let context = Context::new(Some(keys), None);
+
+        update_double_jumping_context(&context, &mut state, jumping);
+    }
+
+    #[test]
+    fn double_jumping_forced_near_edge_does_not_jump() {
+        let pos = Point::new(95, 50);
+        let moving = Moving {
+            pos,
+            dest: pos,
+            timeout: Timeout {
+                started: true,
+                ..Timeout::default()
+            },
+            ..Default::default()
+        };
+        let jumping = DoubleJumping::new(moving, true, false);
+
+        let mut state = PlayerState::default();
+        state.last_known_pos = Some(pos);
+        state.last_known_direction = ActionKeyDirection::Right;
+        state.config.jump_key = KeyKind::Space;
+        state.config.edge_safety_margin = 10;
+
+        let mut idle = MinimapIdle::default();
+        idle.bbox = Rect::new(0, 0, 100, 100);
+        let mut keys = MockKeySender::new();
+        keys.expect_send().never();
+        let mut context = Context::new(Some(keys), None);
+        context.minimap = Minimap::Idle(idle);
+
+        let player = update_double_jumping_context(&context, &mut state, jumping);
+        assert_matches!(
+            player,
+            Player::DoubleJumping(DoubleJumping {
+                moving: Moving {
+                    completed: true,
+                    ..
+                },
+                ..
+            })
+        );
+    }
+
     #[test]
     fn double_jumping_mage_requires_direction_even_when_x_direction_zero() {
         let pos = Point::new(100, 50);
@@ -22,6 +22,10 @@ pub const FALLING_THRESHOLD: i32 = 4;
 /// a [`PlayerAction::Key`] with [`ActionKeyWith::Any`].
 const FALLING_TO_USE_KEY_THRESHOLD: i32 = 5;
 
+/// Maximum x distance from the destination allowed to transition to [`Player::UseKey`] before
+/// the fall completes for a [`PlayerAction::Key`] with [`PlayerActionKey::use_while_falling`].
+const FALLING_TO_USE_KEY_X_THRESHOLD: i32 = 3;
+
 /// Tick to stop helding down [`KeyKind::Down`] at.
 const STOP_DOWN_KEY_TICK: u32 = 3;
 
@@ -137,6 +141,22 @@ fn on_player_action(
         }
         PlayerAction::Key(PlayerActionKey {
             with: ActionKeyWith::Any,
+            use_while_falling: true,
+            ..
+        }) => {
+            let (x_distance, _) = moving.x_distance_direction_from(false, cur_pos);
+            if x_distance > FALLING_TO_USE_KEY_X_THRESHOLD
+                || y_distance >= FALLING_TO_USE_KEY_THRESHOLD
+            {
+                return None;
+            }
+            // Down key might still be held if the fall has just started
+            let _ = context.keys.send_up(KeyKind::Down);
+            Some((Player::UseKey(UseKey::from_action(action)), false))
+        }
+        PlayerAction::Key(PlayerActionKey {
+            with: ActionKeyWith::Any,
+            use_while_falling: false,
             ..
         }) => {
             if has_teleport_key || !moving.completed || y_distance >= FALLING_TO_USE_KEY_THRESHOLD {
@@ -145,13 +165,19 @@ fn on_player_action(
             Some((Player::UseKey(UseKey::from_action(action)), false))
         }
         PlayerAction::Key(PlayerActionKey {
-            with: ActionKeyWith::Stationary | ActionKeyWith::DoubleJump,
+            with:
+                ActionKeyWith::Stationary
+                | ActionKeyWith::DoubleJump
+                | ActionKeyWith::UpJump
+                | ActionKeyWith::Airborne,
             ..
         })
         | PlayerAction::PingPong(_)
         | PlayerAction::Move(_)
         | PlayerAction::SolveRune => None,
-        PlayerAction::Panic(_) | PlayerAction::FamiliarsSwapping(_) => {
+        PlayerAction::Panic(_)
+        | PlayerAction::FamiliarsSwapping(_)
+        | PlayerAction::CollectDrops(_) => {
             unreachable!()
         }
     }
@@ -166,9 +192,12 @@ mod tests {
 
     use super::update_falling_context;
     use crate::{
+        ActionKeyDirection, ActionKeyWith, KeyBinding,
         bridge::MockKeySender,
         context::Context,
-        player::{Player, PlayerState, moving::Moving, timeout::Timeout},
+        player::{
+            Player, PlayerAction, PlayerActionKey, PlayerState, moving::Moving, timeout::Timeout,
+        },
     };
 
     #[test]
@@ -302,5 +331,63 @@ mod tests {
         );
     }
 
-    // TODO: Add tests for handling actions
+    #[test]
+    fn falling_use_key_while_falling() {
+        let pos = Point::new(5, 10);
+        let anchor = Point::new(pos.x, pos.y - 1);
+        let moving = Moving {
+            pos,
+            dest: Point::new(pos.x, pos.y - 3),
+            timeout: Timeout {
+                started: true,
+                total: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let action = PlayerActionKey {
+            key: KeyBinding::A,
+            link_key: None,
+            count: 1,
+            position: None,
+            strict_position: false,
+            direction: ActionKeyDirection::Any,
+            require_direction: None,
+            require_buff: None,
+            probability: None,
+            with: ActionKeyWith::Any,
+            use_while_falling: false,
+            wait_before_use_ticks: 0,
+            wait_before_use_ticks_random_range: 0,
+            wait_after_use_ticks: 0,
+            wait_after_use_ticks_random_range: 0,
+            linked_wait_ticks: 0,
+        };
+
+        let mut state = PlayerState::default();
+        state.last_known_pos = Some(pos);
+        state.set_normal_action(0, PlayerAction::Key(action));
+
+        // (1) Does not use key before the fall completes
+        let context = Context::new(Some(MockKeySender::new()), None);
+        let player = update_falling_context(&context, &mut state, moving, anchor, false);
+        assert_matches!(player, Player::Falling(_, _, _));
+
+        // (2) Uses key before the fall completes if enabled
+        state.set_normal_action(
+            0,
+            PlayerAction::Key(PlayerActionKey {
+                use_while_falling: true,
+                ..action
+            }),
+        );
+        let mut keys = MockKeySender::new();
+        keys.expect_send_up()
+            .withf(|key| matches!(key, KeyKind::Down))
+            .once()
+            .returning(|_| Ok(()));
+        let context = Context::new(Some(keys), None);
+        let player = update_falling_context(&context, &mut state, moving, anchor, false);
+        assert_matches!(player, Player::UseKey(_));
+    }
 }
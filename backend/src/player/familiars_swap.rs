@@ -13,8 +13,9 @@ use crate::{
     array::Array,
     bridge::MouseAction,
     context::Context,
-    database::{FamiliarRarity, SwappableFamiliars},
+    database::{FamiliarRarity, NoSwappableCardsOutcome, SwappableFamiliars},
     detect::{FamiliarLevel, FamiliarRank},
+    network::NotificationKind,
 };
 
 /// Number of familiar slots available.
@@ -22,6 +23,18 @@ const FAMILIAR_SLOTS: usize = 3;
 
 const MAX_RETRY: u32 = 4;
 
+/// Maximum number of times to re-hover a familiar when its level detection fails.
+const MAX_HOVER_RETRY: u32 = 2;
+
+/// Number of ticks after detecting the hovered familiar level to wait before timing out.
+const HOVER_DETECT_LEVEL_TO_TIMEOUT_TICKS: u32 = 5;
+
+/// Maximum number of times to search for the cards again when no swappable card is found.
+const MAX_NO_CARDS_RETRY: u32 = 3;
+
+/// Number of ticks to wait for the cards list to populate before searching for the cards again.
+const NO_CARDS_RETRY_DELAY_TICKS: u32 = 60;
+
 /// Internal state machine representing the current stage of familiar swapping.
 #[derive(Debug, Clone, Copy)]
 enum SwappingStage {
@@ -34,13 +47,15 @@ enum SwappingStage {
     /// Check if slot is free or occupied to release the slot.
     FreeSlots(usize, bool),
     /// Try releasing a single slot.
-    FreeSlot(Timeout, usize),
+    FreeSlot(Timeout, usize, u32),
     /// Find swappable familiar cards.
     FindCards,
     /// Swapping a card into an empty slot.
-    Swapping(Timeout, usize),
+    Swapping(Timeout, usize, u32),
     /// Scrolling the familiar cards list to find more cards.
     Scrolling(Timeout, Option<Rect>, u32),
+    /// Waiting for the familiar cards list to populate before finding cards again.
+    WaitingForCards(Timeout),
     /// Saving the familiar setup.
     Saving(Timeout, u32),
     Completing(Timeout, bool),
@@ -61,6 +76,12 @@ pub struct FamiliarsSwapping {
     swappable_rarities: Array<FamiliarRarity, 2>,
     /// Mouse rest point for other operations.
     mouse_rest: Point,
+    /// Number of ticks to wait after hovering a familiar before detecting its level.
+    hover_settle_ticks: u32,
+    /// The outcome when no swappable card is found.
+    no_cards_outcome: NoSwappableCardsOutcome,
+    /// Number of times finding cards has been retried because no swappable card is found.
+    no_cards_retry_count: u32,
 }
 
 impl Display for FamiliarsSwapping {
@@ -69,12 +90,13 @@ impl Display for FamiliarsSwapping {
             SwappingStage::OpenMenu(_, _) => write!(f, "Opening"),
             SwappingStage::OpenSetup(_, _) => write!(f, "Opening Setup"),
             SwappingStage::FindSlots => write!(f, "Find Slots"),
-            SwappingStage::FreeSlots(_, _) | SwappingStage::FreeSlot(_, _) => {
+            SwappingStage::FreeSlots(_, _) | SwappingStage::FreeSlot(_, _, _) => {
                 write!(f, "Freeing Slots")
             }
             SwappingStage::FindCards => write!(f, "Finding Cards"),
-            SwappingStage::Swapping(_, _) => write!(f, "Swapping"),
+            SwappingStage::Swapping(_, _, _) => write!(f, "Swapping"),
             SwappingStage::Scrolling(_, _, _) => write!(f, "Scrolling"),
+            SwappingStage::WaitingForCards(_) => write!(f, "Waiting For Cards"),
             SwappingStage::Saving(_, _) => write!(f, "Saving"),
             SwappingStage::Completing(_, _) => write!(f, "Completing"),
         }
@@ -85,6 +107,8 @@ impl FamiliarsSwapping {
     pub fn new(
         swappable_slots: SwappableFamiliars,
         swappable_rarities: Array<FamiliarRarity, 2>,
+        hover_settle_ticks: u32,
+        no_cards_outcome: NoSwappableCardsOutcome,
     ) -> Self {
         Self {
             stage: SwappingStage::OpenMenu(Timeout::default(), 0),
@@ -93,6 +117,9 @@ impl FamiliarsSwapping {
             swappable_slots,
             swappable_rarities,
             mouse_rest: Point::new(50, 50),
+            hover_settle_ticks: hover_settle_ticks.max(1),
+            no_cards_outcome,
+            no_cards_retry_count: 0,
         }
     }
 }
@@ -119,13 +146,18 @@ impl FamiliarsSwapping {
     }
 
     #[inline]
-    fn stage_free_slot(self, timeout: Timeout, index: usize) -> FamiliarsSwapping {
-        self.stage(SwappingStage::FreeSlot(timeout, index))
+    fn stage_free_slot(
+        self,
+        timeout: Timeout,
+        index: usize,
+        retry_count: u32,
+    ) -> FamiliarsSwapping {
+        self.stage(SwappingStage::FreeSlot(timeout, index, retry_count))
     }
 
     #[inline]
-    fn stage_swapping(self, timeout: Timeout, index: usize) -> FamiliarsSwapping {
-        self.stage(SwappingStage::Swapping(timeout, index))
+    fn stage_swapping(self, timeout: Timeout, index: usize, retry_count: u32) -> FamiliarsSwapping {
+        self.stage(SwappingStage::Swapping(timeout, index, retry_count))
     }
 
     #[inline]
@@ -176,16 +208,17 @@ pub fn update_familiars_swapping_context(
             SwappingStage::FreeSlots(index, was_freeing) => {
                 update_free_slots(context, swapping, index, was_freeing)
             }
-            SwappingStage::FreeSlot(timeout, index) => {
-                update_free_slot(context, swapping, timeout, index)
+            SwappingStage::FreeSlot(timeout, index, retry_count) => {
+                update_free_slot(context, swapping, timeout, index, retry_count)
             }
             SwappingStage::FindCards => update_find_cards(context, swapping),
-            SwappingStage::Swapping(timeout, index) => {
-                update_swapping(context, swapping, timeout, index)
+            SwappingStage::Swapping(timeout, index, retry_count) => {
+                update_swapping(context, swapping, timeout, index, retry_count)
             }
             SwappingStage::Scrolling(timeout, scrollbar, retry_count) => {
                 update_scrolling(context, swapping, timeout, scrollbar, retry_count)
             }
+            SwappingStage::WaitingForCards(timeout) => update_waiting_for_cards(swapping, timeout),
             SwappingStage::Saving(timeout, retry_count) => {
                 update_saving(context, swapping, timeout, retry_count)
             }
@@ -344,7 +377,7 @@ fn update_free_slots(
                     ..swapping.stage_open_menu(Timeout::default(), 0)
                 }
             } else {
-                swapping.stage_free_slot(Timeout::default(), index)
+                swapping.stage_free_slot(Timeout::default(), index, 0)
             }
         }
         (true, _) => unreachable!(),
@@ -356,20 +389,22 @@ fn update_free_slot(
     swapping: FamiliarsSwapping,
     timeout: Timeout,
     index: usize,
+    retry_count: u32,
 ) -> FamiliarsSwapping {
-    const FAMILIAR_FREE_SLOTS_TIMEOUT: u32 = 10;
-    const FAMILIAR_CHECK_FREE_TICK: u32 = FAMILIAR_FREE_SLOTS_TIMEOUT;
-    const FAMILIAR_CHECK_LVL_5_TICK: u32 = 5;
+    let familiar_check_lvl_5_tick = swapping.hover_settle_ticks;
+    let familiar_free_slots_timeout =
+        familiar_check_lvl_5_tick + HOVER_DETECT_LEVEL_TO_TIMEOUT_TICKS;
+    let familiar_check_free_tick = familiar_free_slots_timeout;
 
     update_with_timeout(
         timeout,
-        FAMILIAR_FREE_SLOTS_TIMEOUT,
+        familiar_free_slots_timeout,
         |timeout| {
             // On start, move mouse to hover over the familiar slot to check level
             let bbox = swapping.slots[index].0;
             let x = bbox.x + bbox.width / 2;
             let _ = context.keys.send_mouse(x, bbox.y + 20, MouseAction::Move);
-            swapping.stage_free_slot(timeout, index)
+            swapping.stage_free_slot(timeout, index, retry_count)
         },
         || swapping.stage_free_slots(index, true),
         |mut timeout| {
@@ -379,7 +414,7 @@ fn update_free_slot(
             let detector = context.detector_unwrap();
 
             match timeout.current {
-                FAMILIAR_CHECK_LVL_5_TICK => {
+                tick if tick == familiar_check_lvl_5_tick => {
                     match detector.detect_familiar_hover_level() {
                         Ok(FamiliarLevel::Level5) => {
                             // Double click to free
@@ -403,14 +438,22 @@ fn update_free_slot(
                                 swapping.stage_completing(Timeout::default(), false)
                             };
                         }
+                        // Tooltip might not have rendered yet, hover again
+                        Err(_) if retry_count < MAX_HOVER_RETRY => {
+                            return swapping.stage_free_slot(
+                                Timeout::default(),
+                                index,
+                                retry_count + 1,
+                            );
+                        }
                         // Could mean UI being closed
                         Err(_) => return swapping.stage_free_slots(index, true),
                     }
                 }
-                FAMILIAR_CHECK_FREE_TICK => {
+                tick if tick == familiar_check_free_tick => {
                     if detector.detect_familiar_slot_is_free(bbox) {
                         // If familiar is free, timeout and set flag
-                        timeout.current = FAMILIAR_FREE_SLOTS_TIMEOUT;
+                        timeout.current = familiar_free_slots_timeout;
                         swapping.slots[index].1 = true;
                     } else {
                         // After double clicking, previous slots will move forward so this loop
@@ -428,7 +471,7 @@ fn update_free_slot(
                 _ => (),
             }
 
-            swapping.stage_free_slot(timeout, index)
+            swapping.stage_free_slot(timeout, index, retry_count)
         },
     )
 }
@@ -454,7 +497,7 @@ fn update_find_cards(context: &Context, mut swapping: FamiliarsSwapping) -> Fami
         // Try scroll
         swapping.stage_scrolling(Timeout::default(), None, 0)
     } else {
-        swapping.stage_swapping(Timeout::default(), 0)
+        swapping.stage_swapping(Timeout::default(), 0, 0)
     }
 }
 
@@ -463,17 +506,18 @@ fn update_swapping(
     swapping: FamiliarsSwapping,
     timeout: Timeout,
     index: usize,
+    retry_count: u32,
 ) -> FamiliarsSwapping {
-    const SWAPPING_TIMEOUT: u32 = 10;
-    const SWAPPING_DETECT_LEVEL_TICK: u32 = 5;
+    let swapping_detect_level_tick = swapping.hover_settle_ticks;
+    let swapping_timeout = swapping_detect_level_tick + HOVER_DETECT_LEVEL_TO_TIMEOUT_TICKS;
 
     update_with_timeout(
         timeout,
-        SWAPPING_TIMEOUT,
+        swapping_timeout,
         |timeout| {
             let (x, y) = bbox_click_point(swapping.cards[index]);
             let _ = context.keys.send_mouse(x, y, MouseAction::Move);
-            swapping.stage_swapping(timeout, index)
+            swapping.stage_swapping(timeout, index, retry_count)
         },
         || {
             // Check free slot in timeout
@@ -490,7 +534,7 @@ fn update_swapping(
             } else if index + 1 < swapping.cards.len() {
                 // At least one slot is free and there are more cards. Could mean double click
                 // failed or familiar already level 5, advances either way.
-                swapping.stage_swapping(Timeout::default(), index + 1)
+                swapping.stage_swapping(Timeout::default(), index + 1, 0)
             } else {
                 // Try scroll for more cards
                 let rest = swapping.mouse_rest;
@@ -501,7 +545,7 @@ fn update_swapping(
         |timeout| {
             let rest = swapping.mouse_rest;
 
-            if timeout.current == SWAPPING_DETECT_LEVEL_TICK {
+            if timeout.current == swapping_detect_level_tick {
                 match context.detector_unwrap().detect_familiar_hover_level() {
                     Ok(FamiliarLevel::Level5) => {
                         // Move to rest position and wait for timeout
@@ -520,11 +564,19 @@ fn update_swapping(
                         if !context.detector_unwrap().detect_familiar_menu_opened() {
                             return swapping.stage_completing(Timeout::default(), false);
                         }
+                        // Tooltip might not have rendered yet, hover again
+                        if retry_count < MAX_HOVER_RETRY {
+                            return swapping.stage_swapping(
+                                Timeout::default(),
+                                index,
+                                retry_count + 1,
+                            );
+                        }
                     }
                 }
             }
 
-            swapping.stage_swapping(timeout, index)
+            swapping.stage_swapping(timeout, index, retry_count)
         },
     )
 }
@@ -552,7 +604,7 @@ fn update_scrolling(
         |timeout| {
             let Ok(scrollbar) = context.detector_unwrap().detect_familiar_scrollbar() else {
                 // TODO: recoverable?
-                return swapping.stage_completing(Timeout::default(), false);
+                return on_no_swappable_cards(context, swapping);
             };
 
             let (x, y) = bbox_click_point(scrollbar);
@@ -578,7 +630,7 @@ fn update_scrolling(
                 }
             }
 
-            swapping.stage_completing(Timeout::default(), false)
+            on_no_swappable_cards(context, swapping)
         },
         |timeout| {
             if timeout.current == SCROLLING_REST_TICK {
@@ -591,6 +643,44 @@ fn update_scrolling(
     )
 }
 
+/// Handles the case when there is no more swappable card after scrolling.
+///
+/// Depending on [`NoSwappableCardsOutcome`], it either completes, completes with a notification
+/// or waits for the cards list to populate before finding cards again.
+fn on_no_swappable_cards(context: &Context, swapping: FamiliarsSwapping) -> FamiliarsSwapping {
+    match swapping.no_cards_outcome {
+        NoSwappableCardsOutcome::Complete => swapping.stage_completing(Timeout::default(), false),
+        NoSwappableCardsOutcome::Notify => {
+            let _ = context
+                .notification
+                .schedule_notification(NotificationKind::FamiliarsNoSwappableCards);
+            swapping.stage_completing(Timeout::default(), false)
+        }
+        NoSwappableCardsOutcome::Retry if swapping.no_cards_retry_count < MAX_NO_CARDS_RETRY => {
+            debug!(target: "player", "no swappable familiar cards found, retrying...");
+            FamiliarsSwapping {
+                no_cards_retry_count: swapping.no_cards_retry_count + 1,
+                ..swapping.stage(SwappingStage::WaitingForCards(Timeout::default()))
+            }
+        }
+        NoSwappableCardsOutcome::Retry => swapping.stage_completing(Timeout::default(), false),
+    }
+}
+
+#[inline]
+fn update_waiting_for_cards(swapping: FamiliarsSwapping, timeout: Timeout) -> FamiliarsSwapping {
+    update_with_timeout(
+        timeout,
+        NO_CARDS_RETRY_DELAY_TICKS,
+        |timeout| swapping.stage(SwappingStage::WaitingForCards(timeout)),
+        || FamiliarsSwapping {
+            cards: Array::new(), // Reset cards array
+            ..swapping.stage(SwappingStage::FindCards)
+        },
+        |timeout| swapping.stage(SwappingStage::WaitingForCards(timeout)),
+    )
+}
+
 #[inline]
 fn update_saving(
     context: &Context,
@@ -684,7 +774,12 @@ mod tests {
     #[test]
     fn update_free_slots_advance_index_if_already_free() {
         let context = Context::new(None, None);
-        let mut swapping = FamiliarsSwapping::new(SwappableFamiliars::All, Array::new());
+        let mut swapping = FamiliarsSwapping::new(
+            SwappableFamiliars::All,
+            Array::new(),
+            5,
+            NoSwappableCardsOutcome::default(),
+        );
         let bbox = Default::default();
         swapping.slots.push((bbox, false));
         swapping.slots.push((bbox, true)); // Index 1 already free
@@ -699,7 +794,12 @@ mod tests {
         keys.expect_send_mouse().once().returning(|_, _, _| Ok(()));
         let context = Context::new(Some(keys), None);
 
-        let mut swapping = FamiliarsSwapping::new(SwappableFamiliars::All, Array::new());
+        let mut swapping = FamiliarsSwapping::new(
+            SwappableFamiliars::All,
+            Array::new(),
+            5,
+            NoSwappableCardsOutcome::default(),
+        );
         let bbox = Default::default();
         swapping.slots.push((bbox, true));
 
@@ -711,20 +811,30 @@ mod tests {
     #[test]
     fn update_free_slots_can_free() {
         let context = Context::new(None, None);
-        let mut swapping = FamiliarsSwapping::new(SwappableFamiliars::All, Array::new());
+        let mut swapping = FamiliarsSwapping::new(
+            SwappableFamiliars::All,
+            Array::new(),
+            5,
+            NoSwappableCardsOutcome::default(),
+        );
         let bbox = Default::default();
         swapping.slots.push((bbox, false));
         // Second slot not free but can free because of SwappableFamiliars::All
         swapping.slots.push((bbox, false));
 
         let result = update_free_slots(&context, swapping, 1, false);
-        assert_matches!(result.stage, SwappingStage::FreeSlot(_, 1));
+        assert_matches!(result.stage, SwappingStage::FreeSlot(_, 1, 0));
     }
 
     #[test]
     fn update_free_slots_cannot_free() {
         let context = Context::new(None, None);
-        let mut swapping = FamiliarsSwapping::new(SwappableFamiliars::Last, Array::new());
+        let mut swapping = FamiliarsSwapping::new(
+            SwappableFamiliars::Last,
+            Array::new(),
+            5,
+            NoSwappableCardsOutcome::default(),
+        );
         let bbox = Default::default();
         swapping.slots.push((bbox, false));
         // Second slot not free but also cannot free because of SwappableFamiliars::Last
@@ -748,7 +858,12 @@ mod tests {
             .returning(|| Ok(FamiliarLevel::Level5));
         let context = Context::new(Some(keys), Some(detector));
 
-        let mut swapping = FamiliarsSwapping::new(SwappableFamiliars::All, Array::new());
+        let mut swapping = FamiliarsSwapping::new(
+            SwappableFamiliars::All,
+            Array::new(),
+            5,
+            NoSwappableCardsOutcome::default(),
+        );
         let bbox = Default::default();
         swapping.slots.push((bbox, false));
 
@@ -757,8 +872,8 @@ mod tests {
             started: true,
             ..Default::default()
         };
-        let result = update_free_slot(&context, swapping, timeout, 0);
-        assert_matches!(result.stage, SwappingStage::FreeSlot(_, 0));
+        let result = update_free_slot(&context, swapping, timeout, 0, 0);
+        assert_matches!(result.stage, SwappingStage::FreeSlot(_, 0, 0));
     }
 
     #[test]
@@ -770,7 +885,12 @@ mod tests {
             .returning(|_| true);
         let context = Context::new(None, Some(detector));
 
-        let mut swapping = FamiliarsSwapping::new(SwappableFamiliars::All, Array::new());
+        let mut swapping = FamiliarsSwapping::new(
+            SwappableFamiliars::All,
+            Array::new(),
+            5,
+            NoSwappableCardsOutcome::default(),
+        );
         let bbox = Default::default();
         swapping.slots.push((bbox, false));
 
@@ -779,12 +899,44 @@ mod tests {
             started: true,
             ..Default::default()
         };
-        let result = update_free_slot(&context, swapping, timeout, 0);
+        let result = update_free_slot(&context, swapping, timeout, 0, 0);
         assert!(result.slots[0].1);
         assert_matches!(
             result.stage,
-            SwappingStage::FreeSlot(Timeout { current: 10, .. }, 0)
+            SwappingStage::FreeSlot(Timeout { current: 10, .. }, 0, 0)
+        );
+    }
+
+    #[test]
+    fn update_free_slot_detect_level_error_hover_again() {
+        let mut detector = MockDetector::default();
+        detector
+            .expect_detect_familiar_hover_level()
+            .returning(|| Err(anyhow::anyhow!("level not found")));
+        let context = Context::new(None, Some(detector));
+
+        let mut swapping = FamiliarsSwapping::new(
+            SwappableFamiliars::All,
+            Array::new(),
+            8,
+            NoSwappableCardsOutcome::default(),
+        );
+        let bbox = Default::default();
+        swapping.slots.push((bbox, false));
+
+        let timeout = Timeout {
+            current: 7, // One tick before detection
+            started: true,
+            ..Default::default()
+        };
+        let result = update_free_slot(&context, swapping, timeout, 0, 0);
+        assert_matches!(
+            result.stage,
+            SwappingStage::FreeSlot(Timeout { started: false, .. }, 0, 1)
         );
+
+        let result = update_free_slot(&context, swapping, timeout, 0, MAX_HOVER_RETRY);
+        assert_matches!(result.stage, SwappingStage::FreeSlots(0, true));
     }
 
     #[test]
@@ -798,7 +950,12 @@ mod tests {
             .returning(|| Ok(FamiliarLevel::Level5));
         let context = Context::new(Some(keys), Some(detector));
 
-        let mut swapping = FamiliarsSwapping::new(SwappableFamiliars::All, Array::new());
+        let mut swapping = FamiliarsSwapping::new(
+            SwappableFamiliars::All,
+            Array::new(),
+            5,
+            NoSwappableCardsOutcome::default(),
+        );
         let bbox = Default::default();
         swapping.cards.push(bbox);
 
@@ -807,7 +964,7 @@ mod tests {
             started: true,
             ..Default::default()
         };
-        update_swapping(&context, swapping, timeout, 0);
+        update_swapping(&context, swapping, timeout, 0, 0);
     }
 
     #[test]
@@ -823,7 +980,12 @@ mod tests {
             .returning(|| Ok(FamiliarLevel::LevelOther));
         let context = Context::new(Some(keys), Some(detector));
 
-        let mut swapping = FamiliarsSwapping::new(SwappableFamiliars::All, Array::new());
+        let mut swapping = FamiliarsSwapping::new(
+            SwappableFamiliars::All,
+            Array::new(),
+            5,
+            NoSwappableCardsOutcome::default(),
+        );
         let bbox = Default::default();
         swapping.cards.push(bbox);
 
@@ -832,7 +994,7 @@ mod tests {
             started: true,
             ..Default::default()
         };
-        update_swapping(&context, swapping, timeout, 0);
+        update_swapping(&context, swapping, timeout, 0, 0);
     }
 
     #[test]
@@ -844,7 +1006,12 @@ mod tests {
             .returning(|_| true);
         let context = Context::new(None, Some(detector));
 
-        let mut swapping = FamiliarsSwapping::new(SwappableFamiliars::All, Array::new());
+        let mut swapping = FamiliarsSwapping::new(
+            SwappableFamiliars::All,
+            Array::new(),
+            5,
+            NoSwappableCardsOutcome::default(),
+        );
         let bbox = Default::default();
         swapping.cards.push(bbox);
         swapping.cards.push(bbox);
@@ -858,8 +1025,8 @@ mod tests {
             ..Default::default()
         };
 
-        let result = update_swapping(&context, swapping, timeout, 0);
-        assert_matches!(result.stage, SwappingStage::Swapping(_, 1));
+        let result = update_swapping(&context, swapping, timeout, 0, 0);
+        assert_matches!(result.stage, SwappingStage::Swapping(_, 1, 0));
     }
 
     #[test]
@@ -873,7 +1040,12 @@ mod tests {
             .returning(|_| true);
         let context = Context::new(Some(keys), Some(detector));
 
-        let mut swapping = FamiliarsSwapping::new(SwappableFamiliars::All, Array::new());
+        let mut swapping = FamiliarsSwapping::new(
+            SwappableFamiliars::All,
+            Array::new(),
+            5,
+            NoSwappableCardsOutcome::default(),
+        );
         let bbox = Default::default();
         swapping.cards.push(bbox);
         for _ in 0..FAMILIAR_SLOTS {
@@ -886,10 +1058,46 @@ mod tests {
             ..Default::default()
         };
 
-        let result = update_swapping(&context, swapping, timeout, 0);
+        let result = update_swapping(&context, swapping, timeout, 0, 0);
         assert_matches!(result.stage, SwappingStage::Scrolling(_, None, 0));
     }
 
+    #[test]
+    fn update_scrolling_no_scrollbar_retry_after_delay() {
+        let mut detector = MockDetector::default();
+        detector
+            .expect_detect_familiar_scrollbar()
+            .returning(|| Err(anyhow::anyhow!("scrollbar not found")));
+        let context = Context::new(None, Some(detector));
+
+        let swapping = FamiliarsSwapping::new(
+            SwappableFamiliars::All,
+            Array::new(),
+            5,
+            NoSwappableCardsOutcome::Retry,
+        );
+        let result = update_scrolling(&context, swapping, Timeout::default(), None, 0);
+        assert_matches!(result.stage, SwappingStage::WaitingForCards(_));
+        assert_eq!(result.no_cards_retry_count, 1);
+
+        let result = update_waiting_for_cards(
+            result,
+            Timeout {
+                current: NO_CARDS_RETRY_DELAY_TICKS,
+                started: true,
+                ..Default::default()
+            },
+        );
+        assert_matches!(result.stage, SwappingStage::FindCards);
+
+        let swapping = FamiliarsSwapping {
+            no_cards_retry_count: MAX_NO_CARDS_RETRY,
+            ..swapping
+        };
+        let result = update_scrolling(&context, swapping, Timeout::default(), None, 0);
+        assert_matches!(result.stage, SwappingStage::Completing(_, false));
+    }
+
     #[test]
     fn update_saving_detect_and_click_save_button() {
         let mut keys = MockKeySender::default();
@@ -902,7 +1110,12 @@ mod tests {
             .returning(|| Ok(Default::default()));
 
         let context = Context::new(Some(keys), Some(detector));
-        let swapping = FamiliarsSwapping::new(SwappableFamiliars::All, Array::new());
+        let swapping = FamiliarsSwapping::new(
+            SwappableFamiliars::All,
+            Array::new(),
+            5,
+            NoSwappableCardsOutcome::default(),
+        );
 
         let timeout = Timeout::default();
         let result = update_saving(&context, swapping, timeout, 0);
@@ -925,7 +1138,12 @@ mod tests {
             .returning(|| Ok(Default::default()));
 
         let context = Context::new(Some(keys), Some(detector));
-        let swapping = FamiliarsSwapping::new(SwappableFamiliars::All, Array::new());
+        let swapping = FamiliarsSwapping::new(
+            SwappableFamiliars::All,
+            Array::new(),
+            5,
+            NoSwappableCardsOutcome::default(),
+        );
 
         let timeout = Timeout {
             current: 14, // PRESS_OK_AT
@@ -103,7 +103,9 @@ pub fn update_grappling_context(
                         }
                     }
                     PlayerAction::Key(_) | PlayerAction::Move(_) | PlayerAction::SolveRune => None,
-                    PlayerAction::Panic(_) | PlayerAction::FamiliarsSwapping(_) => unreachable!(),
+                    PlayerAction::Panic(_)
+                    | PlayerAction::FamiliarsSwapping(_)
+                    | PlayerAction::CollectDrops(_) => unreachable!(),
                 },
                 || Player::Grappling(moving),
             )
@@ -3,18 +3,28 @@ use opencv::core::Point;
 use platforms::windows::KeyKind;
 
 use super::{
-    Player, PlayerAction, PlayerActionAutoMob, PlayerActionKey, PlayerActionMove, PlayerState,
+    Player, PlayerAction, PlayerActionAutoMob, PlayerActionCollectDrops, PlayerActionKey,
+    PlayerActionMove, PlayerState,
     actions::{PlayerActionPingPong, on_action_state_mut, on_ping_pong_double_jump_action},
+    collect_drops::CollectingDrops,
     double_jump::DoubleJumping,
     familiars_swap::FamiliarsSwapping,
     moving::{Moving, find_intermediate_points},
     panic::Panicking,
-    use_key::UseKey,
+    solve_rune::rune_approach_point,
+    up_jump::UpJumping,
+    use_key::{UseKey, is_airborne},
 };
 use crate::{
     ActionKeyDirection, ActionKeyWith, Position, context::Context, minimap::Minimap, rng::Rng,
 };
 
+/// Minimum number of ticks without any action in [`Player::Idle`] before fidgeting.
+const IDLE_FIDGET_MIN_TICKS: u64 = 150;
+
+/// Maximum number of ticks without any action in [`Player::Idle`] before fidgeting.
+const IDLE_FIDGET_MAX_TICKS: u64 = 450;
+
 /// Updates [`Player::Idle`] contextual state.
 ///
 /// This state does not do much on its own except when auto mobbing. It acts as entry
@@ -27,6 +37,10 @@ pub fn update_idle_context(context: &Context, state: &mut PlayerState) -> Player
     let _ = context.keys.send_up(KeyKind::Down);
     let _ = context.keys.send_up(KeyKind::Left);
     let _ = context.keys.send_up(KeyKind::Right);
+    update_idle_fidget(context, state);
+    if state.config.wait_for_ground && !is_grounded(state) {
+        return Player::Idle;
+    }
 
     on_action_state_mut(
         state,
@@ -35,6 +49,50 @@ pub fn update_idle_context(context: &Context, state: &mut PlayerState) -> Player
     )
 }
 
+/// Taps a random movement key after a random number of ticks without any action.
+///
+/// The scheduled tick is reset whenever there is an action so that fidgeting never interferes
+/// with a pending action.
+#[inline]
+fn update_idle_fidget(context: &Context, state: &mut PlayerState) {
+    if !state.config.idle_fidget
+        || context.halting
+        || state.has_normal_action()
+        || state.has_priority_action()
+    {
+        state.idle_fidget_tick = None;
+        return;
+    }
+
+    let tick = *state.idle_fidget_tick.get_or_insert_with(|| {
+        context.tick
+            + context
+                .rng
+                .random_range(IDLE_FIDGET_MIN_TICKS..=IDLE_FIDGET_MAX_TICKS)
+    });
+    if context.tick < tick {
+        return;
+    }
+
+    let (key, direction) = if context.rng.random_bool(0.5) {
+        (KeyKind::Left, ActionKeyDirection::Left)
+    } else {
+        (KeyKind::Right, ActionKeyDirection::Right)
+    };
+    let _ = context.keys.send(key);
+    state.last_known_direction = direction;
+    state.idle_fidget_tick = None;
+}
+
+/// Checks whether the player has landed before starting the next action.
+///
+/// The player is considered grounded when stationary or when the y velocity is within the near
+/// stationary threshold.
+#[inline]
+fn is_grounded(state: &PlayerState) -> bool {
+    state.is_stationary || !is_airborne(state)
+}
+
 fn on_player_action(
     context: &Context,
     state: &mut PlayerState,
@@ -47,12 +105,15 @@ fn on_player_action(
             let intermediates = if state.config.auto_mob_platforms_pathing {
                 match context.minimap {
                     Minimap::Idle(idle) => find_intermediate_points(
+                        &context.rng,
                         &idle.platforms,
                         state.last_known_pos.unwrap(),
                         point,
                         position.allow_adjusting,
-                        state.config.auto_mob_platforms_pathing_up_jump_only,
+                        state.config.auto_mob_platforms_pathing_up_jump_only
+                            || state.config.grappling_key.is_none(),
                         false,
+                        state.config.platforms_pathing_walk_off_double_jump,
                     ),
                     _ => unreachable!(),
                 }
@@ -119,25 +180,52 @@ fn on_player_action(
         }
         PlayerAction::Key(PlayerActionKey {
             position: None,
-            with: ActionKeyWith::Any | ActionKeyWith::Stationary,
+            with: ActionKeyWith::UpJump,
+            direction,
+            ..
+        }) => {
+            if matches!(direction, ActionKeyDirection::Any)
+                || direction == state.last_known_direction
+            {
+                Some((
+                    Player::UpJumping(UpJumping::new(Moving::new(cur_pos, cur_pos, false, None))),
+                    false,
+                ))
+            } else {
+                Some((Player::UseKey(UseKey::from_action(action)), false))
+            }
+        }
+        PlayerAction::Key(PlayerActionKey {
+            position: None,
+            with: ActionKeyWith::Any | ActionKeyWith::Stationary | ActionKeyWith::Airborne,
             ..
         }) => Some((Player::UseKey(UseKey::from_action(action)), false)),
         PlayerAction::SolveRune => {
             if let Minimap::Idle(idle) = context.minimap
-                && let Some(rune) = idle.rune.value().copied()
+                && idle.has_rune()
             {
+                let side = state.config.rune_approach_side;
+                let runes = idle.runes_nearest_to(cur_pos);
+                let rune = rune_approach_point(side, runes[0]);
                 if state.config.rune_platforms_pathing {
                     if !state.is_stationary {
                         return Some((Player::Idle, false));
                     }
-                    let intermediates = find_intermediate_points(
-                        &idle.platforms,
-                        cur_pos,
-                        rune,
-                        true,
-                        state.config.rune_platforms_pathing_up_jump_only,
-                        true,
-                    );
+                    let up_jump_only = state.config.rune_platforms_pathing_up_jump_only
+                        || state.config.grappling_key.is_none();
+                    // Picks the nearest rune that is reachable through the platforms
+                    let intermediates = runes.into_iter().find_map(|rune| {
+                        find_intermediate_points(
+                            &context.rng,
+                            &idle.platforms,
+                            cur_pos,
+                            rune_approach_point(side, rune),
+                            true,
+                            up_jump_only,
+                            true,
+                            state.config.platforms_pathing_walk_off_double_jump,
+                        )
+                    });
                     if let Some(mut intermediates) = intermediates {
                         state.last_destinations = Some(
                             intermediates
@@ -164,10 +252,19 @@ fn on_player_action(
             Player::FamiliarsSwapping(FamiliarsSwapping::new(
                 swapping.swappable_slots,
                 swapping.swappable_rarities,
+                swapping.hover_settle_ticks,
+                swapping.no_swappable_cards_outcome,
             )),
             false,
         )),
         PlayerAction::Panic(panic) => Some((Player::Panicking(Panicking::new(panic.to)), false)),
+        PlayerAction::CollectDrops(PlayerActionCollectDrops { key }) => Some(
+            // Skips collecting when there is no platform to walk across
+            CollectingDrops::new(context, cur_pos, key.into())
+                .map_or((Player::Idle, true), |collecting| {
+                    (Player::CollectingDrops(collecting), false)
+                }),
+        ),
     }
 }
 
@@ -176,3 +273,104 @@ fn get_x_destination(rng: &Rng, position: Position) -> i32 {
     let x_max = position.x.saturating_add(position.x_random_range + 1);
     rng.random_range(x_min..x_max)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches::assert_matches;
+
+    use super::*;
+    use crate::{KeyBinding, bridge::MockKeySender};
+
+    #[test]
+    fn update_idle_fidget_taps_key_when_due() {
+        let mut keys = MockKeySender::default();
+        keys.expect_send()
+            .withf(|key| matches!(key, KeyKind::Left | KeyKind::Right))
+            .once()
+            .returning(|_| Ok(()));
+        let mut context = Context::new(Some(keys), None);
+        context.tick = 10;
+        let mut state = PlayerState::default();
+        state.config.idle_fidget = true;
+        state.idle_fidget_tick = Some(10);
+
+        update_idle_fidget(&context, &mut state);
+
+        assert!(state.idle_fidget_tick.is_none());
+        assert_ne!(state.last_known_direction, ActionKeyDirection::Any);
+    }
+
+    #[test]
+    fn update_idle_fidget_resets_with_action() {
+        let mut keys = MockKeySender::default();
+        keys.expect_send().never();
+        let context = Context::new(Some(keys), None);
+        let mut state = PlayerState::default();
+        state.config.idle_fidget = true;
+        state.idle_fidget_tick = Some(0);
+        state.set_normal_action(0, PlayerAction::SolveRune);
+
+        update_idle_fidget(&context, &mut state);
+
+        assert!(state.idle_fidget_tick.is_none());
+    }
+
+    #[test]
+    fn update_idle_fidget_schedules_tick() {
+        let mut keys = MockKeySender::default();
+        keys.expect_send().never();
+        let context = Context::new(Some(keys), None);
+        let mut state = PlayerState::default();
+        state.config.idle_fidget = true;
+
+        update_idle_fidget(&context, &mut state);
+
+        assert_matches!(
+            state.idle_fidget_tick,
+            Some(IDLE_FIDGET_MIN_TICKS..=IDLE_FIDGET_MAX_TICKS)
+        );
+    }
+
+    #[test]
+    fn update_idle_context_wait_for_ground() {
+        let mut keys = MockKeySender::default();
+        keys.expect_send_up().returning(|_| Ok(()));
+        let context = Context::new(Some(keys), None);
+        let mut state = PlayerState::default();
+        state.config.wait_for_ground = true;
+        state.config.near_stationary_y_velocity_threshold = 0.5;
+        state.last_known_pos = Some(Point::new(5, 5));
+        state.velocity = (0.0, 1.0);
+        state.set_normal_action(
+            0,
+            PlayerAction::Key(PlayerActionKey {
+                key: KeyBinding::A,
+                link_key: None,
+                count: 1,
+                position: None,
+                strict_position: false,
+                direction: ActionKeyDirection::Any,
+                require_direction: None,
+                require_buff: None,
+                probability: None,
+                with: ActionKeyWith::Any,
+                use_while_falling: false,
+                wait_before_use_ticks: 0,
+                wait_before_use_ticks_random_range: 0,
+                wait_after_use_ticks: 0,
+                wait_after_use_ticks_random_range: 0,
+                linked_wait_ticks: 0,
+            }),
+        );
+
+        // (1) Stays idle while airborne
+        let next = update_idle_context(&context, &mut state);
+        assert_matches!(next, Player::Idle);
+        assert!(state.has_normal_action());
+
+        // (2) Starts the action once grounded
+        state.velocity = (0.0, 0.2);
+        let next = update_idle_context(&context, &mut state);
+        assert_matches!(next, Player::UseKey(_));
+    }
+}
@@ -1,13 +1,23 @@
 use super::{
-    Player, PlayerState,
+    JUMP_THRESHOLD, Player, PlayerAction, PlayerActionKey, PlayerState,
+    actions::on_action_state,
     moving::{MOVE_TIMEOUT, Moving},
     state::LastMovement,
     timeout::{ChangeAxis, update_moving_axis_context},
+    use_key::{UseKey, is_airborne},
 };
-use crate::context::Context;
+use crate::{ActionKeyWith, context::Context};
 
 const TIMEOUT: u32 = MOVE_TIMEOUT + 3;
 
+/// Maximum x distance from the destination allowed to transition to [`Player::UseKey`] for a
+/// [`PlayerAction::Key`] with [`ActionKeyWith::Airborne`].
+const USE_KEY_X_THRESHOLD: i32 = 3;
+
+/// Maximum y distance from the destination allowed to transition to [`Player::UseKey`] for a
+/// [`PlayerAction::Key`] with [`ActionKeyWith::Airborne`].
+const USE_KEY_Y_THRESHOLD: i32 = JUMP_THRESHOLD;
+
 pub fn update_jumping_context(
     context: &Context,
     state: &mut PlayerState,
@@ -17,16 +27,187 @@ pub fn update_jumping_context(
         state.last_movement = Some(LastMovement::Jumping);
     }
 
+    let key = state.config.jump_key;
+    let hold_ticks = state.config.jump_hold_ticks;
     update_moving_axis_context(
         moving,
         state.last_known_pos.unwrap(),
         TIMEOUT,
         |moving| {
-            let _ = context.keys.send(state.config.jump_key);
+            if hold_ticks > 0 {
+                let _ = context.keys.send_down(key);
+            } else {
+                let _ = context.keys.send(key);
+            }
             Player::Jumping(moving)
         },
-        None::<fn()>,
-        Player::Jumping,
+        Some(|| {
+            if hold_ticks > 0 {
+                let _ = context.keys.send_up(key);
+            }
+        }),
+        |moving| {
+            if hold_ticks > 0 && moving.timeout.total == hold_ticks {
+                let _ = context.keys.send_up(key);
+            }
+
+            let next = on_action_state(
+                state,
+                |state, action| on_player_action(state, action, moving),
+                || Player::Jumping(moving),
+            );
+            // Releases the held jump key early since it is not released after transitioning
+            if hold_ticks > 0
+                && moving.timeout.total < hold_ticks
+                && matches!(next, Player::UseKey(_))
+            {
+                let _ = context.keys.send_up(key);
+            }
+            next
+        },
         ChangeAxis::Vertical,
     )
 }
+
+/// Handles [`PlayerAction`] during jump.
+///
+/// It currently only handles a key action with [`ActionKeyWith::Airborne`] by transitioning to
+/// [`Player::UseKey`] once the player is airborne and close enough to the destination.
+#[inline]
+fn on_player_action(
+    state: &PlayerState,
+    action: PlayerAction,
+    moving: Moving,
+) -> Option<(Player, bool)> {
+    match action {
+        PlayerAction::Key(PlayerActionKey {
+            with: ActionKeyWith::Airborne,
+            ..
+        }) => {
+            let cur_pos = state.last_known_pos.unwrap();
+            let (x_distance, _) = moving.x_distance_direction_from(false, cur_pos);
+            let (y_distance, _) = moving.y_distance_direction_from(false, cur_pos);
+            if !is_airborne(state)
+                || x_distance > USE_KEY_X_THRESHOLD
+                || y_distance > USE_KEY_Y_THRESHOLD
+            {
+                return None;
+            }
+            Some((Player::UseKey(UseKey::from_action(action)), false))
+        }
+        PlayerAction::Key(_)
+        | PlayerAction::AutoMob(_)
+        | PlayerAction::PingPong(_)
+        | PlayerAction::Move(_)
+        | PlayerAction::SolveRune
+        | PlayerAction::Panic(_)
+        | PlayerAction::FamiliarsSwapping(_)
+        | PlayerAction::CollectDrops(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches::assert_matches;
+
+    use opencv::core::Point;
+    use platforms::windows::KeyKind;
+
+    use super::*;
+    use crate::{ActionKeyDirection, KeyBinding, bridge::MockKeySender, player::timeout::Timeout};
+
+    #[test]
+    fn update_jumping_context_airborne_use_key() {
+        let pos = Point::new(5, 5);
+        let moving = Moving {
+            pos,
+            dest: pos,
+            timeout: Timeout {
+                started: true,
+                total: 2,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let action = PlayerActionKey {
+            key: KeyBinding::A,
+            link_key: None,
+            count: 1,
+            position: None,
+            strict_position: false,
+            direction: ActionKeyDirection::Any,
+            require_direction: None,
+            require_buff: None,
+            probability: None,
+            with: ActionKeyWith::Airborne,
+            use_while_falling: false,
+            wait_before_use_ticks: 0,
+            wait_before_use_ticks_random_range: 0,
+            wait_after_use_ticks: 0,
+            wait_after_use_ticks_random_range: 0,
+            linked_wait_ticks: 0,
+        };
+        let context = Context::new(Some(MockKeySender::new()), None);
+        let mut state = PlayerState::default();
+        state.last_known_pos = Some(pos);
+        state.set_normal_action(0, PlayerAction::Key(action));
+
+        // (1) Does not use key when not airborne
+        let player = update_jumping_context(&context, &mut state, moving);
+        assert_matches!(player, Player::Jumping(_));
+
+        // (2) Uses key when airborne
+        state.velocity = (0.0, 1.0);
+        let player = update_jumping_context(&context, &mut state, moving);
+        assert_matches!(player, Player::UseKey(_));
+    }
+
+    #[test]
+    fn update_jumping_context_airborne_use_key_release_held_jump() {
+        let pos = Point::new(5, 5);
+        let moving = Moving {
+            pos,
+            dest: pos,
+            timeout: Timeout {
+                started: true,
+                total: 2,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let action = PlayerActionKey {
+            key: KeyBinding::A,
+            link_key: None,
+            count: 1,
+            position: None,
+            strict_position: false,
+            direction: ActionKeyDirection::Any,
+            require_direction: None,
+            require_buff: None,
+            probability: None,
+            with: ActionKeyWith::Airborne,
+            use_while_falling: false,
+            wait_before_use_ticks: 0,
+            wait_before_use_ticks_random_range: 0,
+            wait_after_use_ticks: 0,
+            wait_after_use_ticks_random_range: 0,
+            linked_wait_ticks: 0,
+        };
+        let mut keys = MockKeySender::new();
+        keys.expect_send_up()
+            .withf(|key| matches!(key, KeyKind::Space))
+            .once()
+            .returning(|_| Ok(()));
+        let context = Context::new(Some(keys), None);
+        let mut state = PlayerState::default();
+        state.config.jump_key = KeyKind::Space;
+        state.config.jump_hold_ticks = 5;
+        state.last_known_pos = Some(pos);
+        state.velocity = (0.0, 1.0);
+        state.set_normal_action(0, PlayerAction::Key(action));
+
+        // Releases the held jump key before using key
+        let player = update_jumping_context(&context, &mut state, moving);
+        assert_matches!(player, Player::UseKey(_));
+    }
+}
@@ -1,6 +1,8 @@
 use actions::{on_action, on_action_state_mut};
 use adjust::update_adjusting_context;
 use cash_shop::{CashShop, update_cash_shop_context};
+use clean_ui::{CleaningUi, update_cleaning_ui_context};
+use collect_drops::{CollectingDrops, update_collecting_drops_context};
 use double_jump::{DoubleJumping, update_double_jumping_context};
 use fall::update_falling_context;
 use familiars_swap::{FamiliarsSwapping, update_familiars_swapping_context};
@@ -11,6 +13,7 @@ use moving::{MOVE_TIMEOUT, Moving, MovingIntermediates, update_moving_context};
 use opencv::core::Point;
 use panic::update_panicking_context;
 use platforms::windows::KeyKind;
+use recover::update_recovering_context;
 use solve_rune::{SolvingRune, update_solving_rune_context};
 use stall::update_stalling_context;
 use state::LastMovement;
@@ -24,11 +27,14 @@ use crate::{
     context::{Context, Contextual, ControlFlow},
     database::ActionKeyDirection,
     minimap::Minimap,
+    network::NotificationKind,
 };
 
 mod actions;
 mod adjust;
 mod cash_shop;
+mod clean_ui;
+mod collect_drops;
 mod double_jump;
 mod fall;
 mod familiars_swap;
@@ -37,6 +43,7 @@ mod idle;
 mod jump;
 mod moving;
 mod panic;
+mod recover;
 mod solve_rune;
 mod stall;
 mod state;
@@ -47,10 +54,11 @@ mod use_key;
 
 pub use {
     actions::PanicTo, actions::PingPongDirection, actions::PlayerAction,
-    actions::PlayerActionAutoMob, actions::PlayerActionFamiliarsSwapping, actions::PlayerActionKey,
-    actions::PlayerActionMove, actions::PlayerActionPanic, actions::PlayerActionPingPong,
-    double_jump::DOUBLE_JUMP_THRESHOLD, grapple::GRAPPLING_MAX_THRESHOLD,
-    grapple::GRAPPLING_THRESHOLD, panic::Panicking, state::PlayerState,
+    actions::PlayerActionAutoMob, actions::PlayerActionCollectDrops,
+    actions::PlayerActionFamiliarsSwapping, actions::PlayerActionKey, actions::PlayerActionMove,
+    actions::PlayerActionPanic, actions::PlayerActionPingPong, double_jump::DOUBLE_JUMP_THRESHOLD,
+    grapple::GRAPPLING_MAX_THRESHOLD, grapple::GRAPPLING_THRESHOLD, panic::Panicking,
+    state::PlayerConfiguration, state::PlayerState,
 };
 
 /// Minimum y distance from the destination required to perform a jump.
@@ -93,6 +101,12 @@ pub enum Player {
     #[strum(to_string = "FamiliarsSwapping({0})")]
     FamiliarsSwapping(FamiliarsSwapping),
     Panicking(Panicking),
+    /// Walks across the current platform while pressing the pick up key.
+    CollectingDrops(CollectingDrops),
+    /// Closes any known opened menus before the actions start rotating.
+    CleaningUi(CleaningUi),
+    /// Tries to recover the player position after it has not been detected for a while.
+    Recovering(Timeout),
 }
 
 impl Player {
@@ -101,7 +115,7 @@ impl Player {
         const OVERRIDABLE_DISTANCE: i32 = DOUBLE_JUMP_THRESHOLD / 2;
 
         match self {
-            Player::Detecting | Player::Idle => true,
+            Player::Detecting | Player::Idle | Player::CollectingDrops(_) => true,
             Player::Moving(dest, _, _) => {
                 if let Some(pos) = cur_pos {
                     (dest.x - pos.x).abs() >= OVERRIDABLE_DISTANCE
@@ -130,6 +144,8 @@ impl Player {
             | Player::UseKey(_)
             | Player::FamiliarsSwapping(_)
             | Player::Panicking(_)
+            | Player::CleaningUi(_)
+            | Player::Recovering(_)
             | Player::Stalling(_, _) => false,
         }
     }
@@ -152,6 +168,11 @@ impl Contextual for Player {
                 CashShop::Entering,
             ));
         }
+        if state.clean_ui_next_update {
+            state.clean_ui_next_update = false;
+            state.reset_to_idle_next_update = false;
+            return ControlFlow::Next(Player::CleaningUi(CleaningUi::default()));
+        }
 
         let has_position = if state.ignore_pos_update {
             state.last_known_pos.is_some()
@@ -169,9 +190,24 @@ impl Contextual for Player {
             // `update_non_positional_context` is here to continue updating
             // `Player::Unstucking` returned from below when the player
             // is inside the edges of the minimap. And also `Player::CashShopThenExit`.
+            let should_recover = matches!(self, Player::Detecting | Player::Unstucking(_, _, _))
+                && !context.halting
+                && state.track_lost_position();
             if let Some(next) = update_non_positional_context(self, context, state, true) {
                 return ControlFlow::Next(next);
             }
+            // Player has been lost for too long even after unstucking, possibly due to some
+            // undetectable positions or UIs that unstucking cannot close
+            if should_recover {
+                if state.track_lost_position_recovery() {
+                    let _ = context
+                        .notification
+                        .schedule_notification(NotificationKind::LostPositionRecoveryFailed);
+                }
+                let _ = context.keys.send_up(KeyKind::Left);
+                let _ = context.keys.send_up(KeyKind::Right);
+                return ControlFlow::Next(Player::Recovering(Timeout::default()));
+            }
             let next = if !context.halting
                 && let Minimap::Idle(idle) = context.minimap
                 && !idle.partially_overlapping
@@ -189,6 +225,7 @@ impl Contextual for Player {
             }
             return ControlFlow::Next(next);
         };
+        state.clear_lost_position();
 
         let contextual = if state.reset_to_idle_next_update {
             Player::Idle
@@ -245,6 +282,8 @@ fn update_non_positional_context(
             failed_to_detect_player,
         )),
         Player::Panicking(panicking) => Some(update_panicking_context(context, state, panicking)),
+        Player::CleaningUi(cleaning) => Some(update_cleaning_ui_context(context, cleaning)),
+        Player::Recovering(timeout) => Some(update_recovering_context(context, state, timeout)),
         Player::Detecting
         | Player::Idle
         | Player::Moving(_, _, _)
@@ -253,7 +292,8 @@ fn update_non_positional_context(
         | Player::Grappling(_)
         | Player::Jumping(_)
         | Player::UpJumping(_)
-        | Player::Falling(_, _, _) => None,
+        | Player::Falling(_, _, _)
+        | Player::CollectingDrops(_) => None,
     }
 }
 
@@ -280,12 +320,17 @@ fn update_positional_context(
         Player::Falling(moving, anchor, timeout_on_complete) => {
             update_falling_context(context, state, moving, anchor, timeout_on_complete)
         }
+        Player::CollectingDrops(collecting) => {
+            update_collecting_drops_context(context, state, collecting)
+        }
         Player::UseKey(_)
         | Player::Unstucking(_, _, _)
         | Player::Stalling(_, _)
         | Player::SolvingRune(_)
         | Player::FamiliarsSwapping(_)
         | Player::Panicking(_)
+        | Player::CleaningUi(_)
+        | Player::Recovering(_)
         | Player::CashShopThenExit(_, _) => unreachable!(),
     }
 }
@@ -22,6 +22,7 @@ use crate::{
         solve_rune::SolvingRune,
         use_key::UseKey,
     },
+    rng::Rng,
 };
 
 /// Maximum amount of ticks a change in x or y direction must be detected.
@@ -29,6 +30,9 @@ pub const MOVE_TIMEOUT: u32 = 5;
 
 const UP_JUMP_THRESHOLD: i32 = 10;
 
+/// Number of ticks to hold the direction key to walk off the platform edge before double jumping.
+const WALK_OFF_TICKS: u32 = 5;
+
 /// Intermediate points to move by.
 ///
 /// The last point is the destination.
@@ -239,7 +243,8 @@ impl Moving {
 /// or [`Player::Falling`] for matching `y` of `dest`. (e.g. horizontal then vertical)
 ///
 /// In auto mob or intermediate destination, most of the movement thresholds are relaxed for
-/// more fluid movement.
+/// more fluid movement. The double jump, adjusting and jump thresholds are also scaled by the
+/// configured movement speed factor.
 pub fn update_moving_context(
     context: &Context,
     state: &mut PlayerState,
@@ -256,16 +261,29 @@ pub fn update_moving_context(
         );
     }
 
+    if let Some(key) = state.take_move_fire_key() {
+        for _ in 0..key.count {
+            let _ = context.keys.send(key.key.into());
+        }
+    }
+
     let cur_pos = state.last_known_pos.unwrap();
     let disable_adjusting = state.config.disable_adjusting;
     let moving = Moving::new(cur_pos, dest, exact, intermediates);
     let (x_distance, _) = moving.x_distance_direction_from(true, cur_pos);
     let (y_distance, y_direction) = moving.y_distance_direction_from(true, cur_pos);
-    let skip_destination = moving.auto_mob_can_skip_current_destination(state);
+    let arrival_tolerance = state.config.arrival_tolerance;
+    let arrived = !exact
+        && arrival_tolerance > 0
+        && x_distance <= arrival_tolerance
+        && y_distance <= arrival_tolerance;
+    let skip_destination = arrived || moving.auto_mob_can_skip_current_destination(state);
     let is_intermediate = moving.is_destination_intermediate();
 
     match (skip_destination, x_distance, y_direction, y_distance) {
-        (false, d, _, _) if d >= state.double_jump_threshold(is_intermediate) => {
+        (false, d, _, _)
+            if d >= state.movement_threshold(state.double_jump_threshold(is_intermediate)) =>
+        {
             let require_stationary = state.has_ping_pong_action_only()
                 && !matches!(
                     state.last_movement,
@@ -279,8 +297,11 @@ pub fn update_moving_context(
         }
         // Allows disabling adjusting only if `exact` is false
         (false, d, _, _)
-            if (!disable_adjusting && d >= ADJUSTING_MEDIUM_THRESHOLD)
-                || (exact && d >= ADJUSTING_SHORT_THRESHOLD) =>
+            if (!disable_adjusting
+                && d >= state.movement_threshold(ADJUSTING_MEDIUM_THRESHOLD))
+                || (exact
+                    && d >= ADJUSTING_SHORT_THRESHOLD
+                    && !state.is_adjust_corrections_exhausted(dest)) =>
         {
             abort_action_on_state_repeat(Player::Adjusting(moving), context, state)
         }
@@ -292,11 +313,13 @@ pub fn update_moving_context(
             abort_action_on_state_repeat(Player::Grappling(moving), context, state)
         }
         (false, _, y, d) if y > 0 && d >= UP_JUMP_THRESHOLD => {
-            // In auto mob with platforms pathing and up jump only, immediately aborts the action
-            // if there are no intermediate points and the distance is too big to up jump.
+            // In auto mob with platforms pathing and up jump only (or without grappling key),
+            // immediately aborts the action if there are no intermediate points and the distance
+            // is too big to up jump.
             if state.has_auto_mob_action_only()
                 && state.config.auto_mob_platforms_pathing
-                && state.config.auto_mob_platforms_pathing_up_jump_only
+                && (state.config.auto_mob_platforms_pathing_up_jump_only
+                    || state.config.grappling_key.is_none())
                 && intermediates.is_none()
                 && d >= GRAPPLING_THRESHOLD
             {
@@ -306,7 +329,7 @@ pub fn update_moving_context(
             }
             abort_action_on_state_repeat(Player::UpJumping(UpJumping::new(moving)), context, state)
         }
-        (false, _, y, d) if y > 0 && d >= JUMP_THRESHOLD => {
+        (false, _, y, d) if y > 0 && d >= state.movement_threshold(JUMP_THRESHOLD) => {
             abort_action_on_state_repeat(Player::Jumping(moving), context, state)
         }
         // this probably won't work if the platforms are far apart,
@@ -340,6 +363,25 @@ pub fn update_moving_context(
                     let _ = context.keys.send_down(key);
                     return Player::Stalling(Timeout::default(), 3);
                 }
+                if matches!(
+                    moving.intermediate_hint(),
+                    Some(MovementHint::WalkOffAndDoubleJump)
+                ) {
+                    // Keeps holding the direction key to walk off the edge and double jumps
+                    // once falling
+                    state.stalling_timeout_state = Some(Player::DoubleJumping(DoubleJumping::new(
+                        Moving::new(cur_pos, dest, exact, Some(intermediates)),
+                        false,
+                        false,
+                    )));
+                    let key = if dest.x - cur_pos.x >= 0 {
+                        KeyKind::Right
+                    } else {
+                        KeyKind::Left
+                    };
+                    let _ = context.keys.send_down(key);
+                    return Player::Stalling(Timeout::default(), WALK_OFF_TICKS);
+                }
 
                 return Player::Moving(dest, exact, Some(intermediates));
             }
@@ -347,7 +389,7 @@ pub fn update_moving_context(
             let last_known_direction = state.last_known_direction;
             on_action(
                 state,
-                |action| on_player_action(last_known_direction, action, moving),
+                |action| on_player_action(context, last_known_direction, action, moving),
                 || Player::Idle,
             )
         }
@@ -373,6 +415,7 @@ fn abort_action_on_state_repeat(
 }
 
 fn on_player_action(
+    context: &Context,
     last_known_direction: ActionKeyDirection,
     action: PlayerAction,
     moving: Moving,
@@ -380,13 +423,20 @@ fn on_player_action(
     match action {
         PlayerAction::Move(PlayerActionMove {
             wait_after_move_ticks,
+            wait_after_move_ticks_random_range,
+            attack_on_arrival,
             ..
         }) => {
-            if wait_after_move_ticks > 0 {
-                Some((
-                    Player::Stalling(Timeout::default(), wait_after_move_ticks),
-                    false,
-                ))
+            if let Some(key) = attack_on_arrival {
+                let _ = context.keys.send(key.into());
+            }
+            let wait_after_min =
+                wait_after_move_ticks.saturating_sub(wait_after_move_ticks_random_range);
+            let wait_after_max =
+                wait_after_move_ticks.saturating_add(wait_after_move_ticks_random_range + 1);
+            let wait_after = context.rng.random_range(wait_after_min..wait_after_max);
+            if wait_after > 0 {
+                Some((Player::Stalling(Timeout::default(), wait_after), false))
             } else {
                 Some((Player::Idle, true))
             }
@@ -406,7 +456,22 @@ fn on_player_action(
             }
         }
         PlayerAction::Key(PlayerActionKey {
-            with: ActionKeyWith::Any | ActionKeyWith::Stationary,
+            with: ActionKeyWith::UpJump,
+            direction,
+            ..
+        }) => {
+            if matches!(direction, ActionKeyDirection::Any) || direction == last_known_direction {
+                let pos = moving.pos;
+                Some((
+                    Player::UpJumping(UpJumping::new(Moving::new(pos, pos, false, None))),
+                    false,
+                ))
+            } else {
+                Some((Player::UseKey(UseKey::from_action(action)), false))
+            }
+        }
+        PlayerAction::Key(PlayerActionKey {
+            with: ActionKeyWith::Any | ActionKeyWith::Stationary | ActionKeyWith::Airborne,
             ..
         }) => Some((Player::UseKey(UseKey::from_action(action)), false)),
         PlayerAction::AutoMob(_) => Some((
@@ -415,18 +480,27 @@ fn on_player_action(
         )),
         PlayerAction::SolveRune => Some((Player::SolvingRune(SolvingRune::default()), false)),
         PlayerAction::PingPong(_) => Some((Player::Idle, true)),
-        PlayerAction::Panic(_) | PlayerAction::FamiliarsSwapping(_) => unreachable!(),
+        PlayerAction::Panic(_)
+        | PlayerAction::FamiliarsSwapping(_)
+        | PlayerAction::CollectDrops(_) => unreachable!(),
     }
 }
 
+/// Finds the intermediate points to move to `dest` using the platforms.
+///
+/// When `up_jump_only` is true (e.g. there is no grappling key), the vertical distance between
+/// two consecutive points is limited to what an up jump can reach.
 #[inline]
+#[allow(clippy::too_many_arguments)]
 pub fn find_intermediate_points(
+    rng: &Rng,
     platforms: &Array<PlatformWithNeighbors, MAX_PLATFORMS_COUNT>,
     cur_pos: Point,
     dest: Point,
     exact: bool,
     up_jump_only: bool,
     enable_hint: bool,
+    enable_walk_off_hint: bool,
 ) -> Option<MovingIntermediates> {
     let vertical_threshold = if up_jump_only {
         GRAPPLING_THRESHOLD
@@ -434,10 +508,12 @@ pub fn find_intermediate_points(
         GRAPPLING_MAX_THRESHOLD
     };
     let vec = find_points_with(
+        rng,
         platforms,
         cur_pos,
         dest,
         enable_hint,
+        enable_walk_off_hint,
         DOUBLE_JUMP_THRESHOLD,
         JUMP_THRESHOLD,
         vertical_threshold,
@@ -1,17 +1,31 @@
+use log::debug;
+use opencv::core::Rect;
 use platforms::windows::KeyKind;
 
 use super::{
     Player, PlayerState,
     actions::{PanicTo, on_action},
+    state::PlayerConfiguration,
     timeout::{Timeout, update_with_timeout},
 };
-use crate::{bridge::MouseAction, context::Context, minimap::Minimap};
+use crate::{
+    ChangeChannelBlockedFallback,
+    bridge::MouseAction,
+    context::Context,
+    minimap::{Minimap, OtherPlayerFilter},
+    network::NotificationKind,
+};
 
 const MAX_RETRY: u32 = 4;
 
 /// Stages of panicking mode.
 #[derive(Debug, Clone, Copy)]
 enum PanickingStage {
+    /// Waits for the change channel menu to appear before cycling through channels.
+    ///
+    /// The first [`u32`] is the number of change channel key presses and the second is the
+    /// channel change retry count.
+    OpeningChannelMenu(Timeout, u32, u32),
     /// Cycling through channels.
     ChangingChannel(Timeout, u32),
     /// Going to town.
@@ -29,13 +43,26 @@ impl Panicking {
     pub fn new(to: PanicTo) -> Self {
         Self {
             stage: match to {
-                PanicTo::Channel => PanickingStage::ChangingChannel(Timeout::default(), 0),
+                PanicTo::Channel => PanickingStage::OpeningChannelMenu(Timeout::default(), 0, 0),
                 PanicTo::Town => PanickingStage::GoingToTown(Timeout::default(), 0),
             },
             to,
         }
     }
 
+    #[inline]
+    fn stage_opening_channel_menu(
+        self,
+        timeout: Timeout,
+        press_count: u32,
+        retry_count: u32,
+    ) -> Panicking {
+        Panicking {
+            stage: PanickingStage::OpeningChannelMenu(timeout, press_count, retry_count),
+            ..self
+        }
+    }
+
     #[inline]
     fn stage_changing_channel(self, timeout: Timeout, retry_count: u32) -> Panicking {
         Panicking {
@@ -68,6 +95,16 @@ pub fn update_panicking_context(
     panicking: Panicking,
 ) -> Player {
     let panicking = match panicking.stage {
+        PanickingStage::OpeningChannelMenu(timeout, press_count, retry_count) => {
+            update_opening_channel_menu(
+                context,
+                &state.config,
+                panicking,
+                timeout,
+                press_count,
+                retry_count,
+            )
+        }
         PanickingStage::ChangingChannel(timeout, retry_count) => update_changing_channel(
             context,
             state.config.change_channel_key,
@@ -82,9 +119,13 @@ pub fn update_panicking_context(
             timeout,
             retry_count,
         ),
-        PanickingStage::Completing(timeout, completed) => {
-            update_completing(context, panicking, timeout, completed)
-        }
+        PanickingStage::Completing(timeout, completed) => update_completing(
+            context,
+            state.config.panic_other_player_filter,
+            panicking,
+            timeout,
+            completed,
+        ),
     };
     let next = if matches!(panicking.stage, PanickingStage::Completing(_, true)) {
         Player::Idle
@@ -99,6 +140,65 @@ pub fn update_panicking_context(
     )
 }
 
+/// Updates the opening channel menu stage.
+///
+/// It waits for [`PlayerConfiguration::change_channel_menu_wait_ticks`] before checking whether
+/// the change channel menu has appeared. If not, it presses the change channel key and waits
+/// again for up to [`PlayerConfiguration::change_channel_menu_max_retries`] presses. Once the menu
+/// is confirmed opened, it transitions to [`PanickingStage::ChangingChannel`]. Otherwise, the
+/// channel change is retried from the start for up to [`MAX_RETRY`] times.
+///
+/// If a dialog appears instead of the menu after pressing the key, the map is assumed to block
+/// channel change. The dialog is dismissed and
+/// [`PlayerConfiguration::change_channel_blocked_fallback`] is performed instead.
+fn update_opening_channel_menu(
+    context: &Context,
+    config: &PlayerConfiguration,
+    panicking: Panicking,
+    timeout: Timeout,
+    press_count: u32,
+    retry_count: u32,
+) -> Panicking {
+    update_with_timeout(
+        timeout,
+        config.change_channel_menu_wait_ticks.max(1),
+        |timeout| panicking.stage_opening_channel_menu(timeout, press_count, retry_count),
+        || {
+            if !matches!(context.minimap, Minimap::Idle(_)) {
+                return panicking.stage_completing(Timeout::default(), false);
+            }
+            if context
+                .detector_unwrap()
+                .detect_change_channel_menu_opened()
+            {
+                return panicking.stage_changing_channel(Timeout::default(), retry_count);
+            }
+            if press_count > 0 && dismiss_unexpected_dialog(context) {
+                debug!(target: "player", "change channel blocked, falling back");
+                return match config.change_channel_blocked_fallback {
+                    ChangeChannelBlockedFallback::Town => Panicking::new(PanicTo::Town),
+                    ChangeChannelBlockedFallback::None => {
+                        panicking.stage_completing(Timeout::default(), true)
+                    }
+                };
+            }
+            if press_count < config.change_channel_menu_max_retries {
+                let _ = context.keys.send(config.change_channel_key);
+                panicking.stage_opening_channel_menu(
+                    Timeout::default(),
+                    press_count + 1,
+                    retry_count,
+                )
+            } else if retry_count + 1 < MAX_RETRY {
+                panicking.stage_opening_channel_menu(Timeout::default(), 0, retry_count + 1)
+            } else {
+                panicking.stage_completing(Timeout::default(), true)
+            }
+        },
+        |timeout| panicking.stage_opening_channel_menu(timeout, press_count, retry_count),
+    )
+}
+
 fn update_changing_channel(
     context: &Context,
     key: KeyKind,
@@ -125,7 +225,7 @@ fn update_changing_channel(
         || {
             if matches!(context.minimap, Minimap::Idle(_)) {
                 if retry_count + 1 < MAX_RETRY {
-                    panicking.stage_changing_channel(Timeout::default(), retry_count + 1)
+                    panicking.stage_opening_channel_menu(Timeout::default(), 0, retry_count + 1)
                 } else {
                     panicking.stage_completing(Timeout::default(), true)
                 }
@@ -159,6 +259,11 @@ fn update_changing_channel(
     )
 }
 
+/// Updates the going to town stage.
+///
+/// Each attempt opens the Maple Guide menu and clicks a random town. An unexpected dialog is
+/// dismissed and a menu without any detectable town is closed so that the next attempt re-opens
+/// it. After [`MAX_RETRY`] attempts without the map changing, it aborts and notifies.
 fn update_going_to_town(
     context: &Context,
     key: KeyKind,
@@ -172,33 +277,45 @@ fn update_going_to_town(
         timeout,
         50,
         |timeout| {
-            if matches!(context.minimap, Minimap::Idle(_)) {
-                if !context.detector_unwrap().detect_maple_guide_menu_opened() {
-                    let _ = context.keys.send(key);
-                }
-            } else {
+            if !matches!(context.minimap, Minimap::Idle(_)) {
                 return panicking.stage_completing(Timeout::default(), false);
             }
+            if retry_count < MAX_RETRY
+                && !dismiss_unexpected_dialog(context)
+                && !context.detector_unwrap().detect_maple_guide_menu_opened()
+            {
+                let _ = context.keys.send(key);
+            }
             panicking.stage_going_to_town(timeout, retry_count)
         },
         || {
-            if context.detector_unwrap().detect_maple_guide_menu_opened() {
+            if retry_count >= MAX_RETRY {
+                let _ = context
+                    .notification
+                    .schedule_notification(NotificationKind::PanicToTownFailed);
+                return panicking.stage_completing(Timeout::default(), true);
+            }
+            if !dismiss_unexpected_dialog(context)
+                && context.detector_unwrap().detect_maple_guide_menu_opened()
+            {
                 let towns = context.detector_unwrap().detect_maple_guide_towns();
                 let town = context.rng.random_choose(&towns);
                 if let Some(town) = town {
-                    let x = town.x + town.width / 2;
-                    let y = town.y + town.height / 2;
+                    let (x, y) = bbox_click_point(*town);
                     let _ = context.keys.send_mouse(x, y, MouseAction::Click);
+                } else {
+                    // Close the menu so that it is re-opened on the next attempt
+                    let _ = context.keys.send(key);
                 }
             }
-            if retry_count + 1 < MAX_RETRY {
-                panicking.stage_going_to_town(Timeout::default(), retry_count + 1)
-            } else {
-                panicking.stage_completing(Timeout::default(), true)
-            }
+            panicking.stage_going_to_town(Timeout::default(), retry_count + 1)
         },
         |timeout| {
-            if timeout.current == GUIDE_FULLY_OPENED_CHECK_AT
+            if !matches!(context.minimap, Minimap::Idle(_)) {
+                return panicking.stage_completing(Timeout::default(), false);
+            }
+            if retry_count < MAX_RETRY
+                && timeout.current == GUIDE_FULLY_OPENED_CHECK_AT
                 && !context.detector_unwrap().detect_maple_guide_menu_opened()
             {
                 let _ = context.keys.send(key);
@@ -208,8 +325,34 @@ fn update_going_to_town(
     )
 }
 
+/// Dismisses an unexpected dialog by clicking its cancel or ok button.
+///
+/// Returns `true` if a dialog was dismissed.
+#[inline]
+fn dismiss_unexpected_dialog(context: &Context) -> bool {
+    let detector = context.detector_unwrap();
+    let button = detector
+        .detect_esc_cancel_button()
+        .or_else(|_| detector.detect_esc_ok_button());
+    if let Ok(button) = button {
+        let (x, y) = bbox_click_point(button);
+        let _ = context.keys.send_mouse(x, y, MouseAction::Click);
+        true
+    } else {
+        false
+    }
+}
+
+#[inline]
+fn bbox_click_point(bbox: Rect) -> (i32, i32) {
+    let x = bbox.x + bbox.width / 2;
+    let y = bbox.y + bbox.height / 2;
+    (x, y)
+}
+
 fn update_completing(
     context: &Context,
+    filter: OtherPlayerFilter,
     panicking: Panicking,
     timeout: Timeout,
     completed: bool,
@@ -224,8 +367,8 @@ fn update_completing(
         |timeout| panicking.stage_completing(timeout, completed),
         || {
             if let Minimap::Idle(idle) = context.minimap {
-                if idle.has_any_other_player() {
-                    panicking.stage_changing_channel(Timeout::default(), 0)
+                if idle.has_other_player(filter) {
+                    panicking.stage_opening_channel_menu(Timeout::default(), 0, 0)
                 } else {
                     panicking.stage_completing(timeout, true)
                 }
@@ -241,12 +384,13 @@ fn update_completing(
 mod panicking_tests {
     use std::assert_matches::assert_matches;
 
-    use anyhow::Ok;
+    use anyhow::{Ok, anyhow};
 
     use super::*;
     use crate::{
         bridge::MockKeySender,
         detect::MockDetector,
+        detect::OtherPlayerKind,
         minimap::{Minimap, MinimapIdle},
     };
 
@@ -278,6 +422,122 @@ mod panicking_tests {
         assert_matches!(result.stage, PanickingStage::ChangingChannel(_, _));
     }
 
+    #[test]
+    fn update_opening_channel_menu_confirm_opened() {
+        let mut keys = MockKeySender::default();
+        keys.expect_send().never();
+        let mut detector = MockDetector::default();
+        detector
+            .expect_detect_change_channel_menu_opened()
+            .return_const(true);
+        let mut context = Context::new(Some(keys), Some(detector));
+        context.minimap = Minimap::Idle(MinimapIdle::default());
+        let config = PlayerConfiguration {
+            change_channel_menu_wait_ticks: 5,
+            change_channel_menu_max_retries: 2,
+            ..Default::default()
+        };
+        let panicking = Panicking::new(PanicTo::Channel);
+        let timeout = Timeout {
+            current: 5,
+            started: true,
+            ..Default::default()
+        };
+
+        let result = update_opening_channel_menu(&context, &config, panicking, timeout, 1, 0);
+        assert_matches!(
+            result.stage,
+            PanickingStage::ChangingChannel(Timeout { started: false, .. }, 0)
+        );
+    }
+
+    #[test]
+    fn update_opening_channel_menu_press_key_until_max_retries() {
+        let mut keys = MockKeySender::default();
+        keys.expect_send()
+            .withf(|key| matches!(key, KeyKind::F1))
+            .once()
+            .returning(|_| Ok(()));
+        let mut detector = MockDetector::default();
+        detector
+            .expect_detect_change_channel_menu_opened()
+            .return_const(false);
+        detector
+            .expect_detect_esc_cancel_button()
+            .returning(|| Err(anyhow!("button not found")));
+        detector
+            .expect_detect_esc_ok_button()
+            .returning(|| Err(anyhow!("button not found")));
+        let mut context = Context::new(Some(keys), Some(detector));
+        context.minimap = Minimap::Idle(MinimapIdle::default());
+        let config = PlayerConfiguration {
+            change_channel_key: KeyKind::F1,
+            change_channel_menu_wait_ticks: 5,
+            change_channel_menu_max_retries: 2,
+            ..Default::default()
+        };
+        let panicking = Panicking::new(PanicTo::Channel);
+        let timeout = Timeout {
+            current: 5,
+            started: true,
+            ..Default::default()
+        };
+
+        // Presses key when below max retries
+        let result = update_opening_channel_menu(&context, &config, panicking, timeout, 1, 0);
+        assert_matches!(result.stage, PanickingStage::OpeningChannelMenu(_, 2, 0));
+
+        // Retries the channel change after max retries
+        let result = update_opening_channel_menu(&context, &config, panicking, timeout, 2, 0);
+        assert_matches!(result.stage, PanickingStage::OpeningChannelMenu(_, 0, 1));
+
+        // Aborts after max channel change retry
+        let result =
+            update_opening_channel_menu(&context, &config, panicking, timeout, 2, MAX_RETRY - 1);
+        assert_matches!(result.stage, PanickingStage::Completing(_, true));
+    }
+
+    #[test]
+    fn update_opening_channel_menu_blocked_fallback() {
+        let mut keys = MockKeySender::default();
+        keys.expect_send().never();
+        keys.expect_send_mouse().returning(|_, _, _| Ok(()));
+        let mut detector = MockDetector::default();
+        detector
+            .expect_detect_change_channel_menu_opened()
+            .return_const(false);
+        detector
+            .expect_detect_esc_cancel_button()
+            .returning(|| Err(anyhow!("button not found")));
+        detector
+            .expect_detect_esc_ok_button()
+            .returning(|| Ok(Rect::new(10, 10, 10, 10)));
+        let mut context = Context::new(Some(keys), Some(detector));
+        context.minimap = Minimap::Idle(MinimapIdle::default());
+        let mut config = PlayerConfiguration {
+            change_channel_menu_wait_ticks: 5,
+            change_channel_menu_max_retries: 2,
+            ..Default::default()
+        };
+        let panicking = Panicking::new(PanicTo::Channel);
+        let timeout = Timeout {
+            current: 5,
+            started: true,
+            ..Default::default()
+        };
+
+        // Falls back to town
+        let result = update_opening_channel_menu(&context, &config, panicking, timeout, 1, 0);
+        assert_matches!(result.to, PanicTo::Town);
+        assert_matches!(result.stage, PanickingStage::GoingToTown(_, 0));
+
+        // Completes without fallback
+        config.change_channel_blocked_fallback = ChangeChannelBlockedFallback::None;
+        let result = update_opening_channel_menu(&context, &config, panicking, timeout, 1, 0);
+        assert_matches!(result.to, PanicTo::Channel);
+        assert_matches!(result.stage, PanickingStage::Completing(_, true));
+    }
+
     #[test]
     fn update_changing_channel_complete_if_minimap_not_idle() {
         let mut context = Context::new(None, None);
@@ -301,6 +561,12 @@ mod panicking_tests {
         detector
             .expect_detect_maple_guide_menu_opened()
             .return_const(false);
+        detector
+            .expect_detect_esc_cancel_button()
+            .returning(|| Err(anyhow!("button not found")));
+        detector
+            .expect_detect_esc_ok_button()
+            .returning(|| Err(anyhow!("button not found")));
         let mut context = Context::new(Some(keys), Some(detector));
         context.minimap = Minimap::Idle(MinimapIdle::default());
 
@@ -311,6 +577,84 @@ mod panicking_tests {
         assert_matches!(result.stage, PanickingStage::GoingToTown(_, _));
     }
 
+    #[test]
+    fn update_going_to_town_dismiss_unexpected_dialog() {
+        let mut keys = MockKeySender::default();
+        keys.expect_send_mouse()
+            .withf(|x, y, action| *x == 15 && *y == 25 && matches!(action, MouseAction::Click))
+            .once()
+            .returning(|_, _, _| Ok(()));
+        keys.expect_send().never();
+        let mut detector = MockDetector::default();
+        detector
+            .expect_detect_esc_cancel_button()
+            .returning(|| Ok(Rect::new(10, 20, 10, 10)));
+        let mut context = Context::new(Some(keys), Some(detector));
+        context.minimap = Minimap::Idle(MinimapIdle::default());
+
+        let panicking = Panicking::new(PanicTo::Town);
+        let timeout = Timeout::default();
+
+        let result = update_going_to_town(&context, KeyKind::F2, panicking, timeout, 1);
+        assert_matches!(result.stage, PanickingStage::GoingToTown(_, 1));
+    }
+
+    #[test]
+    fn update_going_to_town_retry_with_menu_reopen_if_no_town() {
+        let mut keys = MockKeySender::default();
+        keys.expect_send()
+            .withf(|key| matches!(key, KeyKind::F2))
+            .once()
+            .returning(|_| Ok(()));
+        let mut detector = MockDetector::default();
+        detector
+            .expect_detect_maple_guide_menu_opened()
+            .return_const(true);
+        detector
+            .expect_detect_maple_guide_towns()
+            .returning(Vec::new);
+        detector
+            .expect_detect_esc_cancel_button()
+            .returning(|| Err(anyhow!("button not found")));
+        detector
+            .expect_detect_esc_ok_button()
+            .returning(|| Err(anyhow!("button not found")));
+        let mut context = Context::new(Some(keys), Some(detector));
+        context.minimap = Minimap::Idle(MinimapIdle::default());
+
+        let panicking = Panicking::new(PanicTo::Town);
+        let timeout = Timeout {
+            current: 50,
+            started: true,
+            ..Default::default()
+        };
+
+        let result = update_going_to_town(&context, KeyKind::F2, panicking, timeout, 0);
+        assert_matches!(
+            result.stage,
+            PanickingStage::GoingToTown(Timeout { started: false, .. }, 1)
+        );
+    }
+
+    #[test]
+    fn update_going_to_town_abort_after_max_retry() {
+        let mut keys = MockKeySender::default();
+        keys.expect_send().never();
+        keys.expect_send_mouse().never();
+        let mut context = Context::new(Some(keys), Some(MockDetector::default()));
+        context.minimap = Minimap::Idle(MinimapIdle::default());
+
+        let panicking = Panicking::new(PanicTo::Town);
+        let timeout = Timeout {
+            current: 50,
+            started: true,
+            ..Default::default()
+        };
+
+        let result = update_going_to_town(&context, KeyKind::F2, panicking, timeout, MAX_RETRY);
+        assert_matches!(result.stage, PanickingStage::Completing(_, true));
+    }
+
     #[test]
     fn update_going_to_town_complete_if_not_idle_minimap() {
         let mut detector = MockDetector::default();
@@ -332,7 +676,13 @@ mod panicking_tests {
         let panicking = Panicking::new(PanicTo::Town);
 
         let timeout = Timeout::default();
-        let result = update_completing(&context, panicking, timeout, false);
+        let result = update_completing(
+            &context,
+            OtherPlayerFilter::default(),
+            panicking,
+            timeout,
+            false,
+        );
         assert_matches!(result.stage, PanickingStage::Completing(_, true));
     }
 
@@ -347,7 +697,43 @@ mod panicking_tests {
             ..Default::default()
         };
 
-        let result = update_completing(&context, panicking, timeout, false);
+        let result = update_completing(
+            &context,
+            OtherPlayerFilter::default(),
+            panicking,
+            timeout,
+            false,
+        );
+        assert_matches!(result.stage, PanickingStage::Completing(_, true));
+    }
+
+    #[test]
+    fn update_completing_for_channel_ignore_filtered_players() {
+        let mut idle = MinimapIdle::default();
+        idle.set_other_player_count(OtherPlayerKind::Friend, 1);
+        let mut context = Context::new(None, None);
+        context.minimap = Minimap::Idle(idle);
+        let panicking = Panicking::new(PanicTo::Channel);
+        let timeout = Timeout {
+            current: 245,
+            started: true,
+            ..Default::default()
+        };
+
+        let result = update_completing(
+            &context,
+            OtherPlayerFilter::default(),
+            panicking,
+            timeout,
+            false,
+        );
+        assert_matches!(result.stage, PanickingStage::OpeningChannelMenu(_, 0, 0));
+
+        let filter = OtherPlayerFilter {
+            friend: false,
+            ..OtherPlayerFilter::default()
+        };
+        let result = update_completing(&context, filter, panicking, timeout, false);
         assert_matches!(result.stage, PanickingStage::Completing(_, true));
     }
 }
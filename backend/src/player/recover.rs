@@ -0,0 +1,125 @@
+use platforms::windows::KeyKind;
+
+use super::{
+    Player, PlayerState,
+    timeout::{Timeout, update_with_timeout},
+};
+use crate::{LostPositionRecovery, context::Context, minimap::Minimap};
+
+/// Maximum number of ticks before timing out.
+const TIMEOUT: u32 = 10;
+
+/// Updates [`Player::Recovering`] contextual state.
+///
+/// This state is transitioned to when the player position has not been detected for the configured
+/// number of ticks. It performs the configured [`LostPositionRecovery`] and returns to
+/// [`Player::Detecting`] after [`TIMEOUT`] ticks.
+pub fn update_recovering_context(
+    context: &Context,
+    state: &mut PlayerState,
+    timeout: Timeout,
+) -> Player {
+    let recovery = state.config.lost_position_recovery;
+    let jump_key = state.config.jump_key;
+
+    update_with_timeout(
+        timeout,
+        TIMEOUT,
+        |timeout| {
+            match recovery {
+                LostPositionRecovery::Jump => {
+                    let _ = context.keys.send(jump_key);
+                }
+                LostPositionRecovery::MoveToCenter => {
+                    let to_right = match (context.minimap, state.last_known_pos) {
+                        (Minimap::Idle(idle), Some(pos)) => pos.x <= idle.bbox.width / 2,
+                        _ => context.rng.random_bool(0.5),
+                    };
+                    if to_right {
+                        let _ = context.keys.send_down(KeyKind::Right);
+                    } else {
+                        let _ = context.keys.send_down(KeyKind::Left);
+                    }
+                }
+                LostPositionRecovery::RedetectMinimap => state.request_redetect_minimap(),
+            }
+            Player::Recovering(timeout)
+        },
+        || {
+            if matches!(recovery, LostPositionRecovery::MoveToCenter) {
+                let _ = context.keys.send_up(KeyKind::Right);
+                let _ = context.keys.send_up(KeyKind::Left);
+            }
+            Player::Detecting
+        },
+        |timeout| {
+            if matches!(recovery, LostPositionRecovery::MoveToCenter)
+                && timeout.current == TIMEOUT / 2
+            {
+                let _ = context.keys.send(jump_key);
+            }
+            Player::Recovering(timeout)
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches::assert_matches;
+
+    use opencv::core::Point;
+
+    use super::*;
+    use crate::bridge::MockKeySender;
+
+    #[test]
+    fn update_recovering_context_jump() {
+        let mut keys = MockKeySender::default();
+        keys.expect_send()
+            .withf(|key| matches!(key, KeyKind::Space))
+            .once()
+            .returning(|_| Ok(()));
+        let context = Context::new(Some(keys), None);
+        let mut state = PlayerState::default();
+        state.config.jump_key = KeyKind::Space;
+
+        let next = update_recovering_context(&context, &mut state, Timeout::default());
+        assert_matches!(next, Player::Recovering(Timeout { started: true, .. }));
+    }
+
+    #[test]
+    fn update_recovering_context_redetect_minimap() {
+        let mut keys = MockKeySender::default();
+        keys.expect_send().never();
+        let context = Context::new(Some(keys), None);
+        let mut state = PlayerState::default();
+        state.config.lost_position_recovery = LostPositionRecovery::RedetectMinimap;
+
+        update_recovering_context(&context, &mut state, Timeout::default());
+        assert!(state.take_redetect_minimap());
+    }
+
+    #[test]
+    fn update_recovering_context_move_to_center_timeout() {
+        let mut keys = MockKeySender::default();
+        keys.expect_send_up()
+            .withf(|key| matches!(key, KeyKind::Left | KeyKind::Right))
+            .times(2)
+            .returning(|_| Ok(()));
+        let context = Context::new(Some(keys), None);
+        let mut state = PlayerState::default();
+        state.config.lost_position_recovery = LostPositionRecovery::MoveToCenter;
+        state.last_known_pos = Some(Point::new(5, 5));
+
+        let next = update_recovering_context(
+            &context,
+            &mut state,
+            Timeout {
+                current: TIMEOUT,
+                started: true,
+                ..Timeout::default()
+            },
+        );
+        assert_matches!(next, Player::Detecting);
+    }
+}
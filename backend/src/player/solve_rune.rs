@@ -1,10 +1,16 @@
 use anyhow::Result;
+use opencv::core::Point;
 use platforms::windows::KeyKind;
 
-use super::{Player, PlayerState, actions::PlayerAction};
+use super::{
+    Player, PlayerState,
+    actions::{PlayerAction, PlayerActionAutoMob},
+};
 use crate::{
+    ActionKeyDirection, RuneApproachSide,
     context::Context,
     detect::{ArrowsCalibrating, ArrowsState},
+    network::NotificationKind,
     player::{
         on_action_state_mut,
         timeout::{Timeout, update_with_timeout},
@@ -17,21 +23,40 @@ const SOLVE_START_TICK: u32 = 30;
 
 const PRESS_KEY_INTERVAL: u32 = 8;
 
+/// Number of ticks between each key press while clearing mobs before interacting with the rune.
+const CLEAR_MOBS_KEY_INTERVAL: u32 = 5;
+
+/// The x distance from the rune to stand at when approaching from a configured side.
+const APPROACH_X_OFFSET: i32 = 2;
+
+/// Minimum number of ticks for the timeout after calibration completes.
+///
+/// This ensures there is always enough ticks to press all the keys.
+const MIN_TIMEOUT_AFTER_SOLVE_START: u32 = PRESS_KEY_INTERVAL * 4 + 1;
+
 #[derive(Clone, Copy, Default, Debug)]
 pub struct SolvingRune {
     timeout: Timeout,
     keys: Option<[KeyKind; 4]>,
     key_index: usize,
     calibrating: ArrowsCalibrating,
+    clear_mobs_timeout: Timeout,
+    mobs_cleared: bool,
 }
 
 /// Updates the [`Player::SolvingRune`] contextual state
 ///
 /// Though this state can only be transitioned via [`Player::Moving`]
 /// with [`PlayerAction::SolveRune`], it is not required. This state does:
+/// - Before timeout start, clears nearby mobs if configured
+/// - Before timeout start, faces the rune if an approach side is configured
 /// - On timeout start, sends the interact key
 /// - On timeout update, detects the rune and sends the keys
 /// - On timeout end or rune is solved before timing out, transitions to `Player::Idle`
+///
+/// Calibrating the rune arrows is bounded by the configured rune calibration timeout. When the
+/// rune arrows fail to calibrate in time, the attempt is abandoned and counted toward the maximum
+/// attempts.
 pub fn update_solving_rune_context(
     context: &Context,
     state: &mut PlayerState,
@@ -45,6 +70,26 @@ pub fn update_solving_rune_context(
     if !solving_rune.timeout.started && !state.is_stationary {
         return Player::SolvingRune(solving_rune);
     }
+    if !solving_rune.timeout.started
+        && !solving_rune.mobs_cleared
+        && let Some(ticks) = state.config.rune_clear_mobs_ticks
+        && let Some(key) = clear_mobs_key(state)
+    {
+        return update_clearing_mobs(context, solving_rune, key, ticks);
+    }
+    if !solving_rune.timeout.started
+        && let Some(direction) = rune_facing_direction(state.config.rune_approach_side)
+        && direction != state.last_known_direction
+    {
+        let key = match direction {
+            ActionKeyDirection::Left => KeyKind::Left,
+            ActionKeyDirection::Right => KeyKind::Right,
+            ActionKeyDirection::Any => unreachable!(),
+        };
+        let _ = context.keys.send(key);
+        state.last_known_direction = direction;
+        return Player::SolvingRune(solving_rune);
+    }
 
     let update_timeout = |timeout| {
         Player::SolvingRune(SolvingRune {
@@ -52,14 +97,29 @@ pub fn update_solving_rune_context(
             ..solving_rune
         })
     };
+    // Holding must be released before the next key is pressed
+    let hold_ticks = state
+        .config
+        .rune_arrow_hold_ticks
+        .map(|ticks| ticks.clamp(1, PRESS_KEY_INTERVAL - 1));
+    let calibration_end_tick = SOLVE_START_TICK + state.config.rune_calibration_timeout_ticks;
+    // Only extends the timeout when there are not enough ticks left to press all the keys
+    let max_timeout = TIMEOUT
+        .max(calibration_end_tick + MIN_TIMEOUT_AFTER_SOLVE_START + hold_ticks.unwrap_or_default());
     let next = update_with_timeout(
         solving_rune.timeout,
-        TIMEOUT,
+        max_timeout,
         |timeout| {
             let _ = context.keys.send(state.config.interact_key);
             update_timeout(timeout)
         },
         || {
+            if hold_ticks.is_some()
+                && let Some(keys) = solving_rune.keys
+                && solving_rune.key_index > 0
+            {
+                let _ = context.keys.send_up(keys[solving_rune.key_index - 1]);
+            }
             // likely a spinning rune if the bot can't detect and timeout
             Player::Idle
         },
@@ -68,9 +128,22 @@ pub fn update_solving_rune_context(
                 return update_timeout(timeout);
             }
             if solving_rune.keys.is_none() {
+                if timeout.total > calibration_end_tick {
+                    return Player::Idle;
+                }
                 return calibrate_rune_arrows(context, timeout, &mut state.rune_task, solving_rune)
                     .unwrap_or(update_timeout(timeout));
             }
+            if let Some(hold_ticks) = hold_ticks
+                && let Some(keys) = solving_rune.keys
+                && solving_rune.key_index > 0
+                && timeout.current % PRESS_KEY_INTERVAL == hold_ticks
+            {
+                let _ = context.keys.send_up(keys[solving_rune.key_index - 1]);
+                if solving_rune.key_index >= keys.len() {
+                    return Player::Idle;
+                }
+            }
             if timeout.current % PRESS_KEY_INTERVAL != 0 {
                 return update_timeout(timeout);
             }
@@ -82,9 +155,13 @@ pub fn update_solving_rune_context(
             );
             let keys = solving_rune.keys.unwrap();
             let key_index = solving_rune.key_index;
-            let _ = context.keys.send(keys[key_index]);
+            if hold_ticks.is_some() {
+                let _ = context.keys.send_down(keys[key_index]);
+            } else {
+                let _ = context.keys.send(keys[key_index]);
+            }
             let key_index = solving_rune.key_index + 1;
-            if key_index >= keys.len() {
+            if key_index >= keys.len() && hold_ticks.is_none() {
                 Player::Idle
             } else {
                 Player::SolvingRune(SolvingRune {
@@ -104,8 +181,10 @@ pub fn update_solving_rune_context(
                 if is_terminal {
                     if solving_rune.keys.is_some() {
                         state.rune_validate_timeout = Some(Timeout::default());
-                    } else {
-                        state.track_rune_fail_count();
+                    } else if state.track_rune_calibration_fail_count() {
+                        let _ = context
+                            .notification
+                            .schedule_notification(NotificationKind::RuneAbandoned);
                     }
                 }
                 Some((next, is_terminal))
@@ -115,6 +194,7 @@ pub fn update_solving_rune_context(
             | PlayerAction::Panic(_)
             | PlayerAction::Key(_)
             | PlayerAction::FamiliarsSwapping(_)
+            | PlayerAction::CollectDrops(_)
             | PlayerAction::Move(_) => {
                 unreachable!()
             }
@@ -123,6 +203,75 @@ pub fn update_solving_rune_context(
     )
 }
 
+/// Updates the pre-interact phase that clears nearby mobs for `ticks` using `key`.
+///
+/// The key is sent every [`CLEAR_MOBS_KEY_INTERVAL`] ticks.
+fn update_clearing_mobs(
+    context: &Context,
+    solving_rune: SolvingRune,
+    key: KeyKind,
+    ticks: u32,
+) -> Player {
+    update_with_timeout(
+        solving_rune.clear_mobs_timeout,
+        ticks.max(1),
+        |timeout| {
+            let _ = context.keys.send(key);
+            Player::SolvingRune(SolvingRune {
+                clear_mobs_timeout: timeout,
+                ..solving_rune
+            })
+        },
+        || {
+            Player::SolvingRune(SolvingRune {
+                mobs_cleared: true,
+                ..solving_rune
+            })
+        },
+        |timeout| {
+            if timeout.current % CLEAR_MOBS_KEY_INTERVAL == 0 {
+                let _ = context.keys.send(key);
+            }
+            Player::SolvingRune(SolvingRune {
+                clear_mobs_timeout: timeout,
+                ..solving_rune
+            })
+        },
+    )
+}
+
+/// Gets the point to move to for approaching `rune` from `side`.
+#[inline]
+pub fn rune_approach_point(side: RuneApproachSide, rune: Point) -> Point {
+    match side {
+        RuneApproachSide::Either => rune,
+        RuneApproachSide::Left => Point::new(rune.x - APPROACH_X_OFFSET, rune.y),
+        RuneApproachSide::Right => Point::new(rune.x + APPROACH_X_OFFSET, rune.y),
+    }
+}
+
+/// Gets the direction to face the rune after approaching from `side`.
+#[inline]
+fn rune_facing_direction(side: RuneApproachSide) -> Option<ActionKeyDirection> {
+    match side {
+        RuneApproachSide::Either => None,
+        RuneApproachSide::Left => Some(ActionKeyDirection::Right),
+        RuneApproachSide::Right => Some(ActionKeyDirection::Left),
+    }
+}
+
+#[inline]
+fn clear_mobs_key(state: &PlayerState) -> Option<KeyKind> {
+    state.config.rune_clear_mobs_key.or_else(|| {
+        if let Some(PlayerAction::AutoMob(PlayerActionAutoMob { key, .. })) = state.normal_action()
+        {
+            Some(key.into())
+        } else {
+            None
+        }
+    })
+}
+
 fn calibrate_rune_arrows(
     context: &Context,
     timeout: Timeout,
@@ -177,3 +326,203 @@ fn calibrate_rune_arrows_async(
         Update::Err(_) | Update::Pending => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches::assert_matches;
+
+    use super::*;
+    use crate::bridge::MockKeySender;
+
+    fn solving_rune(current: u32, key_index: usize) -> SolvingRune {
+        SolvingRune {
+            timeout: Timeout {
+                current,
+                total: SOLVE_START_TICK + current,
+                started: true,
+            },
+            keys: Some([KeyKind::Up, KeyKind::Down, KeyKind::Left, KeyKind::Right]),
+            key_index,
+            calibrating: ArrowsCalibrating::default(),
+            clear_mobs_timeout: Timeout::default(),
+            mobs_cleared: false,
+        }
+    }
+
+    #[test]
+    fn update_solving_rune_context_calibration_timeout() {
+        let context = Context::new(None, None);
+        let mut state = PlayerState::default();
+        state.is_stationary = true;
+        state.config.rune_calibration_timeout_ticks = 10;
+        state.config.rune_calibration_max_attempts = 1;
+        state.set_normal_action(0, PlayerAction::SolveRune);
+
+        let next = update_solving_rune_context(
+            &context,
+            &mut state,
+            SolvingRune {
+                timeout: Timeout {
+                    current: SOLVE_START_TICK + 10,
+                    total: SOLVE_START_TICK + 10,
+                    started: true,
+                },
+                ..SolvingRune::default()
+            },
+        );
+        assert_matches!(next, Player::Idle);
+        assert!(!state.has_normal_action());
+        assert!(state.is_rune_abandoned());
+    }
+
+    #[test]
+    fn update_solving_rune_context_clear_mobs_before_interact() {
+        let mut keys = MockKeySender::default();
+        keys.expect_send()
+            .withf(|key| matches!(key, KeyKind::A))
+            .once()
+            .returning(|_| Ok(()));
+        let context = Context::new(Some(keys), None);
+        let mut state = PlayerState::default();
+        state.is_stationary = true;
+        state.config.interact_key = KeyKind::Space;
+        state.config.rune_clear_mobs_ticks = Some(10);
+        state.config.rune_clear_mobs_key = Some(KeyKind::A);
+
+        // (1) Sends the clear mobs key instead of the interact key
+        let next = update_solving_rune_context(&context, &mut state, SolvingRune::default());
+        assert_matches!(
+            next,
+            Player::SolvingRune(SolvingRune {
+                timeout: Timeout { started: false, .. },
+                clear_mobs_timeout: Timeout { started: true, .. },
+                mobs_cleared: false,
+                ..
+            })
+        );
+        let _ = context.keys; // Drop for test checkpoint
+
+        // (2) Completes clearing after timing out
+        let mut keys = MockKeySender::default();
+        keys.expect_send().never();
+        let context = Context::new(Some(keys), None);
+        let next = update_solving_rune_context(
+            &context,
+            &mut state,
+            SolvingRune {
+                clear_mobs_timeout: Timeout {
+                    current: 10,
+                    total: 10,
+                    started: true,
+                },
+                ..SolvingRune::default()
+            },
+        );
+        assert_matches!(
+            next,
+            Player::SolvingRune(SolvingRune {
+                mobs_cleared: true,
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn update_solving_rune_context_face_rune_before_interact() {
+        let mut keys = MockKeySender::default();
+        keys.expect_send()
+            .withf(|key| matches!(key, KeyKind::Right))
+            .once()
+            .returning(|_| Ok(()));
+        let context = Context::new(Some(keys), None);
+        let mut state = PlayerState::default();
+        state.is_stationary = true;
+        state.config.interact_key = KeyKind::Space;
+        state.config.rune_approach_side = RuneApproachSide::Left;
+        state.last_known_direction = ActionKeyDirection::Left;
+
+        // (1) Faces the rune instead of sending the interact key
+        let next = update_solving_rune_context(&context, &mut state, SolvingRune::default());
+        assert_matches!(
+            next,
+            Player::SolvingRune(SolvingRune {
+                timeout: Timeout { started: false, .. },
+                ..
+            })
+        );
+        assert_eq!(state.last_known_direction, ActionKeyDirection::Right);
+        let _ = context.keys; // Drop for test checkpoint
+
+        // (2) Sends the interact key once facing the rune
+        let mut keys = MockKeySender::default();
+        keys.expect_send()
+            .withf(|key| matches!(key, KeyKind::Space))
+            .once()
+            .returning(|_| Ok(()));
+        let context = Context::new(Some(keys), None);
+        let next = update_solving_rune_context(&context, &mut state, SolvingRune::default());
+        assert_matches!(
+            next,
+            Player::SolvingRune(SolvingRune {
+                timeout: Timeout { started: true, .. },
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn rune_approach_point_offset_by_side() {
+        let rune = Point::new(10, 5);
+
+        assert_eq!(rune_approach_point(RuneApproachSide::Either, rune), rune);
+        assert_eq!(
+            rune_approach_point(RuneApproachSide::Left, rune),
+            Point::new(10 - APPROACH_X_OFFSET, 5)
+        );
+        assert_eq!(
+            rune_approach_point(RuneApproachSide::Right, rune),
+            Point::new(10 + APPROACH_X_OFFSET, 5)
+        );
+    }
+
+    #[test]
+    fn update_solving_rune_context_hold_press_key() {
+        let mut keys = MockKeySender::default();
+        keys.expect_send_down()
+            .withf(|key| matches!(key, KeyKind::Up))
+            .once()
+            .returning(|_| Ok(()));
+        keys.expect_send().never();
+        let context = Context::new(Some(keys), None);
+        let mut state = PlayerState::default();
+        state.is_stationary = true;
+        state.config.rune_arrow_hold_ticks = Some(3);
+
+        let next = update_solving_rune_context(
+            &context,
+            &mut state,
+            solving_rune(PRESS_KEY_INTERVAL - 1, 0),
+        );
+        assert_matches!(next, Player::SolvingRune(SolvingRune { key_index: 1, .. }));
+    }
+
+    #[test]
+    fn update_solving_rune_context_hold_release_last_key() {
+        let mut keys = MockKeySender::default();
+        keys.expect_send_up()
+            .withf(|key| matches!(key, KeyKind::Right))
+            .once()
+            .returning(|_| Ok(()));
+        let context = Context::new(Some(keys), None);
+        let mut state = PlayerState::default();
+        state.is_stationary = true;
+        state.config.rune_arrow_hold_ticks = Some(3);
+
+        let next = update_solving_rune_context(
+            &context,
+            &mut state,
+            solving_rune(PRESS_KEY_INTERVAL * 4 + 2, 4),
+        );
+        assert_matches!(next, Player::Idle);
+    }
+}
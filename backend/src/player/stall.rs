@@ -45,7 +45,9 @@ pub fn update_stalling_context(
                 Some((next, matches!(next, Player::Idle)))
             }
             PlayerAction::SolveRune => None,
-            PlayerAction::Panic(_) | PlayerAction::FamiliarsSwapping(_) => unreachable!(),
+            PlayerAction::Panic(_)
+            | PlayerAction::FamiliarsSwapping(_)
+            | PlayerAction::CollectDrops(_) => unreachable!(),
         },
         || next,
     )
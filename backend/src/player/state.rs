@@ -1,23 +1,28 @@
-use std::{collections::HashMap, range::Range};
+use std::{
+    collections::{HashMap, VecDeque},
+    range::Range,
+    sync::Arc,
+    time::Instant,
+};
 
 use anyhow::Result;
 use log::debug;
-use opencv::core::{Point, Rect};
+use opencv::core::{Mat, Point, Rect};
 use platforms::windows::KeyKind;
-use rand::seq::IteratorRandom;
 
 use super::{
-    DOUBLE_JUMP_THRESHOLD, JUMP_THRESHOLD, MOVE_TIMEOUT, Player, PlayerAction,
+    DOUBLE_JUMP_THRESHOLD, JUMP_THRESHOLD, MOVE_TIMEOUT, Player, PlayerAction, PlayerActionKey,
     double_jump::DOUBLE_JUMP_AUTO_MOB_THRESHOLD, fall::FALLING_THRESHOLD, timeout::Timeout,
 };
 use crate::{
-    ActionKeyDirection, Class,
+    ActionKeyDirection, ChangeChannelBlockedFallback, Class, LostPositionRecovery,
+    RuneApproachSide,
     array::Array,
     bridge::MouseAction,
     buff::{Buff, BuffKind},
     context::Context,
     detect::ArrowsState,
-    minimap::Minimap,
+    minimap::{Minimap, OtherPlayerFilter},
     network::NotificationKind,
     player::timeout::update_with_timeout,
     task::{Task, Update, update_detection_task},
@@ -72,6 +77,25 @@ const UNSTUCK_COUNT_THRESHOLD: u32 = 6;
 /// The number of times [`Player::Unstucking`] can be transitioned to before entering GAMBA MODE.
 const UNSTUCK_GAMBA_MODE_COUNT: u32 = 3;
 
+/// The window in milliseconds for counting [`Player::Unstucking`] transitions before
+/// re-detecting the minimap.
+const UNSTUCK_REDETECT_MINIMAP_WINDOW_MILLIS: u128 = 30_000;
+
+/// The window in milliseconds for counting solved runes.
+const RUNE_SOLVED_WINDOW_MILLIS: u128 = 60_000;
+
+/// The number of consecutive health updates reading zero health before the player is considered
+/// dead regardless of the tomb detection.
+const HEALTH_DEPLETED_COUNT: u32 = 3;
+
+/// The number of consecutive health updates reading below
+/// [`PlayerConfiguration::critical_health_percent`] before the health is considered critical.
+const HEALTH_CRITICAL_COUNT: u32 = 3;
+
+/// The number of consecutive [`Player::Recovering`] transitions before the recovery is considered
+/// failed.
+const LOST_POSITION_RECOVERY_MAX_COUNT: u32 = 3;
+
 /// The number of samples to store for approximating velocity.
 const VELOCITY_SAMPLES: usize = MOVE_TIMEOUT as usize;
 
@@ -94,6 +118,27 @@ pub struct PlayerConfiguration {
     pub class: Class,
     /// Whether to disable [`Player::Adjusting`].
     pub disable_adjusting: bool,
+    /// Whether to jump in [`Player::Adjusting`] when walking has not moved the player for
+    /// a few ticks.
+    pub adjust_unstuck_jump: bool,
+    /// Whether to use key in [`Player::Adjusting`] while still walking when the destination of
+    /// a key action is near.
+    pub adjust_attack_while_walking: bool,
+    /// How much to shorten small movement key presses in [`Player::Adjusting`] as the player
+    /// nears the destination, from `0.0` (no damping) to `1.0`.
+    pub adjust_damping: f32,
+    /// Maximum number of direction reversals in [`Player::Adjusting`] before accepting the
+    /// current position with [`None`] indicating no limit.
+    pub adjust_max_correction_cycles: Option<u32>,
+    /// Maximum x velocity allowed to be considered as near stationary.
+    pub near_stationary_x_velocity_threshold: f32,
+    /// Maximum y velocity allowed to be considered as near stationary.
+    pub near_stationary_y_velocity_threshold: f32,
+    /// Maximum x and y velocity allowed to use a key with [`crate::ActionKeyWith::Stationary`].
+    ///
+    /// This is separate from the near stationary thresholds used by [`Player::DoubleJumping`].
+    /// A value of 0 requires the player to be fully stationary.
+    pub stationary_cast_velocity_threshold: f32,
     /// Enables platform pathing for rune.
     pub rune_platforms_pathing: bool,
     /// Uses only up jump(s) in rune platform pathing.
@@ -106,6 +151,9 @@ pub struct PlayerConfiguration {
     ///
     /// TODO: This shouldn't be here...
     pub auto_mob_platforms_bound: bool,
+    /// Walks off the edge and then double jumps to cross wide gaps to a lower platform in
+    /// platform pathing.
+    pub platforms_pathing_walk_off_double_jump: bool,
     /// The interact key.
     pub interact_key: KeyKind,
     /// The `Rope Lift` skill key.
@@ -116,6 +164,17 @@ pub struct PlayerConfiguration {
     ///
     /// Replaces the previously default [`KeyKind::Space`] key.
     pub jump_key: KeyKind,
+    /// The flash jump key used for double jumping with [`None`] indicating [`Self::jump_key`].
+    pub flash_jump_key: Option<KeyKind>,
+    /// The number of ticks to hold the jump key in [`Player::Jumping`].
+    ///
+    /// The jump key is only tapped when this is 0.
+    pub jump_hold_ticks: u32,
+    /// The factor to scale the movement distance thresholds by with [`None`] indicating
+    /// no scaling.
+    ///
+    /// A factor above 1 is for characters moving faster than usual and vice versa.
+    pub movement_speed_factor: Option<f32>,
     /// The up jump key with [`None`] indicating composite jump (Up arrow + Double Space).
     pub upjump_key: Option<KeyKind>,
     /// The cash shop key.
@@ -126,12 +185,78 @@ pub struct PlayerConfiguration {
     pub maple_guide_key: KeyKind,
     /// The change channel key.
     pub change_channel_key: KeyKind,
+    /// The number of ticks to wait before and after pressing [`Self::change_channel_key`] for
+    /// the change channel menu to appear.
+    pub change_channel_menu_wait_ticks: u32,
+    /// The maximum number of [`Self::change_channel_key`] presses to open the change channel
+    /// menu before retrying the channel change.
+    pub change_channel_menu_max_retries: u32,
+    /// The fallback to perform when changing channel is blocked in the current map.
+    pub change_channel_blocked_fallback: ChangeChannelBlockedFallback,
     /// The potion key.
     pub potion_key: KeyKind,
     /// Uses potion when health is below a percentage.
     pub use_potion_below_percent: Option<f32>,
     /// Milliseconds interval to update current health.
     pub update_health_millis: Option<u64>,
+    /// Maximum number of ticks for calibrating rune arrows before abandoning the attempt.
+    pub rune_calibration_timeout_ticks: u32,
+    /// Maximum number of ticks to wait for the rune buff after sending all the rune arrow keys
+    /// before counting the attempt as failed.
+    pub rune_validate_ticks: u32,
+    /// Maximum number of calibration attempts before giving up solving the current rune.
+    pub rune_calibration_max_attempts: u32,
+    /// The number of ticks to hold down each rune arrow key with [`None`] indicating tapping.
+    pub rune_arrow_hold_ticks: Option<u32>,
+    /// The side to approach and face the rune from before interacting.
+    pub rune_approach_side: RuneApproachSide,
+    /// The number of ticks to clear nearby mobs before interacting with the rune with [`None`]
+    /// indicating disabled.
+    pub rune_clear_mobs_ticks: Option<u32>,
+    /// The key to clear nearby mobs with before interacting with the rune.
+    ///
+    /// If [`None`], the key of the current [`PlayerAction::AutoMob`] is used instead.
+    pub rune_clear_mobs_key: Option<KeyKind>,
+    /// The maximum number of runes to solve within a minute with [`None`] indicating unlimited.
+    pub max_runes_solved_per_minute: Option<u32>,
+    /// The number of [`Player::Unstucking`] transitions within a window before re-detecting
+    /// the minimap with [`None`] indicating disabled.
+    pub redetect_minimap_unstuck_count: Option<u32>,
+    /// The kinds of other players that cause [`Player::Panicking`] to keep changing channel.
+    pub panic_other_player_filter: OtherPlayerFilter,
+    /// Whether to abort a strict positioned key action instead of using the key when the player
+    /// fails to reach the position in time.
+    pub abort_strict_position_on_timeout: bool,
+    /// The maximum x and y distance from a non-exact destination for the player to be considered
+    /// arrived regardless of the movement thresholds with `0` indicating disabled.
+    pub arrival_tolerance: i32,
+    /// The x distance from the minimap edges within which double jumping toward the edge is
+    /// suppressed with `0` indicating disabled.
+    pub edge_safety_margin: i32,
+    /// Whether to stop the actions when the health is depleted for
+    /// [`HEALTH_DEPLETED_COUNT`] consecutive updates.
+    pub stop_on_health_depleted: bool,
+    /// The health ratio below which the health is considered critical with [`None`] indicating
+    /// disabled.
+    pub critical_health_percent: Option<f32>,
+    /// The number of ticks to stay inside the cash shop after entering.
+    pub cash_shop_dwell_ticks: u32,
+    /// The number of ticks to wait before exiting the cash shop after
+    /// [`Self::cash_shop_dwell_ticks`].
+    pub cash_shop_exit_wait_ticks: u32,
+    /// Whether to occasionally tap a movement key during a prolonged [`Player::Idle`] without
+    /// any action.
+    pub idle_fidget: bool,
+    /// Whether to wait in [`Player::Idle`] until the player is grounded before starting the
+    /// next action.
+    pub wait_for_ground: bool,
+    /// Whether to close any known opened menus before the actions start rotating.
+    pub clean_ui_on_start: bool,
+    /// The number of ticks without detecting the player position before transitioning to
+    /// [`Player::Recovering`] with [`None`] indicating disabled.
+    pub lost_position_recovery_ticks: Option<u32>,
+    /// The recovery to perform in [`Player::Recovering`].
+    pub lost_position_recovery: LostPositionRecovery,
 }
 
 /// The player persistent states.
@@ -160,6 +285,15 @@ pub struct PlayerState {
     health_bar: Option<Rect>,
     /// The task for the health bar.
     health_bar_task: Option<Task<Result<Rect>>>,
+    /// The number of consecutive health updates reading zero health.
+    health_depleted_count: u32,
+    /// Whether the actions should be stopped because the health is depleted.
+    health_depleted: bool,
+    /// Whether an action has completed since the last [`Self::take_action_completed`].
+    action_completed: bool,
+    /// The number of consecutive health updates reading below
+    /// [`PlayerConfiguration::critical_health_percent`].
+    health_critical_count: u32,
     /// Track if the player moved within a specified ticks to determine if the player is
     /// stationary.
     is_stationary_timeout: Timeout,
@@ -171,6 +305,8 @@ pub struct PlayerState {
     is_dead_task: Option<Task<Result<bool>>>,
     /// The task for detecting the tomb OK button when player is dead.
     is_dead_button_task: Option<Task<Result<Rect>>>,
+    /// The additional user-supplied templates for detecting if player is dead.
+    death_templates: Arc<Vec<Mat>>,
     /// Approximates the player direction for using key.
     pub(super) last_known_direction: ActionKeyDirection,
     /// Tracks last destination points for displaying to UI.
@@ -197,6 +333,8 @@ pub struct PlayerState {
     /// to [`None`] when the destination (possibly intermediate) is reached or
     /// in [`Player::Idle`].
     pub(super) last_movement: Option<LastMovement>,
+    /// The tick to tap a movement key at during [`Player::Idle`] when idle fidget is enabled.
+    pub(super) idle_fidget_tick: Option<u64>,
     // TODO: 2 maps fr?
     /// Tracks [`Self::last_movement`] to abort normal action when its position is not accurate.
     ///
@@ -229,19 +367,50 @@ pub struct PlayerState {
     ///
     /// Resets when threshold reached or position changed.
     unstuck_transitioned_count: u32,
+    /// The number of times player transitioned to [`Player::Unstucking`] within the current
+    /// re-detect minimap window.
+    unstuck_redetect_minimap_count: u32,
+    /// The start of the current re-detect minimap window.
+    unstuck_redetect_minimap_instant: Option<Instant>,
+    /// Whether the minimap should be re-detected because of repeated [`Player::Unstucking`].
+    redetect_minimap: bool,
+    /// The number of consecutive ticks the player position has not been detected.
+    lost_position_ticks: u32,
+    /// The number of consecutive [`Player::Recovering`] transitions without detecting the player
+    /// position in between.
+    lost_position_recovery_count: u32,
     /// Unstuck task for detecting settings when mis-pressing ESC key.
     pub(super) unstuck_task: Option<Task<Result<bool>>>,
     /// Rune solving task.
     pub(super) rune_task: Option<Task<Result<ArrowsState>>>,
     /// The number of times [`Player::SolvingRune`] failed.
     rune_failed_count: u32,
+    /// The number of times [`Player::SolvingRune`] timed out while calibrating rune arrows.
+    rune_calibration_failed_count: u32,
+    /// Whether solving the current rune is abandoned after too many calibration failures.
+    ///
+    /// Resets when the rune is no longer on the minimap.
+    rune_abandoned: bool,
     /// Indicates the state will be transitioned to [`Player::CashShopThenExit`] in the next tick.
     pub(super) rune_cash_shop: bool,
+    /// Indicates the state will be transitioned to [`Player::CleaningUi`] in the next tick.
+    pub(super) clean_ui_next_update: bool,
     /// [`Timeout`] for validating whether the rune is solved.
     ///
     /// This is [`Some`] when [`Player::SolvingRune`] successfully detects the rune
     /// and sends all the keys.
     pub(super) rune_validate_timeout: Option<Timeout>,
+    /// The key to send when [`Player::Moving`] starts for the current move action.
+    ///
+    /// Set by a linked key action that fires during the following move.
+    move_fire_key: Option<PlayerActionKey>,
+    /// The destination and number of direction reversals in [`Player::Adjusting`] toward it.
+    adjust_corrections: Option<(Point, u32)>,
+    /// The instants at which runes were successfully solved within
+    /// [`RUNE_SOLVED_WINDOW_MILLIS`].
+    rune_solved_instants: VecDeque<Instant>,
+    /// The last instant a key was used for a normal action.
+    last_attack_instant: Option<Instant>,
     /// A state to return to after stalling.
     ///
     /// Resets when [`Player::Stalling`] timed out or in [`Player::Idle`].
@@ -260,6 +429,7 @@ impl PlayerState {
     pub fn reset(&mut self) {
         *self = PlayerState {
             config: self.config,
+            death_templates: self.death_templates.clone(),
             reset_to_idle_next_update: true,
             ..PlayerState::default()
         };
@@ -303,6 +473,11 @@ impl PlayerState {
         self.normal_action = None;
     }
 
+    #[cfg(test)]
+    pub fn priority_action(&self) -> Option<PlayerAction> {
+        self.priority_action
+    }
+
     /// The priority action name for displaying to UI.
     #[inline]
     pub fn priority_action_name(&self) -> Option<String> {
@@ -352,12 +527,55 @@ impl PlayerState {
             .then_some(prev_id)
     }
 
+    /// The approximated direction the player is facing.
+    #[inline]
+    pub fn last_known_direction(&self) -> ActionKeyDirection {
+        self.last_known_direction
+    }
+
     /// Whether the player is validating whether the rune is solved.
     #[inline]
     pub fn is_validating_rune(&self) -> bool {
         self.rune_validate_timeout.is_some()
     }
 
+    /// Whether solving the current rune is abandoned.
+    #[inline]
+    pub fn is_rune_abandoned(&self) -> bool {
+        self.rune_abandoned
+    }
+
+    /// Sets the additional templates for detecting if player is dead.
+    #[inline]
+    pub fn set_death_templates(&mut self, templates: Vec<Mat>) {
+        self.death_templates = Arc::new(templates);
+    }
+
+    /// Sets the key to send once the next [`Player::Moving`] starts.
+    #[inline]
+    pub fn set_move_fire_key(&mut self, key: PlayerActionKey) {
+        self.move_fire_key = Some(key);
+    }
+
+    /// Takes the key set by [`Self::set_move_fire_key`].
+    #[inline]
+    pub(super) fn take_move_fire_key(&mut self) -> Option<PlayerActionKey> {
+        self.move_fire_key.take()
+    }
+
+    /// Whether [`PlayerConfiguration::max_runes_solved_per_minute`] has been reached.
+    #[inline]
+    pub fn is_rune_solving_capped(&self) -> bool {
+        self.config.max_runes_solved_per_minute.is_some_and(|max| {
+            let count = self
+                .rune_solved_instants
+                .iter()
+                .filter(|instant| instant.elapsed().as_millis() < RUNE_SOLVED_WINDOW_MILLIS)
+                .count();
+            count >= max as usize
+        })
+    }
+
     /// Whether there is a priority rune action.
     #[inline]
     pub fn has_rune_action(&self) -> bool {
@@ -376,6 +594,12 @@ impl PlayerState {
         !self.has_priority_action() && matches!(self.normal_action, Some(PlayerAction::PingPong(_)))
     }
 
+    /// Requests transitioning to [`Player::CleaningUi`] in the next tick if enabled.
+    #[inline]
+    pub fn request_clean_ui(&mut self) {
+        self.clean_ui_next_update = self.config.clean_ui_on_start;
+    }
+
     /// Clears both on-going normal and priority actions due to being aborted.
     #[inline]
     pub fn clear_actions_aborted(&mut self) {
@@ -388,6 +612,8 @@ impl PlayerState {
     #[inline]
     pub(super) fn clear_action_completed(&mut self) {
         self.clear_last_movement();
+        self.adjust_corrections = None;
+        self.action_completed = true;
         if self.has_priority_action() {
             self.priority_action = None;
         } else {
@@ -414,6 +640,30 @@ impl PlayerState {
         }
     }
 
+    /// Tracks a direction reversal of [`Player::Adjusting`] toward `dest`.
+    #[inline]
+    pub(super) fn track_adjust_correction(&mut self, dest: Point) {
+        let count = match self.adjust_corrections {
+            Some((prev_dest, count)) if prev_dest == dest => count + 1,
+            _ => 1,
+        };
+        self.adjust_corrections = Some((dest, count));
+    }
+
+    /// Whether [`PlayerConfiguration::adjust_max_correction_cycles`] is reached for `dest`.
+    ///
+    /// When reached, the current position is accepted as close enough to `dest`.
+    #[inline]
+    pub(super) fn is_adjust_corrections_exhausted(&self, dest: Point) -> bool {
+        match (
+            self.adjust_corrections,
+            self.config.adjust_max_correction_cycles,
+        ) {
+            (Some((prev_dest, count)), Some(max)) => prev_dest == dest && count >= max,
+            _ => false,
+        }
+    }
+
     /// Increments the rune validation fail count and sets [`PlayerState::rune_cash_shop`]
     /// if needed.
     #[inline]
@@ -425,11 +675,62 @@ impl PlayerState {
         }
     }
 
+    /// Records a successfully solved rune and drops the ones outside
+    /// [`RUNE_SOLVED_WINDOW_MILLIS`].
+    #[inline]
+    fn track_rune_solved(&mut self) {
+        let now = Instant::now();
+        while let Some(instant) = self.rune_solved_instants.front()
+            && now.duration_since(*instant).as_millis() >= RUNE_SOLVED_WINDOW_MILLIS
+        {
+            self.rune_solved_instants.pop_front();
+        }
+        self.rune_solved_instants.push_back(now);
+    }
+
+    /// Tracks the current instant as the last attack if a key is used for a normal action.
+    #[inline]
+    pub(super) fn track_last_attack(&mut self) {
+        if self.has_normal_action() && !self.has_priority_action() {
+            self.last_attack_instant = Some(Instant::now());
+        }
+    }
+
+    #[cfg(test)]
+    pub fn set_last_attack_instant(&mut self, instant: Option<Instant>) {
+        self.last_attack_instant = instant;
+    }
+
+    /// Whether a key has been used for a normal action within the last `lull_millis`.
+    #[inline]
+    pub fn is_in_combat(&self, lull_millis: u64) -> bool {
+        self.last_attack_instant
+            .is_some_and(|instant| instant.elapsed().as_millis() < lull_millis as u128)
+    }
+
+    /// Increments the rune calibration fail count and abandons the current rune if
+    /// [`PlayerConfiguration::rune_calibration_max_attempts`] is reached.
+    ///
+    /// Returns `true` when the rune is abandoned.
+    #[inline]
+    pub(super) fn track_rune_calibration_fail_count(&mut self) -> bool {
+        self.rune_calibration_failed_count += 1;
+        // Keeps counting toward the cash shop fallback across abandoned runes
+        self.track_rune_fail_count();
+        if self.rune_calibration_failed_count >= self.config.rune_calibration_max_attempts.max(1) {
+            self.rune_calibration_failed_count = 0;
+            self.rune_abandoned = true;
+            return true;
+        }
+        false
+    }
+
     /// Increments the unstucking transitioned counter.
     ///
     /// Returns `true` when [`Player::Unstucking`] should enter GAMBA MODE.
     #[inline]
     pub(super) fn track_unstucking_transitioned(&mut self) -> bool {
+        self.track_unstucking_redetect_minimap();
         self.unstuck_transitioned_count += 1;
         if self.unstuck_transitioned_count >= UNSTUCK_GAMBA_MODE_COUNT {
             self.unstuck_transitioned_count = 0;
@@ -439,6 +740,137 @@ impl PlayerState {
         }
     }
 
+    /// Increments the unstucking re-detect minimap counter.
+    ///
+    /// Sets [`Self::redetect_minimap`] when the count reaches
+    /// [`PlayerConfiguration::redetect_minimap_unstuck_count`] within
+    /// [`UNSTUCK_REDETECT_MINIMAP_WINDOW_MILLIS`].
+    #[inline]
+    fn track_unstucking_redetect_minimap(&mut self) {
+        let Some(count) = self.config.redetect_minimap_unstuck_count else {
+            return;
+        };
+        let now = Instant::now();
+        let window_elapsed = self.unstuck_redetect_minimap_instant.is_none_or(|instant| {
+            now.duration_since(instant).as_millis() >= UNSTUCK_REDETECT_MINIMAP_WINDOW_MILLIS
+        });
+        if window_elapsed {
+            self.unstuck_redetect_minimap_instant = Some(now);
+            self.unstuck_redetect_minimap_count = 0;
+        }
+
+        self.unstuck_redetect_minimap_count += 1;
+        if self.unstuck_redetect_minimap_count >= count.max(1) {
+            debug!(target: "player", "repeated unstucking, re-detecting minimap");
+            self.unstuck_redetect_minimap_count = 0;
+            self.unstuck_redetect_minimap_instant = None;
+            self.redetect_minimap = true;
+        }
+    }
+
+    /// Takes the re-detect minimap flag set by repeated [`Player::Unstucking`].
+    #[inline]
+    pub fn take_redetect_minimap(&mut self) -> bool {
+        std::mem::take(&mut self.redetect_minimap)
+    }
+
+    /// Requests re-detecting the minimap in the next tick.
+    #[inline]
+    pub(super) fn request_redetect_minimap(&mut self) {
+        self.redetect_minimap = true;
+    }
+
+    /// Tracks the number of consecutive ticks the player position has not been detected.
+    ///
+    /// Returns `true` when the count reaches [`PlayerConfiguration::lost_position_recovery_ticks`]
+    /// and [`Player::Recovering`] should be transitioned to.
+    #[inline]
+    pub(super) fn track_lost_position(&mut self) -> bool {
+        let Some(ticks) = self.config.lost_position_recovery_ticks else {
+            return false;
+        };
+        self.lost_position_ticks += 1;
+        self.lost_position_ticks >= ticks.max(1)
+    }
+
+    /// Tracks the number of consecutive [`Player::Recovering`] transitions and resets the lost
+    /// position ticks.
+    ///
+    /// Returns `true` when the count reaches [`LOST_POSITION_RECOVERY_MAX_COUNT`] and the recovery
+    /// is considered failed.
+    #[inline]
+    pub(super) fn track_lost_position_recovery(&mut self) -> bool {
+        self.lost_position_ticks = 0;
+        self.lost_position_recovery_count += 1;
+        if self.lost_position_recovery_count >= LOST_POSITION_RECOVERY_MAX_COUNT {
+            self.lost_position_recovery_count = 0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Clears the lost position tracking after the player position is detected.
+    #[inline]
+    pub(super) fn clear_lost_position(&mut self) {
+        self.lost_position_ticks = 0;
+        self.lost_position_recovery_count = 0;
+    }
+
+    /// Takes the health depleted flag set by [`Self::track_health_depleted`].
+    #[inline]
+    pub fn take_health_depleted(&mut self) -> bool {
+        std::mem::take(&mut self.health_depleted)
+    }
+
+    /// Takes the action completed flag set by [`Self::clear_action_completed`].
+    #[inline]
+    pub fn take_action_completed(&mut self) -> bool {
+        std::mem::take(&mut self.action_completed)
+    }
+
+    /// Whether the health has been below [`PlayerConfiguration::critical_health_percent`] for
+    /// [`HEALTH_CRITICAL_COUNT`] consecutive updates.
+    #[inline]
+    pub fn is_health_critical(&self) -> bool {
+        self.health_critical_count >= HEALTH_CRITICAL_COUNT
+    }
+
+    /// Tracks consecutive health updates reading below
+    /// [`PlayerConfiguration::critical_health_percent`].
+    #[inline]
+    fn track_health_critical(&mut self, (current, max): (u32, u32)) {
+        match self.config.critical_health_percent {
+            Some(percentage) if max > 0 && (current as f32 / max as f32) < percentage => {
+                self.health_critical_count = self.health_critical_count.saturating_add(1);
+            }
+            _ => self.health_critical_count = 0,
+        }
+    }
+
+    /// Tracks consecutive health updates reading zero health.
+    ///
+    /// Sets [`Self::health_depleted`] and notifies when the count reaches
+    /// [`HEALTH_DEPLETED_COUNT`]. This is a last resort for when the tomb is not detected.
+    #[inline]
+    fn track_health_depleted(&mut self, context: &Context, (current, _): (u32, u32)) {
+        if !self.config.stop_on_health_depleted || current > 0 {
+            self.health_depleted_count = 0;
+            return;
+        }
+
+        self.health_depleted_count += 1;
+        if self.health_depleted_count == HEALTH_DEPLETED_COUNT {
+            debug!(target: "player", "health depleted, stopping actions");
+            self.health_depleted = true;
+            if !self.is_dead {
+                let _ = context
+                    .notification
+                    .schedule_notification(NotificationKind::PlayerIsDead);
+            }
+        }
+    }
+
     /// Increments the unstucking counter.
     ///
     /// Returns `true` when the player should transition to [`Player::Unstucking`].
@@ -525,6 +957,16 @@ impl PlayerState {
         }
     }
 
+    /// Scales the movement distance `threshold` by [`PlayerConfiguration::movement_speed_factor`].
+    #[inline]
+    pub(super) fn movement_threshold(&self, threshold: i32) -> i32 {
+        self.config
+            .movement_speed_factor
+            .map_or(threshold, |factor| {
+                (threshold as f32 * factor).round() as i32
+            })
+    }
+
     #[inline]
     pub(super) fn should_disable_grappling(&self) -> bool {
         // FIXME: ?????
@@ -546,12 +988,12 @@ impl PlayerState {
             Minimap::Idle(idle) => (idle.bbox.width, idle.platforms),
             _ => unreachable!(),
         };
-        let point = self
-            .auto_mob_pathing_points
-            .iter()
-            .enumerate()
-            .map(|(i, point)| (i, *point))
-            .choose(&mut rand::rng());
+        let point = context.rng.random_choose_iter(
+            self.auto_mob_pathing_points
+                .iter()
+                .enumerate()
+                .map(|(i, point)| (i, *point)),
+        );
         if let Some((i, _)) = point {
             self.auto_mob_pathing_points.remove(i);
         }
@@ -637,7 +1079,7 @@ impl PlayerState {
             .keys()
             .copied()
             .filter(|y| (mob_pos.y - y).abs() <= AUTO_MOB_REACHABLE_Y_THRESHOLD);
-        let y = ys.choose(&mut rand::rng());
+        let y = context.rng.random_choose_iter(ys);
 
         // Checking whether y is solidified yet is not needed because y will only be added
         // to the xs map when it is solidified. As for populated xs from platforms, the
@@ -729,6 +1171,7 @@ impl PlayerState {
             | PlayerAction::Key(_)
             | PlayerAction::Move(_)
             | PlayerAction::Panic(_)
+            | PlayerAction::CollectDrops(_)
             | PlayerAction::SolveRune => {
                 unreachable!()
             }
@@ -852,6 +1295,7 @@ impl PlayerState {
         if self.update_position_state(context) {
             self.update_health_state(context);
             self.update_rune_validating_state(context);
+            self.update_rune_abandoned_state(context);
             self.update_is_dead_state(context);
             return true;
         }
@@ -949,30 +1393,48 @@ impl PlayerState {
     /// Updates the rune validation [`Timeout`].
     ///
     /// [`PlayerState::rune_validate_timeout`] is [`Some`] only when [`Player::SolvingRune`]
-    /// successfully detects and sends all the keys. After about 12 seconds, it
-    /// will check if the player has the rune buff.
+    /// successfully detects and sends all the keys. The rune is solved as soon as the player has
+    /// the rune buff within [`PlayerConfiguration::rune_validate_ticks`]. Otherwise, it is
+    /// counted as a failure once the window ends so that solving can be retried.
     #[inline]
     fn update_rune_validating_state(&mut self, context: &Context) {
-        const VALIDATE_TIMEOUT: u32 = 375;
-
         debug_assert!(self.rune_failed_count < MAX_RUNE_FAILED_COUNT);
         debug_assert!(!self.rune_cash_shop);
-        self.rune_validate_timeout = self.rune_validate_timeout.and_then(|timeout| {
-            update_with_timeout(
-                timeout,
-                VALIDATE_TIMEOUT,
-                Some,
-                || {
-                    if matches!(context.buffs[BuffKind::Rune], Buff::No) {
-                        self.track_rune_fail_count();
-                    } else {
-                        self.rune_failed_count = 0;
-                    }
-                    None
-                },
-                Some,
-            )
-        });
+        let Some(timeout) = self.rune_validate_timeout else {
+            return;
+        };
+        if matches!(context.buffs[BuffKind::Rune], Buff::Yes) {
+            self.rune_validate_timeout = None;
+            self.rune_failed_count = 0;
+            self.track_rune_solved();
+            let _ = context
+                .notification
+                .schedule_notification(NotificationKind::RuneSolved);
+            return;
+        }
+
+        self.rune_validate_timeout = update_with_timeout(
+            timeout,
+            self.config.rune_validate_ticks.max(1),
+            Some,
+            || {
+                self.track_rune_fail_count();
+                None
+            },
+            Some,
+        );
+    }
+
+    /// Resets [`PlayerState::rune_abandoned`] and the calibration fail count once the rune is no
+    /// longer on the minimap.
+    #[inline]
+    fn update_rune_abandoned_state(&mut self, context: &Context) {
+        if let Minimap::Idle(idle) = context.minimap
+            && !idle.has_rune()
+        {
+            self.rune_abandoned = false;
+            self.rune_calibration_failed_count = 0;
+        }
     }
 
     /// Updates the player current health.
@@ -986,13 +1448,18 @@ impl PlayerState {
         if let Player::SolvingRune(_) = context.player {
             return;
         }
-        if self.config.use_potion_below_percent.is_none() {
+        if self.config.use_potion_below_percent.is_none()
+            && !self.config.stop_on_health_depleted
+            && self.config.critical_health_percent.is_none()
+        {
             {
                 let this = &mut *self;
                 this.health = None;
                 this.health_task = None;
                 this.health_bar = None;
                 this.health_bar_task = None;
+                this.health_depleted_count = 0;
+                this.health_critical_count = 0;
             };
             return;
         }
@@ -1023,11 +1490,15 @@ impl PlayerState {
             return;
         };
 
-        let percentage = self.config.use_potion_below_percent.unwrap();
+        self.health = Some(health);
+        self.track_health_depleted(context, health);
+        self.track_health_critical(health);
+
+        let Some(percentage) = self.config.use_potion_below_percent else {
+            return;
+        };
         let (current, max) = health;
         let ratio = current as f32 / max as f32;
-
-        self.health = Some(health);
         if ratio <= percentage {
             let _ = context.keys.send(self.config.potion_key);
         }
@@ -1038,9 +1509,10 @@ impl PlayerState {
     /// Upon being dead, a notification will be scheduled to notify the user.
     #[inline]
     fn update_is_dead_state(&mut self, context: &Context) {
+        let templates = self.death_templates.clone();
         let Update::Ok(is_dead) =
-            update_detection_task(context, 3000, &mut self.is_dead_task, |detector| {
-                Ok(detector.detect_player_is_dead())
+            update_detection_task(context, 3000, &mut self.is_dead_task, move |detector| {
+                Ok(detector.detect_player_is_dead(&templates))
             })
         else {
             return;
@@ -1085,15 +1557,182 @@ mod tests {
 
     use opencv::core::{Point, Rect};
 
+    use super::{HEALTH_DEPLETED_COUNT, LOST_POSITION_RECOVERY_MAX_COUNT, MAX_RUNE_FAILED_COUNT};
     use crate::{
         Position,
         array::Array,
+        buff::{Buff, BuffKind},
         context::Context,
         minimap::{Minimap, MinimapIdle},
         pathing::{Platform, find_neighbors},
-        player::{PlayerAction, PlayerActionAutoMob, PlayerState},
+        player::{PlayerAction, PlayerActionAutoMob, PlayerState, timeout::Timeout},
     };
 
+    #[test]
+    fn track_rune_calibration_fail_count_abandon_on_max_attempts() {
+        let mut state = PlayerState::default();
+        state.config.rune_calibration_max_attempts = 2;
+
+        assert!(!state.track_rune_calibration_fail_count());
+        assert!(!state.is_rune_abandoned());
+        assert_eq!(state.rune_failed_count, 1);
+
+        assert!(state.track_rune_calibration_fail_count());
+        assert!(state.is_rune_abandoned());
+        assert_eq!(state.rune_calibration_failed_count, 0);
+        assert_eq!(state.rune_failed_count, 2);
+    }
+
+    #[test]
+    fn track_rune_calibration_fail_count_cash_shop_across_abandons() {
+        let mut state = PlayerState::default();
+        state.config.rune_calibration_max_attempts = 3;
+
+        for _ in 0..MAX_RUNE_FAILED_COUNT - 1 {
+            state.track_rune_calibration_fail_count();
+        }
+        assert!(!state.rune_cash_shop);
+
+        state.track_rune_calibration_fail_count();
+        assert!(state.rune_cash_shop);
+        assert_eq!(state.rune_failed_count, 0);
+    }
+
+    #[test]
+    fn update_rune_validating_state_within_window() {
+        let mut context = Context::new(None, None);
+        let mut state = PlayerState::default();
+        state.config.rune_validate_ticks = 2;
+
+        // (1) Fails after the window ends without the rune buff
+        state.rune_validate_timeout = Some(Timeout::default());
+        for _ in 0..4 {
+            state.update_rune_validating_state(&context);
+        }
+        assert!(state.rune_validate_timeout.is_none());
+        assert_eq!(state.rune_failed_count, 1);
+        assert!(state.rune_solved_instants.is_empty());
+
+        // (2) Succeeds as soon as the rune buff is detected
+        state.rune_validate_timeout = Some(Timeout::default());
+        state.update_rune_validating_state(&context);
+        assert!(state.rune_validate_timeout.is_some());
+
+        context.buffs[BuffKind::Rune] = Buff::Yes;
+        state.update_rune_validating_state(&context);
+        assert!(state.rune_validate_timeout.is_none());
+        assert_eq!(state.rune_failed_count, 0);
+        assert_eq!(state.rune_solved_instants.len(), 1);
+    }
+
+    #[test]
+    fn is_rune_solving_capped_max_runes_solved_per_minute() {
+        let mut state = PlayerState::default();
+        state.track_rune_solved();
+        state.track_rune_solved();
+        assert!(!state.is_rune_solving_capped());
+
+        state.config.max_runes_solved_per_minute = Some(3);
+        assert!(!state.is_rune_solving_capped());
+
+        state.track_rune_solved();
+        assert!(state.is_rune_solving_capped());
+    }
+
+    #[test]
+    fn movement_threshold_scale_with_speed_factor() {
+        let mut state = PlayerState::default();
+        assert_eq!(state.movement_threshold(25), 25);
+
+        state.config.movement_speed_factor = Some(1.2);
+        assert_eq!(state.movement_threshold(25), 30);
+
+        state.config.movement_speed_factor = Some(0.5);
+        assert_eq!(state.movement_threshold(7), 4);
+    }
+
+    #[test]
+    fn track_lost_position_recovery_after_ticks() {
+        let mut state = PlayerState::default();
+        assert!(!state.track_lost_position());
+
+        state.config.lost_position_recovery_ticks = Some(2);
+        assert!(!state.track_lost_position());
+        assert!(state.track_lost_position());
+
+        for _ in 0..LOST_POSITION_RECOVERY_MAX_COUNT - 1 {
+            assert!(!state.track_lost_position_recovery());
+        }
+        assert!(state.track_lost_position_recovery());
+        assert_eq!(state.lost_position_ticks, 0);
+
+        state.track_lost_position();
+        state.track_lost_position_recovery();
+        state.clear_lost_position();
+        assert_eq!(state.lost_position_ticks, 0);
+        assert_eq!(state.lost_position_recovery_count, 0);
+    }
+
+    #[test]
+    fn track_unstucking_transitioned_redetect_minimap() {
+        let mut state = PlayerState::default();
+        state.track_unstucking_transitioned();
+        assert!(!state.take_redetect_minimap());
+
+        state.config.redetect_minimap_unstuck_count = Some(2);
+        state.track_unstucking_transitioned();
+        assert!(!state.take_redetect_minimap());
+        state.track_unstucking_transitioned();
+        assert!(state.take_redetect_minimap());
+        assert!(!state.take_redetect_minimap());
+        assert_eq!(state.unstuck_redetect_minimap_count, 0);
+    }
+
+    #[test]
+    fn track_health_depleted_consecutive_updates() {
+        let context = Context::new(None, None);
+        let mut state = PlayerState::default();
+        for _ in 0..HEALTH_DEPLETED_COUNT {
+            state.track_health_depleted(&context, (0, 100));
+        }
+        assert!(!state.take_health_depleted());
+
+        state.config.stop_on_health_depleted = true;
+        for _ in 0..HEALTH_DEPLETED_COUNT - 1 {
+            state.track_health_depleted(&context, (0, 100));
+        }
+        state.track_health_depleted(&context, (10, 100));
+        assert!(!state.take_health_depleted());
+        assert_eq!(state.health_depleted_count, 0);
+
+        for _ in 0..HEALTH_DEPLETED_COUNT {
+            state.track_health_depleted(&context, (0, 100));
+        }
+        assert!(state.take_health_depleted());
+        assert!(!state.take_health_depleted());
+    }
+
+    #[test]
+    fn track_health_critical_consecutive_updates() {
+        let mut state = PlayerState::default();
+        for _ in 0..HEALTH_CRITICAL_COUNT {
+            state.track_health_critical((10, 100));
+        }
+        assert!(!state.is_health_critical());
+
+        state.config.critical_health_percent = Some(0.2);
+        for _ in 0..HEALTH_CRITICAL_COUNT - 1 {
+            state.track_health_critical((10, 100));
+        }
+        state.track_health_critical((50, 100));
+        assert!(!state.is_health_critical());
+
+        for _ in 0..HEALTH_CRITICAL_COUNT {
+            state.track_health_critical((10, 100));
+        }
+        assert!(state.is_health_critical());
+    }
+
     #[test]
     fn auto_mob_pick_reachable_y_should_ignore_solidified_x_range() {
         let context = Context::new(None, None);
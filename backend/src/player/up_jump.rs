@@ -152,6 +152,7 @@ pub fn update_up_jumping_context(
                 }
             }
 
+            let y_velocity = state.velocity.1;
             on_action(
                 state,
                 |action| match action {
@@ -198,12 +199,27 @@ pub fn update_up_jumping_context(
                         }
                     }
                     PlayerAction::Key(PlayerActionKey {
-                        with: ActionKeyWith::Stationary | ActionKeyWith::DoubleJump,
+                        with: ActionKeyWith::UpJump,
+                        ..
+                    }) => {
+                        // Uses the key near the top when the up jump starts slowing down
+                        if !moving.completed || y_velocity > UP_JUMPED_Y_VELOCITY_THRESHOLD {
+                            return None;
+                        }
+                        Some((Player::UseKey(UseKey::from_action(action)), false))
+                    }
+                    PlayerAction::Key(PlayerActionKey {
+                        with:
+                            ActionKeyWith::Stationary
+                            | ActionKeyWith::DoubleJump
+                            | ActionKeyWith::Airborne,
                         ..
                     })
                     | PlayerAction::Move(_)
                     | PlayerAction::SolveRune => None,
-                    PlayerAction::Panic(_) | PlayerAction::FamiliarsSwapping(_) => unreachable!(),
+                    PlayerAction::Panic(_)
+                    | PlayerAction::FamiliarsSwapping(_)
+                    | PlayerAction::CollectDrops(_) => unreachable!(),
                 },
                 || Player::UpJumping(up_jumping.moving(moving)),
             )
@@ -9,9 +9,10 @@ use super::{
         PlayerAction, PlayerActionKey, PlayerActionPingPong, on_ping_pong_double_jump_action,
     },
     double_jump::DoubleJumping,
+    up_jump::UpJumping,
 };
 use crate::{
-    ActionKeyDirection, ActionKeyWith, Class, KeyBinding, LinkKeyBinding,
+    ActionKeyDirection, ActionKeyWith, Class, KeyBinding, LinkKeyBinding, Position,
     context::Context,
     player::{
         LastMovement, MOVE_TIMEOUT, Moving, Player, on_action_state_mut, update_with_timeout,
@@ -24,20 +25,33 @@ const CHANGE_DIRECTION_TIMEOUT: u32 = 3;
 /// The tick to which the actual key will be pressed for [`LinkKeyBinding::Along`].
 const LINK_ALONG_PRESS_TICK: u32 = 2;
 
+/// The maximum x distance outside of the position random range for the player to be considered
+/// at the strict position.
+const STRICT_POSITION_X_THRESHOLD: i32 = 1;
+
+/// The total number of ticks for waiting the player to reach the strict position before timing
+/// out.
+const STRICT_POSITION_TIMEOUT: u32 = 15;
+
 /// The different stages of using key.
 #[derive(Clone, Copy, Debug)]
 pub enum UseKeyStage {
     /// Checks whether [`ActionKeyWith`] and [`ActionKeyDirection`] are satisfied and stalls
     /// for [`UseKey::wait_before_use_ticks`].
     Precondition,
+    /// Waits for the player to be at [`UseKey::strict_position`].
+    ///
+    /// Returns to [`UseKeyStage::Precondition`] once the player is at the position or upon
+    /// timeout. Upon timeout, the action is aborted instead if configured to do so.
+    ConfirmingPosition(Timeout),
     /// Changes direction to match [`ActionKeyDirection`].
     ///
     /// Returns to [`UseKeyStage::Precondition`] upon timeout.
     ChangingDirection(Timeout),
-    /// Ensures player double jumped or is stationary.
+    /// Ensures player double jumped, is stationary or is airborne.
     ///
     /// Returns to [`UseKeyStage::Precondition`] if player is stationary or
-    /// transfers to [`Player::DoubleJumping`].
+    /// transfers to [`Player::DoubleJumping`] or [`Player::Jumping`].
     EnsuringUseWith,
     /// Uses the actual key with optional [`LinkKeyBinding`] and stalls
     /// for [`UseKey::wait_after_use_ticks`].
@@ -54,8 +68,16 @@ pub struct UseKey {
     current_count: u32,
     direction: ActionKeyDirection,
     with: ActionKeyWith,
+    /// The position the player must be at before using the key.
+    ///
+    /// Cleared once confirmed so that it is only waited for once.
+    strict_position: Option<Position>,
     wait_before_use_ticks: u32,
+    wait_before_use_ticks_random_range: u32,
     wait_after_use_ticks: u32,
+    wait_after_use_ticks_random_range: u32,
+    /// The ticks to wait once after the last use before the next linked action.
+    linked_wait_ticks: u32,
     stage: UseKeyStage,
 }
 
@@ -71,40 +93,31 @@ impl UseKey {
                 key,
                 link_key,
                 count,
+                position,
+                strict_position,
                 direction,
                 with,
                 wait_before_use_ticks,
                 wait_before_use_ticks_random_range,
                 wait_after_use_ticks,
                 wait_after_use_ticks_random_range,
+                linked_wait_ticks,
                 ..
-            }) => {
-                let wait_before_min =
-                    wait_before_use_ticks.saturating_sub(wait_before_use_ticks_random_range);
-                let wait_before_max =
-                    wait_before_use_ticks.saturating_add(wait_before_use_ticks_random_range + 1);
-                // TODO: Replace rand with Rng
-                let wait_before = rand::random_range(wait_before_min..wait_before_max);
-
-                let wait_after_min =
-                    wait_after_use_ticks.saturating_sub(wait_after_use_ticks_random_range);
-                let wait_after_max =
-                    wait_after_use_ticks.saturating_add(wait_after_use_ticks_random_range + 1);
-                // TODO: Replace rand with Rng
-                let wait_after = rand::random_range(wait_after_min..wait_after_max);
-
-                Self {
-                    key,
-                    link_key,
-                    count,
-                    current_count: 0,
-                    direction,
-                    with,
-                    wait_before_use_ticks: wait_before,
-                    wait_after_use_ticks: wait_after,
-                    stage: UseKeyStage::Precondition,
-                }
-            }
+            }) => Self {
+                key,
+                link_key,
+                count,
+                current_count: 0,
+                direction,
+                with,
+                strict_position: position.filter(|_| strict_position),
+                wait_before_use_ticks,
+                wait_before_use_ticks_random_range,
+                wait_after_use_ticks,
+                wait_after_use_ticks_random_range,
+                linked_wait_ticks,
+                stage: UseKeyStage::Precondition,
+            },
             PlayerAction::AutoMob(mob) => Self {
                 key: mob.key,
                 link_key: None,
@@ -119,28 +132,44 @@ impl UseKey {
                     None => unreachable!(),
                 },
                 with: ActionKeyWith::Any,
+                strict_position: None,
                 wait_before_use_ticks: mob.wait_before_ticks,
+                wait_before_use_ticks_random_range: 0,
                 wait_after_use_ticks: mob.wait_after_ticks,
+                wait_after_use_ticks_random_range: 0,
+                linked_wait_ticks: 0,
                 stage: UseKeyStage::Precondition,
             },
-            PlayerAction::PingPong(ping_pong) => Self {
-                key: ping_pong.key,
-                link_key: None,
-                count: ping_pong.count,
-                current_count: 0,
-                direction: if matches!(ping_pong.direction, PingPongDirection::Left) {
-                    ActionKeyDirection::Left
-                } else {
-                    ActionKeyDirection::Right
-                },
-                with: ActionKeyWith::Any,
-                wait_before_use_ticks: ping_pong.wait_before_ticks,
-                wait_after_use_ticks: ping_pong.wait_after_ticks,
-                stage: UseKeyStage::Precondition,
-            },
+            PlayerAction::PingPong(ping_pong) => {
+                let (key, direction) = match ping_pong.direction {
+                    PingPongDirection::Left => (
+                        ping_pong.alternate_key.unwrap_or(ping_pong.key),
+                        ActionKeyDirection::Left,
+                    ),
+                    PingPongDirection::Right => (ping_pong.key, ActionKeyDirection::Right),
+                };
+                Self {
+                    key,
+                    link_key: None,
+                    count: ping_pong.count,
+                    current_count: 0,
+                    direction,
+                    with: ActionKeyWith::Any,
+                    strict_position: None,
+                    wait_before_use_ticks: ping_pong.wait_before_ticks,
+                    wait_before_use_ticks_random_range: 0,
+                    wait_after_use_ticks: ping_pong.wait_after_ticks,
+                    wait_after_use_ticks_random_range: 0,
+                    linked_wait_ticks: 0,
+                    // Always changes direction since the last known direction may lag behind
+                    // the turn
+                    stage: UseKeyStage::ChangingDirection(Timeout::default()),
+                }
+            }
             PlayerAction::FamiliarsSwapping(_)
             | PlayerAction::SolveRune
             | PlayerAction::Panic(_)
+            | PlayerAction::CollectDrops(_)
             | PlayerAction::Move { .. } => {
                 unreachable!()
             }
@@ -164,6 +193,15 @@ pub fn update_use_key_context(
     let next = match use_key.stage {
         UseKeyStage::Precondition => {
             debug_assert!(use_key.current_count < use_key.count);
+            if use_key
+                .strict_position
+                .is_some_and(|position| !is_at_strict_position(state, position))
+            {
+                return Player::UseKey(UseKey {
+                    stage: UseKeyStage::ConfirmingPosition(Timeout::default()),
+                    ..use_key
+                });
+            }
             if !ensure_direction(state, use_key.direction) {
                 return Player::UseKey(UseKey {
                     stage: UseKeyStage::ChangingDirection(Timeout::default()),
@@ -182,22 +220,71 @@ pub fn update_use_key_context(
             );
             debug_assert!(
                 matches!(use_key.with, ActionKeyWith::Any)
-                    || (matches!(use_key.with, ActionKeyWith::Stationary) && state.is_stationary)
+                    || (matches!(use_key.with, ActionKeyWith::Stationary)
+                        && is_stationary_for_use(state))
                     || (matches!(use_key.with, ActionKeyWith::DoubleJump)
                         && matches!(state.last_movement, Some(LastMovement::DoubleJumping)))
+                    || (matches!(use_key.with, ActionKeyWith::UpJump)
+                        && matches!(state.last_movement, Some(LastMovement::UpJumping)))
+                    || (matches!(use_key.with, ActionKeyWith::Airborne) && is_airborne(state))
             );
             let next = Player::UseKey(UseKey {
                 stage: UseKeyStage::Using(Timeout::default(), false),
                 ..use_key
             });
-            if use_key.wait_before_use_ticks > 0 {
+            let wait_before_min = use_key
+                .wait_before_use_ticks
+                .saturating_sub(use_key.wait_before_use_ticks_random_range);
+            let wait_before_max = use_key
+                .wait_before_use_ticks
+                .saturating_add(use_key.wait_before_use_ticks_random_range + 1);
+            let wait_before = context.rng.random_range(wait_before_min..wait_before_max);
+            if wait_before > 0 {
                 state.stalling_timeout_state = Some(next);
-                Player::Stalling(Timeout::default(), use_key.wait_before_use_ticks)
+                Player::Stalling(Timeout::default(), wait_before)
             } else {
                 state.use_immediate_control_flow = true;
                 next
             }
         }
+        UseKeyStage::ConfirmingPosition(timeout) => {
+            let position = use_key.strict_position.unwrap();
+            if is_at_strict_position(state, position) {
+                Player::UseKey(UseKey {
+                    strict_position: None,
+                    stage: UseKeyStage::Precondition,
+                    ..use_key
+                })
+            } else {
+                update_with_timeout(
+                    timeout,
+                    STRICT_POSITION_TIMEOUT,
+                    |timeout| {
+                        Player::UseKey(UseKey {
+                            stage: UseKeyStage::ConfirmingPosition(timeout),
+                            ..use_key
+                        })
+                    },
+                    || {
+                        if state.config.abort_strict_position_on_timeout {
+                            Player::Idle
+                        } else {
+                            Player::UseKey(UseKey {
+                                strict_position: None,
+                                stage: UseKeyStage::Precondition,
+                                ..use_key
+                            })
+                        }
+                    },
+                    |timeout| {
+                        Player::UseKey(UseKey {
+                            stage: UseKeyStage::ConfirmingPosition(timeout),
+                            ..use_key
+                        })
+                    },
+                )
+            }
+        }
         UseKeyStage::ChangingDirection(timeout) => {
             let key = match use_key.direction {
                 ActionKeyDirection::Left => KeyKind::Left,
@@ -233,7 +320,7 @@ pub fn update_use_key_context(
         UseKeyStage::EnsuringUseWith => match use_key.with {
             ActionKeyWith::Any => unreachable!(),
             ActionKeyWith::Stationary => {
-                let stage = if state.is_stationary {
+                let stage = if is_stationary_for_use(state) {
                     UseKeyStage::Precondition
                 } else {
                     UseKeyStage::EnsuringUseWith
@@ -248,6 +335,21 @@ pub fn update_use_key_context(
                     true,
                 ))
             }
+            ActionKeyWith::UpJump => {
+                let pos = state.last_known_pos.unwrap();
+                Player::UpJumping(UpJumping::new(Moving::new(pos, pos, false, None)))
+            }
+            ActionKeyWith::Airborne => {
+                if is_airborne(state) {
+                    Player::UseKey(UseKey {
+                        stage: UseKeyStage::Precondition,
+                        ..use_key
+                    })
+                } else {
+                    let pos = state.last_known_pos.unwrap();
+                    Player::Jumping(Moving::new(pos, pos, false, None))
+                }
+            }
         },
         UseKeyStage::Using(timeout, completed) => {
             debug_assert!(use_key.link_key.is_some() || !completed);
@@ -299,13 +401,26 @@ pub fn update_use_key_context(
                     let _ = context.keys.send(use_key.key.into());
                 }
             }
+            state.track_last_attack();
             let next = Player::UseKey(UseKey {
                 stage: UseKeyStage::Postcondition,
                 ..use_key
             });
-            if use_key.wait_after_use_ticks > 0 {
+            let wait_after_min = use_key
+                .wait_after_use_ticks
+                .saturating_sub(use_key.wait_after_use_ticks_random_range);
+            let wait_after_max = use_key
+                .wait_after_use_ticks
+                .saturating_add(use_key.wait_after_use_ticks_random_range + 1);
+            let wait_after = context.rng.random_range(wait_after_min..wait_after_max);
+            let wait_after = if use_key.current_count + 1 >= use_key.count {
+                wait_after + use_key.linked_wait_ticks
+            } else {
+                wait_after
+            };
+            if wait_after > 0 {
                 state.stalling_timeout_state = Some(next);
-                Player::Stalling(Timeout::default(), use_key.wait_after_use_ticks)
+                Player::Stalling(Timeout::default(), wait_after)
             } else {
                 next
             }
@@ -358,12 +473,20 @@ pub fn update_use_key_context(
             PlayerAction::Move(_) => None,
             PlayerAction::FamiliarsSwapping(_)
             | PlayerAction::SolveRune
-            | PlayerAction::Panic(_) => unreachable!(),
+            | PlayerAction::Panic(_)
+            | PlayerAction::CollectDrops(_) => unreachable!(),
         },
         || next,
     )
 }
 
+/// Whether the player x is within [`STRICT_POSITION_X_THRESHOLD`] of `position` random range.
+#[inline]
+fn is_at_strict_position(state: &PlayerState, position: Position) -> bool {
+    let x = state.last_known_pos.unwrap().x;
+    (x - position.x).abs() <= position.x_random_range + STRICT_POSITION_X_THRESHOLD
+}
+
 #[inline]
 fn ensure_direction(state: &PlayerState, direction: ActionKeyDirection) -> bool {
     match direction {
@@ -378,13 +501,34 @@ fn ensure_direction(state: &PlayerState, direction: ActionKeyDirection) -> bool
 fn ensure_use_with(state: &PlayerState, use_key: UseKey) -> bool {
     match use_key.with {
         ActionKeyWith::Any => true,
-        ActionKeyWith::Stationary => state.is_stationary,
+        ActionKeyWith::Stationary => is_stationary_for_use(state),
         ActionKeyWith::DoubleJump => {
             matches!(state.last_movement, Some(LastMovement::DoubleJumping))
         }
+        ActionKeyWith::UpJump => matches!(state.last_movement, Some(LastMovement::UpJumping)),
+        ActionKeyWith::Airborne => is_airborne(state),
     }
 }
 
+/// Checks whether the player is off the ground to use a key with [`ActionKeyWith::Airborne`].
+///
+/// The player is considered airborne when the y velocity exceeds the near stationary threshold.
+#[inline]
+pub fn is_airborne(state: &PlayerState) -> bool {
+    state.velocity.1 > state.config.near_stationary_y_velocity_threshold
+}
+
+/// Checks whether the player is stationary enough to use a key with [`ActionKeyWith::Stationary`].
+///
+/// When the configured stationary cast velocity threshold is positive, the player is
+/// also considered stationary if both velocity axes are within the threshold.
+#[inline]
+fn is_stationary_for_use(state: &PlayerState) -> bool {
+    let threshold = state.config.stationary_cast_velocity_threshold;
+    state.is_stationary
+        || (threshold > 0.0 && state.velocity.0 <= threshold && state.velocity.1 <= threshold)
+}
+
 #[inline]
 fn update_link_key(
     context: &Context,
@@ -452,18 +596,53 @@ fn update_link_key(
 mod tests {
     use std::assert_matches::assert_matches;
 
+    use opencv::core::Point;
     use platforms::windows::KeyKind;
 
     use crate::{
-        ActionKeyDirection, ActionKeyWith, KeyBinding, LinkKeyBinding,
+        ActionKeyDirection, ActionKeyWith, KeyBinding, LinkKeyBinding, Position,
         bridge::MockKeySender,
         context::Context,
         player::{
-            Player, PlayerState, Timeout, update_non_positional_context,
-            use_key::{UseKey, UseKeyStage, update_use_key_context},
+            LastMovement, PingPongDirection, Player, PlayerAction, PlayerActionPingPong,
+            PlayerState, Timeout, update_non_positional_context,
+            use_key::{STRICT_POSITION_TIMEOUT, UseKey, UseKeyStage, update_use_key_context},
         },
     };
 
+    #[test]
+    fn use_key_from_ping_pong_action() {
+        let action = PlayerActionPingPong {
+            key: KeyBinding::A,
+            count: 1,
+            direction: PingPongDirection::Right,
+            alternate_key: Some(KeyBinding::B),
+            ..Default::default()
+        };
+
+        let use_key = UseKey::from_action(PlayerAction::PingPong(action));
+        assert_eq!(use_key.key, KeyBinding::A);
+        assert_eq!(use_key.direction, ActionKeyDirection::Right);
+        assert_matches!(
+            use_key.stage,
+            UseKeyStage::ChangingDirection(Timeout { started: false, .. })
+        );
+
+        let use_key = UseKey::from_action(PlayerAction::PingPong(PlayerActionPingPong {
+            direction: PingPongDirection::Left,
+            ..action
+        }));
+        assert_eq!(use_key.key, KeyBinding::B);
+        assert_eq!(use_key.direction, ActionKeyDirection::Left);
+
+        let use_key = UseKey::from_action(PlayerAction::PingPong(PlayerActionPingPong {
+            direction: PingPongDirection::Left,
+            alternate_key: None,
+            ..action
+        }));
+        assert_eq!(use_key.key, KeyBinding::A);
+    }
+
     #[test]
     fn use_key_ensure_use_with() {
         let mut state = PlayerState::default();
@@ -475,8 +654,12 @@ mod tests {
             current_count: 0,
             direction: ActionKeyDirection::Any,
             with: ActionKeyWith::Stationary,
+            strict_position: None,
             wait_before_use_ticks: 0,
+            wait_before_use_ticks_random_range: 0,
             wait_after_use_ticks: 0,
+            wait_after_use_ticks_random_range: 0,
+            linked_wait_ticks: 0,
             stage: UseKeyStage::Precondition,
         };
 
@@ -503,6 +686,184 @@ mod tests {
         );
     }
 
+    #[test]
+    fn use_key_ensure_use_with_up_jump() {
+        let mut state = PlayerState::default();
+        state.last_known_pos = Some(Point::new(5, 5));
+        let context = Context::new(None, None);
+        let use_key = UseKey {
+            key: KeyBinding::A,
+            link_key: None,
+            count: 1,
+            current_count: 0,
+            direction: ActionKeyDirection::Any,
+            with: ActionKeyWith::UpJump,
+            strict_position: None,
+            wait_before_use_ticks: 0,
+            wait_before_use_ticks_random_range: 0,
+            wait_after_use_ticks: 0,
+            wait_after_use_ticks_random_range: 0,
+            linked_wait_ticks: 0,
+            stage: UseKeyStage::EnsuringUseWith,
+        };
+
+        // up jumps when not yet up jumped
+        let player =
+            update_non_positional_context(Player::UseKey(use_key), &context, &mut state, false)
+                .unwrap();
+        assert_matches!(player, Player::UpJumping(_));
+
+        // uses key after up jumped
+        state.last_movement = Some(LastMovement::UpJumping);
+        let player = update_non_positional_context(
+            Player::UseKey(UseKey {
+                stage: UseKeyStage::Precondition,
+                ..use_key
+            }),
+            &context,
+            &mut state,
+            false,
+        )
+        .unwrap();
+        assert_matches!(
+            player,
+            Player::UseKey(UseKey {
+                stage: UseKeyStage::Using(_, _),
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn use_key_ensure_use_with_stationary_velocity_threshold() {
+        let mut state = PlayerState::default();
+        state.velocity = (0.2, 0.1);
+        let context = Context::new(None, None);
+        let use_key = UseKey {
+            key: KeyBinding::A,
+            link_key: None,
+            count: 1,
+            current_count: 0,
+            direction: ActionKeyDirection::Any,
+            with: ActionKeyWith::Stationary,
+            strict_position: None,
+            wait_before_use_ticks: 0,
+            wait_before_use_ticks_random_range: 0,
+            wait_after_use_ticks: 0,
+            wait_after_use_ticks_random_range: 0,
+            linked_wait_ticks: 0,
+            stage: UseKeyStage::EnsuringUseWith,
+        };
+
+        // not stationary without threshold
+        let player =
+            update_non_positional_context(Player::UseKey(use_key), &context, &mut state, false)
+                .unwrap();
+        assert_matches!(
+            player,
+            Player::UseKey(UseKey {
+                stage: UseKeyStage::EnsuringUseWith,
+                ..
+            })
+        );
+
+        // stationary with velocity within threshold
+        state.config.stationary_cast_velocity_threshold = 0.25;
+        let player =
+            update_non_positional_context(Player::UseKey(use_key), &context, &mut state, false)
+                .unwrap();
+        assert_matches!(
+            player,
+            Player::UseKey(UseKey {
+                stage: UseKeyStage::Precondition,
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn use_key_strict_position() {
+        let mut state = PlayerState::default();
+        state.last_known_pos = Some(Point::new(20, 10));
+        let context = Context::new(None, None);
+        let use_key = UseKey {
+            key: KeyBinding::A,
+            link_key: None,
+            count: 1,
+            current_count: 0,
+            direction: ActionKeyDirection::Any,
+            with: ActionKeyWith::Any,
+            strict_position: Some(Position {
+                x: 10,
+                ..Position::default()
+            }),
+            wait_before_use_ticks: 0,
+            wait_before_use_ticks_random_range: 0,
+            wait_after_use_ticks: 0,
+            wait_after_use_ticks_random_range: 0,
+            linked_wait_ticks: 0,
+            stage: UseKeyStage::Precondition,
+        };
+
+        // confirming position start
+        let mut player = Player::UseKey(use_key);
+        player = update_non_positional_context(player, &context, &mut state, false).unwrap();
+        assert_matches!(
+            player,
+            Player::UseKey(UseKey {
+                stage: UseKeyStage::ConfirmingPosition(_),
+                ..
+            })
+        );
+
+        // confirming position complete
+        state.last_known_pos = Some(Point::new(11, 10));
+        player = update_non_positional_context(player, &context, &mut state, false).unwrap();
+        assert_matches!(
+            player,
+            Player::UseKey(UseKey {
+                strict_position: None,
+                stage: UseKeyStage::Precondition,
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn use_key_strict_position_abort_on_timeout() {
+        let mut state = PlayerState::default();
+        state.last_known_pos = Some(Point::new(20, 10));
+        state.config.abort_strict_position_on_timeout = true;
+        let context = Context::new(None, None);
+        let use_key = UseKey {
+            key: KeyBinding::A,
+            link_key: None,
+            count: 1,
+            current_count: 0,
+            direction: ActionKeyDirection::Any,
+            with: ActionKeyWith::Any,
+            strict_position: Some(Position {
+                x: 10,
+                ..Position::default()
+            }),
+            wait_before_use_ticks: 0,
+            wait_before_use_ticks_random_range: 0,
+            wait_after_use_ticks: 0,
+            wait_after_use_ticks_random_range: 0,
+            linked_wait_ticks: 0,
+            stage: UseKeyStage::ConfirmingPosition(Timeout {
+                started: true,
+                current: STRICT_POSITION_TIMEOUT,
+                total: STRICT_POSITION_TIMEOUT,
+            }),
+        };
+
+        assert_matches!(
+            update_use_key_context(&context, &mut state, use_key),
+            Player::Idle
+        );
+    }
+
     #[test]
     fn use_key_change_direction() {
         let mut keys = MockKeySender::new();
@@ -521,8 +882,12 @@ mod tests {
             current_count: 0,
             direction: ActionKeyDirection::Left,
             with: ActionKeyWith::Any,
+            strict_position: None,
             wait_before_use_ticks: 0,
+            wait_before_use_ticks_random_range: 0,
             wait_after_use_ticks: 0,
+            wait_after_use_ticks_random_range: 0,
+            linked_wait_ticks: 0,
             stage: UseKeyStage::Precondition,
         };
 
@@ -585,8 +950,12 @@ mod tests {
             current_count: 0,
             direction: ActionKeyDirection::Any,
             with: ActionKeyWith::Any,
+            strict_position: None,
             wait_before_use_ticks: 0,
+            wait_before_use_ticks_random_range: 0,
             wait_after_use_ticks: 0,
+            wait_after_use_ticks_random_range: 0,
+            linked_wait_ticks: 0,
             stage: UseKeyStage::Precondition,
         };
 
@@ -638,8 +1007,12 @@ mod tests {
             current_count: 0,
             direction: ActionKeyDirection::Any,
             with: ActionKeyWith::Any,
+            strict_position: None,
             wait_before_use_ticks: 10,
+            wait_before_use_ticks_random_range: 0,
             wait_after_use_ticks: 20,
+            wait_after_use_ticks_random_range: 0,
+            linked_wait_ticks: 0,
             stage: UseKeyStage::Precondition,
         };
 
@@ -687,6 +1060,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn use_key_linked_wait_after_last_count() {
+        let mut keys = MockKeySender::new();
+        keys.expect_send()
+            .withf(|key| matches!(key, KeyKind::A))
+            .times(2)
+            .returning(|_| Ok(()));
+        let mut state = PlayerState::default();
+        let context = Context::new(Some(keys), None);
+        let use_key = UseKey {
+            key: KeyBinding::A,
+            link_key: None,
+            count: 2,
+            current_count: 0,
+            direction: ActionKeyDirection::Any,
+            with: ActionKeyWith::Any,
+            strict_position: None,
+            wait_before_use_ticks: 0,
+            wait_before_use_ticks_random_range: 0,
+            wait_after_use_ticks: 0,
+            wait_after_use_ticks_random_range: 0,
+            linked_wait_ticks: 10,
+            stage: UseKeyStage::Using(Timeout::default(), false),
+        };
+
+        // does not wait between repeats
+        assert_matches!(
+            update_use_key_context(&context, &mut state, use_key),
+            Player::UseKey(UseKey {
+                stage: UseKeyStage::Postcondition,
+                ..
+            })
+        );
+        assert!(state.stalling_timeout_state.is_none());
+
+        // waits once after the last repeat
+        assert_matches!(
+            update_use_key_context(
+                &context,
+                &mut state,
+                UseKey {
+                    current_count: 1,
+                    ..use_key
+                }
+            ),
+            Player::Stalling(_, 10)
+        );
+    }
+
     #[test]
     fn use_key_link_along() {
         let mut state = PlayerState::default();
@@ -698,8 +1120,12 @@ mod tests {
             current_count: 0,
             direction: ActionKeyDirection::Any,
             with: ActionKeyWith::Any,
+            strict_position: None,
             wait_before_use_ticks: 0,
+            wait_before_use_ticks_random_range: 0,
             wait_after_use_ticks: 0,
+            wait_after_use_ticks_random_range: 0,
+            linked_wait_ticks: 0,
             stage: UseKeyStage::Using(Timeout::default(), false),
         };
 
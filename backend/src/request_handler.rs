@@ -1,12 +1,13 @@
 #[cfg(debug_assertions)]
 use std::sync::LazyLock;
+use std::time::Duration;
 #[cfg(debug_assertions)]
 use std::time::Instant;
 
 #[cfg(debug_assertions)]
 use include_dir::{Dir, include_dir};
 use log::debug;
-use opencv::core::{MatTraitConst, MatTraitConstManual, Vec4b};
+use opencv::core::{MatTraitConst, MatTraitConstManual, Point, Vec4b};
 #[cfg(debug_assertions)]
 use opencv::{
     core::{Mat, ModifyInplace, Vector},
@@ -21,26 +22,36 @@ use tokio::sync::broadcast;
 
 #[cfg(debug_assertions)]
 use crate::debug::{
-    save_image_for_training, save_image_for_training_to, save_minimap_for_training,
+    debug_buffs_region, save_image_for_training, save_image_for_training_to,
+    save_minimap_for_training,
 };
 #[cfg(debug_assertions)]
-use crate::detect::{ArrowsCalibrating, ArrowsState, CachedDetector, Detector};
+use crate::detect::{ArrowsCalibrating, ArrowsState, CachedDetector, Detector, buffs_region_bbox};
 #[cfg(debug_assertions)]
 use crate::mat::OwnedMat;
 use crate::{
     Action, ActionCondition, ActionKey, Bound, CaptureMode, Configuration, GameState, KeyBinding,
-    KeyBindingConfiguration, Minimap as MinimapData, PotionMode, RequestHandler, Settings,
+    KeyBindingConfiguration, Minimap as MinimapData, PotionMode, RequestHandler, RotationMode,
+    RuneArrowPressStyle, Settings,
     bridge::{ImageCapture, ImageCaptureKind, KeySenderMethod},
     buff::{BuffKind, BuffState},
-    context::Context,
-    database::InputMethod,
-    minimap::{Minimap, MinimapState},
-    player::PlayerState,
+    context::{Context, MS_PER_TICK, context_rng_seed},
+    database::{InputMethod, query_seeds},
+    detect::load_grayscale_templates,
+    minimap::{Minimap, MinimapState, OtherPlayerFilter},
+    player::{PlayerConfiguration, PlayerState},
     poll_request,
+    rng::Rng,
     rotator::{Rotator, RotatorBuildArgs},
     skill::SkillKind,
 };
 
+/// Maximum number of pets that can be fed.
+const MAX_FEED_PET_COUNT: u32 = 3;
+
+/// Number of ticks between each attempt to re-select a closed capture handle.
+const CAPTURE_HANDLE_RESELECT_INTERVAL_TICKS: u64 = 30;
+
 pub struct DefaultRequestHandler<'a> {
     pub context: &'a mut Context,
     pub config: &'a mut Configuration,
@@ -55,10 +66,12 @@ pub struct DefaultRequestHandler<'a> {
     pub key_receiver: &'a mut KeyReceiver,
     pub image_capture: &'a mut ImageCapture,
     pub capture_handles: &'a mut Vec<(String, Handle)>,
-    pub selected_capture_handle: &'a mut Option<Handle>,
+    pub selected_capture_handle: &'a mut Option<(String, Handle)>,
     #[cfg(debug_assertions)]
     pub recording_images_id: &'a mut Option<String>,
     #[cfg(debug_assertions)]
+    pub recording_macro: &'a mut Option<Vec<(KeyBinding, Instant)>>,
+    #[cfg(debug_assertions)]
     pub infering_rune: &'a mut Option<(ArrowsCalibrating, Instant)>,
 }
 
@@ -71,6 +84,31 @@ impl DefaultRequestHandler<'_> {
         poll_key(self);
     }
 
+    /// Re-selects the capture handle whose title matches the previously selected one after its
+    /// window has been closed.
+    ///
+    /// Returns `true` if a new capture handle has been selected.
+    pub fn poll_capture_handle(&mut self) -> bool {
+        if !self.settings.enable_capture_handle_reselection
+            || self.context.tick % CAPTURE_HANDLE_RESELECT_INTERVAL_TICKS != 0
+        {
+            return false;
+        }
+        let Some((title, handle)) = self.selected_capture_handle.clone() else {
+            return false;
+        };
+        if handle.is_valid() {
+            return false;
+        }
+
+        let (names, _) = self.on_query_capture_handles();
+        let Some(index) = names.iter().position(|name| name == &title) else {
+            return false;
+        };
+        self.on_select_capture_handle(Some(index));
+        true
+    }
+
     #[cfg(debug_assertions)]
     pub fn poll_debug(&mut self) {
         if let Some((calibrating, instant)) = self.infering_rune.as_ref().copied() {
@@ -110,17 +148,36 @@ impl DefaultRequestHandler<'_> {
     }
 
     fn update_rotator_actions(&mut self) {
-        let mode = self
-            .minimap
-            .data()
-            .map(|minimap| minimap.rotation_mode)
-            .unwrap_or_default()
-            .into();
+        let mode = self.minimap.rotation_mode().unwrap_or_default().into();
         let reset_on_erda = self
             .minimap
             .data()
             .map(|minimap| minimap.actions_any_reset_on_erda_condition)
             .unwrap_or_default();
+        let merge_distance = self
+            .minimap
+            .data()
+            .map(|minimap| minimap.actions_merge_distance)
+            .unwrap_or_default();
+        let turn_around_key = self
+            .minimap
+            .data()
+            .and_then(|minimap| minimap.actions_turn_around_key);
+        let (home_position, home_idle_millis) = self
+            .minimap
+            .data()
+            .map(|minimap| (minimap.home_position, minimap.home_idle_millis))
+            .unwrap_or_default();
+        let actions_offset = self
+            .minimap
+            .data()
+            .map(|minimap| Point::new(minimap.actions_offset_x, minimap.actions_offset_y))
+            .unwrap_or_default();
+        let familiar_swap_safe_position = self
+            .settings
+            .familiars
+            .move_to_safe_spot
+            .then_some(home_position);
         let actions = config_actions(self.config)
             .into_iter()
             .chain(self.actions.iter().copied())
@@ -129,18 +186,54 @@ impl DefaultRequestHandler<'_> {
             mode,
             actions: actions.as_slice(),
             buffs: self.buffs,
+            buff_all_keys: if self.config.enable_buff_all {
+                self.config.buff_all_keys.as_slice()
+            } else {
+                &[]
+            },
+            buff_all_spacing_millis: self.config.buff_all_spacing_millis,
+            timer_only_buffs: &self.config.timer_only_buffs,
+            timer_only_buff_recast_millis: self.config.timer_only_buff_recast_millis,
+            buff_combat_lull_millis: self
+                .config
+                .skip_buff_in_combat
+                .then_some(self.config.buff_combat_lull_millis),
             familiar_essence_key: self.config.familiar_essence_key.key,
             familiar_swappable_slots: self.settings.familiars.swappable_familiars,
             familiar_swappable_rarities: &self.settings.familiars.swappable_rarities,
             familiar_swap_check_millis: self.settings.familiars.swap_check_millis,
+            familiar_hover_settle_ticks: self.settings.familiars.hover_settle_ticks,
+            familiar_no_swappable_cards_outcome: self.settings.familiars.no_swappable_cards_outcome,
+            familiar_swap_safe_position,
+            familiar_essence_check_millis: self.settings.familiars.essence_check_millis,
+            pickup_key: self.config.pickup_key.key,
+            collect_drops_millis: self.settings.collect_drops_millis,
             panic_mode: self.settings.panic_mode,
+            panic_defensive_key: self.settings.panic_defensive_key.key,
+            panic_other_player_filter: panic_other_player_filter(self.settings),
             enable_panic_mode: self.settings.enable_panic_mode,
             enable_rune_solving: self.settings.enable_rune_solving,
+            enable_focus_rune_solving: self.settings.enable_focus_rune_solving,
+            auto_mob_priority_grace_millis: self.settings.auto_mob_priority_grace_millis,
+            enable_rune_solving_ignore_buff: self.settings.enable_rune_solving_ignore_buff,
             enable_change_channel_on_elite_boss_appear: self
                 .settings
                 .enable_change_channel_on_elite_boss_appear,
+            enable_panic_on_elite_boss_low_health: self
+                .settings
+                .enable_panic_on_elite_boss_low_health,
+            elite_boss_consumable_keys: &self.settings.elite_boss_consumable_keys,
             enable_familiars_swapping: self.settings.familiars.enable_familiars_swapping,
+            enable_collect_drops: self.settings.enable_collect_drops,
+            interact_key: self.config.interact_key.key,
+            loot_object_check_millis: self.settings.loot_object_check_millis,
+            enable_loot_objects: self.settings.enable_loot_objects,
             enable_reset_normal_actions_on_erda: reset_on_erda,
+            normal_actions_merge_distance: merge_distance,
+            normal_actions_turn_around_key: turn_around_key,
+            home_position,
+            home_idle_millis,
+            actions_offset,
         };
 
         self.rotator.build_actions(args);
@@ -154,6 +247,8 @@ impl RequestHandler for DefaultRequestHandler<'_> {
             if halting {
                 self.rotator.reset_queue();
                 self.player.clear_actions_aborted();
+            } else {
+                self.player.request_clean_ui();
             }
         }
     }
@@ -176,7 +271,7 @@ impl RequestHandler for DefaultRequestHandler<'_> {
     }
 
     fn on_update_minimap(&mut self, preset: Option<String>, minimap: MinimapData) {
-        self.minimap.set_data(minimap);
+        self.minimap.set_data(minimap, preset.clone());
 
         let minimap = self.minimap.data().unwrap();
         self.player.reset();
@@ -187,22 +282,55 @@ impl RequestHandler for DefaultRequestHandler<'_> {
         self.player.config.auto_mob_platforms_pathing_up_jump_only =
             minimap.auto_mob_platforms_pathing_up_jump_only;
         self.player.config.auto_mob_platforms_bound = minimap.auto_mob_platforms_bound;
+        self.player.config.platforms_pathing_walk_off_double_jump =
+            minimap.platforms_pathing_walk_off_double_jump;
+        self.rotator.update_bound_polygon(
+            minimap
+                .bound_polygon
+                .iter()
+                .map(|(x, y)| Point::new(*x, *y))
+                .collect(),
+        );
         *self.actions = preset
             .and_then(|preset| minimap.actions.get(&preset).cloned())
             .unwrap_or_default();
         self.update_rotator_actions();
     }
 
+    fn on_update_auto_mob_bound(&mut self, bound: Bound) {
+        if let Some(RotationMode::AutoMobbing(auto_mobbing)) = self.minimap.rotation_mode_mut() {
+            auto_mobbing.bound = bound;
+        }
+        self.rotator.update_auto_mob_bound(bound);
+    }
+
     fn on_update_configuration(&mut self, config: Configuration) {
         *self.config = config;
         *self.buffs = config_buffs(self.config);
         self.player.reset();
         self.player.config.class = self.config.class;
         self.player.config.disable_adjusting = self.config.disable_adjusting;
+        self.player.config.adjust_unstuck_jump = self.config.adjust_unstuck_jump;
+        self.player.config.adjust_attack_while_walking = self.config.adjust_attack_while_walking;
+        self.player.config.adjust_damping = self.config.adjust_damping_percent / 100.0;
+        self.player.config.adjust_max_correction_cycles =
+            (self.config.adjust_max_correction_cycles > 0)
+                .then_some(self.config.adjust_max_correction_cycles);
+        self.player.config.near_stationary_x_velocity_threshold =
+            self.config.near_stationary_x_velocity_threshold;
+        self.player.config.near_stationary_y_velocity_threshold =
+            self.config.near_stationary_y_velocity_threshold;
+        self.player.config.stationary_cast_velocity_threshold =
+            self.config.stationary_cast_velocity_threshold;
         self.player.config.interact_key = self.config.interact_key.key.into();
         self.player.config.grappling_key = self.config.ropelift_key.map(|key| key.key.into());
         self.player.config.teleport_key = self.config.teleport_key.map(|key| key.key.into());
         self.player.config.jump_key = self.config.jump_key.key.into();
+        self.player.config.flash_jump_key = self.config.flash_jump_key.map(|key| key.key.into());
+        self.player.config.jump_hold_ticks = (self.config.jump_hold_millis / MS_PER_TICK) as u32;
+        self.player.config.movement_speed_factor = (self.config.movement_speed_factor > 0.0
+            && self.config.movement_speed_factor != 1.0)
+            .then_some(self.config.movement_speed_factor);
         self.player.config.upjump_key = self.config.up_jump_key.map(|key| key.key.into());
         self.player.config.cash_shop_key = self.config.cash_shop_key.key.into();
         self.player.config.familiar_key = self.config.familiar_menu_key.key.into();
@@ -222,20 +350,41 @@ impl RequestHandler for DefaultRequestHandler<'_> {
     }
 
     fn on_update_settings(&mut self, settings: Settings) {
-        let mut handle_or_default = self.selected_capture_handle.unwrap_or(self.context.handle);
+        let handle_or_default = self
+            .selected_capture_handle
+            .as_ref()
+            .map(|(_, handle)| *handle)
+            .unwrap_or(self.context.handle);
 
-        if settings.capture_mode != self.settings.capture_mode {
-            self.image_capture
-                .set_mode(handle_or_default, settings.capture_mode);
+        if settings.enable_fixed_rng_seed != self.settings.enable_fixed_rng_seed
+            || settings.fixed_rng_seed != self.settings.fixed_rng_seed
+        {
+            let seed = context_rng_seed(&settings, &query_seeds());
+            self.context.rng = Rng::new(seed);
+            self.context.keys.set_seed(seed);
+        }
+
+        if settings.capture_mode != self.settings.capture_mode
+            || (matches!(settings.capture_mode, CaptureMode::MonitorRegion)
+                && (settings.capture_monitor_index != self.settings.capture_monitor_index
+                    || settings.capture_monitor_region != self.settings.capture_monitor_region))
+        {
+            self.image_capture.set_mode(handle_or_default, &settings);
         }
 
         if settings.input_method != self.settings.input_method
             || settings.input_method_rpc_server_url != self.settings.input_method_rpc_server_url
+            || settings.input_method_rpc_timeout_millis
+                != self.settings.input_method_rpc_timeout_millis
         {
-            if let ImageCaptureKind::BitBltArea(capture) = self.image_capture.kind() {
-                handle_or_default = capture.handle();
-                *self.key_receiver = KeyReceiver::new(handle_or_default, KeyInputKind::Foreground);
-            }
+            let handle_or_default = if let ImageCaptureKind::BitBltArea(capture) =
+                self.image_capture.kind()
+            {
+                *self.key_receiver = KeyReceiver::new(capture.handle(), KeyInputKind::Foreground);
+                capture.handle()
+            } else {
+                handle_or_default
+            };
             match settings.input_method {
                 InputMethod::Default => {
                     let kind = if matches!(settings.capture_mode, CaptureMode::BitBltArea) {
@@ -251,12 +400,27 @@ impl RequestHandler for DefaultRequestHandler<'_> {
                     self.context.keys.set_method(KeySenderMethod::Rpc(
                         handle_or_default,
                         settings.input_method_rpc_server_url.clone(),
+                        Duration::from_millis(settings.input_method_rpc_timeout_millis),
                     ));
                 }
             }
         }
 
         *self.settings = settings;
+        apply_settings(&mut self.player.config, self.settings);
+        self.player.set_death_templates(load_grayscale_templates(
+            &self.settings.death_template_paths,
+        ));
+        self.rotator
+            .set_loot_object_templates(load_grayscale_templates(
+                &self.settings.loot_object_template_paths,
+            ));
+        self.minimap
+            .set_border_whiteness_threshold(self.settings.minimap_border_whiteness_threshold);
+        self.minimap
+            .set_elite_boss_detection_millis(self.settings.elite_boss_detection_millis);
+        self.minimap
+            .set_rune_stable_detection_count(self.settings.rune_stable_detection_count);
         self.buff_states.iter_mut().for_each(|state| {
             state.update_enabled_state(self.config, self.settings);
         });
@@ -277,6 +441,9 @@ impl RequestHandler for DefaultRequestHandler<'_> {
             normal_action: self.player.normal_action_name(),
             priority_action: self.player.priority_action_name(),
             erda_shower_state: self.context.skills[SkillKind::ErdaShower].to_string(),
+            input_connected: self.context.keys.is_connected(),
+            minimap_id: self.minimap.data().and_then(|minimap| minimap.id),
+            minimap_preset: self.minimap.preset().map(|preset| preset.to_string()),
             destinations: self
                 .player
                 .last_destinations
@@ -322,7 +489,7 @@ impl RequestHandler for DefaultRequestHandler<'_> {
             .map(|(name, _)| name)
             .cloned()
             .collect::<Vec<_>>();
-        let selected = if let Some(selected_handle) = self.selected_capture_handle {
+        let selected = if let Some((_, selected_handle)) = self.selected_capture_handle {
             self.capture_handles
                 .iter()
                 .enumerate()
@@ -335,18 +502,24 @@ impl RequestHandler for DefaultRequestHandler<'_> {
     }
 
     fn on_select_capture_handle(&mut self, index: Option<usize>) {
-        if matches!(self.settings.capture_mode, CaptureMode::BitBltArea) {
+        if matches!(
+            self.settings.capture_mode,
+            CaptureMode::BitBltArea | CaptureMode::MonitorRegion
+        ) {
             return;
         }
 
-        let handle = index
+        let selected = index
             .and_then(|index| self.capture_handles.get(index))
-            .map(|(_, handle)| *handle);
-        let handle_or_default = handle.unwrap_or(self.context.handle);
+            .cloned();
+        let handle_or_default = selected
+            .as_ref()
+            .map(|(_, handle)| *handle)
+            .unwrap_or(self.context.handle);
 
-        *self.selected_capture_handle = handle;
+        *self.selected_capture_handle = selected;
         self.image_capture
-            .set_mode(handle_or_default, self.settings.capture_mode);
+            .set_mode(handle_or_default, self.settings);
         *self.key_receiver = KeyReceiver::new(handle_or_default, KeyInputKind::Fixed);
         match self.settings.input_method {
             InputMethod::Default => {
@@ -359,6 +532,7 @@ impl RequestHandler for DefaultRequestHandler<'_> {
                 self.context.keys.set_method(KeySenderMethod::Rpc(
                     handle_or_default,
                     self.settings.input_method_rpc_server_url.clone(),
+                    Duration::from_millis(self.settings.input_method_rpc_timeout_millis),
                 ));
             }
         }
@@ -379,8 +553,7 @@ impl RequestHandler for DefaultRequestHandler<'_> {
     #[cfg(debug_assertions)]
     fn on_infer_minimap(&self) {
         if let Some(ref detector) = self.context.detector {
-            // FIXME: 160 matches one in minimap.rs
-            if let Ok(rect) = detector.detect_minimap(160) {
+            if let Ok(rect) = detector.detect_minimap(self.minimap.border_whiteness_threshold()) {
                 save_minimap_for_training(detector.mat(), rect);
             }
         }
@@ -395,6 +568,17 @@ impl RequestHandler for DefaultRequestHandler<'_> {
         };
     }
 
+    #[cfg(debug_assertions)]
+    fn on_record_macro(&mut self, start: bool) -> Option<Vec<Action>> {
+        if start {
+            *self.recording_macro = Some(vec![]);
+            return None;
+        }
+        self.recording_macro
+            .take()
+            .map(|keys| macro_actions(keys.as_slice()))
+    }
+
     #[cfg(debug_assertions)]
     fn on_test_spin_rune(&self) {
         static SPIN_TEST_DIR: Dir<'static> = include_dir!("$SPIN_TEST_DIR");
@@ -434,6 +618,15 @@ impl RequestHandler for DefaultRequestHandler<'_> {
             }
         }
     }
+
+    #[cfg(debug_assertions)]
+    fn on_view_buffs_region(&self) {
+        if let Some(ref detector) = self.context.detector {
+            let mat = detector.mat();
+            let bbox = buffs_region_bbox(mat.size().unwrap(), self.settings.buffs_region);
+            debug_buffs_region(mat, bbox);
+        }
+    }
 }
 
 // TODO: should only handle a single matched key binding
@@ -443,14 +636,67 @@ fn poll_key(handler: &mut DefaultRequestHandler) {
         return;
     };
     debug!(target: "handler", "received key {received_key:?}");
+    #[cfg(debug_assertions)]
+    let is_hotkey = [
+        handler.settings.toggle_actions_key,
+        handler.settings.emergency_town_key,
+        handler.settings.toggle_mode_key,
+    ]
+    .into_iter()
+    .any(|KeyBindingConfiguration { key, enabled }| enabled && KeyKind::from(key) == received_key);
     if let KeyBindingConfiguration { key, enabled: true } = handler.settings.toggle_actions_key
         && KeyKind::from(key) == received_key
     {
         handler.on_rotate_actions(!handler.context.halting);
     }
+    if let KeyBindingConfiguration { key, enabled: true } = handler.settings.emergency_town_key
+        && KeyKind::from(key) == received_key
+    {
+        handler.rotator.queue_emergency_town_action(handler.player);
+    }
+    if let KeyBindingConfiguration { key, enabled: true } = handler.settings.toggle_mode_key
+        && KeyKind::from(key) == received_key
+        && let Some(preset) = handler.minimap.toggle_mode_preset()
+    {
+        debug!(target: "handler", "toggling mode to preset {preset}");
+        let minimap = handler.minimap.data().cloned().unwrap();
+        handler.on_update_minimap(Some(preset), minimap);
+    }
+    // Hotkeys are not part of the macro being recorded
+    #[cfg(debug_assertions)]
+    if !is_hotkey && let Some(keys) = handler.recording_macro.as_mut() {
+        keys.push((received_key.into(), Instant::now()));
+    }
     let _ = handler.key_sender.send(received_key.into());
 }
 
+/// Converts the recorded macro `keys` into a chain of linked [`Action::Key`]s.
+///
+/// The time between two consecutive keys is used as the first key wait after milliseconds.
+#[cfg(debug_assertions)]
+fn macro_actions(keys: &[(KeyBinding, Instant)]) -> Vec<Action> {
+    keys.iter()
+        .enumerate()
+        .map(|(i, (key, instant))| {
+            let wait_after_use_millis = keys
+                .get(i + 1)
+                .map(|(_, next)| next.duration_since(*instant).as_millis() as u64)
+                .unwrap_or_default();
+            let condition = if i == 0 {
+                ActionCondition::Any
+            } else {
+                ActionCondition::Linked
+            };
+            Action::Key(ActionKey {
+                key: *key,
+                condition,
+                wait_after_use_millis,
+                ..ActionKey::default()
+            })
+        })
+        .collect()
+}
+
 #[inline]
 fn extract_minimap(context: &Context, mat: &impl MatTraitConst) -> Option<(Vec<u8>, usize, usize)> {
     if let Minimap::Idle(idle) = context.minimap {
@@ -469,6 +715,59 @@ fn extract_minimap(context: &Context, mat: &impl MatTraitConst) -> Option<(Vec<u
     None
 }
 
+fn panic_other_player_filter(settings: &Settings) -> OtherPlayerFilter {
+    OtherPlayerFilter {
+        guildie: settings.panic_react_to_guildies,
+        stranger: settings.panic_react_to_strangers,
+        friend: settings.panic_react_to_friends,
+    }
+}
+
+/// Applies the [`Settings`]-derived fields to the player [`PlayerConfiguration`].
+pub fn apply_settings(config: &mut PlayerConfiguration, settings: &Settings) {
+    config.rune_calibration_timeout_ticks =
+        (settings.rune_calibration_timeout_millis / MS_PER_TICK) as u32;
+    config.rune_validate_ticks = (settings.rune_validate_millis / MS_PER_TICK) as u32;
+    config.rune_calibration_max_attempts = settings.rune_calibration_max_attempts;
+    config.rune_arrow_hold_ticks =
+        matches!(settings.rune_arrow_press_style, RuneArrowPressStyle::Hold)
+            .then_some((settings.rune_arrow_hold_millis / MS_PER_TICK) as u32);
+    config.rune_approach_side = settings.rune_approach_side;
+    config.rune_clear_mobs_ticks = settings
+        .enable_rune_clear_mobs
+        .then_some((settings.rune_clear_mobs_millis / MS_PER_TICK) as u32);
+    config.rune_clear_mobs_key = settings
+        .rune_clear_mobs_key
+        .enabled
+        .then_some(settings.rune_clear_mobs_key.key.into());
+    config.max_runes_solved_per_minute =
+        (settings.max_runes_solved_per_minute > 0).then_some(settings.max_runes_solved_per_minute);
+    config.redetect_minimap_unstuck_count = settings
+        .enable_redetect_minimap_on_unstuck
+        .then_some(settings.redetect_minimap_unstuck_count);
+    config.abort_strict_position_on_timeout = settings.abort_strict_position_on_timeout;
+    config.arrival_tolerance = settings.arrival_tolerance.max(0);
+    config.change_channel_menu_wait_ticks =
+        (settings.change_channel_menu_wait_millis / MS_PER_TICK) as u32;
+    config.change_channel_menu_max_retries = settings.change_channel_menu_max_retries;
+    config.change_channel_blocked_fallback = settings.change_channel_blocked_fallback;
+    config.edge_safety_margin = settings.edge_safety_margin.max(0);
+    config.panic_other_player_filter = panic_other_player_filter(settings);
+    config.stop_on_health_depleted = settings.stop_on_health_depleted;
+    config.idle_fidget = settings.enable_idle_fidget;
+    config.wait_for_ground = settings.wait_for_ground;
+    config.cash_shop_dwell_ticks = (settings.cash_shop_dwell_millis / MS_PER_TICK) as u32;
+    config.cash_shop_exit_wait_ticks = (settings.cash_shop_exit_wait_millis / MS_PER_TICK) as u32;
+    config.critical_health_percent = settings
+        .enable_panic_on_elite_boss_low_health
+        .then_some(settings.elite_boss_low_health_percent / 100.0);
+    config.clean_ui_on_start = settings.enable_clean_ui_on_start;
+    config.lost_position_recovery_ticks = settings
+        .enable_lost_position_recovery
+        .then_some((settings.lost_position_recovery_millis / MS_PER_TICK) as u32);
+    config.lost_position_recovery = settings.lost_position_recovery;
+}
+
 pub fn config_buffs(config: &Configuration) -> Vec<(BuffKind, KeyBinding)> {
     BuffKind::iter()
         .filter_map(|kind| {
@@ -536,13 +835,13 @@ fn config_actions(config: &Configuration) -> Vec<Action> {
             key,
             count: 1,
             condition: ActionCondition::EveryMillis(config.feed_pet_millis),
-            wait_before_use_millis: 350,
-            wait_after_use_millis: 350,
+            wait_before_use_millis: config.feed_pet_spacing_millis,
+            wait_after_use_millis: config.feed_pet_spacing_millis,
             ..ActionKey::default()
         });
-        vec.push(feed_pet_action);
-        vec.push(feed_pet_action);
-        vec.push(feed_pet_action);
+        for _ in 0..config.feed_pet_count.clamp(1, MAX_FEED_PET_COUNT) {
+            vec.push(feed_pet_action);
+        }
     }
     if let KeyBindingConfiguration { key, enabled: true } = config.potion_key
         && let PotionMode::EveryMillis(millis) = config.potion_mode
@@ -565,3 +864,55 @@ fn config_actions(config: &Configuration) -> Vec<Action> {
     );
     vec
 }
+
+#[cfg(all(test, debug_assertions))]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+
+    #[test]
+    fn macro_actions_linked_with_recorded_timing() {
+        let now = Instant::now();
+        let keys = [
+            (KeyBinding::A, now),
+            (KeyBinding::B, now + Duration::from_millis(150)),
+            (KeyBinding::C, now + Duration::from_millis(400)),
+        ];
+
+        let actions = macro_actions(&keys);
+        assert_eq!(actions.len(), 3);
+        assert_eq!(
+            actions[0],
+            Action::Key(ActionKey {
+                key: KeyBinding::A,
+                condition: ActionCondition::Any,
+                wait_after_use_millis: 150,
+                ..ActionKey::default()
+            })
+        );
+        assert_eq!(
+            actions[1],
+            Action::Key(ActionKey {
+                key: KeyBinding::B,
+                condition: ActionCondition::Linked,
+                wait_after_use_millis: 250,
+                ..ActionKey::default()
+            })
+        );
+        assert_eq!(
+            actions[2],
+            Action::Key(ActionKey {
+                key: KeyBinding::C,
+                condition: ActionCondition::Linked,
+                wait_after_use_millis: 0,
+                ..ActionKey::default()
+            })
+        );
+    }
+
+    #[test]
+    fn macro_actions_empty() {
+        assert!(macro_actions(&[]).is_empty());
+    }
+}
@@ -1,7 +1,11 @@
 use std::cell::RefCell;
 
 use noise::{NoiseFn, Perlin};
-use rand::{Rng as RandRng, SeedableRng, rngs::StdRng};
+use rand::{
+    Rng as RandRng, SeedableRng,
+    rngs::StdRng,
+    seq::{IteratorRandom, SliceRandom},
+};
 use rand_distr::{
     Distribution, Normal,
     uniform::{SampleRange, SampleUniform},
@@ -9,6 +13,11 @@ use rand_distr::{
 
 pub type RngSeed = [u8; 32];
 
+/// Expands a fixed `value` into a [`RngSeed`] deterministically.
+pub fn seed_from_u64(value: u64) -> RngSeed {
+    StdRng::seed_from_u64(value).random()
+}
+
 /// A wrapper around `StdRng`.
 #[derive(Debug)]
 pub struct Rng {
@@ -71,6 +80,16 @@ impl Rng {
         }
     }
 
+    #[inline]
+    pub fn random_choose_iter<I: Iterator>(&self, iter: I) -> Option<I::Item> {
+        iter.choose(&mut *self.inner.borrow_mut())
+    }
+
+    #[inline]
+    pub fn shuffle<T>(&self, items: &mut [T]) {
+        items.shuffle(&mut *self.inner.borrow_mut());
+    }
+
     /// Samples a random `(delay, tick count)` pair.
     ///
     /// The delay is sampled from a normal distribution with mean `mean_ms` and
@@ -129,7 +148,7 @@ impl Rng {
 
 #[cfg(test)]
 mod tests {
-    use super::Rng;
+    use super::{Rng, seed_from_u64};
 
     const SEED: [u8; 32] = [
         64, 241, 206, 219, 49, 21, 218, 145, 254, 152, 68, 176, 242, 238, 152, 14, 176, 241, 153,
@@ -152,4 +171,17 @@ mod tests {
         assert!(mean - 84.88451 < 0.01);
         assert!(std - 29.786358 < 0.01);
     }
+
+    #[test]
+    fn random_choose_iter_fixed_seed() {
+        let first = Rng::new(seed_from_u64(42));
+        let second = Rng::new(seed_from_u64(42));
+
+        for _ in 0..10 {
+            assert_eq!(
+                first.random_choose_iter(0..100),
+                second.random_choose_iter(0..100)
+            );
+        }
+    }
 }
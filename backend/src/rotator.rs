@@ -1,28 +1,35 @@
 use std::{
     assert_matches::debug_assert_matches,
+    cell::Cell,
     collections::{HashSet, VecDeque},
-    sync::atomic::{AtomicU32, Ordering},
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
     time::Instant,
 };
 
 use anyhow::Result;
 use log::debug;
-use opencv::core::{Point, Rect};
+use opencv::core::{Mat, Point, Rect};
 use ordered_hash_map::OrderedHashMap;
-use rand::seq::IteratorRandom;
 
 use crate::{
-    ActionKeyDirection, ActionKeyWith, AutoMobbing, FamiliarRarity, KeyBinding, PanicMode,
-    Position, RotationMode, SwappableFamiliars,
+    ActionKeyDirection, ActionKeyWith, AutoMobbing, Bound, EmptyMapAction, FamiliarRarity,
+    HoldSpot, KeyBinding, NoSwappableCardsOutcome, PanicMode, Position, RotationMode,
+    SwappableFamiliars,
     array::Array,
     buff::{Buff, BuffKind},
     context::{Context, MS_PER_TICK},
-    database::{Action, ActionCondition, ActionKey, ActionMove, PingPong},
-    minimap::Minimap,
+    database::{
+        Action, ActionCondition, ActionKey, ActionMove, BuffKind as BuffKindData, PingPong,
+    },
+    minimap::{Minimap, OtherPlayerFilter},
+    pathing::PlatformWithNeighbors,
     player::{
         GRAPPLING_THRESHOLD, PanicTo, PingPongDirection, Player, PlayerAction, PlayerActionAutoMob,
-        PlayerActionFamiliarsSwapping, PlayerActionKey, PlayerActionPanic, PlayerActionPingPong,
-        PlayerState,
+        PlayerActionCollectDrops, PlayerActionFamiliarsSwapping, PlayerActionKey, PlayerActionMove,
+        PlayerActionPanic, PlayerActionPingPong, PlayerState,
     },
     skill::{Skill, SkillKind},
     task::{Task, Update, update_detection_task},
@@ -30,6 +37,9 @@ use crate::{
 
 const COOLDOWN_BETWEEN_QUEUE_MILLIS: u128 = 20_000;
 
+/// Maximum milliseconds a missing buff can be deferred while the player is in combat.
+const MAX_BUFF_COMBAT_DEFER_MILLIS: u128 = 30_000;
+
 /// [`Condition`] evaluation result.
 enum ConditionResult {
     /// The action will be queued.
@@ -70,10 +80,16 @@ struct PriorityAction {
     condition: Condition,
     /// The kind the above predicate was derived from.
     condition_kind: Option<ActionCondition>,
+    /// The logical key shared by equivalent actions.
+    ///
+    /// This action is ignored while another action with the same key is in queue or executing.
+    dedupe_key: Option<PriorityActionDedupeKey>,
     /// The inner action.
     inner: RotatorAction,
     /// Whether to queue this action to the front of [`Rotator::priority_actions_queue`].
     queue_to_front: bool,
+    /// Whether this action can be deferred while the player is auto mobbing.
+    tier: PriorityActionTier,
     /// Whether this action is being ignored.
     ///
     /// While ignored, [`Self::last_queued_time`] will be updated to [`Instant::now`].
@@ -84,6 +100,35 @@ struct PriorityAction {
     last_queued_time: Option<Instant>,
 }
 
+/// The urgency of a [`PriorityAction`] when it conflicts with an on-going auto mob action.
+///
+/// By default, buffs and familiar essence replenishing, familiars swapping and drops collecting
+/// are [`PriorityActionTier::Deferrable`]. Everything else, including user-defined actions
+/// (e.g. potions), rune solving and panicking, is [`PriorityActionTier::Urgent`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum PriorityActionTier {
+    /// Preempts the on-going auto mob action as soon as possible.
+    #[default]
+    Urgent,
+    /// Waits for the on-going auto mob action to complete for up to
+    /// [`Rotator::priority_actions_auto_mob_grace_millis`].
+    Deferrable,
+}
+
+/// The logical key identifying equivalent [`PriorityAction`]s.
+///
+/// Only one [`PriorityAction`] with the same key can be queuing or executing at a time to avoid
+/// redundant casts when the same buff, potion or rune action is configured more than once.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PriorityActionDedupeKey {
+    /// An action casting the buff of [`BuffKind`].
+    Buff(BuffKind),
+    /// A user-defined key action without position (e.g. potion).
+    Key(KeyBinding),
+    /// The rune solving action.
+    Rune,
+}
+
 /// The action that will be passed to the player
 ///
 /// There are [`RotatorAction::Single`] and [`RotatorAction::Linked`] actions.
@@ -100,6 +145,8 @@ enum RotatorAction {
 #[derive(Clone, Debug)]
 struct LinkedAction {
     inner: PlayerAction,
+    /// Whether [`Self::inner`] key is sent while the next linked move is in progress.
+    fire_during_move: bool,
     next: Option<Box<LinkedAction>>,
 }
 
@@ -109,8 +156,10 @@ pub enum RotatorMode {
     StartToEnd,
     #[default]
     StartToEndThenReverse,
+    ShuffledCycle,
     AutoMobbing(AutoMobbing),
     PingPong(PingPong),
+    HoldSpot(HoldSpot),
 }
 
 impl From<RotationMode> for RotatorMode {
@@ -118,8 +167,10 @@ impl From<RotationMode> for RotatorMode {
         match mode {
             RotationMode::StartToEnd => RotatorMode::StartToEnd,
             RotationMode::StartToEndThenReverse => RotatorMode::StartToEndThenReverse,
+            RotationMode::ShuffledCycle => RotatorMode::ShuffledCycle,
             RotationMode::AutoMobbing(auto_mobbing) => RotatorMode::AutoMobbing(auto_mobbing),
             RotationMode::PingPong(ping_pong) => RotatorMode::PingPong(ping_pong),
+            RotationMode::HoldSpot(hold_spot) => RotatorMode::HoldSpot(hold_spot),
         }
     }
 }
@@ -133,10 +184,56 @@ pub struct Rotator {
     normal_index: usize,
     /// Whether [`Self::normal_actions`] is being accessed from the end
     normal_actions_backward: bool,
+    /// The shuffled indices of [`Self::normal_actions`] used when [`Self::normal_rotate_mode`] is
+    /// [`RotatorMode::ShuffledCycle`]
+    ///
+    /// Reshuffled at the start of each pass when [`Self::normal_index`] wraps around.
+    normal_shuffled_indices: Vec<usize>,
     normal_actions_reset_on_erda: bool,
+    /// The distance from the player within which a [`RotatorMode::StartToEndThenReverse`] action
+    /// is merged into the current position
+    normal_actions_merge_distance: i32,
+    /// The key action used when a [`RotatorMode::StartToEndThenReverse`] rotation reverses
+    normal_actions_turn_around: Option<(u32, PlayerAction)>,
+    /// Whether [`Self::normal_actions_turn_around`] should be used on the next normal rotation
+    normal_actions_turn_around_pending: bool,
     normal_rotate_mode: RotatorMode,
     /// The [`Task`] used when [`Self::normal_rotate_mode`] is [`RotatorMode::AutoMobbing`]
     auto_mob_task: Option<Task<Result<Vec<Point>>>>,
+    /// The bound used when [`Self::normal_rotate_mode`] is [`RotatorMode::AutoMobbing`]
+    ///
+    /// Stored separately from the mode so that it can be updated without rebuilding actions.
+    auto_mob_bound: Bound,
+    /// The polygon overriding [`Self::auto_mob_bound`] and [`PingPong::bound`]
+    ///
+    /// Empty when the rectangle bound should be used.
+    bound_polygon: Vec<Point>,
+    /// The last time a mob was detected when [`Self::normal_rotate_mode`] is
+    /// [`RotatorMode::AutoMobbing`]
+    auto_mob_last_mobs_instant: Option<Instant>,
+    /// The first time the player has no action after the previous auto-mob action when
+    /// [`Self::normal_rotate_mode`] is [`RotatorMode::AutoMobbing`]
+    ///
+    /// Used to delay choosing a new target by [`AutoMobbing::retarget_interval_millis`].
+    auto_mob_retarget_instant: Option<Instant>,
+    /// The id of the [`PlayerAction::Panic`] to channel action inside [`Self::priority_actions`]
+    /// used when the map is empty for [`EmptyMapAction::ChangeChannel`]
+    auto_mob_empty_map_action_id: Option<u32>,
+    /// The accumulated mob counts of each platform since the preferred platform was last chosen
+    ///
+    /// Indexed the same as [`crate::minimap::MinimapIdle::platforms`].
+    auto_mob_platform_counts: Vec<u32>,
+    /// The preferred platform index and the last time a mob was detected on it when
+    /// [`AutoMobbing::platform_dwell_millis`] is enabled
+    auto_mob_preferred_platform: Option<(usize, Instant)>,
+    /// The [`Task`] used to detect lootable objects for [`Self::loot_object_action_id`]
+    loot_object_task: Option<Task<Result<Vec<Point>>>>,
+    /// The templates used to detect lootable objects
+    loot_object_templates: Arc<Vec<Mat>>,
+    loot_object_check_millis: u64,
+    /// The id of the interact [`PlayerAction::Key`] action inside [`Self::priority_actions`]
+    /// used to loot the detected object
+    loot_object_action_id: Option<u32>,
     priority_actions: OrderedHashMap<u32, PriorityAction>,
     /// The currently executing [`RotatorAction::Linked`] action
     priority_queuing_linked_action: Option<(u32, Box<LinkedAction>)>,
@@ -144,22 +241,63 @@ pub struct Rotator {
     ///
     /// Populates from [`Self::priority_actions`] when its predicate for queuing is true
     priority_actions_queue: VecDeque<u32>,
+    /// Whether to hold off non-essential priority actions while the player has a
+    /// [`PlayerAction::SolveRune`] action
+    priority_actions_focus_rune: bool,
+    /// The maximum milliseconds to hold off [`PriorityActionTier::Deferrable`] actions while the
+    /// player has a normal [`PlayerAction::AutoMob`] action
+    ///
+    /// Zero disables the grace.
+    priority_actions_auto_mob_grace_millis: u64,
+    /// The [`Instant`] the front action in [`Self::priority_actions_queue`] started being held
+    /// off for [`Self::priority_actions_auto_mob_grace_millis`]
+    priority_actions_auto_mob_deferred_instant: Option<Instant>,
+    /// The id of the emergency [`PlayerAction::Panic`] to town action inside
+    /// [`Self::priority_actions`]
+    emergency_town_action_id: Option<u32>,
 }
 
 pub struct RotatorBuildArgs<'a> {
     pub mode: RotatorMode,
     pub actions: &'a [Action],
     pub buffs: &'a [(BuffKind, KeyBinding)],
+    pub buff_all_keys: &'a [KeyBinding],
+    pub buff_all_spacing_millis: u64,
+    pub timer_only_buffs: &'a [BuffKindData],
+    pub timer_only_buff_recast_millis: u64,
+    pub buff_combat_lull_millis: Option<u64>,
     pub familiar_essence_key: KeyBinding,
     pub familiar_swappable_slots: SwappableFamiliars,
     pub familiar_swappable_rarities: &'a HashSet<FamiliarRarity>,
     pub familiar_swap_check_millis: u64,
+    pub familiar_hover_settle_ticks: u32,
+    pub familiar_no_swappable_cards_outcome: NoSwappableCardsOutcome,
+    pub familiar_swap_safe_position: Option<Position>,
+    pub familiar_essence_check_millis: u64,
+    pub pickup_key: KeyBinding,
+    pub collect_drops_millis: u64,
     pub panic_mode: PanicMode,
+    pub panic_defensive_key: KeyBinding,
+    pub panic_other_player_filter: OtherPlayerFilter,
     pub enable_panic_mode: bool,
     pub enable_rune_solving: bool,
+    pub enable_focus_rune_solving: bool,
+    pub auto_mob_priority_grace_millis: u64,
+    pub enable_rune_solving_ignore_buff: bool,
     pub enable_change_channel_on_elite_boss_appear: bool,
+    pub enable_panic_on_elite_boss_low_health: bool,
+    pub elite_boss_consumable_keys: &'a [KeyBinding],
     pub enable_familiars_swapping: bool,
+    pub enable_collect_drops: bool,
+    pub interact_key: KeyBinding,
+    pub loot_object_check_millis: u64,
+    pub enable_loot_objects: bool,
     pub enable_reset_normal_actions_on_erda: bool,
+    pub normal_actions_merge_distance: i32,
+    pub normal_actions_turn_around_key: Option<KeyBinding>,
+    pub home_position: Position,
+    pub home_idle_millis: u64,
+    pub actions_offset: Point,
 }
 
 impl Rotator {
@@ -168,23 +306,76 @@ impl Rotator {
             mode,
             actions,
             buffs,
+            buff_all_keys,
+            buff_all_spacing_millis,
+            timer_only_buffs,
+            timer_only_buff_recast_millis,
+            buff_combat_lull_millis,
             familiar_essence_key,
             familiar_swappable_slots,
             familiar_swappable_rarities,
             familiar_swap_check_millis,
+            familiar_hover_settle_ticks,
+            familiar_no_swappable_cards_outcome,
+            familiar_swap_safe_position,
+            familiar_essence_check_millis,
+            pickup_key,
+            collect_drops_millis,
             panic_mode,
+            panic_defensive_key,
+            panic_other_player_filter,
             enable_panic_mode,
             enable_rune_solving,
+            enable_focus_rune_solving,
+            auto_mob_priority_grace_millis,
+            enable_rune_solving_ignore_buff,
             enable_change_channel_on_elite_boss_appear,
+            enable_panic_on_elite_boss_low_health,
+            elite_boss_consumable_keys,
             enable_familiars_swapping,
+            enable_collect_drops,
+            interact_key,
+            loot_object_check_millis,
+            enable_loot_objects,
             enable_reset_normal_actions_on_erda,
+            normal_actions_merge_distance,
+            normal_actions_turn_around_key,
+            home_position,
+            home_idle_millis,
+            actions_offset,
         } = args;
+        let actions = actions
+            .iter()
+            .map(|action| offset_action_position(*action, actions_offset))
+            .map(|action| ignore_undetectable_required_buff(action, buffs))
+            .collect::<Vec<_>>();
+        let actions = actions.as_slice();
         debug!(target: "rotator", "preparing actions {actions:?} {buffs:?}");
         self.reset_queue();
         self.normal_actions.clear();
         self.normal_rotate_mode = mode;
         self.normal_actions_reset_on_erda = enable_reset_normal_actions_on_erda;
+        self.normal_actions_merge_distance = normal_actions_merge_distance;
+        self.normal_actions_turn_around = normal_actions_turn_around_key.map(|key| {
+            (
+                self.id_counter.fetch_add(1, Ordering::Relaxed),
+                turn_around_action(key),
+            )
+        });
+        self.priority_actions_focus_rune = enable_focus_rune_solving;
+        self.priority_actions_auto_mob_grace_millis = auto_mob_priority_grace_millis;
         self.priority_actions.clear();
+        self.emergency_town_action_id = None;
+        self.auto_mob_last_mobs_instant = None;
+        self.auto_mob_retarget_instant = None;
+        self.auto_mob_empty_map_action_id = None;
+        self.auto_mob_platform_counts.clear();
+        self.auto_mob_preferred_platform = None;
+        self.loot_object_check_millis = loot_object_check_millis;
+        self.loot_object_action_id = None;
+        if let RotatorMode::AutoMobbing(AutoMobbing { bound, .. }) = self.normal_rotate_mode {
+            self.auto_mob_bound = bound;
+        }
 
         let mut i = 0;
         while i < actions.len() {
@@ -220,19 +411,32 @@ impl Rotator {
             }
         }
 
+        if let RotatorMode::AutoMobbing(AutoMobbing {
+            empty_map_action: EmptyMapAction::ChangeChannel,
+            ..
+        }) = self.normal_rotate_mode
+        {
+            let id = self.id_counter.fetch_add(1, Ordering::Relaxed);
+            self.priority_actions
+                .insert(id, auto_mob_empty_map_change_channel_priority_action());
+            self.auto_mob_empty_map_action_id = Some(id);
+        }
         if buffs
             .iter()
             .any(|(buff, _)| matches!(buff, BuffKind::Familiar))
         {
             self.priority_actions.insert(
                 self.id_counter.fetch_add(1, Ordering::Relaxed),
-                familiar_essence_replenish_priority_action(familiar_essence_key),
+                familiar_essence_replenish_priority_action(
+                    familiar_essence_key,
+                    familiar_essence_check_millis,
+                ),
             );
         }
         if enable_rune_solving {
             self.priority_actions.insert(
                 self.id_counter.fetch_add(1, Ordering::Relaxed),
-                solve_rune_priority_action(),
+                solve_rune_priority_action(enable_rune_solving_ignore_buff),
             );
         }
         if enable_change_channel_on_elite_boss_appear {
@@ -241,68 +445,201 @@ impl Rotator {
                 elite_boss_change_channel_priority_action(),
             );
         }
+        if enable_panic_on_elite_boss_low_health {
+            self.priority_actions.insert(
+                self.id_counter.fetch_add(1, Ordering::Relaxed),
+                elite_boss_low_health_panic_priority_action(panic_mode),
+            );
+        }
+        if !elite_boss_consumable_keys.is_empty() {
+            self.priority_actions.insert(
+                self.id_counter.fetch_add(1, Ordering::Relaxed),
+                elite_boss_consumable_priority_action(elite_boss_consumable_keys),
+            );
+        }
         if enable_familiars_swapping {
             self.priority_actions.insert(
                 self.id_counter.fetch_add(1, Ordering::Relaxed),
-                priority_action(
-                    RotatorAction::Single(PlayerAction::FamiliarsSwapping(
-                        PlayerActionFamiliarsSwapping {
-                            swappable_slots: familiar_swappable_slots,
-                            swappable_rarities: Array::from_iter(
-                                familiar_swappable_rarities.clone(),
-                            ),
-                        },
-                    )),
-                    ActionCondition::EveryMillis(familiar_swap_check_millis),
-                    true,
-                ),
+                PriorityAction {
+                    tier: PriorityActionTier::Deferrable,
+                    ..priority_action(
+                        familiars_swapping_action(
+                            PlayerActionFamiliarsSwapping {
+                                swappable_slots: familiar_swappable_slots,
+                                swappable_rarities: Array::from_iter(
+                                    familiar_swappable_rarities.clone(),
+                                ),
+                                hover_settle_ticks: familiar_hover_settle_ticks,
+                                no_swappable_cards_outcome: familiar_no_swappable_cards_outcome,
+                            },
+                            familiar_swap_safe_position,
+                        ),
+                        ActionCondition::EveryMillis(familiar_swap_check_millis),
+                        true,
+                    )
+                },
+            );
+        }
+        if enable_collect_drops {
+            self.priority_actions.insert(
+                self.id_counter.fetch_add(1, Ordering::Relaxed),
+                PriorityAction {
+                    tier: PriorityActionTier::Deferrable,
+                    ..priority_action(
+                        RotatorAction::Single(PlayerAction::CollectDrops(
+                            PlayerActionCollectDrops { key: pickup_key },
+                        )),
+                        ActionCondition::EveryMillis(collect_drops_millis),
+                        false,
+                    )
+                },
+            );
+        }
+        if enable_loot_objects {
+            let id = self.id_counter.fetch_add(1, Ordering::Relaxed);
+            self.priority_actions
+                .insert(id, loot_object_priority_action(interact_key));
+            self.loot_object_action_id = Some(id);
+        }
+        if home_idle_millis > 0 {
+            self.priority_actions.insert(
+                self.id_counter.fetch_add(1, Ordering::Relaxed),
+                return_home_priority_action(home_position, home_idle_millis),
             );
         }
         if enable_panic_mode {
             self.priority_actions.insert(
                 self.id_counter.fetch_add(1, Ordering::Relaxed),
-                panic_priority_action(panic_mode),
+                panic_priority_action(panic_mode, panic_defensive_key, panic_other_player_filter),
+            );
+        }
+        // Buffs whose key is not in the buff all keys are still cast individually
+        let (buff_all_kinds, buffs) = buffs
+            .iter()
+            .copied()
+            .partition::<Vec<_>, _>(|(_, key)| buff_all_keys.contains(key));
+        if !buff_all_kinds.is_empty() {
+            let kinds = buff_all_kinds
+                .into_iter()
+                .map(|(kind, _)| kind)
+                .collect::<Vec<_>>();
+            let recast_millis = kinds
+                .iter()
+                .any(|kind| is_timer_only_buff(timer_only_buffs, *kind))
+                .then_some(timer_only_buff_recast_millis);
+            self.priority_actions.insert(
+                self.id_counter.fetch_add(1, Ordering::Relaxed),
+                buff_all_priority_action(
+                    kinds,
+                    buff_all_keys,
+                    (buff_all_spacing_millis / MS_PER_TICK) as u32,
+                    recast_millis,
+                    buff_combat_lull_millis,
+                ),
             );
         }
-        for (i, key) in buffs.iter().copied() {
+        for (i, key) in buffs {
+            let recast_millis =
+                is_timer_only_buff(timer_only_buffs, i).then_some(timer_only_buff_recast_millis);
             self.priority_actions.insert(
                 self.id_counter.fetch_add(1, Ordering::Relaxed),
-                buff_priority_action(i, key),
+                buff_priority_action(i, key, recast_millis, buff_combat_lull_millis),
             );
         }
     }
 
+    /// Updates the bound used by [`RotatorMode::AutoMobbing`] without rebuilding actions.
+    #[inline]
+    pub fn update_auto_mob_bound(&mut self, bound: Bound) {
+        self.auto_mob_bound = bound;
+    }
+
+    /// Sets the templates used to detect lootable objects.
+    #[inline]
+    pub fn set_loot_object_templates(&mut self, templates: Vec<Mat>) {
+        self.loot_object_templates = Arc::new(templates);
+    }
+
+    /// Updates the polygon used in place of the auto-mobbing and ping-pong rectangle bound.
+    ///
+    /// The polygon is in minimap coordinate and is ignored if it has fewer than three vertices.
+    #[inline]
+    pub fn update_bound_polygon(&mut self, polygon: Vec<Point>) {
+        self.bound_polygon = if polygon.len() >= 3 { polygon } else { vec![] };
+    }
+
     #[inline]
     pub fn reset_queue(&mut self) {
         self.normal_actions_backward = false;
         self.reset_normal_actions_queue();
         self.priority_actions_queue.clear();
+        self.priority_actions_auto_mob_deferred_instant = None;
         self.priority_queuing_linked_action = None;
     }
 
+    /// Overrides any on-going action with a [`PlayerAction::Panic`] to town action.
+    ///
+    /// Unlike other priority actions, this action does not go through
+    /// [`Self::priority_actions_queue`] but is set directly to the player regardless of the
+    /// player's current state.
+    pub fn queue_emergency_town_action(&mut self, player: &mut PlayerState) {
+        if let Some(id) = self.emergency_town_action_id.take() {
+            self.priority_actions.remove(&id);
+        }
+        let id = self.id_counter.fetch_add(1, Ordering::Relaxed);
+        let action = emergency_town_priority_action();
+        let inner = match action.inner {
+            RotatorAction::Single(inner) => inner,
+            RotatorAction::Linked(_) => unreachable!(),
+        };
+
+        debug!(target: "rotator", "queuing emergency go to town action");
+        self.reset_queue();
+        self.priority_actions.insert(id, action);
+        self.emergency_town_action_id = Some(id);
+        player.clear_actions_aborted();
+        player.set_priority_action(id, inner);
+    }
+
     #[inline]
     fn reset_normal_actions_queue(&mut self) {
         self.normal_index = 0;
         self.normal_queuing_linked_action = None;
+        self.normal_actions_turn_around_pending = false;
     }
 
     #[inline]
     pub fn rotate_action(&mut self, context: &Context, player: &mut PlayerState) {
-        if context.halting || matches!(context.player, Player::CashShopThenExit(_, _)) {
+        if context.halting
+            || context.transitioning
+            || context.map_loading
+            || context.capture_invalid
+            || context.focus_lost
+            || context.apm_throttled
+            || matches!(
+                context.player,
+                Player::CashShopThenExit(_, _) | Player::CleaningUi(_)
+            )
+        {
             return;
         }
         self.rotate_priority_actions(context, player);
+        self.rotate_loot_objects(context, player);
         self.rotate_priority_actions_queue(context, player);
         if !player.has_priority_action() && !player.has_normal_action() {
             match self.normal_rotate_mode {
-                RotatorMode::StartToEnd => self.rotate_start_to_end(player),
-                RotatorMode::StartToEndThenReverse => self.rotate_start_to_end_then_reverse(player),
+                RotatorMode::StartToEnd => self.rotate_start_to_end(context, player),
+                RotatorMode::StartToEndThenReverse => {
+                    self.rotate_start_to_end_then_reverse(context, player)
+                }
+                RotatorMode::ShuffledCycle => self.rotate_shuffled_cycle(context, player),
                 RotatorMode::AutoMobbing(auto_mobbing) => {
                     self.rotate_auto_mobbing(context, player, auto_mobbing)
                 }
                 RotatorMode::PingPong(ping_pong) => {
                     self.rotate_ping_pong(context, player, ping_pong)
                 }
+                RotatorMode::HoldSpot(hold_spot) => self.rotate_hold_spot(player, hold_spot),
             }
         }
     }
@@ -357,6 +694,39 @@ impl Rotator {
             })
         }
 
+        /// Checks if another action with the same [`PriorityAction::dedupe_key`] as `id` is in
+        /// queue or executing.
+        #[inline]
+        fn has_equivalent_action_queuing_or_executing(
+            rotator: &Rotator,
+            player: &PlayerState,
+            id: u32,
+        ) -> bool {
+            let Some(key) = rotator
+                .priority_actions
+                .get(&id)
+                .and_then(|action| action.dedupe_key)
+            else {
+                return false;
+            };
+            let is_equivalent = |other: u32| {
+                other != id
+                    && rotator
+                        .priority_actions
+                        .get(&other)
+                        .is_some_and(|action| action.dedupe_key == Some(key))
+            };
+            player.priority_action_id().is_some_and(is_equivalent)
+                || rotator
+                    .priority_queuing_linked_action
+                    .as_ref()
+                    .is_some_and(|(other, _)| is_equivalent(*other))
+                || rotator
+                    .priority_actions_queue
+                    .iter()
+                    .any(|other| is_equivalent(*other))
+        }
+
         // Keeps ignoring while there is any type of erda condition action inside the queue
         let has_erda_action = has_erda_action_queuing_or_executing(self, player);
         let ids = self.priority_actions.keys().copied().collect::<Vec<_>>(); // why?
@@ -367,6 +737,8 @@ impl Rotator {
             // or executing
             let has_linked_action =
                 is_priority_linked_action_queuing_or_executing(self, player, id);
+            let has_equivalent_action =
+                has_equivalent_action_queuing_or_executing(self, player, id);
             let action = self.priority_actions.get_mut(&id).unwrap();
 
             action.ignoring = match action.condition_kind {
@@ -384,7 +756,7 @@ impl Rotator {
                         || has_linked_action
                 }
                 Some(ActionCondition::Any) => unreachable!(),
-            };
+            } || has_equivalent_action;
             if action.ignoring {
                 action.last_queued_time = Some(Instant::now());
                 continue;
@@ -460,9 +832,81 @@ impl Rotator {
             })
         }
 
+        /// Checks if the front action in [`Self::priority_actions_queue`] can still be rotated
+        /// while the player is solving rune.
+        ///
+        /// Only [`PlayerAction::Panic`] is considered essential as potion by percentage is handled
+        /// by the player itself.
+        #[inline]
+        fn is_front_action_essential_during_rune(rotator: &Rotator) -> bool {
+            if rotator.priority_queuing_linked_action.is_some() {
+                return false;
+            }
+            rotator
+                .priority_actions_queue
+                .front()
+                .and_then(|id| rotator.priority_actions.get(id))
+                .is_some_and(|action| {
+                    matches!(action.inner, RotatorAction::Single(PlayerAction::Panic(_)))
+                })
+        }
+
         if self.priority_actions_queue.is_empty() && self.priority_queuing_linked_action.is_none() {
             return;
         }
+        /// Checks if actions in [`Self::priority_actions_queue`] can be held off to let the
+        /// player complete the normal [`PlayerAction::AutoMob`] action.
+        #[inline]
+        fn can_defer_during_auto_mob(rotator: &Rotator, player: &PlayerState) -> bool {
+            rotator.priority_actions_auto_mob_grace_millis > 0
+                && rotator.priority_queuing_linked_action.is_none()
+                && matches!(player.normal_action(), Some(PlayerAction::AutoMob(_)))
+        }
+
+        /// Finds the index of the first action in [`Self::priority_actions_queue`] that cannot be
+        /// held off.
+        ///
+        /// Only [`PriorityActionTier::Deferrable`] action can be held off.
+        #[inline]
+        fn first_non_deferrable_index(rotator: &Rotator) -> Option<usize> {
+            rotator.priority_actions_queue.iter().position(|id| {
+                rotator
+                    .priority_actions
+                    .get(id)
+                    .is_none_or(|action| !matches!(action.tier, PriorityActionTier::Deferrable))
+            })
+        }
+
+        if self.priority_actions_focus_rune
+            && player.has_rune_action()
+            && !is_front_action_essential_during_rune(self)
+        {
+            return;
+        }
+        if can_defer_during_auto_mob(self, player) {
+            match first_non_deferrable_index(self) {
+                // Skips over the deferrable actions so they do not block the action behind
+                Some(index) => {
+                    if index > 0 {
+                        let id = self.priority_actions_queue.remove(index).unwrap();
+                        self.priority_actions_queue.push_front(id);
+                    }
+                }
+                None => {
+                    let instant = *self
+                        .priority_actions_auto_mob_deferred_instant
+                        .get_or_insert_with(Instant::now);
+                    if !at_least_millis_passed_since(
+                        Some(instant),
+                        self.priority_actions_auto_mob_grace_millis as u128,
+                    ) {
+                        return;
+                    }
+                }
+            }
+        } else {
+            self.priority_actions_auto_mob_deferred_instant = None;
+        }
         if !context
             .player
             .can_action_override_current_state(player.last_known_pos)
@@ -495,6 +939,7 @@ impl Rotator {
         }
 
         self.priority_actions_queue.pop_front();
+        self.priority_actions_auto_mob_deferred_instant = None;
         match action.inner.clone() {
             RotatorAction::Single(inner) => {
                 if action.queue_to_front {
@@ -531,17 +976,34 @@ impl Rotator {
             return;
         };
         let AutoMobbing {
-            bound,
             key,
             key_count,
             key_wait_before_millis,
             key_wait_after_millis,
+            empty_map_timeout_millis,
+            scale_count_with_mobs,
+            retarget_interval_millis,
+            prefer_center,
+            platform_dwell_millis,
+            ..
         } = auto_mobbing;
-        let bound = if player.config.auto_mob_platforms_bound {
-            idle.platforms_bound.unwrap_or(bound.into())
-        } else {
-            bound.into()
-        };
+        let retarget_instant = *self
+            .auto_mob_retarget_instant
+            .get_or_insert_with(Instant::now);
+        if !at_least_millis_passed_since(Some(retarget_instant), retarget_interval_millis as u128) {
+            return;
+        }
+
+        let polygon_bound = polygon_bounding_rect(&self.bound_polygon);
+        let platforms_bound = player
+            .config
+            .auto_mob_platforms_bound
+            .then_some(idle.platforms_bound)
+            .flatten();
+        let use_polygon = platforms_bound.is_none() && polygon_bound.is_some();
+        let bound = platforms_bound
+            .or(polygon_bound)
+            .unwrap_or(self.auto_mob_bound.into());
         let Update::Ok(points) =
             update_detection_task(context, 0, &mut self.auto_mob_task, move |detector| {
                 detector.detect_mobs(idle.bbox, bound, pos)
@@ -549,13 +1011,49 @@ impl Rotator {
         else {
             return;
         };
-        let Some(point) = points
-            .iter()
+
+        let now = Instant::now();
+        let last_mobs_instant = *self.auto_mob_last_mobs_instant.get_or_insert(now);
+        if !points.is_empty() {
+            self.auto_mob_last_mobs_instant = Some(now);
+        } else if let Some(id) = self.auto_mob_empty_map_action_id
+            && now.duration_since(last_mobs_instant).as_millis() >= empty_map_timeout_millis as u128
+        {
+            debug!(target: "rotator", "auto mob map is empty, queuing change channel action");
+            self.auto_mob_last_mobs_instant = Some(now);
+            if !self.priority_actions_queue.contains(&id) {
+                self.priority_actions_queue.push_front(id);
+            }
+            return;
+        }
+
+        let mut points = points
+            .into_iter()
             .filter(|point| {
                 let y = idle.bbox.height - point.y;
                 y <= pos.y || (y - pos.y).abs() <= GRAPPLING_THRESHOLD
             })
-            .choose(&mut rand::rng())
+            .filter(|point| !use_polygon || polygon_contains_point(&self.bound_polygon, *point))
+            .collect::<Vec<_>>();
+        if platform_dwell_millis > 0 && !idle.platforms.is_empty() {
+            let platforms = idle.platforms.into_iter().collect::<Vec<_>>();
+            if !self.auto_mob_retain_preferred_platform_points(
+                &mut points,
+                &platforms,
+                idle.bbox.height,
+                platform_dwell_millis,
+                now,
+            ) {
+                return;
+            }
+        }
+        let count = auto_mob_key_count(key_count, scale_count_with_mobs, points.len());
+        if prefer_center {
+            auto_mob_retain_center_points(&mut points, idle.bbox);
+        }
+        let Some(point) = context
+            .rng
+            .random_choose_iter(points.iter())
             .map(|point| Point::new(point.x, idle.bbox.height - point.y))
             .and_then(|point| {
                 debug!(target: "rotator", "auto mob raw position {point:?}");
@@ -569,11 +1067,12 @@ impl Rotator {
         else {
             return;
         };
+        self.auto_mob_retarget_instant = None;
         player.set_normal_action(
             u32::MAX,
             PlayerAction::AutoMob(PlayerActionAutoMob {
                 key,
-                count: key_count.max(1),
+                count,
                 wait_before_ticks: (key_wait_before_millis / MS_PER_TICK) as u32,
                 wait_after_ticks: (key_wait_after_millis / MS_PER_TICK) as u32,
                 position: Position {
@@ -586,6 +1085,115 @@ impl Rotator {
         );
     }
 
+    /// Queues the loot object action to the nearest detected lootable object.
+    ///
+    /// The position of the action inside [`Self::priority_actions`] is replaced with the object
+    /// position before queuing so that the player moves to it and presses the interact key.
+    fn rotate_loot_objects(&mut self, context: &Context, player: &PlayerState) {
+        let Some(id) = self.loot_object_action_id else {
+            return;
+        };
+        if self.loot_object_templates.is_empty()
+            || player.priority_action_id() == Some(id)
+            || self.priority_actions_queue.contains(&id)
+        {
+            return;
+        }
+        let Minimap::Idle(idle) = context.minimap else {
+            return;
+        };
+        let Some(pos) = player.last_known_pos else {
+            return;
+        };
+        let templates = self.loot_object_templates.clone();
+        let Update::Ok(points) = update_detection_task(
+            context,
+            self.loot_object_check_millis,
+            &mut self.loot_object_task,
+            move |detector| Ok(detector.detect_loot_objects(idle.bbox, pos, &templates)),
+        ) else {
+            return;
+        };
+        let Some(point) = points
+            .into_iter()
+            .map(|point| Point::new(point.x, idle.bbox.height - point.y))
+            .min_by_key(|point| (point.x - pos.x).abs() + (point.y - pos.y).abs())
+        else {
+            return;
+        };
+        let Some(action) = self.priority_actions.get_mut(&id) else {
+            return;
+        };
+        if let RotatorAction::Single(PlayerAction::Key(key)) = &mut action.inner {
+            key.position = Some(Position {
+                x: point.x,
+                x_random_range: 0,
+                y: point.y,
+                allow_adjusting: true,
+            });
+        }
+        debug!(target: "rotator", "queuing loot object action at {point:?}");
+        self.priority_actions_queue.push_back(id);
+    }
+
+    /// Retains only `points` on the preferred platform.
+    ///
+    /// Mobs detected on each platform are accumulated so that platforms can be ranked. When there
+    /// is no preferred platform or it has been empty for `dwell_millis`, the top-ranked platform
+    /// that currently has mobs becomes the preferred one and the accumulated counts are reset.
+    ///
+    /// `points` are in detection coordinate. Returns `false` if the player should stay and wait
+    /// for mobs on the preferred platform.
+    fn auto_mob_retain_preferred_platform_points(
+        &mut self,
+        points: &mut Vec<Point>,
+        platforms: &[PlatformWithNeighbors],
+        bbox_height: i32,
+        dwell_millis: u64,
+        now: Instant,
+    ) -> bool {
+        let indices = points
+            .iter()
+            .map(|point| {
+                auto_mob_platform_index(platforms, Point::new(point.x, bbox_height - point.y))
+            })
+            .collect::<Vec<_>>();
+        self.auto_mob_platform_counts.resize(platforms.len(), 0);
+        for index in indices.iter().copied().flatten() {
+            self.auto_mob_platform_counts[index] += 1;
+        }
+
+        let has_mobs = |index| indices.contains(&Some(index));
+        match self.auto_mob_preferred_platform {
+            Some((index, _)) if has_mobs(index) => {
+                self.auto_mob_preferred_platform = Some((index, now));
+            }
+            Some((_, instant))
+                if now.duration_since(instant).as_millis() < dwell_millis as u128 =>
+            {
+                return false;
+            }
+            _ => {
+                self.auto_mob_preferred_platform = self
+                    .auto_mob_platform_counts
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| has_mobs(*index))
+                    .max_by_key(|(_, count)| **count)
+                    .map(|(index, _)| (index, now));
+                self.auto_mob_platform_counts.fill(0);
+                let preferred = self.auto_mob_preferred_platform.map(|(index, _)| index);
+                debug!(target: "rotator", "auto mob preferred platform {preferred:?}");
+            }
+        }
+
+        if let Some((preferred, _)) = self.auto_mob_preferred_platform {
+            let mut indices = indices.into_iter();
+            points.retain(|_| indices.next().unwrap() == Some(preferred));
+        }
+        true
+    }
+
     fn rotate_ping_pong(
         &mut self,
         context: &Context,
@@ -605,6 +1213,8 @@ impl Rotator {
             key_count,
             key_wait_before_millis,
             key_wait_after_millis,
+            edge_inset,
+            alternate_key,
         } = ping_pong;
 
         let bbox = idle.bbox;
@@ -615,10 +1225,17 @@ impl Rotator {
         } else {
             PingPongDirection::Right
         };
+        let bound = if self.bound_polygon.is_empty() {
+            bound
+        } else {
+            polygon_bound_at(&self.bound_polygon, bbox.height - pos.y).unwrap_or(bound)
+        };
+        // Insets x-wise so the player turns around before reaching the bound edges
+        let edge_inset = edge_inset.clamp(0, bound.width / 2);
         let bound = Rect::new(
-            bound.x,
+            bound.x + edge_inset,
             bbox.height - (bound.y + bound.height),
-            bound.width,
+            bound.width - edge_inset * 2,
             bound.height,
         );
 
@@ -631,11 +1248,66 @@ impl Rotator {
                 wait_after_ticks: (key_wait_after_millis / MS_PER_TICK) as u32,
                 bound,
                 direction,
+                alternate_key,
+            }),
+        );
+    }
+
+    /// Rotates the [`HoldSpot`] key action.
+    ///
+    /// The key is used in place unless the player has drifted away from the spot by more than
+    /// [`HoldSpot::reposition_threshold`], in which case the player moves back before using it.
+    /// When [`HoldSpot::turn_and_attack`] is enabled, the key is used facing the opposite direction
+    /// of the last known direction.
+    fn rotate_hold_spot(&mut self, player: &mut PlayerState, hold_spot: HoldSpot) {
+        debug_assert!(!player.has_normal_action() && !player.has_priority_action());
+        let Some(pos) = player.last_known_pos else {
+            return;
+        };
+        let HoldSpot {
+            position,
+            key,
+            key_count,
+            key_wait_before_millis,
+            key_wait_after_millis,
+            reposition_threshold,
+            turn_and_attack,
+        } = hold_spot;
+        let drifted = (pos.x - position.x).abs() > reposition_threshold
+            || (pos.y - position.y).abs() > reposition_threshold;
+        let direction = if turn_and_attack {
+            match player.last_known_direction() {
+                ActionKeyDirection::Left => ActionKeyDirection::Right,
+                ActionKeyDirection::Right | ActionKeyDirection::Any => ActionKeyDirection::Left,
+            }
+        } else {
+            ActionKeyDirection::Any
+        };
+
+        player.set_normal_action(
+            u32::MAX - 2,
+            PlayerAction::Key(PlayerActionKey {
+                key,
+                link_key: None,
+                count: key_count.max(1),
+                position: drifted.then_some(position),
+                strict_position: false,
+                direction,
+                require_direction: None,
+                require_buff: None,
+                probability: None,
+                with: ActionKeyWith::Any,
+                use_while_falling: false,
+                wait_before_use_ticks: (key_wait_before_millis / MS_PER_TICK) as u32,
+                wait_before_use_ticks_random_range: 0,
+                wait_after_use_ticks: (key_wait_after_millis / MS_PER_TICK) as u32,
+                wait_after_use_ticks_random_range: 0,
+                linked_wait_ticks: 0,
             }),
         );
     }
 
-    fn rotate_start_to_end(&mut self, player: &mut PlayerState) {
+    fn rotate_start_to_end(&mut self, context: &Context, player: &mut PlayerState) {
         debug_assert!(!player.has_normal_action() && !player.has_priority_action());
         if self.normal_actions.is_empty() {
             return;
@@ -646,6 +1318,12 @@ impl Rotator {
         debug_assert!(self.normal_index < self.normal_actions.len());
         let (id, action) = self.normal_actions[self.normal_index].clone();
         self.normal_index = (self.normal_index + 1) % self.normal_actions.len();
+        if !is_facing_required_direction(player, rotator_action_require_direction(&action))
+            || !has_required_buff(context, rotator_action_require_buff(&action))
+            || !passes_probability(context, rotator_action_probability(&action))
+        {
+            return;
+        }
         match action {
             RotatorAction::Single(action) => {
                 player.set_normal_action(id, action);
@@ -657,7 +1335,7 @@ impl Rotator {
         }
     }
 
-    fn rotate_start_to_end_then_reverse(&mut self, player: &mut PlayerState) {
+    fn rotate_start_to_end_then_reverse(&mut self, context: &Context, player: &mut PlayerState) {
         debug_assert!(!player.has_normal_action() && !player.has_priority_action());
         if self.normal_actions.is_empty() {
             return;
@@ -665,12 +1343,22 @@ impl Rotator {
         if self.rotate_queuing_linked_action(player, false) {
             return;
         }
+        if self.normal_actions_turn_around_pending {
+            self.normal_actions_turn_around_pending = false;
+            if let Some((id, action)) = self.normal_actions_turn_around {
+                player.set_normal_action(id, action);
+                return;
+            }
+        }
 
         debug_assert!(self.normal_index < self.normal_actions.len());
         let len = self.normal_actions.len();
         if (self.normal_index + 1) == len {
             self.normal_actions_backward = !self.normal_actions_backward;
             self.normal_index = 0;
+            // The action at either end is used right after reversing, so the turn around
+            // action is used after it
+            self.normal_actions_turn_around_pending = len > 1;
         }
 
         let i = if self.normal_actions_backward {
@@ -681,6 +1369,53 @@ impl Rotator {
         let (id, action) = self.normal_actions[i].clone();
 
         self.normal_index += 1;
+        if !is_facing_required_direction(player, rotator_action_require_direction(&action))
+            || !has_required_buff(context, rotator_action_require_buff(&action))
+            || !passes_probability(context, rotator_action_probability(&action))
+        {
+            return;
+        }
+        match action {
+            RotatorAction::Single(action) => {
+                if let Some(action) = merge_nearby_action(
+                    action,
+                    player.last_known_pos,
+                    self.normal_actions_merge_distance,
+                ) {
+                    player.set_normal_action(id, action);
+                }
+            }
+            RotatorAction::Linked(action) => {
+                self.normal_queuing_linked_action = Some((id, Box::new(action)));
+                self.rotate_queuing_linked_action(player, false);
+            }
+        }
+    }
+
+    fn rotate_shuffled_cycle(&mut self, context: &Context, player: &mut PlayerState) {
+        debug_assert!(!player.has_normal_action() && !player.has_priority_action());
+        if self.normal_actions.is_empty() {
+            return;
+        }
+        if self.rotate_queuing_linked_action(player, false) {
+            return;
+        }
+
+        let len = self.normal_actions.len();
+        debug_assert!(self.normal_index < len);
+        if self.normal_index == 0 || self.normal_shuffled_indices.len() != len {
+            self.normal_shuffled_indices = (0..len).collect();
+            context.rng.shuffle(&mut self.normal_shuffled_indices);
+        }
+        let (id, action) =
+            self.normal_actions[self.normal_shuffled_indices[self.normal_index]].clone();
+        self.normal_index = (self.normal_index + 1) % len;
+        if !is_facing_required_direction(player, rotator_action_require_direction(&action))
+            || !has_required_buff(context, rotator_action_require_buff(&action))
+            || !passes_probability(context, rotator_action_probability(&action))
+        {
+            return;
+        }
         match action {
             RotatorAction::Single(action) => {
                 player.set_normal_action(id, action);
@@ -707,11 +1442,26 @@ impl Rotator {
             return false;
         }
         let (id, action) = linked_action.take().unwrap();
-        *linked_action = action.next.map(|action| (id, action));
+        let LinkedAction {
+            inner,
+            fire_during_move,
+            next,
+        } = *action;
+        // Hands the key over to the player to send during the next move instead of blocking
+        let (inner, next) = match (inner, next) {
+            (PlayerAction::Key(key), Some(next))
+                if fire_during_move && matches!(next.inner, PlayerAction::Move(_)) =>
+            {
+                player.set_move_fire_key(key);
+                (next.inner, next.next)
+            }
+            (inner, next) => (inner, next),
+        };
+        *linked_action = next.map(|action| (id, action));
         if is_priority {
-            player.set_priority_action(id, action.inner);
+            player.set_priority_action(id, inner);
         } else {
-            player.set_normal_action(id, action.inner);
+            player.set_normal_action(id, inner);
         }
         true
     }
@@ -720,7 +1470,8 @@ impl Rotator {
 /// Creates a [`RotatorAction`] with `start_action` as the initial action
 ///
 /// If `start_action` is linked, this function returns [`RotatorAction::Linked`] with [`usize`] as
-/// the offset from `start_index` to the next non-linked action.
+/// the offset from `start_index` to the next non-linked action. The linked wait of
+/// `start_action` is added to the wait after of every linked action except the last one.
 /// Otherwise, this returns [`RotatorAction::Single`] with [`usize`] offset of 1.
 #[inline]
 fn rotator_action(
@@ -745,8 +1496,27 @@ fn rotator_action(
             _ => return (RotatorAction::Single(start_action.into()), 1),
         }
     }
+    let linked_wait_millis = match start_action {
+        Action::Move(ActionMove {
+            linked_wait_millis, ..
+        })
+        | Action::Key(ActionKey {
+            linked_wait_millis, ..
+        }) => linked_wait_millis,
+    };
+    let linked_wait_ticks = (linked_wait_millis / MS_PER_TICK) as u32;
+    let fire_during_move = |action: &Action| {
+        matches!(
+            action,
+            Action::Key(ActionKey {
+                fire_during_move: true,
+                ..
+            })
+        )
+    };
     let mut head = LinkedAction {
         inner: start_action.into(),
+        fire_during_move: fire_during_move(&start_action),
         next: None,
     };
     let mut current = &mut head;
@@ -763,8 +1533,10 @@ fn rotator_action(
             }) => {
                 let action = LinkedAction {
                     inner: (*action).into(),
+                    fire_during_move: fire_during_move(action),
                     next: None,
                 };
+                current.inner = player_action_with_linked_wait(current.inner, linked_wait_ticks);
                 current.next = Some(Box::new(action));
                 current = current.next.as_mut().unwrap();
                 offset += 1;
@@ -775,28 +1547,328 @@ fn rotator_action(
     (RotatorAction::Linked(head), offset)
 }
 
+/// Offsets the position of `action` by `offset` if it has one.
 #[inline]
-fn priority_action(
-    action: RotatorAction,
-    condition: ActionCondition,
-    queue_to_front: bool,
-) -> PriorityAction {
-    debug_assert_matches!(
-        condition,
-        ActionCondition::EveryMillis(_) | ActionCondition::ErdaShowerOffCooldown
-    );
-    PriorityAction {
-        inner: action,
-        condition: Condition(Box::new(move |context, _, last_queued_time| {
-            if should_queue_fixed_action(context, last_queued_time, condition) {
-                ConditionResult::Queue
-            } else {
-                ConditionResult::Skip
-            }
-        })),
-        condition_kind: Some(condition),
-        queue_to_front,
-        ignoring: false,
+fn offset_action_position(action: Action, offset: Point) -> Action {
+    let offset_position = |position: Position| Position {
+        x: position.x + offset.x,
+        y: position.y + offset.y,
+        ..position
+    };
+    match action {
+        Action::Move(action) => Action::Move(ActionMove {
+            position: offset_position(action.position),
+            ..action
+        }),
+        Action::Key(action) => Action::Key(ActionKey {
+            position: action.position.map(offset_position),
+            ..action
+        }),
+    }
+}
+
+/// Adds `ticks` to the wait after of `action` to delay the next linked action.
+#[inline]
+fn player_action_with_linked_wait(action: PlayerAction, ticks: u32) -> PlayerAction {
+    match action {
+        PlayerAction::Key(key) => PlayerAction::Key(PlayerActionKey {
+            linked_wait_ticks: ticks,
+            ..key
+        }),
+        PlayerAction::Move(action) => PlayerAction::Move(PlayerActionMove {
+            wait_after_move_ticks: action.wait_after_move_ticks + ticks,
+            ..action
+        }),
+        _ => unreachable!(),
+    }
+}
+
+/// Merges `action` into the player current position `pos` if the action position is within
+/// `distance`.
+///
+/// Returns [`None`] if `action` is a [`PlayerAction::Move`] that can be skipped. A non-strict
+/// [`PlayerAction::Key`] is used in place without moving.
+#[inline]
+fn merge_nearby_action(
+    action: PlayerAction,
+    pos: Option<Point>,
+    distance: i32,
+) -> Option<PlayerAction> {
+    let Some(pos) = pos.filter(|_| distance > 0) else {
+        return Some(action);
+    };
+    let is_nearby = |position: Position| {
+        let dx = position.x - pos.x;
+        let dy = position.y - pos.y;
+        dx * dx + dy * dy <= distance * distance
+    };
+
+    match action {
+        PlayerAction::Move(PlayerActionMove { position, .. }) if is_nearby(position) => None,
+        PlayerAction::Key(
+            key @ PlayerActionKey {
+                position: Some(position),
+                strict_position: false,
+                ..
+            },
+        ) if is_nearby(position) => Some(PlayerAction::Key(PlayerActionKey {
+            position: None,
+            ..key
+        })),
+        action => Some(action),
+    }
+}
+
+/// Computes the number of times to use the auto mob key.
+///
+/// When `scale_count_with_mobs` is true, the count is the number of detected mobs clamped to
+/// `key_count` as the maximum. Otherwise, `key_count` is used.
+#[inline]
+fn auto_mob_key_count(key_count: u32, scale_count_with_mobs: bool, mobs_count: usize) -> u32 {
+    let key_count = key_count.max(1);
+    if scale_count_with_mobs {
+        (mobs_count as u32).clamp(1, key_count)
+    } else {
+        key_count
+    }
+}
+
+/// Finds the index of the platform in `platforms` that `point` is on.
+///
+/// `point` is in player coordinate. The platform is the closest one at or below `point` that
+/// contains `point.x`.
+#[inline]
+fn auto_mob_platform_index(platforms: &[PlatformWithNeighbors], point: Point) -> Option<usize> {
+    platforms
+        .iter()
+        .enumerate()
+        .filter(|(_, platform)| platform.xs().contains(&point.x) && platform.y() <= point.y)
+        .min_by_key(|(_, platform)| point.y - platform.y())
+        .map(|(index, _)| index)
+}
+
+/// Retains the half of `points` nearest to the center of `bbox`.
+///
+/// `points` are in `bbox` coordinate. At least one point is retained if `points` is not empty.
+#[inline]
+fn auto_mob_retain_center_points(points: &mut Vec<Point>, bbox: Rect) {
+    let center = Point::new(bbox.width / 2, bbox.height / 2);
+    points.sort_by_key(|point| {
+        let distance = *point - center;
+        distance.x * distance.x + distance.y * distance.y
+    });
+    points.truncate(points.len().div_ceil(2));
+}
+
+/// Computes the bounding rectangle of `polygon`.
+///
+/// Returns [`None`] if `polygon` is empty.
+#[inline]
+fn polygon_bounding_rect(polygon: &[Point]) -> Option<Rect> {
+    let x_min = polygon.iter().map(|point| point.x).min()?;
+    let x_max = polygon.iter().map(|point| point.x).max()?;
+    let y_min = polygon.iter().map(|point| point.y).min()?;
+    let y_max = polygon.iter().map(|point| point.y).max()?;
+    Some(Rect::new(x_min, y_min, x_max - x_min, y_max - y_min))
+}
+
+/// Checks whether `point` is inside `polygon` using the even-odd rule.
+#[inline]
+fn polygon_contains_point(polygon: &[Point], point: Point) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len() - 1;
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[j]);
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < a.x + (b.x - a.x) * (point.y - a.y) / (b.y - a.y)
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Computes the ping-pong [`Bound`] of `polygon` at the horizontal line `y`.
+///
+/// The returned bound spans the leftmost and rightmost intersections of `polygon` edges with
+/// the line `y` x-wise and the whole `polygon` y-wise. If the line does not intersect
+/// `polygon`, the bounding rectangle of `polygon` is used.
+#[inline]
+fn polygon_bound_at(polygon: &[Point], y: i32) -> Option<Bound> {
+    let rect = polygon_bounding_rect(polygon)?;
+    let y = y.clamp(rect.y, rect.y + rect.height);
+    let mut j = polygon.len() - 1;
+    let mut span = None::<(i32, i32)>;
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[j]);
+        j = i;
+        if a.y.min(b.y) > y || a.y.max(b.y) < y {
+            continue;
+        }
+        let xs = if a.y == b.y {
+            (a.x.min(b.x), a.x.max(b.x))
+        } else {
+            let x = a.x + (b.x - a.x) * (y - a.y) / (b.y - a.y);
+            (x, x)
+        };
+        span = Some(span.map_or(xs, |(start, end)| (start.min(xs.0), end.max(xs.1))));
+    }
+    let (start, end) = span.unwrap_or((rect.x, rect.x + rect.width));
+
+    Some(Bound {
+        x: start,
+        y: rect.y,
+        width: end - start,
+        height: rect.height,
+    })
+}
+
+/// Retrieves the required facing direction of the first action in `action`.
+#[inline]
+fn rotator_action_require_direction(action: &RotatorAction) -> Option<ActionKeyDirection> {
+    let inner = match action {
+        RotatorAction::Single(inner) => inner,
+        RotatorAction::Linked(linked) => &linked.inner,
+    };
+    match inner {
+        PlayerAction::Key(PlayerActionKey {
+            require_direction, ..
+        }) => *require_direction,
+        _ => None,
+    }
+}
+
+/// Retrieves the required active buff of the first action in `action`.
+#[inline]
+fn rotator_action_require_buff(action: &RotatorAction) -> Option<BuffKind> {
+    let inner = match action {
+        RotatorAction::Single(inner) => inner,
+        RotatorAction::Linked(linked) => &linked.inner,
+    };
+    match inner {
+        PlayerAction::Key(PlayerActionKey { require_buff, .. }) => *require_buff,
+        _ => None,
+    }
+}
+
+/// Retrieves the probability of the first action in `action`.
+#[inline]
+fn rotator_action_probability(action: &RotatorAction) -> Option<f32> {
+    let inner = match action {
+        RotatorAction::Single(inner) => inner,
+        RotatorAction::Linked(linked) => &linked.inner,
+    };
+    match inner {
+        PlayerAction::Key(PlayerActionKey { probability, .. })
+        | PlayerAction::Move(PlayerActionMove { probability, .. }) => *probability,
+        _ => None,
+    }
+}
+
+/// Whether the random roll passes `probability`.
+///
+/// Returns `true` if there is no probability.
+#[inline]
+fn passes_probability(context: &Context, probability: Option<f32>) -> bool {
+    probability
+        .is_none_or(|probability| context.rng.random_bool(probability.clamp(0.0, 1.0) as f64))
+}
+
+/// Whether `require_buff` is currently active.
+///
+/// Returns `true` if there is no required buff.
+#[inline]
+fn has_required_buff(context: &Context, require_buff: Option<BuffKind>) -> bool {
+    require_buff.is_none_or(|buff| matches!(context.buffs[buff], Buff::Yes))
+}
+
+/// Removes the required buff of `action` if it is not one of the enabled `buffs`.
+///
+/// A buff that is not enabled is never detected, so requiring it would prevent `action` from
+/// ever being used.
+#[inline]
+fn ignore_undetectable_required_buff(action: Action, buffs: &[(BuffKind, KeyBinding)]) -> Action {
+    match action {
+        Action::Key(
+            key @ ActionKey {
+                require_buff: Some(require_buff),
+                ..
+            },
+        ) if !buffs
+            .iter()
+            .any(|(buff, _)| *buff == BuffKind::from(require_buff)) =>
+        {
+            Action::Key(ActionKey {
+                require_buff: None,
+                ..key
+            })
+        }
+        _ => action,
+    }
+}
+
+/// Whether `kind` is recasted on a timer instead of being detected.
+#[inline]
+fn is_timer_only_buff(timer_only_buffs: &[BuffKindData], kind: BuffKind) -> bool {
+    timer_only_buffs
+        .iter()
+        .any(|buff| BuffKind::from(*buff) == kind)
+}
+
+/// Whether the player is currently facing `require_direction`.
+///
+/// Returns `true` if there is no required direction.
+#[inline]
+fn is_facing_required_direction(
+    player: &PlayerState,
+    require_direction: Option<ActionKeyDirection>,
+) -> bool {
+    require_direction.is_none_or(|direction| {
+        matches!(direction, ActionKeyDirection::Any) || direction == player.last_known_direction()
+    })
+}
+
+#[inline]
+fn priority_action(
+    action: RotatorAction,
+    condition: ActionCondition,
+    queue_to_front: bool,
+) -> PriorityAction {
+    debug_assert_matches!(
+        condition,
+        ActionCondition::EveryMillis(_) | ActionCondition::ErdaShowerOffCooldown
+    );
+    let require_direction = rotator_action_require_direction(&action);
+    let require_buff = rotator_action_require_buff(&action);
+    let dedupe_key = match action {
+        RotatorAction::Single(PlayerAction::Key(PlayerActionKey {
+            key,
+            position: None,
+            ..
+        })) => Some(PriorityActionDedupeKey::Key(key)),
+        _ => None,
+    };
+
+    PriorityAction {
+        inner: action,
+        condition: Condition(Box::new(move |context, player, last_queued_time| {
+            if !is_facing_required_direction(player, require_direction)
+                || !has_required_buff(context, require_buff)
+            {
+                return ConditionResult::Skip;
+            }
+            if should_queue_fixed_action(context, last_queued_time, condition) {
+                ConditionResult::Queue
+            } else {
+                ConditionResult::Skip
+            }
+        })),
+        condition_kind: Some(condition),
+        dedupe_key,
+        queue_to_front,
+        tier: PriorityActionTier::Urgent,
+        ignoring: false,
         last_queued_time: None,
     }
 }
@@ -805,17 +1877,20 @@ fn priority_action(
 /// when it is detected as depleted.
 ///
 /// The action will only queue if:
-/// - Enough time has passed since the last queue attempt.
+/// - At least `check_millis` has passed since the last queue attempt.
 /// - The familiar buff is currently active.
 /// - Familiar essence is detected as depleted.
 ///
 /// If the essence is not depleted, the action will be marked as [`ConditionResult::Ignore`]
 /// and temporarily ignored in subsequent queue do to `last_queued_time` being updated.
 #[inline]
-fn familiar_essence_replenish_priority_action(key: KeyBinding) -> PriorityAction {
+fn familiar_essence_replenish_priority_action(
+    key: KeyBinding,
+    check_millis: u64,
+) -> PriorityAction {
     PriorityAction {
-        condition: Condition(Box::new(|context, _, last_queued_time| {
-            if !at_least_millis_passed_since(last_queued_time, COOLDOWN_BETWEEN_QUEUE_MILLIS) {
+        condition: Condition(Box::new(move |context, _, last_queued_time| {
+            if !at_least_millis_passed_since(last_queued_time, check_millis as u128) {
                 return ConditionResult::Skip;
             }
             if !matches!(context.buffs[BuffKind::Familiar], Buff::Yes) {
@@ -828,19 +1903,27 @@ fn familiar_essence_replenish_priority_action(key: KeyBinding) -> PriorityAction
             }
         })),
         condition_kind: None,
+        dedupe_key: None,
         inner: RotatorAction::Single(PlayerAction::Key(PlayerActionKey {
             key,
             link_key: None,
             count: 1,
             position: None,
+            strict_position: false,
             direction: ActionKeyDirection::Any,
+            require_direction: None,
+            require_buff: None,
+            probability: None,
             with: ActionKeyWith::Any,
+            use_while_falling: false,
             wait_before_use_ticks: 5,
             wait_before_use_ticks_random_range: 0,
             wait_after_use_ticks: 0,
             wait_after_use_ticks_random_range: 0,
+            linked_wait_ticks: 0,
         })),
         queue_to_front: true,
+        tier: PriorityActionTier::Deferrable,
         ignoring: false,
         last_queued_time: None,
     }
@@ -850,31 +1933,38 @@ fn familiar_essence_replenish_priority_action(key: KeyBinding) -> PriorityAction
 ///
 /// This action queues if all the following conditions are met:
 /// - The player is not currently validating a rune.
+/// - The player has not abandoned solving the current rune.
+/// - The player has not reached the maximum number of runes solved per minute.
 /// - Enough time has passed since the last queue attempt.
 /// - The minimap is in the [`Minimap::Idle`] state.
 /// - A rune is present on the minimap.
-/// - The player currently has no rune buff.
+/// - The player currently has no rune buff or `ignore_buff` is true.
 #[inline]
-fn solve_rune_priority_action() -> PriorityAction {
+fn solve_rune_priority_action(ignore_buff: bool) -> PriorityAction {
     PriorityAction {
-        condition: Condition(Box::new(|context, player, last_queued_time| {
-            if player.is_validating_rune() {
+        condition: Condition(Box::new(move |context, player, last_queued_time| {
+            if player.is_validating_rune()
+                || player.is_rune_abandoned()
+                || player.is_rune_solving_capped()
+            {
                 return ConditionResult::Skip;
             }
             if !at_least_millis_passed_since(last_queued_time, COOLDOWN_BETWEEN_QUEUE_MILLIS) {
                 return ConditionResult::Skip;
             }
             if let Minimap::Idle(idle) = context.minimap
-                && idle.rune.value().is_some()
-                && matches!(context.buffs[BuffKind::Rune], Buff::No)
+                && idle.has_rune()
+                && (ignore_buff || matches!(context.buffs[BuffKind::Rune], Buff::No))
             {
                 return ConditionResult::Queue;
             }
             ConditionResult::Skip
         })),
         condition_kind: None,
+        dedupe_key: Some(PriorityActionDedupeKey::Rune),
         inner: RotatorAction::Single(PlayerAction::SolveRune),
         queue_to_front: true,
+        tier: PriorityActionTier::Urgent,
         ignoring: false,
         last_queued_time: None,
     }
@@ -885,58 +1975,232 @@ fn solve_rune_priority_action() -> PriorityAction {
 /// The action queues if:
 /// - Enough time has passed since the last queue attempt.
 /// - The minimap is in the [`Minimap::Idle`] state.
-/// - The specified buff is currently missing.
+/// - The specified buff is currently missing or `recast_millis` has passed since the last queue
+///   if the buff is recasted on a timer only.
+/// - The player has not attacked within `combat_lull_millis` if provided or the buff has been
+///   deferred for [`MAX_BUFF_COMBAT_DEFER_MILLIS`].
 #[inline]
-fn buff_priority_action(buff: BuffKind, key: KeyBinding) -> PriorityAction {
+fn buff_priority_action(
+    buff: BuffKind,
+    key: KeyBinding,
+    recast_millis: Option<u64>,
+    combat_lull_millis: Option<u64>,
+) -> PriorityAction {
+    let deferred_since = Cell::new(None);
+
     PriorityAction {
-        condition: Condition(Box::new(move |context, _, last_queued_time| {
+        condition: Condition(Box::new(move |context, player, last_queued_time| {
             if !at_least_millis_passed_since(last_queued_time, COOLDOWN_BETWEEN_QUEUE_MILLIS) {
                 return ConditionResult::Skip;
             }
             if !matches!(context.minimap, Minimap::Idle(_)) {
                 return ConditionResult::Skip;
             }
-            if matches!(context.buffs[buff], Buff::No) {
-                ConditionResult::Queue
-            } else {
+            let missing = match recast_millis {
+                Some(millis) => at_least_millis_passed_since(last_queued_time, millis as u128),
+                None => matches!(context.buffs[buff], Buff::No),
+            };
+            if !missing {
+                deferred_since.set(None);
+                return ConditionResult::Skip;
+            }
+            if should_defer_buff_in_combat(player, combat_lull_millis, &deferred_since) {
                 ConditionResult::Skip
+            } else {
+                ConditionResult::Queue
             }
         })),
         condition_kind: None,
+        dedupe_key: Some(PriorityActionDedupeKey::Buff(buff)),
         inner: RotatorAction::Single(PlayerAction::Key(PlayerActionKey {
             key,
             link_key: None,
             count: 1,
             position: None,
+            strict_position: false,
             direction: ActionKeyDirection::Any,
+            require_direction: None,
+            require_buff: None,
+            probability: None,
             with: ActionKeyWith::Stationary,
+            use_while_falling: false,
             wait_before_use_ticks: 10,
             wait_before_use_ticks_random_range: 0,
             wait_after_use_ticks: 10,
             wait_after_use_ticks_random_range: 0,
+            linked_wait_ticks: 0,
         })),
         queue_to_front: true,
+        tier: PriorityActionTier::Deferrable,
         ignoring: false,
         last_queued_time: None,
     }
 }
 
+/// Creates a [`RotatorAction::Linked`] priority action to press all `keys` in order when any of
+/// the tracked `buffs` is not active.
+///
+/// This replaces the per-buff actions from [`buff_priority_action`] of the tracked `buffs` for
+/// setups that cast multiple buffs through a sequence of keys. Each key is spaced by
+/// `spacing_ticks`.
+///
+/// The action queues if:
+/// - Enough time has passed since the last queue attempt.
+/// - The minimap is in the [`Minimap::Idle`] state.
+/// - Any of the tracked buffs is currently missing or `recast_millis` has passed since the last
+///   queue if any of the tracked buffs is recasted on a timer only.
+/// - The player has not attacked within `combat_lull_millis` if provided or the buff has been
+///   deferred for [`MAX_BUFF_COMBAT_DEFER_MILLIS`].
 #[inline]
-fn panic_priority_action(mode: PanicMode) -> PriorityAction {
-    let to = match mode {
-        PanicMode::CycleChannel => PanicTo::Channel,
-        PanicMode::GoToTown => PanicTo::Town,
+fn buff_all_priority_action(
+    buffs: Vec<BuffKind>,
+    keys: &[KeyBinding],
+    spacing_ticks: u32,
+    recast_millis: Option<u64>,
+    combat_lull_millis: Option<u64>,
+) -> PriorityAction {
+    debug_assert!(!keys.is_empty());
+
+    let key_action = |key, wait_before_use_ticks, wait_after_use_ticks| {
+        PlayerAction::Key(PlayerActionKey {
+            key,
+            link_key: None,
+            count: 1,
+            position: None,
+            strict_position: false,
+            direction: ActionKeyDirection::Any,
+            require_direction: None,
+            require_buff: None,
+            probability: None,
+            with: ActionKeyWith::Stationary,
+            use_while_falling: false,
+            wait_before_use_ticks,
+            wait_before_use_ticks_random_range: 0,
+            wait_after_use_ticks,
+            wait_after_use_ticks_random_range: 0,
+            linked_wait_ticks: 0,
+        })
     };
+    let last = keys.len() - 1;
+    let linked = keys
+        .iter()
+        .copied()
+        .enumerate()
+        .rev()
+        .fold(None, |next, (i, key)| {
+            let wait_before = if i == 0 { 10 } else { 0 };
+            let wait_after = if i == last { 10 } else { spacing_ticks };
+            Some(Box::new(LinkedAction {
+                inner: key_action(key, wait_before, wait_after),
+                fire_during_move: false,
+                next,
+            }))
+        })
+        .unwrap();
+    let deferred_since = Cell::new(None);
 
     PriorityAction {
-        condition: Condition(Box::new(|context, _, last_queued_time| {
+        condition: Condition(Box::new(move |context, player, last_queued_time| {
+            if !at_least_millis_passed_since(last_queued_time, COOLDOWN_BETWEEN_QUEUE_MILLIS) {
+                return ConditionResult::Skip;
+            }
+            if !matches!(context.minimap, Minimap::Idle(_)) {
+                return ConditionResult::Skip;
+            }
+            let missing = recast_millis.is_some_and(|millis| {
+                at_least_millis_passed_since(last_queued_time, millis as u128)
+            }) || buffs
+                .iter()
+                .any(|buff| matches!(context.buffs[*buff], Buff::No));
+            if !missing {
+                deferred_since.set(None);
+                return ConditionResult::Skip;
+            }
+            if should_defer_buff_in_combat(player, combat_lull_millis, &deferred_since) {
+                ConditionResult::Skip
+            } else {
+                ConditionResult::Queue
+            }
+        })),
+        condition_kind: None,
+        dedupe_key: None,
+        inner: RotatorAction::Linked(*linked),
+        queue_to_front: true,
+        tier: PriorityActionTier::Deferrable,
+        ignoring: false,
+        last_queued_time: None,
+    }
+}
+
+/// Whether a missing buff should be deferred because the player has attacked within
+/// `combat_lull_millis`.
+///
+/// `deferred_since` tracks when the deferral started so that the buff is no longer deferred once
+/// [`MAX_BUFF_COMBAT_DEFER_MILLIS`] has passed under continuous combat.
+#[inline]
+fn should_defer_buff_in_combat(
+    player: &PlayerState,
+    combat_lull_millis: Option<u64>,
+    deferred_since: &Cell<Option<Instant>>,
+) -> bool {
+    if !combat_lull_millis.is_some_and(|millis| player.is_in_combat(millis)) {
+        deferred_since.set(None);
+        return false;
+    }
+    let since = deferred_since.get().unwrap_or_else(Instant::now);
+    if since.elapsed().as_millis() >= MAX_BUFF_COMBAT_DEFER_MILLIS {
+        deferred_since.set(None);
+        return false;
+    }
+    deferred_since.set(Some(since));
+    true
+}
+
+/// Creates a priority action that triggers when other players appear on the minimap.
+///
+/// Depending on [`PanicMode`], the action either flees using [`PlayerAction::Panic`] or uses
+/// `defensive_key` and continues the rotation. Only other players allowed by `filter` are
+/// considered.
+#[inline]
+fn panic_priority_action(
+    mode: PanicMode,
+    defensive_key: KeyBinding,
+    filter: OtherPlayerFilter,
+) -> PriorityAction {
+    let action = match mode {
+        PanicMode::CycleChannel => PlayerAction::Panic(PlayerActionPanic {
+            to: PanicTo::Channel,
+        }),
+        PanicMode::GoToTown => PlayerAction::Panic(PlayerActionPanic { to: PanicTo::Town }),
+        PanicMode::Defensive => PlayerAction::Key(PlayerActionKey {
+            key: defensive_key,
+            link_key: None,
+            count: 1,
+            position: None,
+            strict_position: false,
+            direction: ActionKeyDirection::Any,
+            require_direction: None,
+            require_buff: None,
+            probability: None,
+            with: ActionKeyWith::Any,
+            use_while_falling: false,
+            wait_before_use_ticks: 0,
+            wait_before_use_ticks_random_range: 0,
+            wait_after_use_ticks: 0,
+            wait_after_use_ticks_random_range: 0,
+            linked_wait_ticks: 0,
+        }),
+    };
+
+    PriorityAction {
+        condition: Condition(Box::new(move |context, _, last_queued_time| {
             if context.halting {
                 return ConditionResult::Ignore;
             }
             match context.minimap {
                 Minimap::Detecting => ConditionResult::Skip,
                 Minimap::Idle(idle) => {
-                    if !idle.has_any_other_player() || last_queued_time.is_none() {
+                    if !idle.has_other_player(filter) || last_queued_time.is_none() {
                         return ConditionResult::Ignore;
                     }
                     if at_least_millis_passed_since(last_queued_time, 15000) {
@@ -948,58 +2212,340 @@ fn panic_priority_action(mode: PanicMode) -> PriorityAction {
             }
         })),
         condition_kind: None,
-        inner: RotatorAction::Single(PlayerAction::Panic(PlayerActionPanic { to })),
+        dedupe_key: None,
+        inner: RotatorAction::Single(action),
         queue_to_front: true,
+        tier: PriorityActionTier::Urgent,
         ignoring: false,
         last_queued_time: None,
     }
 }
 
+/// Creates a [`PlayerAction::Panic`] to town priority action for emergency.
+///
+/// This action never queues by itself and is only set directly to the player by
+/// [`Rotator::queue_emergency_town_action`]. It is queued to front so that it cannot be
+/// overriden by other priority actions while executing.
 #[inline]
-fn elite_boss_change_channel_priority_action() -> PriorityAction {
+fn emergency_town_priority_action() -> PriorityAction {
     PriorityAction {
-        condition: Condition(Box::new(|context, _, last_queued_time| {
-            if !at_least_millis_passed_since(last_queued_time, 15000) {
-                return ConditionResult::Skip;
-            }
-            if let Minimap::Idle(idle) = context.minimap
-                && idle.has_elite_boss
-            {
-                ConditionResult::Queue
-            } else {
-                ConditionResult::Skip
-            }
-        })),
+        condition: Condition(Box::new(|_, _, _| ConditionResult::Skip)),
         condition_kind: None,
-        inner: RotatorAction::Single(PlayerAction::Panic(PlayerActionPanic {
-            to: PanicTo::Channel,
-        })),
+        dedupe_key: None,
+        inner: RotatorAction::Single(PlayerAction::Panic(PlayerActionPanic { to: PanicTo::Town })),
         queue_to_front: true,
+        tier: PriorityActionTier::Urgent,
         ignoring: false,
         last_queued_time: None,
     }
 }
 
+/// Creates a [`PlayerAction::Key`] priority action to press `interact_key` at a lootable object.
+///
+/// The condition always skips because the action is queued manually by
+/// [`Rotator::rotate_loot_objects`] once an object is detected.
 #[inline]
-fn at_least_millis_passed_since(last_queued_time: Option<Instant>, millis: u128) -> bool {
-    last_queued_time
-        .map(|instant| Instant::now().duration_since(instant).as_millis() >= millis)
-        .unwrap_or(true)
+fn loot_object_priority_action(interact_key: KeyBinding) -> PriorityAction {
+    PriorityAction {
+        condition: Condition(Box::new(|_, _, _| ConditionResult::Skip)),
+        condition_kind: None,
+        dedupe_key: None,
+        inner: RotatorAction::Single(PlayerAction::Key(PlayerActionKey {
+            key: interact_key,
+            link_key: None,
+            count: 1,
+            position: None,
+            strict_position: false,
+            direction: ActionKeyDirection::Any,
+            require_direction: None,
+            require_buff: None,
+            probability: None,
+            with: ActionKeyWith::Stationary,
+            use_while_falling: false,
+            wait_before_use_ticks: 5,
+            wait_before_use_ticks_random_range: 0,
+            wait_after_use_ticks: 10,
+            wait_after_use_ticks_random_range: 0,
+            linked_wait_ticks: 0,
+        })),
+        queue_to_front: false,
+        tier: PriorityActionTier::Deferrable,
+        ignoring: false,
+        last_queued_time: None,
+    }
 }
 
+/// Creates the [`RotatorAction`] to swap familiars with `swapping`.
+///
+/// If `safe_position` is provided, the player moves to that position before swapping so that the
+/// swapping menus are not opened while being attacked. The rotation then resumes as usual.
 #[inline]
-fn should_queue_fixed_action(
-    context: &Context,
-    last_queued_time: Option<Instant>,
-    condition: ActionCondition,
-) -> bool {
-    let millis_should_passed = match condition {
-        ActionCondition::EveryMillis(millis) => millis as u128,
-        ActionCondition::ErdaShowerOffCooldown => COOLDOWN_BETWEEN_QUEUE_MILLIS,
-        ActionCondition::Linked | ActionCondition::Any => unreachable!(),
+fn familiars_swapping_action(
+    swapping: PlayerActionFamiliarsSwapping,
+    safe_position: Option<Position>,
+) -> RotatorAction {
+    let Some(position) = safe_position else {
+        return RotatorAction::Single(PlayerAction::FamiliarsSwapping(swapping));
     };
-    if !at_least_millis_passed_since(last_queued_time, millis_should_passed) {
-        return false;
+
+    RotatorAction::Linked(LinkedAction {
+        inner: PlayerAction::Move(PlayerActionMove {
+            position,
+            probability: None,
+            wait_after_move_ticks: 0,
+            wait_after_move_ticks_random_range: 0,
+            attack_on_arrival: None,
+        }),
+        fire_during_move: false,
+        next: Some(Box::new(LinkedAction {
+            inner: PlayerAction::FamiliarsSwapping(swapping),
+            fire_during_move: false,
+            next: None,
+        })),
+    })
+}
+
+/// Creates a [`PlayerAction::Move`] priority action to move back to `position` after idling.
+///
+/// The action queues if:
+/// - The minimap is in the [`Minimap::Idle`] state.
+/// - The player has been in [`Player::Idle`] without any action for at least `idle_millis`.
+/// - The player is not already near `position`.
+#[inline]
+fn return_home_priority_action(position: Position, idle_millis: u64) -> PriorityAction {
+    const HOME_NEARBY_DISTANCE: i32 = 3;
+
+    let idle_since = Cell::new(None::<Instant>);
+
+    PriorityAction {
+        condition: Condition(Box::new(move |context, player, _| {
+            let is_idle = matches!(context.player, Player::Idle)
+                && !player.has_normal_action()
+                && !player.has_priority_action();
+            if !is_idle || !matches!(context.minimap, Minimap::Idle(_)) {
+                idle_since.set(None);
+                return ConditionResult::Skip;
+            }
+            let since = idle_since.get().unwrap_or_else(Instant::now);
+            idle_since.set(Some(since));
+            if !at_least_millis_passed_since(Some(since), idle_millis as u128) {
+                return ConditionResult::Skip;
+            }
+            idle_since.set(None);
+            let is_home = player.last_known_pos.is_some_and(|pos| {
+                (pos.x - position.x).abs() <= HOME_NEARBY_DISTANCE
+                    && (pos.y - position.y).abs() <= HOME_NEARBY_DISTANCE
+            });
+            if is_home {
+                ConditionResult::Skip
+            } else {
+                ConditionResult::Queue
+            }
+        })),
+        condition_kind: None,
+        dedupe_key: None,
+        inner: RotatorAction::Single(PlayerAction::Move(PlayerActionMove {
+            position,
+            probability: None,
+            wait_after_move_ticks: 0,
+            wait_after_move_ticks_random_range: 0,
+            attack_on_arrival: None,
+        })),
+        queue_to_front: false,
+        tier: PriorityActionTier::Deferrable,
+        ignoring: false,
+        last_queued_time: None,
+    }
+}
+
+#[inline]
+fn elite_boss_change_channel_priority_action() -> PriorityAction {
+    PriorityAction {
+        condition: Condition(Box::new(|context, _, last_queued_time| {
+            if !at_least_millis_passed_since(last_queued_time, 15000) {
+                return ConditionResult::Skip;
+            }
+            if let Minimap::Idle(idle) = context.minimap
+                && idle.has_elite_boss
+            {
+                ConditionResult::Queue
+            } else {
+                ConditionResult::Skip
+            }
+        })),
+        condition_kind: None,
+        dedupe_key: None,
+        inner: RotatorAction::Single(PlayerAction::Panic(PlayerActionPanic {
+            to: PanicTo::Channel,
+        })),
+        queue_to_front: true,
+        tier: PriorityActionTier::Urgent,
+        ignoring: false,
+        last_queued_time: None,
+    }
+}
+
+/// Creates a [`PlayerAction::Panic`] priority action for when the health stays critical during an
+/// elite boss.
+///
+/// The action goes to town if `mode` is [`PanicMode::GoToTown`] and changes channel otherwise.
+#[inline]
+fn elite_boss_low_health_panic_priority_action(mode: PanicMode) -> PriorityAction {
+    let to = match mode {
+        PanicMode::GoToTown => PanicTo::Town,
+        PanicMode::CycleChannel | PanicMode::Defensive => PanicTo::Channel,
+    };
+
+    PriorityAction {
+        condition: Condition(Box::new(|context, player, last_queued_time| {
+            if !at_least_millis_passed_since(last_queued_time, 15000) {
+                return ConditionResult::Skip;
+            }
+            if let Minimap::Idle(idle) = context.minimap
+                && idle.has_elite_boss
+                && player.is_health_critical()
+            {
+                ConditionResult::Queue
+            } else {
+                ConditionResult::Skip
+            }
+        })),
+        condition_kind: None,
+        dedupe_key: None,
+        inner: RotatorAction::Single(PlayerAction::Panic(PlayerActionPanic { to })),
+        queue_to_front: true,
+        tier: PriorityActionTier::Urgent,
+        ignoring: false,
+        last_queued_time: None,
+    }
+}
+
+/// Creates a [`RotatorAction::Linked`] priority action to press all consumable `keys` in order
+/// once when an elite boss appears.
+///
+/// The action is queued only on the rising edge of
+/// [`crate::minimap::MinimapIdle::has_elite_boss`] so it is not repeated while the same elite
+/// boss is still alive.
+fn elite_boss_consumable_priority_action(keys: &[KeyBinding]) -> PriorityAction {
+    debug_assert!(!keys.is_empty());
+
+    let linked = keys
+        .iter()
+        .copied()
+        .rev()
+        .fold(None, |next, key| {
+            Some(Box::new(LinkedAction {
+                inner: PlayerAction::Key(PlayerActionKey {
+                    key,
+                    link_key: None,
+                    count: 1,
+                    position: None,
+                    strict_position: false,
+                    direction: ActionKeyDirection::Any,
+                    require_direction: None,
+                    require_buff: None,
+                    probability: None,
+                    with: ActionKeyWith::Any,
+                    use_while_falling: false,
+                    wait_before_use_ticks: 0,
+                    wait_before_use_ticks_random_range: 0,
+                    wait_after_use_ticks: 5,
+                    wait_after_use_ticks_random_range: 0,
+                    linked_wait_ticks: 0,
+                }),
+                fire_during_move: false,
+                next,
+            }))
+        })
+        .unwrap();
+    let had_elite_boss = Cell::new(false);
+
+    PriorityAction {
+        condition: Condition(Box::new(move |context, _, _| {
+            let Minimap::Idle(idle) = context.minimap else {
+                return ConditionResult::Skip;
+            };
+            let had = had_elite_boss.replace(idle.has_elite_boss);
+            if idle.has_elite_boss && !had {
+                ConditionResult::Queue
+            } else {
+                ConditionResult::Skip
+            }
+        })),
+        condition_kind: None,
+        dedupe_key: None,
+        inner: RotatorAction::Linked(*linked),
+        queue_to_front: true,
+        tier: PriorityActionTier::Urgent,
+        ignoring: false,
+        last_queued_time: None,
+    }
+}
+
+/// Creates a [`PlayerAction::Key`] action for when a [`RotatorMode::StartToEndThenReverse`]
+/// rotation reverses.
+#[inline]
+fn turn_around_action(key: KeyBinding) -> PlayerAction {
+    PlayerAction::Key(PlayerActionKey {
+        key,
+        link_key: None,
+        count: 1,
+        position: None,
+        strict_position: false,
+        direction: ActionKeyDirection::Any,
+        require_direction: None,
+        require_buff: None,
+        probability: None,
+        with: ActionKeyWith::Any,
+        use_while_falling: false,
+        wait_before_use_ticks: 0,
+        wait_before_use_ticks_random_range: 0,
+        wait_after_use_ticks: 0,
+        wait_after_use_ticks_random_range: 0,
+        linked_wait_ticks: 0,
+    })
+}
+
+/// Creates a [`PlayerAction::Panic`] to channel action for when the map is empty in auto mobbing.
+///
+/// The condition always skips because the action is queued manually by
+/// [`Rotator::rotate_auto_mobbing`] once the empty map timeout is reached.
+#[inline]
+fn auto_mob_empty_map_change_channel_priority_action() -> PriorityAction {
+    PriorityAction {
+        condition: Condition(Box::new(|_, _, _| ConditionResult::Skip)),
+        condition_kind: None,
+        dedupe_key: None,
+        inner: RotatorAction::Single(PlayerAction::Panic(PlayerActionPanic {
+            to: PanicTo::Channel,
+        })),
+        queue_to_front: true,
+        tier: PriorityActionTier::Urgent,
+        ignoring: false,
+        last_queued_time: None,
+    }
+}
+
+#[inline]
+fn at_least_millis_passed_since(last_queued_time: Option<Instant>, millis: u128) -> bool {
+    last_queued_time
+        .map(|instant| Instant::now().duration_since(instant).as_millis() >= millis)
+        .unwrap_or(true)
+}
+
+#[inline]
+fn should_queue_fixed_action(
+    context: &Context,
+    last_queued_time: Option<Instant>,
+    condition: ActionCondition,
+) -> bool {
+    let millis_should_passed = match condition {
+        ActionCondition::EveryMillis(millis) => millis as u128,
+        ActionCondition::ErdaShowerOffCooldown => COOLDOWN_BETWEEN_QUEUE_MILLIS,
+        ActionCondition::Linked | ActionCondition::Any => unreachable!(),
+    };
+    if !at_least_millis_passed_since(last_queued_time, millis_should_passed) {
+        return false;
     }
     if matches!(condition, ActionCondition::ErdaShowerOffCooldown)
         && !matches!(context.skills[SkillKind::ErdaShower], Skill::Idle(_, _))
@@ -1013,13 +2559,20 @@ fn should_queue_fixed_action(
 mod tests {
     use std::{
         assert_matches::assert_matches,
+        sync::LazyLock,
         time::{Duration, Instant},
     };
 
     use opencv::core::{Point, Vec4b};
 
     use super::*;
-    use crate::{Position, buff::BuffKind, minimap::MinimapIdle, skill::SkillKind};
+    use crate::{
+        Position,
+        buff::BuffKind,
+        minimap::MinimapIdle,
+        pathing::{Platform, find_neighbors},
+        skill::SkillKind,
+    };
 
     const NORMAL_ACTION: Action = Action::Move(ActionMove {
         position: Position {
@@ -1029,7 +2582,11 @@ mod tests {
             allow_adjusting: false,
         },
         condition: ActionCondition::Any,
+        probability: None,
         wait_after_move_millis: 0,
+        wait_after_move_millis_random_range: 0,
+        linked_wait_millis: 0,
+        attack_on_arrival: None,
     });
     const PRIORITY_ACTION: Action = Action::Move(ActionMove {
         position: Position {
@@ -1039,9 +2596,76 @@ mod tests {
             allow_adjusting: false,
         },
         condition: ActionCondition::ErdaShowerOffCooldown,
+        probability: None,
         wait_after_move_millis: 0,
+        wait_after_move_millis_random_range: 0,
+        linked_wait_millis: 0,
+        attack_on_arrival: None,
     });
 
+    /// Creates [`RotatorBuildArgs`] for `actions` with everything else disabled.
+    fn build_args(actions: &[Action]) -> RotatorBuildArgs<'_> {
+        static FAMILIAR_SWAPPABLE_RARITIES: LazyLock<HashSet<FamiliarRarity>> =
+            LazyLock::new(HashSet::default);
+
+        RotatorBuildArgs {
+            mode: RotatorMode::default(),
+            actions,
+            buffs: &[],
+            buff_all_keys: &[],
+            buff_all_spacing_millis: 0,
+            timer_only_buffs: &[],
+            timer_only_buff_recast_millis: 0,
+            buff_combat_lull_millis: None,
+            familiar_essence_key: KeyBinding::default(),
+            familiar_swappable_slots: SwappableFamiliars::default(),
+            familiar_swappable_rarities: &FAMILIAR_SWAPPABLE_RARITIES,
+            familiar_swap_check_millis: 0,
+            familiar_hover_settle_ticks: 0,
+            familiar_no_swappable_cards_outcome: NoSwappableCardsOutcome::default(),
+            familiar_swap_safe_position: None,
+            familiar_essence_check_millis: 0,
+            pickup_key: KeyBinding::default(),
+            collect_drops_millis: 0,
+            panic_mode: PanicMode::default(),
+            panic_defensive_key: KeyBinding::default(),
+            panic_other_player_filter: OtherPlayerFilter::default(),
+            enable_panic_mode: false,
+            enable_rune_solving: false,
+            enable_focus_rune_solving: false,
+            auto_mob_priority_grace_millis: 0,
+            enable_rune_solving_ignore_buff: false,
+            enable_change_channel_on_elite_boss_appear: false,
+            enable_panic_on_elite_boss_low_health: false,
+            elite_boss_consumable_keys: &[],
+            enable_familiars_swapping: false,
+            enable_collect_drops: false,
+            interact_key: KeyBinding::default(),
+            loot_object_check_millis: 0,
+            enable_loot_objects: false,
+            enable_reset_normal_actions_on_erda: false,
+            normal_actions_merge_distance: 0,
+            normal_actions_turn_around_key: None,
+            home_position: Position::default(),
+            home_idle_millis: 0,
+            actions_offset: Point::default(),
+        }
+    }
+
+    /// Creates an always queued [`PriorityAction`] with neutral defaults.
+    fn queued_priority_action(inner: RotatorAction, queue_to_front: bool) -> PriorityAction {
+        PriorityAction {
+            condition: Condition(Box::new(|_, _, _| ConditionResult::Queue)),
+            condition_kind: None,
+            dedupe_key: None,
+            inner,
+            queue_to_front,
+            tier: PriorityActionTier::Urgent,
+            ignoring: false,
+            last_queued_time: None,
+        }
+    }
+
     #[test]
     fn rotator_at_least_millis_passed_since() {
         let now = Instant::now();
@@ -1104,19 +2728,9 @@ mod tests {
         let actions = vec![NORMAL_ACTION, NORMAL_ACTION, PRIORITY_ACTION];
         let buffs = vec![(BuffKind::Rune, KeyBinding::default()); 4];
         let args = RotatorBuildArgs {
-            mode: RotatorMode::default(),
-            actions: &actions,
             buffs: &buffs,
-            familiar_essence_key: KeyBinding::default(),
-            familiar_swappable_slots: SwappableFamiliars::default(),
-            familiar_swappable_rarities: &HashSet::default(),
-            familiar_swap_check_millis: 0,
-            panic_mode: PanicMode::default(),
-            enable_panic_mode: false,
             enable_rune_solving: true,
-            enable_change_channel_on_elite_boss_appear: false,
-            enable_familiars_swapping: false,
-            enable_reset_normal_actions_on_erda: false,
+            ..build_args(&actions)
         };
 
         rotator.build_actions(args);
@@ -1124,6 +2738,257 @@ mod tests {
         assert_eq!(rotator.normal_actions.len(), 2);
     }
 
+    #[test]
+    fn rotator_auto_mob_key_count() {
+        assert_eq!(auto_mob_key_count(3, false, 1), 3);
+        assert_eq!(auto_mob_key_count(0, false, 5), 1);
+        assert_eq!(auto_mob_key_count(3, true, 0), 1);
+        assert_eq!(auto_mob_key_count(3, true, 2), 2);
+        assert_eq!(auto_mob_key_count(3, true, 10), 3);
+    }
+
+    #[test]
+    fn rotator_auto_mob_retain_center_points() {
+        let bbox = Rect::new(0, 0, 100, 40);
+        let mut points = vec![
+            Point::new(5, 20),
+            Point::new(55, 20),
+            Point::new(95, 20),
+            Point::new(40, 25),
+            Point::new(20, 10),
+        ];
+
+        auto_mob_retain_center_points(&mut points, bbox);
+        assert_eq!(
+            points,
+            vec![Point::new(55, 20), Point::new(40, 25), Point::new(20, 10)]
+        );
+
+        let mut points = vec![];
+        auto_mob_retain_center_points(&mut points, bbox);
+        assert!(points.is_empty());
+    }
+
+    #[test]
+    fn rotator_offset_action_position() {
+        let offset = Point::new(2, -3);
+
+        assert_matches!(
+            offset_action_position(NORMAL_ACTION, offset),
+            Action::Move(ActionMove {
+                position: Position { x: 2, y: -3, .. },
+                ..
+            })
+        );
+        assert_matches!(
+            offset_action_position(Action::Key(ActionKey::default()), offset),
+            Action::Key(ActionKey { position: None, .. })
+        );
+        assert_matches!(
+            offset_action_position(
+                Action::Key(ActionKey {
+                    position: Some(Position {
+                        x: 10,
+                        y: 10,
+                        ..Position::default()
+                    }),
+                    ..ActionKey::default()
+                }),
+                offset
+            ),
+            Action::Key(ActionKey {
+                position: Some(Position { x: 12, y: 7, .. }),
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn merge_nearby_action_skips_move_and_uses_key_in_place() {
+        let position = Position {
+            x: 10,
+            x_random_range: 0,
+            y: 10,
+            allow_adjusting: false,
+        };
+        let move_action = PlayerAction::Move(PlayerActionMove {
+            position,
+            probability: None,
+            wait_after_move_ticks: 0,
+            wait_after_move_ticks_random_range: 0,
+            attack_on_arrival: None,
+        });
+        let key_action = PlayerActionKey {
+            key: KeyBinding::A,
+            link_key: None,
+            count: 1,
+            position: Some(position),
+            strict_position: false,
+            direction: ActionKeyDirection::Any,
+            require_direction: None,
+            require_buff: None,
+            probability: None,
+            with: ActionKeyWith::Any,
+            use_while_falling: false,
+            wait_before_use_ticks: 0,
+            wait_before_use_ticks_random_range: 0,
+            wait_after_use_ticks: 0,
+            wait_after_use_ticks_random_range: 0,
+            linked_wait_ticks: 0,
+        };
+        let near = Some(Point::new(12, 11));
+        let far = Some(Point::new(30, 10));
+
+        assert_matches!(merge_nearby_action(move_action, near, 5), None);
+        assert_matches!(merge_nearby_action(move_action, far, 5), Some(_));
+        assert_matches!(merge_nearby_action(move_action, near, 0), Some(_));
+        assert_matches!(
+            merge_nearby_action(PlayerAction::Key(key_action), near, 5),
+            Some(PlayerAction::Key(PlayerActionKey { position: None, .. }))
+        );
+        assert_matches!(
+            merge_nearby_action(
+                PlayerAction::Key(PlayerActionKey {
+                    strict_position: true,
+                    ..key_action
+                }),
+                near,
+                5
+            ),
+            Some(PlayerAction::Key(PlayerActionKey {
+                position: Some(_),
+                ..
+            }))
+        );
+    }
+
+    #[test]
+    fn rotator_polygon_contains_point_and_bound_at() {
+        // L-shaped polygon missing the top right corner
+        let polygon = vec![
+            Point::new(0, 0),
+            Point::new(40, 0),
+            Point::new(40, 20),
+            Point::new(100, 20),
+            Point::new(100, 40),
+            Point::new(0, 40),
+        ];
+
+        assert!(polygon_contains_point(&polygon, Point::new(20, 10)));
+        assert!(polygon_contains_point(&polygon, Point::new(70, 30)));
+        assert!(!polygon_contains_point(&polygon, Point::new(70, 10)));
+        assert!(!polygon_contains_point(&polygon, Point::new(120, 30)));
+
+        assert_eq!(
+            polygon_bound_at(&polygon, 10),
+            Some(Bound {
+                x: 0,
+                y: 0,
+                width: 40,
+                height: 40
+            })
+        );
+        assert_eq!(
+            polygon_bound_at(&polygon, 30),
+            Some(Bound {
+                x: 0,
+                y: 0,
+                width: 100,
+                height: 40
+            })
+        );
+        assert_eq!(polygon_bound_at(&[], 30), None);
+    }
+
+    #[test]
+    fn rotator_auto_mobbing_retain_preferred_platform_points() {
+        let platforms = find_neighbors(
+            &[Platform::new(0..50, 10), Platform::new(0..50, 30)],
+            25,
+            7,
+            41,
+        );
+        let lower = Point::new(10, 88);
+        let upper = Point::new(20, 68);
+        let now = Instant::now();
+        let mut rotator = Rotator::default();
+
+        // Prefers the platform with the most mobs
+        let mut points = vec![lower, upper, upper];
+        assert!(rotator.auto_mob_retain_preferred_platform_points(
+            &mut points,
+            &platforms,
+            100,
+            5000,
+            now
+        ));
+        assert_eq!(points, vec![upper, upper]);
+
+        // Stays while the preferred platform has been empty for less than the dwell time
+        let mut points = vec![lower];
+        assert!(!rotator.auto_mob_retain_preferred_platform_points(
+            &mut points,
+            &platforms,
+            100,
+            5000,
+            now + Duration::from_millis(1000)
+        ));
+
+        // Moves to another platform after the dwell time
+        let mut points = vec![lower];
+        assert!(rotator.auto_mob_retain_preferred_platform_points(
+            &mut points,
+            &platforms,
+            100,
+            5000,
+            now + Duration::from_millis(6000)
+        ));
+        assert_eq!(points, vec![lower]);
+        assert_matches!(rotator.auto_mob_preferred_platform, Some((0, _)));
+    }
+
+    #[test]
+    fn rotator_auto_mobbing_retarget_interval() {
+        let mut rotator = Rotator::default();
+        let mut player = PlayerState::default();
+        player.last_known_pos = Some(Point::default());
+        let mut context = Context::new(None, None);
+        context.minimap = Minimap::Idle(MinimapIdle::default());
+        let auto_mobbing = AutoMobbing {
+            retarget_interval_millis: 1000,
+            ..AutoMobbing::default()
+        };
+
+        rotator.rotate_auto_mobbing(&context, &mut player, auto_mobbing);
+        assert!(rotator.auto_mob_retarget_instant.is_some());
+        assert!(rotator.auto_mob_task.is_none());
+        assert!(!player.has_normal_action());
+    }
+
+    #[test]
+    fn rotator_build_actions_auto_mobbing_empty_map_change_channel() {
+        let mut rotator = Rotator::default();
+        let actions = vec![NORMAL_ACTION, PRIORITY_ACTION];
+        let args = RotatorBuildArgs {
+            mode: RotatorMode::AutoMobbing(AutoMobbing {
+                empty_map_action: EmptyMapAction::ChangeChannel,
+                ..AutoMobbing::default()
+            }),
+            ..build_args(&actions)
+        };
+
+        rotator.build_actions(args);
+        let id = rotator.auto_mob_empty_map_action_id.unwrap();
+        assert_eq!(rotator.priority_actions.len(), 2);
+        assert_matches!(
+            rotator.priority_actions.get(&id).unwrap().inner,
+            RotatorAction::Single(PlayerAction::Panic(PlayerActionPanic {
+                to: PanicTo::Channel
+            }))
+        );
+        assert!(rotator.normal_actions.is_empty());
+    }
+
     #[test]
     fn rotator_rotate_action_start_to_end_then_reverse() {
         let mut rotator = Rotator::default();
@@ -1167,12 +3032,136 @@ mod tests {
     }
 
     #[test]
-    fn rotator_rotate_action_start_to_end() {
+    fn rotator_rotate_action_start_to_end_then_reverse_turn_around() {
         let mut rotator = Rotator::default();
         let mut player = PlayerState::default();
         let context = Context::new(None, None);
-        rotator.normal_rotate_mode = RotatorMode::StartToEnd;
-        for i in 0..2 {
+        rotator.normal_rotate_mode = RotatorMode::StartToEndThenReverse;
+        rotator.normal_actions_turn_around = Some((3, turn_around_action(KeyBinding::A)));
+        for i in 0..3 {
+            rotator
+                .normal_actions
+                .push((i, RotatorAction::Single(NORMAL_ACTION.into())));
+        }
+
+        for expected in [0, 1, 2, 3, 1, 0, 3, 1] {
+            player.clear_actions_aborted();
+            rotator.rotate_action(&context, &mut player);
+            assert_eq!(player.normal_action_id(), Some(expected));
+            if expected == 3 {
+                assert_matches!(
+                    player.normal_action(),
+                    Some(PlayerAction::Key(PlayerActionKey {
+                        key: KeyBinding::A,
+                        ..
+                    }))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rotator_rotate_action_skip_when_transitioning() {
+        let mut rotator = Rotator::default();
+        let mut player = PlayerState::default();
+        let mut context = Context::new(None, None);
+        context.transitioning = true;
+        rotator.normal_rotate_mode = RotatorMode::StartToEnd;
+        rotator
+            .normal_actions
+            .push((0, RotatorAction::Single(NORMAL_ACTION.into())));
+
+        rotator.rotate_action(&context, &mut player);
+        assert!(!player.has_normal_action());
+
+        context.transitioning = false;
+        rotator.rotate_action(&context, &mut player);
+        assert!(player.has_normal_action());
+    }
+
+    #[test]
+    fn rotator_rotate_action_skip_when_map_loading() {
+        let mut rotator = Rotator::default();
+        let mut player = PlayerState::default();
+        let mut context = Context::new(None, None);
+        context.map_loading = true;
+        rotator.normal_rotate_mode = RotatorMode::StartToEnd;
+        rotator
+            .normal_actions
+            .push((0, RotatorAction::Single(NORMAL_ACTION.into())));
+
+        rotator.rotate_action(&context, &mut player);
+        assert!(!player.has_normal_action());
+
+        context.map_loading = false;
+        rotator.rotate_action(&context, &mut player);
+        assert!(player.has_normal_action());
+    }
+
+    #[test]
+    fn rotator_rotate_action_skip_when_capture_invalid() {
+        let mut rotator = Rotator::default();
+        let mut player = PlayerState::default();
+        let mut context = Context::new(None, None);
+        context.capture_invalid = true;
+        rotator.normal_rotate_mode = RotatorMode::StartToEnd;
+        rotator
+            .normal_actions
+            .push((0, RotatorAction::Single(NORMAL_ACTION.into())));
+
+        rotator.rotate_action(&context, &mut player);
+        assert!(!player.has_normal_action());
+
+        context.capture_invalid = false;
+        rotator.rotate_action(&context, &mut player);
+        assert!(player.has_normal_action());
+    }
+
+    #[test]
+    fn rotator_rotate_action_skip_failed_probability() {
+        let mut rotator = Rotator::default();
+        let mut player = PlayerState::default();
+        let context = Context::new(None, None);
+        let Action::Move(action) = NORMAL_ACTION else {
+            unreachable!()
+        };
+        rotator.normal_rotate_mode = RotatorMode::StartToEnd;
+        rotator.normal_actions.push((
+            0,
+            RotatorAction::Single(
+                Action::Move(ActionMove {
+                    probability: Some(0.0),
+                    ..action
+                })
+                .into(),
+            ),
+        ));
+        rotator.normal_actions.push((
+            1,
+            RotatorAction::Single(
+                Action::Move(ActionMove {
+                    probability: Some(1.0),
+                    ..action
+                })
+                .into(),
+            ),
+        ));
+
+        rotator.rotate_action(&context, &mut player);
+        assert!(!player.has_normal_action());
+        assert_eq!(rotator.normal_index, 1);
+
+        rotator.rotate_action(&context, &mut player);
+        assert_eq!(player.normal_action_id(), Some(1));
+    }
+
+    #[test]
+    fn rotator_rotate_action_start_to_end() {
+        let mut rotator = Rotator::default();
+        let mut player = PlayerState::default();
+        let context = Context::new(None, None);
+        rotator.normal_rotate_mode = RotatorMode::StartToEnd;
+        for i in 0..2 {
             rotator
                 .normal_actions
                 .push((i, RotatorAction::Single(NORMAL_ACTION.into())));
@@ -1191,12 +3180,300 @@ mod tests {
         assert_eq!(rotator.normal_index, 0);
     }
 
+    #[test]
+    fn rotator_rotate_action_shuffled_cycle() {
+        let mut rotator = Rotator::default();
+        let mut player = PlayerState::default();
+        let context = Context::new(None, None);
+        rotator.normal_rotate_mode = RotatorMode::ShuffledCycle;
+        for i in 0..5 {
+            rotator
+                .normal_actions
+                .push((i, RotatorAction::Single(NORMAL_ACTION.into())));
+        }
+
+        for _ in 0..2 {
+            let mut ids = HashSet::new();
+            for _ in 0..5 {
+                rotator.rotate_action(&context, &mut player);
+                assert!(player.has_normal_action());
+                ids.insert(player.normal_action_id().unwrap());
+                player.clear_actions_aborted();
+            }
+            // Each action is rotated exactly once per cycle
+            assert_eq!(ids.len(), 5);
+            assert_eq!(rotator.normal_index, 0);
+        }
+    }
+
+    #[test]
+    fn rotator_rotate_action_require_direction() {
+        let mut rotator = Rotator::default();
+        let mut player = PlayerState::default();
+        let context = Context::new(None, None);
+        let action = Action::Key(ActionKey {
+            require_direction: Some(ActionKeyDirection::Left),
+            ..ActionKey::default()
+        });
+        let actions = [action, NORMAL_ACTION];
+        let args = RotatorBuildArgs {
+            mode: RotatorMode::StartToEnd,
+            ..build_args(&actions)
+        };
+        rotator.build_actions(args);
+
+        // skipped because the player is not facing left
+        rotator.rotate_action(&context, &mut player);
+        assert!(!player.has_normal_action());
+        assert_eq!(rotator.normal_index, 1);
+
+        rotator.rotate_action(&context, &mut player);
+        assert_matches!(player.normal_action(), Some(PlayerAction::Move(_)));
+    }
+
+    #[test]
+    fn rotator_priority_action_require_buff() {
+        let mut context = Context::new(None, None);
+        let mut player = PlayerState::default();
+        let action = Action::Key(ActionKey {
+            require_buff: Some(BuffKindData::SayramElixir),
+            condition: ActionCondition::EveryMillis(0),
+            ..ActionKey::default()
+        });
+        let (action, _) = rotator_action(action, 0, &[action]);
+        let action = priority_action(action, ActionCondition::EveryMillis(0), false);
+
+        // skipped because the buff is not active
+        assert!(matches!(
+            (action.condition.0)(&context, &mut player, None),
+            ConditionResult::Skip
+        ));
+
+        context.buffs[BuffKind::SayramElixir] = Buff::Yes;
+        assert!(matches!(
+            (action.condition.0)(&context, &mut player, None),
+            ConditionResult::Queue
+        ));
+    }
+
+    #[test]
+    fn rotator_build_actions_ignore_undetectable_required_buff() {
+        let mut rotator = Rotator::default();
+        let mut player = PlayerState::default();
+        let context = Context::new(None, None);
+        let action = Action::Key(ActionKey {
+            require_buff: Some(BuffKindData::SayramElixir),
+            ..ActionKey::default()
+        });
+        let actions = [action];
+
+        // used because the required buff is not enabled
+        let args = RotatorBuildArgs {
+            mode: RotatorMode::StartToEnd,
+            ..build_args(&actions)
+        };
+        rotator.build_actions(args);
+        rotator.rotate_action(&context, &mut player);
+        assert!(player.has_normal_action());
+
+        // skipped because the required buff is enabled but not active
+        player.clear_actions_aborted();
+        let buffs = [(BuffKind::SayramElixir, KeyBinding::A)];
+        let args = RotatorBuildArgs {
+            mode: RotatorMode::StartToEnd,
+            buffs: &buffs,
+            ..build_args(&actions)
+        };
+        rotator.build_actions(args);
+        rotator.rotate_action(&context, &mut player);
+        assert!(!player.has_normal_action());
+    }
+
+    #[test]
+    fn solve_rune_priority_action_ignore_buff() {
+        let mut player = PlayerState::default();
+        let mut minimap = MinimapIdle::default();
+        minimap
+            .runes
+            .set_value(Array::from_iter([Point::default()]));
+        let mut context = Context::new(None, None);
+        context.minimap = Minimap::Idle(minimap);
+        context.buffs[BuffKind::Rune] = Buff::Yes;
+
+        let action = solve_rune_priority_action(false);
+        assert!(matches!(
+            (action.condition.0)(&context, &mut player, None),
+            ConditionResult::Skip
+        ));
+
+        let action = solve_rune_priority_action(true);
+        assert!(matches!(
+            (action.condition.0)(&context, &mut player, None),
+            ConditionResult::Queue
+        ));
+    }
+
+    #[test]
+    fn rotator_build_actions_buff_all_keeps_uncovered_buffs() {
+        let mut rotator = Rotator::default();
+        let buffs = [
+            (BuffKind::SayramElixir, KeyBinding::A),
+            (BuffKind::LegionLuck, KeyBinding::C),
+        ];
+        let buff_all_keys = [KeyBinding::A, KeyBinding::B];
+        let args = RotatorBuildArgs {
+            buffs: &buffs,
+            buff_all_keys: &buff_all_keys,
+            ..build_args(&[])
+        };
+        rotator.build_actions(args);
+
+        assert_eq!(rotator.priority_actions.len(), 2);
+        assert!(rotator.priority_actions.values().any(|action| matches!(
+            action.inner,
+            RotatorAction::Linked(LinkedAction {
+                inner: PlayerAction::Key(PlayerActionKey {
+                    key: KeyBinding::A,
+                    ..
+                }),
+                ..
+            })
+        )));
+        assert!(rotator.priority_actions.values().any(|action| matches!(
+            action.inner,
+            RotatorAction::Single(PlayerAction::Key(PlayerActionKey {
+                key: KeyBinding::C,
+                ..
+            }))
+        )));
+    }
+
+    #[test]
+    fn buff_all_priority_action_any_buff_missing() {
+        let mut player = PlayerState::default();
+        let mut context = Context::new(None, None);
+        context.minimap = Minimap::Idle(MinimapIdle::default());
+        context.buffs[BuffKind::SayramElixir] = Buff::Yes;
+        context.buffs[BuffKind::LegionLuck] = Buff::Yes;
+
+        let action = buff_all_priority_action(
+            vec![BuffKind::SayramElixir, BuffKind::LegionLuck],
+            &[KeyBinding::A, KeyBinding::B],
+            5,
+            None,
+            None,
+        );
+        assert_matches!(
+            &action.inner,
+            RotatorAction::Linked(LinkedAction {
+                inner: PlayerAction::Key(PlayerActionKey {
+                    key: KeyBinding::A,
+                    wait_after_use_ticks: 5,
+                    ..
+                }),
+                next: Some(_),
+                ..
+            })
+        );
+        assert!(matches!(
+            (action.condition.0)(&context, &mut player, None),
+            ConditionResult::Skip
+        ));
+
+        context.buffs[BuffKind::LegionLuck] = Buff::No;
+        assert!(matches!(
+            (action.condition.0)(&context, &mut player, None),
+            ConditionResult::Queue
+        ));
+    }
+
+    #[test]
+    fn buff_priority_action_timer_only_recast() {
+        let mut player = PlayerState::default();
+        let mut context = Context::new(None, None);
+        context.minimap = Minimap::Idle(MinimapIdle::default());
+        context.buffs[BuffKind::SayramElixir] = Buff::Yes;
+
+        let action =
+            buff_priority_action(BuffKind::SayramElixir, KeyBinding::A, Some(60_000), None);
+        assert!(matches!(
+            (action.condition.0)(&context, &mut player, None),
+            ConditionResult::Queue
+        ));
+        assert!(matches!(
+            (action.condition.0)(
+                &context,
+                &mut player,
+                Some(Instant::now() - Duration::from_secs(30))
+            ),
+            ConditionResult::Skip
+        ));
+        assert!(matches!(
+            (action.condition.0)(
+                &context,
+                &mut player,
+                Some(Instant::now() - Duration::from_secs(60))
+            ),
+            ConditionResult::Queue
+        ));
+    }
+
+    #[test]
+    fn buff_priority_action_skip_in_combat() {
+        let mut player = PlayerState::default();
+        let mut context = Context::new(None, None);
+        context.minimap = Minimap::Idle(MinimapIdle::default());
+        context.buffs[BuffKind::SayramElixir] = Buff::No;
+
+        let action = buff_priority_action(BuffKind::SayramElixir, KeyBinding::A, None, Some(2000));
+        player.set_last_attack_instant(Some(Instant::now()));
+        assert!(matches!(
+            (action.condition.0)(&context, &mut player, None),
+            ConditionResult::Skip
+        ));
+
+        player.set_last_attack_instant(Some(Instant::now() - Duration::from_secs(3)));
+        assert!(matches!(
+            (action.condition.0)(&context, &mut player, None),
+            ConditionResult::Queue
+        ));
+    }
+
+    #[test]
+    fn should_defer_buff_in_combat_max_deferral() {
+        let mut player = PlayerState::default();
+        let deferred_since = Cell::new(None);
+
+        player.set_last_attack_instant(Some(Instant::now()));
+        assert!(should_defer_buff_in_combat(
+            &player,
+            Some(2000),
+            &deferred_since
+        ));
+        assert!(deferred_since.get().is_some());
+        assert!(!should_defer_buff_in_combat(&player, None, &deferred_since));
+        assert!(deferred_since.get().is_none());
+
+        // continuous combat no longer defers once the buff has been missing for too long
+        deferred_since.set(Some(
+            Instant::now() - Duration::from_millis(MAX_BUFF_COMBAT_DEFER_MILLIS as u64),
+        ));
+        assert!(!should_defer_buff_in_combat(
+            &player,
+            Some(2000),
+            &deferred_since
+        ));
+        assert!(deferred_since.get().is_none());
+    }
+
     #[test]
     fn rotator_priority_action_queue() {
         let mut rotator = Rotator::default();
         let mut player = PlayerState::default();
         let mut minimap = MinimapIdle::default();
-        minimap.rune.set_value(Point::default());
+        minimap
+            .runes
+            .set_value(Array::from_iter([Point::default()]));
         let mut context = Context::new(None, None);
         context.minimap = Minimap::Idle(minimap);
         context.buffs[BuffKind::Rune] = Buff::No;
@@ -1210,11 +3487,7 @@ mod tests {
                         ConditionResult::Skip
                     }
                 })),
-                condition_kind: None,
-                inner: RotatorAction::Single(PlayerAction::SolveRune),
-                queue_to_front: true,
-                ignoring: false,
-                last_queued_time: None,
+                ..queued_priority_action(RotatorAction::Single(PlayerAction::SolveRune), true)
             },
         );
 
@@ -1223,6 +3496,32 @@ mod tests {
         assert_eq!(player.priority_action_id(), Some(55));
     }
 
+    #[test]
+    fn rotator_priority_action_dedupe_key() {
+        let mut rotator = Rotator::default();
+        let mut player = PlayerState::default();
+        let mut context = Context::new(None, None);
+        context.minimap = Minimap::Idle(MinimapIdle::default());
+        context.buffs[BuffKind::SayramElixir] = Buff::No;
+        rotator.priority_actions.insert(
+            1,
+            buff_priority_action(BuffKind::SayramElixir, KeyBinding::A, None, None),
+        );
+        rotator.priority_actions.insert(
+            2,
+            buff_priority_action(BuffKind::SayramElixir, KeyBinding::B, None, None),
+        );
+
+        rotator.rotate_priority_actions(&context, &mut player);
+        assert_eq!(rotator.priority_actions_queue.len(), 1);
+
+        // Still ignores after the queued action is moved to the player
+        rotator.rotate_priority_actions_queue(&context, &mut player);
+        assert!(player.priority_action_id().is_some());
+        rotator.rotate_priority_actions(&context, &mut player);
+        assert!(rotator.priority_actions_queue.is_empty());
+    }
+
     #[test]
     fn rotator_priority_action_queue_to_front() {
         let mut rotator = Rotator::default();
@@ -1231,25 +3530,11 @@ mod tests {
         // queue 2 non-front priority actions
         rotator.priority_actions.insert(
             2,
-            PriorityAction {
-                condition: Condition(Box::new(|_, _, _| ConditionResult::Queue)),
-                condition_kind: None,
-                inner: RotatorAction::Single(NORMAL_ACTION.into()),
-                queue_to_front: false,
-                ignoring: false,
-                last_queued_time: None,
-            },
+            queued_priority_action(RotatorAction::Single(NORMAL_ACTION.into()), false),
         );
         rotator.priority_actions.insert(
             3,
-            PriorityAction {
-                condition: Condition(Box::new(|_, _, _| ConditionResult::Queue)),
-                condition_kind: None,
-                inner: RotatorAction::Single(NORMAL_ACTION.into()),
-                queue_to_front: false,
-                ignoring: false,
-                last_queued_time: None,
-            },
+            queued_priority_action(RotatorAction::Single(NORMAL_ACTION.into()), false),
         );
 
         rotator.rotate_action(&context, &mut player);
@@ -1259,14 +3544,7 @@ mod tests {
         // add 1 front priority action
         rotator.priority_actions.insert(
             4,
-            PriorityAction {
-                condition: Condition(Box::new(|_, _, _| ConditionResult::Queue)),
-                condition_kind: None,
-                inner: RotatorAction::Single(NORMAL_ACTION.into()),
-                queue_to_front: true,
-                ignoring: false,
-                last_queued_time: None,
-            },
+            queued_priority_action(RotatorAction::Single(NORMAL_ACTION.into()), true),
         );
 
         // non-front priority action get replaced
@@ -1280,14 +3558,7 @@ mod tests {
         // add another front priority action
         rotator.priority_actions.insert(
             5,
-            PriorityAction {
-                condition: Condition(Box::new(|_, _, _| ConditionResult::Queue)),
-                condition_kind: None,
-                inner: RotatorAction::Single(NORMAL_ACTION.into()),
-                queue_to_front: true,
-                ignoring: false,
-                last_queued_time: None,
-            },
+            queued_priority_action(RotatorAction::Single(NORMAL_ACTION.into()), true),
         );
 
         // queued front priority action cannot be replaced
@@ -1301,26 +3572,437 @@ mod tests {
     }
 
     #[test]
-    fn rotator_priority_linked_action() {
+    fn rotator_priority_action_queue_focus_rune() {
+        let mut rotator = Rotator::default();
+        let mut player = PlayerState::default();
+        let context = Context::new(None, None);
+        rotator.priority_actions_focus_rune = true;
+        rotator.priority_actions.insert(
+            2,
+            queued_priority_action(RotatorAction::Single(NORMAL_ACTION.into()), true),
+        );
+        player.set_priority_action(1, PlayerAction::SolveRune);
+
+        // front priority action is held off while solving rune
+        rotator.rotate_action(&context, &mut player);
+        assert_eq!(rotator.priority_actions_queue, VecDeque::from_iter([2]));
+        assert_matches!(player.priority_action(), Some(PlayerAction::SolveRune));
+
+        // rotates once rune action is completed
+        player.clear_actions_aborted();
+        rotator.rotate_action(&context, &mut player);
+        assert!(rotator.priority_actions_queue.is_empty());
+        assert_eq!(player.priority_action_id(), Some(2));
+    }
+
+    #[test]
+    fn rotator_priority_action_queue_auto_mob_grace() {
         let mut rotator = Rotator::default();
         let mut player = PlayerState::default();
         let context = Context::new(None, None);
+        rotator.priority_actions_auto_mob_grace_millis = 1000;
         rotator.priority_actions.insert(
             2,
             PriorityAction {
-                condition: Condition(Box::new(|_, _, _| ConditionResult::Queue)),
-                condition_kind: None,
-                inner: RotatorAction::Linked(LinkedAction {
+                tier: PriorityActionTier::Deferrable,
+                ..queued_priority_action(RotatorAction::Single(NORMAL_ACTION.into()), true)
+            },
+        );
+        player.set_normal_action(1, PlayerAction::AutoMob(PlayerActionAutoMob::default()));
+
+        // deferrable priority action is held off while auto mobbing
+        rotator.rotate_action(&context, &mut player);
+        assert_eq!(rotator.priority_actions_queue, VecDeque::from_iter([2]));
+        assert!(rotator.priority_actions_auto_mob_deferred_instant.is_some());
+        assert!(!player.has_priority_action());
+
+        // rotates once the grace has passed
+        rotator.priority_actions_auto_mob_deferred_instant =
+            Some(Instant::now() - Duration::from_millis(1000));
+        rotator.rotate_action(&context, &mut player);
+        assert!(rotator.priority_actions_queue.is_empty());
+        assert!(rotator.priority_actions_auto_mob_deferred_instant.is_none());
+        assert_eq!(player.priority_action_id(), Some(2));
+    }
+
+    #[test]
+    fn rotator_priority_action_queue_auto_mob_grace_skip_deferrable() {
+        let mut rotator = Rotator::default();
+        let mut player = PlayerState::default();
+        let context = Context::new(None, None);
+        rotator.priority_actions_auto_mob_grace_millis = 1000;
+        rotator.priority_actions.insert(
+            2,
+            PriorityAction {
+                tier: PriorityActionTier::Deferrable,
+                ..queued_priority_action(RotatorAction::Single(NORMAL_ACTION.into()), true)
+            },
+        );
+        rotator.priority_actions.insert(
+            3,
+            queued_priority_action(RotatorAction::Single(NORMAL_ACTION.into()), true),
+        );
+        player.set_normal_action(1, PlayerAction::AutoMob(PlayerActionAutoMob::default()));
+
+        // urgent priority action behind the deferrable one is not blocked
+        rotator.rotate_action(&context, &mut player);
+        assert_eq!(rotator.priority_actions_queue, VecDeque::from_iter([2]));
+        assert_eq!(player.priority_action_id(), Some(3));
+    }
+
+    #[test]
+    fn rotator_emergency_town_action() {
+        let mut rotator = Rotator::default();
+        let mut player = PlayerState::default();
+        let context = Context::new(None, None);
+        rotator.priority_actions.insert(
+            2,
+            queued_priority_action(RotatorAction::Single(NORMAL_ACTION.into()), true),
+        );
+        rotator.id_counter.store(3, Ordering::Relaxed);
+        player.set_normal_action(1, NORMAL_ACTION.into());
+
+        rotator.queue_emergency_town_action(&mut player);
+        assert_eq!(rotator.emergency_town_action_id, Some(3));
+        assert_eq!(player.priority_action_id(), Some(3));
+        assert!(!player.has_normal_action());
+        assert_matches!(
+            player.priority_action(),
+            Some(PlayerAction::Panic(PlayerActionPanic { to: PanicTo::Town }))
+        );
+
+        // emergency action cannot be replaced by another front priority action
+        rotator.rotate_action(&context, &mut player);
+        assert_eq!(
+            rotator.priority_actions_queue,
+            VecDeque::from_iter([2].into_iter())
+        );
+        assert_eq!(player.priority_action_id(), Some(3));
+
+        // previous emergency action is removed
+        rotator.queue_emergency_town_action(&mut player);
+        assert!(rotator.priority_actions.get(&3).is_none());
+        assert_eq!(rotator.emergency_town_action_id, Some(4));
+        assert!(rotator.priority_actions_queue.is_empty());
+    }
+
+    #[test]
+    fn elite_boss_low_health_panic_priority_action_mode() {
+        let action = elite_boss_low_health_panic_priority_action(PanicMode::GoToTown);
+        assert_matches!(
+            action.inner,
+            RotatorAction::Single(PlayerAction::Panic(PlayerActionPanic { to: PanicTo::Town }))
+        );
+
+        let action = elite_boss_low_health_panic_priority_action(PanicMode::Defensive);
+        assert_matches!(
+            action.inner,
+            RotatorAction::Single(PlayerAction::Panic(PlayerActionPanic {
+                to: PanicTo::Channel
+            }))
+        );
+    }
+
+    #[test]
+    fn elite_boss_low_health_panic_priority_action_skip_without_elite_boss() {
+        let action = elite_boss_low_health_panic_priority_action(PanicMode::CycleChannel);
+        let mut context = Context::new(None, None);
+        context.minimap = Minimap::Idle(MinimapIdle::default());
+        let mut player = PlayerState::default();
+
+        assert!(matches!(
+            (action.condition.0)(&context, &mut player, None),
+            ConditionResult::Skip
+        ));
+    }
+
+    #[test]
+    fn elite_boss_consumable_priority_action_queue_on_rising_edge() {
+        let action = elite_boss_consumable_priority_action(&[KeyBinding::A, KeyBinding::B]);
+        let mut context = Context::new(None, None);
+        let mut player = PlayerState::default();
+        let mut idle = MinimapIdle::default();
+        assert_matches!(
+            action.inner,
+            RotatorAction::Linked(LinkedAction {
+                inner: PlayerAction::Key(PlayerActionKey {
+                    key: KeyBinding::A,
+                    ..
+                }),
+                next: Some(_),
+                ..
+            })
+        );
+
+        // No elite boss
+        context.minimap = Minimap::Idle(idle);
+        assert!(matches!(
+            (action.condition.0)(&context, &mut player, None),
+            ConditionResult::Skip
+        ));
+
+        // Elite boss appears
+        idle.has_elite_boss = true;
+        context.minimap = Minimap::Idle(idle);
+        assert!(matches!(
+            (action.condition.0)(&context, &mut player, None),
+            ConditionResult::Queue
+        ));
+
+        // Elite boss still alive
+        assert!(matches!(
+            (action.condition.0)(&context, &mut player, None),
+            ConditionResult::Skip
+        ));
+
+        // Elite boss disappears and appears again
+        idle.has_elite_boss = false;
+        context.minimap = Minimap::Idle(idle);
+        assert!(matches!(
+            (action.condition.0)(&context, &mut player, None),
+            ConditionResult::Skip
+        ));
+        idle.has_elite_boss = true;
+        context.minimap = Minimap::Idle(idle);
+        assert!(matches!(
+            (action.condition.0)(&context, &mut player, None),
+            ConditionResult::Queue
+        ));
+    }
+
+    #[test]
+    fn loot_object_priority_action_queued_manually() {
+        let action = loot_object_priority_action(KeyBinding::Space);
+        let context = Context::new(None, None);
+        let mut player = PlayerState::default();
+
+        assert_matches!(
+            action.inner,
+            RotatorAction::Single(PlayerAction::Key(PlayerActionKey {
+                key: KeyBinding::Space,
+                position: None,
+                with: ActionKeyWith::Stationary,
+                ..
+            }))
+        );
+        assert!(matches!(
+            (action.condition.0)(&context, &mut player, None),
+            ConditionResult::Skip
+        ));
+    }
+
+    #[test]
+    fn familiars_swapping_action_move_to_safe_position() {
+        let swapping = PlayerActionFamiliarsSwapping {
+            swappable_slots: SwappableFamiliars::default(),
+            swappable_rarities: Array::new(),
+            hover_settle_ticks: 0,
+            no_swappable_cards_outcome: NoSwappableCardsOutcome::default(),
+        };
+        assert_matches!(
+            familiars_swapping_action(swapping, None),
+            RotatorAction::Single(PlayerAction::FamiliarsSwapping(_))
+        );
+
+        let position = Position {
+            x: 20,
+            y: 5,
+            ..Position::default()
+        };
+        assert_matches!(
+            familiars_swapping_action(swapping, Some(position)),
+            RotatorAction::Linked(LinkedAction {
+                inner: PlayerAction::Move(PlayerActionMove {
+                    position: Position { x: 20, y: 5, .. },
+                    ..
+                }),
+                next: Some(_),
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn return_home_priority_action_queue_after_idle() {
+        let position = Position {
+            x: 50,
+            y: 10,
+            ..Position::default()
+        };
+        let mut context = Context::new(None, None);
+        context.minimap = Minimap::Idle(MinimapIdle::default());
+        context.player = Player::Idle;
+        let mut player = PlayerState::default();
+        player.last_known_pos = Some(Point::new(10, 10));
+
+        // (1) Does not queue before idling long enough
+        let action = return_home_priority_action(position, 60_000);
+        assert!(matches!(
+            (action.condition.0)(&context, &mut player, None),
+            ConditionResult::Skip
+        ));
+
+        // (2) Queues once idled long enough
+        let action = return_home_priority_action(position, 0);
+        assert_matches!(
+            action.inner,
+            RotatorAction::Single(PlayerAction::Move(PlayerActionMove {
+                position: Position { x: 50, y: 10, .. },
+                ..
+            }))
+        );
+        assert!(matches!(
+            (action.condition.0)(&context, &mut player, None),
+            ConditionResult::Queue
+        ));
+
+        // (3) Does not queue when already at home
+        player.last_known_pos = Some(Point::new(51, 10));
+        assert!(matches!(
+            (action.condition.0)(&context, &mut player, None),
+            ConditionResult::Skip
+        ));
+
+        // (4) Does not queue when the player has an action
+        player.last_known_pos = Some(Point::new(10, 10));
+        player.set_normal_action(0, PlayerAction::SolveRune);
+        assert!(matches!(
+            (action.condition.0)(&context, &mut player, None),
+            ConditionResult::Skip
+        ));
+    }
+
+    #[test]
+    fn panic_priority_action_defensive_mode_uses_key() {
+        let action = panic_priority_action(PanicMode::Defensive, KeyBinding::A);
+        assert_matches!(
+            action.inner,
+            RotatorAction::Single(PlayerAction::Key(PlayerActionKey {
+                key: KeyBinding::A,
+                position: None,
+                ..
+            }))
+        );
+
+        let action = panic_priority_action(PanicMode::GoToTown, KeyBinding::A);
+        assert_matches!(
+            action.inner,
+            RotatorAction::Single(PlayerAction::Panic(PlayerActionPanic { to: PanicTo::Town }))
+        );
+    }
+
+    #[test]
+    fn rotator_normal_linked_action_waits() {
+        let mut rotator = Rotator::default();
+        let mut player = PlayerState::default();
+        let context = Context::new(None, None);
+        let head = Action::Key(ActionKey {
+            wait_after_use_millis: 100,
+            linked_wait_millis: 300,
+            ..ActionKey::default()
+        });
+        let linked = Action::Key(ActionKey {
+            condition: ActionCondition::Linked,
+            wait_before_use_millis: 200,
+            wait_after_use_millis: 100,
+            ..ActionKey::default()
+        });
+        let actions = [head, linked, linked];
+        let args = RotatorBuildArgs {
+            mode: RotatorMode::StartToEnd,
+            ..build_args(&actions)
+        };
+        rotator.build_actions(args);
+        let wait_ticks = |millis: u64| (millis / MS_PER_TICK) as u32;
+
+        // head keeps its own wait after and the linked wait separately
+        rotator.rotate_action(&context, &mut player);
+        assert_matches!(
+            player.normal_action(),
+            Some(PlayerAction::Key(PlayerActionKey {
+                wait_before_use_ticks: 0,
+                wait_after_use_ticks,
+                linked_wait_ticks,
+                ..
+            })) if wait_after_use_ticks == wait_ticks(100) && linked_wait_ticks == wait_ticks(300)
+        );
+
+        // middle linked action keeps its own waits and the linked wait
+        player.clear_actions_aborted();
+        rotator.rotate_action(&context, &mut player);
+        assert_matches!(
+            player.normal_action(),
+            Some(PlayerAction::Key(PlayerActionKey {
+                wait_before_use_ticks,
+                wait_after_use_ticks,
+                linked_wait_ticks,
+                ..
+            })) if wait_before_use_ticks == wait_ticks(200)
+                && wait_after_use_ticks == wait_ticks(100)
+                && linked_wait_ticks == wait_ticks(300)
+        );
+
+        // last linked action does not wait for the linked wait
+        player.clear_actions_aborted();
+        rotator.rotate_action(&context, &mut player);
+        assert_matches!(
+            player.normal_action(),
+            Some(PlayerAction::Key(PlayerActionKey {
+                wait_before_use_ticks,
+                wait_after_use_ticks,
+                linked_wait_ticks: 0,
+                ..
+            })) if wait_before_use_ticks == wait_ticks(200)
+                && wait_after_use_ticks == wait_ticks(100)
+        );
+        assert!(rotator.normal_queuing_linked_action.is_none());
+    }
+
+    #[test]
+    fn rotator_normal_linked_action_fire_during_move() {
+        let mut rotator = Rotator::default();
+        let mut player = PlayerState::default();
+        let context = Context::new(None, None);
+        let head = Action::Key(ActionKey {
+            fire_during_move: true,
+            ..ActionKey::default()
+        });
+        let linked = Action::Move(ActionMove {
+            condition: ActionCondition::Linked,
+            ..ActionMove::default()
+        });
+        let actions = [head, linked];
+        let args = RotatorBuildArgs {
+            mode: RotatorMode::StartToEnd,
+            ..build_args(&actions)
+        };
+        rotator.build_actions(args);
+
+        // key is handed over to the move instead of being set as the action
+        rotator.rotate_action(&context, &mut player);
+        assert_matches!(player.normal_action(), Some(PlayerAction::Move(_)));
+        assert!(rotator.normal_queuing_linked_action.is_none());
+    }
+
+    #[test]
+    fn rotator_priority_linked_action() {
+        let mut rotator = Rotator::default();
+        let mut player = PlayerState::default();
+        let context = Context::new(None, None);
+        rotator.priority_actions.insert(
+            2,
+            queued_priority_action(
+                RotatorAction::Linked(LinkedAction {
                     inner: NORMAL_ACTION.into(),
+                    fire_during_move: false,
                     next: Some(Box::new(LinkedAction {
                         inner: NORMAL_ACTION.into(),
+                        fire_during_move: false,
                         next: None,
                     })),
                 }),
-                queue_to_front: false,
-                ignoring: false,
-                last_queued_time: None,
-            },
+                false,
+            ),
         );
 
         // linked action queued
@@ -1332,14 +4014,7 @@ mod tests {
         // linked action cannot be replaced by queue to front
         rotator.priority_actions.insert(
             4,
-            PriorityAction {
-                condition: Condition(Box::new(|_, _, _| ConditionResult::Queue)),
-                condition_kind: None,
-                inner: RotatorAction::Single(PlayerAction::SolveRune),
-                queue_to_front: true,
-                ignoring: false,
-                last_queued_time: None,
-            },
+            queued_priority_action(RotatorAction::Single(PlayerAction::SolveRune), true),
         );
         rotator.rotate_action(&context, &mut player);
         assert_eq!(
@@ -1378,6 +4053,8 @@ mod tests {
                 key_count: 1,
                 key_wait_before_millis: 0,
                 key_wait_after_millis: 0,
+                edge_inset: 0,
+                alternate_key: None,
             },
         );
 
@@ -1401,6 +4078,8 @@ mod tests {
                 key_count: 1,
                 key_wait_before_millis: 0,
                 key_wait_after_millis: 0,
+                edge_inset: 0,
+                alternate_key: None,
             },
         );
 
@@ -1412,4 +4091,92 @@ mod tests {
             }))
         );
     }
+
+    #[test]
+    fn rotate_ping_pong_edge_inset() {
+        let mut rotator = Rotator::default();
+        let mut player = PlayerState::default();
+        let mut idle = MinimapIdle::default();
+        idle.bbox = Rect::new(0, 0, 100, 100);
+
+        let mut context = Context::new(None, None);
+        context.minimap = Minimap::Idle(idle);
+
+        player.last_known_pos = Some(Point::new(80, 50));
+        rotator.rotate_ping_pong(
+            &context,
+            &mut player,
+            PingPong {
+                bound: Rect::new(20, 20, 60, 60).into(),
+                edge_inset: 10,
+                ..PingPong::default()
+            },
+        );
+
+        assert_matches!(
+            player.normal_action(),
+            Some(PlayerAction::PingPong(PlayerActionPingPong { bound, .. }))
+                if bound.x == 30 && bound.width == 40
+        );
+    }
+
+    #[test]
+    fn rotate_hold_spot_reposition() {
+        let mut rotator = Rotator::default();
+        let mut player = PlayerState::default();
+        let hold_spot = HoldSpot {
+            position: Position {
+                x: 50,
+                y: 20,
+                ..Position::default()
+            },
+            reposition_threshold: 5,
+            ..HoldSpot::default()
+        };
+
+        // Near the spot -> Use key in place
+        player.last_known_pos = Some(Point::new(53, 20));
+        rotator.rotate_hold_spot(&mut player, hold_spot);
+        assert_matches!(
+            player.normal_action(),
+            Some(PlayerAction::Key(PlayerActionKey { position: None, .. }))
+        );
+
+        // Knocked away -> Move back to the spot
+        player.clear_actions_aborted();
+        player.last_known_pos = Some(Point::new(40, 20));
+        rotator.rotate_hold_spot(&mut player, hold_spot);
+        assert_matches!(
+            player.normal_action(),
+            Some(PlayerAction::Key(PlayerActionKey {
+                position: Some(Position { x: 50, y: 20, .. }),
+                ..
+            }))
+        );
+    }
+
+    #[test]
+    fn rotate_hold_spot_turn_and_attack() {
+        let mut rotator = Rotator::default();
+        let mut player = PlayerState::default();
+        player.last_known_pos = Some(Point::new(50, 20));
+        let hold_spot = HoldSpot {
+            position: Position {
+                x: 50,
+                y: 20,
+                ..Position::default()
+            },
+            turn_and_attack: true,
+            ..HoldSpot::default()
+        };
+
+        rotator.rotate_hold_spot(&mut player, hold_spot);
+        assert_matches!(
+            player.normal_action(),
+            Some(PlayerAction::Key(PlayerActionKey {
+                direction: ActionKeyDirection::Left,
+                ..
+            }))
+        );
+    }
 }
@@ -1,6 +1,6 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use anyhow::{Error, Ok, bail};
+use anyhow::{Error, bail};
 use bit_vec::BitVec;
 use input::key_input_client::KeyInputClient;
 pub use input::{Coordinate, MouseAction};
@@ -9,23 +9,40 @@ use platforms::windows::KeyKind;
 use tokio::runtime::Handle;
 use tokio::task::block_in_place;
 use tokio::time::timeout;
-use tonic::Request;
 use tonic::transport::{Channel, Endpoint};
+use tonic::{Code, Request, Response, Status};
 
 mod input {
     tonic::include_proto!("input");
 }
 
+/// The initial backoff before retrying to reach the server after a request failed because the
+/// server is unreachable.
+///
+/// The backoff is doubled after each failed retry up to [`MAX_RETRY_BACKOFF`].
+const RETRY_BACKOFF: Duration = Duration::from_millis(250);
+
+/// The maximum backoff before retrying to reach the server.
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(2);
+
 #[derive(Debug)]
 pub struct KeysService {
     client: KeyInputClient<Channel>,
     url: String,
     key_down: BitVec, // TODO: is a bit wrong good?
     mouse_coordinate: Coordinate,
+    /// The timeout for each request attempt.
+    timeout: Duration,
+    /// Whether the last request reached the server.
+    connected: bool,
+    /// The current backoff before retrying to reach the server.
+    retry_backoff: Duration,
+    /// The instant before which requests fail immediately because the server is unreachable.
+    retry_instant: Option<Instant>,
 }
 
 impl KeysService {
-    pub fn connect<D>(dest: D) -> Result<Self, Error>
+    pub fn connect<D>(dest: D, request_timeout: Duration) -> Result<Self, Error>
     where
         D: TryInto<Endpoint>,
         D: AsRef<str>,
@@ -40,6 +57,10 @@ impl KeysService {
             url: dest.as_ref().to_string(),
             key_down: BitVec::from_elem(128, false),
             mouse_coordinate: Coordinate::Screen,
+            timeout: request_timeout,
+            connected: true,
+            retry_backoff: RETRY_BACKOFF,
+            retry_instant: None,
         })
     }
 
@@ -47,6 +68,14 @@ impl KeysService {
         &self.url
     }
 
+    pub fn set_timeout(&mut self, request_timeout: Duration) {
+        self.timeout = request_timeout;
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
     pub fn reset(&mut self) {
         for i in 0..self.key_down.len() {
             if Key::try_from(i as i32).is_ok() {
@@ -61,14 +90,12 @@ impl KeysService {
     }
 
     pub fn init(&mut self, seed: &[u8]) -> Result<(), Error> {
-        let response = block_future(async {
-            self.client
-                .init(KeyInitRequest {
-                    seed: seed.to_vec(),
-                })
-                .await
-        })?
-        .into_inner();
+        let response = self.request(|mut client| {
+            let request = Request::new(KeyInitRequest {
+                seed: seed.to_vec(),
+            });
+            async move { client.init(request).await }
+        })?;
         self.mouse_coordinate = response.mouse_coordinate();
         Ok(())
     }
@@ -85,33 +112,31 @@ impl KeysService {
         y: i32,
         action: MouseAction,
     ) -> Result<(), Error> {
-        Ok(block_future(async move {
-            self.client
-                .send_mouse(Request::new(MouseRequest {
-                    width,
-                    height,
-                    x,
-                    y,
-                    action: action.into(),
-                }))
-                .await?;
-            Ok(())
-        })?)
+        self.request(|mut client| {
+            let request = Request::new(MouseRequest {
+                width,
+                height,
+                x,
+                y,
+                action: action.into(),
+            });
+            async move { client.send_mouse(request).await }
+        })?;
+        Ok(())
     }
 
     // TODO: Use gRPC enum instead of platforms
     pub fn send(&mut self, key: KeyKind, down_ms: f32) -> Result<(), Error> {
-        Ok(block_future(async move {
-            let kind = from_key_kind(key);
+        let kind = from_key_kind(key);
+        self.request(|mut client| {
             let request = Request::new(KeyRequest {
                 key: kind.into(),
                 down_ms,
             });
-
-            self.client.send(request).await?;
-            self.key_down.set(i32::from(kind) as usize, false);
-            Ok(())
-        })?)
+            async move { client.send(request).await }
+        })?;
+        self.key_down.set(i32::from(kind) as usize, false);
+        Ok(())
     }
 
     // TODO: Use gRPC enum instead of platforms
@@ -119,14 +144,13 @@ impl KeysService {
         if !self.can_send_key(key, false) {
             bail!("key not sent");
         }
-        Ok(block_future(async move {
-            let kind = from_key_kind(key);
+        let kind = from_key_kind(key);
+        self.request(|mut client| {
             let request = Request::new(KeyUpRequest { key: kind.into() });
-
-            self.client.send_up(request).await?;
-            self.key_down.set(i32::from(kind) as usize, false);
-            Ok(())
-        })?)
+            async move { client.send_up(request).await }
+        })?;
+        self.key_down.set(i32::from(kind) as usize, false);
+        Ok(())
     }
 
     // TODO: Use gRPC enum instead of platforms
@@ -134,14 +158,48 @@ impl KeysService {
         if !self.can_send_key(key, true) {
             bail!("key not sent");
         }
-        Ok(block_future(async move {
-            let kind = from_key_kind(key);
+        let kind = from_key_kind(key);
+        self.request(|mut client| {
             let request = Request::new(KeyDownRequest { key: kind.into() });
+            async move { client.send_down(request).await }
+        })?;
+        self.key_down.set(i32::from(kind) as usize, true);
+        Ok(())
+    }
 
-            self.client.send_down(request).await?;
-            self.key_down.set(i32::from(kind) as usize, true);
-            Ok(())
-        })?)
+    /// Sends a request created by `f` with [`Self::timeout`].
+    ///
+    /// When the server is unreachable or the request timed out, subsequent requests fail
+    /// immediately without blocking until the backoff starting at [`RETRY_BACKOFF`] has passed.
+    /// [`Self::connected`] is updated according to the result.
+    fn request<T, F, Fut>(&mut self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(KeyInputClient<Channel>) -> Fut,
+        Fut: Future<Output = Result<Response<T>, Status>>,
+    {
+        if self
+            .retry_instant
+            .is_some_and(|instant| Instant::now() < instant)
+        {
+            bail!("server unreachable");
+        }
+        let client = self.client.clone();
+        let request_timeout = self.timeout;
+        let result = block_future(async move {
+            timeout(request_timeout, f(client))
+                .await
+                .map_err(|_| Status::deadline_exceeded("request timed out"))
+                .and_then(|result| result)
+        });
+        self.connected = !matches!(&result, Err(status) if is_unreachable(status));
+        if self.connected {
+            self.retry_backoff = RETRY_BACKOFF;
+            self.retry_instant = None;
+        } else {
+            self.retry_instant = Some(Instant::now() + self.retry_backoff);
+            self.retry_backoff = (self.retry_backoff * 2).min(MAX_RETRY_BACKOFF);
+        }
+        Ok(result?.into_inner())
     }
 
     // TODO: Use gRPC enum instead of platforms
@@ -159,6 +217,11 @@ fn block_future<F: Future>(f: F) -> F::Output {
     block_in_place(|| Handle::current().block_on(f))
 }
 
+#[inline]
+fn is_unreachable(status: &Status) -> bool {
+    matches!(status.code(), Code::Unavailable | Code::DeadlineExceeded)
+}
+
 // TODO: Use gRPC enum instead of platforms
 #[inline]
 fn from_key_kind(key: KeyKind) -> Key {
@@ -233,6 +296,29 @@ fn from_key_kind(key: KeyKind) -> Key {
         KeyKind::Esc => Key::Esc,
         KeyKind::Shift => Key::Shift,
         KeyKind::Alt => Key::Alt,
+        KeyKind::Numpad0 => Key::Numpad0,
+        KeyKind::Numpad1 => Key::Numpad1,
+        KeyKind::Numpad2 => Key::Numpad2,
+        KeyKind::Numpad3 => Key::Numpad3,
+        KeyKind::Numpad4 => Key::Numpad4,
+        KeyKind::Numpad5 => Key::Numpad5,
+        KeyKind::Numpad6 => Key::Numpad6,
+        KeyKind::Numpad7 => Key::Numpad7,
+        KeyKind::Numpad8 => Key::Numpad8,
+        KeyKind::Numpad9 => Key::Numpad9,
+        KeyKind::NumpadMultiply => Key::NumpadMultiply,
+        KeyKind::NumpadAdd => Key::NumpadAdd,
+        KeyKind::NumpadSubtract => Key::NumpadSubtract,
+        KeyKind::NumpadDecimal => Key::NumpadDecimal,
+        KeyKind::NumpadDivide => Key::NumpadDivide,
+        KeyKind::Minus => Key::Minus,
+        KeyKind::Equal => Key::Equal,
+        KeyKind::LeftBracket => Key::LeftBracket,
+        KeyKind::RightBracket => Key::RightBracket,
+        KeyKind::Backslash => Key::Backslash,
+        KeyKind::Tab => Key::Tab,
+        KeyKind::CapsLock => Key::CapsLock,
+        KeyKind::Backspace => Key::Backspace,
     }
 }
 
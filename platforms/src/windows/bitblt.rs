@@ -4,21 +4,24 @@ use std::ptr;
 use std::slice;
 
 use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
 use windows::Win32::Foundation::RECT;
 use windows::Win32::Graphics::Gdi::BI_BITFIELDS;
 use windows::Win32::Graphics::Gdi::BITMAPV4HEADER;
 use windows::Win32::Graphics::Gdi::BitBlt;
 use windows::Win32::Graphics::Gdi::CreateDCW;
+use windows::Win32::Graphics::Gdi::EnumDisplayMonitors;
 use windows::Win32::Graphics::Gdi::GetMonitorInfoW;
 use windows::Win32::Graphics::Gdi::MONITOR_DEFAULTTONULL;
 use windows::Win32::Graphics::Gdi::MONITORINFO;
 use windows::Win32::Graphics::Gdi::MONITORINFOEXW;
 use windows::Win32::Graphics::Gdi::MonitorFromWindow;
 use windows::Win32::Graphics::Gdi::{
-    CreateCompatibleDC, CreateDIBSection, DIB_RGB_COLORS, DeleteDC, GetDC, HBITMAP, HDC, ReleaseDC,
-    SRCCOPY, SelectObject,
+    CreateCompatibleDC, CreateDIBSection, DIB_RGB_COLORS, DeleteDC, GetDC, HBITMAP, HDC, HMONITOR,
+    ReleaseDC, SRCCOPY, SelectObject,
 };
 use windows::Win32::UI::WindowsAndMessaging::GetClientRect;
+use windows::core::BOOL;
 use windows::core::Owned;
 use windows::core::PCWSTR;
 
@@ -110,40 +113,95 @@ impl BitBltCapture {
             return Err(Error::InvalidWindowSize);
         }
 
-        let bitmap_dc = &bitmap.dc;
-        let object = unsafe { SelectObject(bitmap_dc.inner, (*bitmap.inner).into()) };
-        if object.is_invalid() {
-            return Err(Error::from_last_win_error());
-        }
         let (left, top) = offset.unwrap_or((0, 0));
-        let result = unsafe {
-            BitBlt(
-                bitmap_dc.inner,
-                0,
-                0,
-                bitmap.width,
-                bitmap.height,
-                Some(handle_dc.inner),
-                left,
-                top,
-                SRCCOPY,
-            )
-        };
-        let _ = unsafe { SelectObject(bitmap_dc.inner, object) };
-        if let Err(error) = result {
-            return Err(Error::from(error));
+        copy_to_frame(bitmap, &handle_dc, left, top)
+    }
+}
+
+/// A `BitBlt` capture of a fixed region on a monitor.
+///
+/// Unlike [`BitBltCapture`], this capture does not depend on any window and always captures
+/// whatever is visible inside the region.
+#[derive(Debug)]
+pub struct MonitorRegionCapture {
+    monitor_index: usize,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    bitmap: Option<Bitmap>,
+}
+
+impl MonitorRegionCapture {
+    /// Creates a new monitor region capture
+    ///
+    /// `monitor_index` is the index of the monitor in the order enumerated by the system.
+    /// `x` and `y` are relative to the top-left of that monitor.
+    pub fn new(monitor_index: usize, x: i32, y: i32, width: i32, height: i32) -> Self {
+        Self {
+            monitor_index,
+            x,
+            y,
+            width,
+            height,
+            bitmap: None,
         }
-        // SAFETY: I swear on the love of Axis Order, this call passed the safety vibe check
-        let ptr = unsafe { slice::from_raw_parts(bitmap.buffer, bitmap.size) };
-        let data = ptr.to_vec();
-        Ok(Frame {
-            width: bitmap.width,
-            height: bitmap.height,
-            data,
-        })
+    }
+
+    pub fn grab(&mut self) -> Result<Frame, Error> {
+        if self.width <= 0 || self.height <= 0 {
+            return Err(Error::InvalidWindowSize);
+        }
+
+        let monitor = get_monitor_from_index(self.monitor_index)?;
+        let monitor_dc = get_device_context_from_hmonitor(monitor, &mut None)?;
+        if self.bitmap.is_none() {
+            self.bitmap = Some(create_bitmap(monitor_dc.inner, self.width, self.height)?);
+        }
+
+        copy_to_frame(self.bitmap.as_ref().unwrap(), &monitor_dc, self.x, self.y)
     }
 }
 
+#[inline]
+fn copy_to_frame(
+    bitmap: &Bitmap,
+    source_dc: &DeviceContext,
+    left: i32,
+    top: i32,
+) -> Result<Frame, Error> {
+    let bitmap_dc = &bitmap.dc;
+    let object = unsafe { SelectObject(bitmap_dc.inner, (*bitmap.inner).into()) };
+    if object.is_invalid() {
+        return Err(Error::from_last_win_error());
+    }
+    let result = unsafe {
+        BitBlt(
+            bitmap_dc.inner,
+            0,
+            0,
+            bitmap.width,
+            bitmap.height,
+            Some(source_dc.inner),
+            left,
+            top,
+            SRCCOPY,
+        )
+    };
+    let _ = unsafe { SelectObject(bitmap_dc.inner, object) };
+    if let Err(error) = result {
+        return Err(Error::from(error));
+    }
+    // SAFETY: I swear on the love of Axis Order, this call passed the safety vibe check
+    let ptr = unsafe { slice::from_raw_parts(bitmap.buffer, bitmap.size) };
+    let data = ptr.to_vec();
+    Ok(Frame {
+        width: bitmap.width,
+        height: bitmap.height,
+        data,
+    })
+}
+
 #[inline]
 fn get_rect(handle: HWND) -> Result<RECT, Error> {
     let mut rect = RECT::default();
@@ -160,6 +218,39 @@ fn get_device_context_from_monitor(
     if monitor.is_invalid() {
         return Err(Error::WindowNotFound);
     }
+    get_device_context_from_hmonitor(monitor, offset)
+}
+
+#[inline]
+fn get_monitor_from_index(index: usize) -> Result<HMONITOR, Error> {
+    unsafe extern "system" fn callback(
+        monitor: HMONITOR,
+        _: HDC,
+        _: *mut RECT,
+        data: LPARAM,
+    ) -> BOOL {
+        let monitors = unsafe { &mut *(data.0 as *mut Vec<HMONITOR>) };
+        monitors.push(monitor);
+        true.into()
+    }
+
+    let mut monitors = Vec::<HMONITOR>::new();
+    let _ = unsafe {
+        EnumDisplayMonitors(
+            None,
+            None,
+            Some(callback),
+            LPARAM(&raw mut monitors as isize),
+        )
+    };
+    monitors.get(index).copied().ok_or(Error::WindowNotFound)
+}
+
+#[inline]
+fn get_device_context_from_hmonitor(
+    monitor: HMONITOR,
+    offset: &mut Option<(i32, i32)>,
+) -> Result<DeviceContext, Error> {
     let mut info = MONITORINFOEXW {
         monitorInfo: MONITORINFO {
             cbSize: mem::size_of::<MONITORINFOEXW>() as u32,
@@ -6,7 +6,7 @@ use windows::{
         Graphics::Dwm::{DWMWA_CLOAKED, DwmGetWindowAttribute},
         UI::WindowsAndMessaging::{
             EnumWindows, GWL_EXSTYLE, GWL_STYLE, GetClassNameW, GetWindowLongPtrW, GetWindowTextW,
-            IsWindowVisible, WS_DISABLED, WS_EX_TOOLWINDOW,
+            IsWindow, IsWindowVisible, WS_DISABLED, WS_EX_TOOLWINDOW,
         },
     },
     core::BOOL,
@@ -72,6 +72,12 @@ impl Handle {
         }
     }
 
+    /// Whether the window this handle refers to still exists.
+    pub fn is_valid(&self) -> bool {
+        self.query_handle()
+            .is_some_and(|handle| unsafe { IsWindow(Some(handle)) }.as_bool())
+    }
+
     pub(crate) fn query_handle(&self) -> Option<HWND> {
         match self.kind {
             HandleKind::Fixed(handle) => Some(handle),
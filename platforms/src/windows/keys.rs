@@ -22,13 +22,16 @@ use windows::{
                 KEYEVENTF_EXTENDEDKEY, KEYEVENTF_KEYUP, MAPVK_VK_TO_VSC_EX, MOUSE_EVENT_FLAGS,
                 MOUSEEVENTF_ABSOLUTE, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP, MOUSEEVENTF_MOVE,
                 MOUSEEVENTF_WHEEL, MOUSEINPUT, MapVirtualKeyW, SendInput, VIRTUAL_KEY, VK_0, VK_1,
-                VK_2, VK_3, VK_4, VK_5, VK_6, VK_7, VK_8, VK_9, VK_A, VK_B, VK_C, VK_CONTROL, VK_D,
-                VK_DELETE, VK_DOWN, VK_E, VK_END, VK_ESCAPE, VK_F, VK_F1, VK_F2, VK_F3, VK_F4,
-                VK_F5, VK_F6, VK_F7, VK_F8, VK_F9, VK_F10, VK_F11, VK_F12, VK_G, VK_H, VK_HOME,
-                VK_I, VK_INSERT, VK_J, VK_K, VK_L, VK_LEFT, VK_M, VK_MENU, VK_N, VK_NEXT, VK_O,
-                VK_OEM_1, VK_OEM_2, VK_OEM_3, VK_OEM_7, VK_OEM_COMMA, VK_OEM_PERIOD, VK_P,
-                VK_PRIOR, VK_Q, VK_R, VK_RETURN, VK_RIGHT, VK_S, VK_SHIFT, VK_SPACE, VK_T, VK_U,
-                VK_UP, VK_V, VK_W, VK_X, VK_Y, VK_Z,
+                VK_2, VK_3, VK_4, VK_5, VK_6, VK_7, VK_8, VK_9, VK_A, VK_ADD, VK_B, VK_BACK, VK_C,
+                VK_CAPITAL, VK_CONTROL, VK_D, VK_DECIMAL, VK_DELETE, VK_DIVIDE, VK_DOWN, VK_E,
+                VK_END, VK_ESCAPE, VK_F, VK_F1, VK_F2, VK_F3, VK_F4, VK_F5, VK_F6, VK_F7, VK_F8,
+                VK_F9, VK_F10, VK_F11, VK_F12, VK_G, VK_H, VK_HOME, VK_I, VK_INSERT, VK_J, VK_K,
+                VK_L, VK_LEFT, VK_M, VK_MENU, VK_MULTIPLY, VK_N, VK_NEXT, VK_NUMPAD0, VK_NUMPAD1,
+                VK_NUMPAD2, VK_NUMPAD3, VK_NUMPAD4, VK_NUMPAD5, VK_NUMPAD6, VK_NUMPAD7, VK_NUMPAD8,
+                VK_NUMPAD9, VK_O, VK_OEM_1, VK_OEM_2, VK_OEM_3, VK_OEM_4, VK_OEM_5, VK_OEM_6,
+                VK_OEM_7, VK_OEM_COMMA, VK_OEM_MINUS, VK_OEM_PERIOD, VK_OEM_PLUS, VK_P, VK_PRIOR,
+                VK_Q, VK_R, VK_RETURN, VK_RIGHT, VK_S, VK_SHIFT, VK_SPACE, VK_SUBTRACT, VK_T,
+                VK_TAB, VK_U, VK_UP, VK_V, VK_W, VK_X, VK_Y, VK_Z,
             },
             WindowsAndMessaging::{
                 CallNextHookEx, GetForegroundWindow, GetSystemMetrics, GetWindowRect,
@@ -217,6 +220,29 @@ pub enum KeyKind {
     Esc,
     Shift,
     Alt,
+    Numpad0,
+    Numpad1,
+    Numpad2,
+    Numpad3,
+    Numpad4,
+    Numpad5,
+    Numpad6,
+    Numpad7,
+    Numpad8,
+    Numpad9,
+    NumpadMultiply,
+    NumpadAdd,
+    NumpadSubtract,
+    NumpadDecimal,
+    NumpadDivide,
+    Minus,
+    Equal,
+    LeftBracket,
+    RightBracket,
+    Backslash,
+    Tab,
+    CapsLock,
+    Backspace,
 }
 
 impl Keys {
@@ -280,6 +306,12 @@ impl Keys {
         self.send_input(kind, false)
     }
 
+    /// Whether [`Self::handle`] can currently receive input according to [`KeyInputKind`].
+    pub fn is_foreground(&self) -> bool {
+        self.get_handle()
+            .is_ok_and(|handle| is_foreground(handle, self.key_input_kind))
+    }
+
     pub fn send_down(&self, kind: KeyKind) -> Result<(), Error> {
         self.send_input(kind, true)
     }
@@ -386,6 +418,29 @@ impl TryFrom<VIRTUAL_KEY> for KeyKind {
             VK_ESCAPE => KeyKind::Esc,
             VK_SHIFT => KeyKind::Shift,
             VK_MENU => KeyKind::Alt,
+            VK_NUMPAD0 => KeyKind::Numpad0,
+            VK_NUMPAD1 => KeyKind::Numpad1,
+            VK_NUMPAD2 => KeyKind::Numpad2,
+            VK_NUMPAD3 => KeyKind::Numpad3,
+            VK_NUMPAD4 => KeyKind::Numpad4,
+            VK_NUMPAD5 => KeyKind::Numpad5,
+            VK_NUMPAD6 => KeyKind::Numpad6,
+            VK_NUMPAD7 => KeyKind::Numpad7,
+            VK_NUMPAD8 => KeyKind::Numpad8,
+            VK_NUMPAD9 => KeyKind::Numpad9,
+            VK_MULTIPLY => KeyKind::NumpadMultiply,
+            VK_ADD => KeyKind::NumpadAdd,
+            VK_SUBTRACT => KeyKind::NumpadSubtract,
+            VK_DECIMAL => KeyKind::NumpadDecimal,
+            VK_DIVIDE => KeyKind::NumpadDivide,
+            VK_OEM_MINUS => KeyKind::Minus,
+            VK_OEM_PLUS => KeyKind::Equal,
+            VK_OEM_4 => KeyKind::LeftBracket,
+            VK_OEM_6 => KeyKind::RightBracket,
+            VK_OEM_5 => KeyKind::Backslash,
+            VK_TAB => KeyKind::Tab,
+            VK_CAPITAL => KeyKind::CapsLock,
+            VK_BACK => KeyKind::Backspace,
             _ => return Err(crate::windows::Error::KeyNotFound),
         })
     }
@@ -464,6 +519,29 @@ impl From<KeyKind> for VIRTUAL_KEY {
             KeyKind::Esc => VK_ESCAPE,
             KeyKind::Shift => VK_SHIFT,
             KeyKind::Alt => VK_MENU,
+            KeyKind::Numpad0 => VK_NUMPAD0,
+            KeyKind::Numpad1 => VK_NUMPAD1,
+            KeyKind::Numpad2 => VK_NUMPAD2,
+            KeyKind::Numpad3 => VK_NUMPAD3,
+            KeyKind::Numpad4 => VK_NUMPAD4,
+            KeyKind::Numpad5 => VK_NUMPAD5,
+            KeyKind::Numpad6 => VK_NUMPAD6,
+            KeyKind::Numpad7 => VK_NUMPAD7,
+            KeyKind::Numpad8 => VK_NUMPAD8,
+            KeyKind::Numpad9 => VK_NUMPAD9,
+            KeyKind::NumpadMultiply => VK_MULTIPLY,
+            KeyKind::NumpadAdd => VK_ADD,
+            KeyKind::NumpadSubtract => VK_SUBTRACT,
+            KeyKind::NumpadDecimal => VK_DECIMAL,
+            KeyKind::NumpadDivide => VK_DIVIDE,
+            KeyKind::Minus => VK_OEM_MINUS,
+            KeyKind::Equal => VK_OEM_PLUS,
+            KeyKind::LeftBracket => VK_OEM_4,
+            KeyKind::RightBracket => VK_OEM_6,
+            KeyKind::Backslash => VK_OEM_5,
+            KeyKind::Tab => VK_TAB,
+            KeyKind::CapsLock => VK_CAPITAL,
+            KeyKind::Backspace => VK_BACK,
         }
     }
 }
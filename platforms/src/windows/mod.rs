@@ -6,6 +6,7 @@ use std::{
     thread,
 };
 
+use windows::Win32::System::Diagnostics::Debug::Beep;
 use windows::Win32::UI::WindowsAndMessaging::{
     DispatchMessageW, GetMessageW, MSG, TranslateMessage,
 };
@@ -49,3 +50,10 @@ pub fn init() {
         barrier.wait();
     }
 }
+
+/// Plays a tone of `frequency` hertz for `duration_millis` milliseconds without blocking.
+pub fn beep(frequency: u32, duration_millis: u32) {
+    thread::spawn(move || unsafe {
+        let _ = Beep(frequency, duration_millis);
+    });
+}
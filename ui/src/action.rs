@@ -4,9 +4,11 @@ use std::{
     str::FromStr,
 };
 
+#[cfg(debug_assertions)]
+use backend::record_macro;
 use backend::{
-    Action, ActionCondition, ActionKey, ActionKeyDirection, ActionKeyWith, ActionMove,
-    IntoEnumIterator, LinkKeyBinding, Minimap, ParseError, Position, Settings,
+    Action, ActionCondition, ActionKey, ActionKeyDirection, ActionKeyWith, ActionMove, BuffKind,
+    IntoEnumIterator, KeyBinding, LinkKeyBinding, Minimap, ParseError, Position, Settings,
 };
 use dioxus::{document::eval, prelude::*};
 use futures_util::StreamExt;
@@ -16,7 +18,8 @@ use crate::{
     AppMessage,
     icons::{PositionIcon, XIcon},
     input::{
-        Checkbox, KeyBindingInput, MillisInput, NumberInputI32, NumberInputU32, use_auto_numeric,
+        Checkbox, KeyBindingInput, MillisInput, NumberInputI32, NumberInputU32, PercentageInput,
+        use_auto_numeric,
     },
     platform::Platforms,
     rotation::Rotations,
@@ -64,7 +67,7 @@ pub fn Actions(
     );
     let rotation_mode_view = use_memo(move || {
         minimap()
-            .map(|minimap| minimap.rotation_mode)
+            .map(|minimap| minimap.rotation_mode_of(preset().as_deref()))
             .unwrap_or_default()
     });
     let reset_on_erda_view = use_memo(move || {
@@ -72,6 +75,23 @@ pub fn Actions(
             .map(|minimap| minimap.actions_any_reset_on_erda_condition)
             .unwrap_or_default()
     });
+    let merge_distance_view = use_memo(move || {
+        minimap()
+            .map(|minimap| minimap.actions_merge_distance)
+            .unwrap_or_default()
+    });
+    let turn_around_key_view =
+        use_memo(move || minimap().and_then(|minimap| minimap.actions_turn_around_key));
+    let home_view = use_memo(move || {
+        minimap()
+            .map(|minimap| (minimap.home_position, minimap.home_idle_millis))
+            .unwrap_or_default()
+    });
+    let actions_offset_view = use_memo(move || {
+        minimap()
+            .map(|minimap| (minimap.actions_offset_x, minimap.actions_offset_y))
+            .unwrap_or_default()
+    });
 
     use_effect(move || {
         if preset().is_none() {
@@ -117,7 +137,7 @@ pub fn Actions(
                         disabled: minimap().is_none(),
                         on_rotation_mode: move |mode| {
                             if let Some(mut minimap) = minimap.peek().clone() {
-                                minimap.rotation_mode = mode;
+                                *minimap.rotation_mode_of_mut(preset.peek().as_deref()) = mode;
                                 coroutine.send(ActionsMessage::UpdateMinimap(minimap));
                             }
                         },
@@ -127,8 +147,38 @@ pub fn Actions(
                                 coroutine.send(ActionsMessage::UpdateMinimap(minimap));
                             }
                         },
+                        on_merge_distance: move |distance| {
+                            if let Some(mut minimap) = minimap.peek().clone() {
+                                minimap.actions_merge_distance = distance;
+                                coroutine.send(ActionsMessage::UpdateMinimap(minimap));
+                            }
+                        },
+                        on_turn_around_key: move |key| {
+                            if let Some(mut minimap) = minimap.peek().clone() {
+                                minimap.actions_turn_around_key = key;
+                                coroutine.send(ActionsMessage::UpdateMinimap(minimap));
+                            }
+                        },
+                        on_home: move |(position, idle_millis)| {
+                            if let Some(mut minimap) = minimap.peek().clone() {
+                                minimap.home_position = position;
+                                minimap.home_idle_millis = idle_millis;
+                                coroutine.send(ActionsMessage::UpdateMinimap(minimap));
+                            }
+                        },
+                        on_actions_offset: move |(x, y)| {
+                            if let Some(mut minimap) = minimap.peek().clone() {
+                                minimap.actions_offset_x = x;
+                                minimap.actions_offset_y = y;
+                                coroutine.send(ActionsMessage::UpdateMinimap(minimap));
+                            }
+                        },
                         rotation_mode: rotation_mode_view(),
                         reset_on_erda: reset_on_erda_view(),
+                        merge_distance: merge_distance_view(),
+                        turn_around_key: turn_around_key_view(),
+                        home: home_view(),
+                        actions_offset: actions_offset_view(),
                     }
                 },
                 TAB_PLATFORMS => rsx! {
@@ -290,6 +340,19 @@ fn ActionPresetTab(
             update_minimap(minimap);
         }
     });
+    #[cfg(debug_assertions)]
+    let mut recording_macro = use_signal(|| false);
+    #[cfg(debug_assertions)]
+    let mut recorded_macro = use_signal::<Option<Vec<Action>>>(|| None);
+    #[cfg(debug_assertions)]
+    let on_save_macro = use_callback(move |_| {
+        if let Some((mut minimap, preset)) = minimap().zip(preset())
+            && let Some(recorded) = recorded_macro.take()
+        {
+            minimap.actions.get_mut(&preset).unwrap().extend(recorded);
+            update_minimap(minimap);
+        }
+    });
     let exclude_linked =
         use_memo(move || matches!(editing_action(), Some((_, 0))) || actions().is_empty());
 
@@ -390,6 +453,36 @@ fn ActionPresetTab(
                                 }
                             }
                         }
+                        {
+                            #[cfg(debug_assertions)]
+                            rsx! {
+                                div { class: "grid grid-cols-2 gap-x-2",
+                                    button {
+                                        class: "button-secondary h-6",
+                                        disabled: preset().is_none(),
+                                        onclick: move |_| async move {
+                                            let current = *recording_macro.peek();
+                                            recorded_macro.set(record_macro(!current).await);
+                                            recording_macro.set(!current);
+                                        },
+                                        if recording_macro() {
+                                            "Stop recording"
+                                        } else {
+                                            "Record macro"
+                                        }
+                                    }
+                                    button {
+                                        class: "button-primary h-6",
+                                        disabled: preset().is_none()
+                                            || recorded_macro().is_none_or(Vec::is_empty),
+                                        onclick: move |_| {
+                                            on_save_macro(());
+                                        },
+                                        "Save as action"
+                                    }
+                                }
+                            }
+                        }
                     }
                 }
                 ActionItemList {
@@ -493,9 +586,15 @@ fn ActionItem(
                     allow_adjusting,
                 },
             condition,
+            probability,
             wait_after_move_millis,
+            wait_after_move_millis_random_range,
+            linked_wait_millis,
+            attack_on_arrival,
         } = action;
         let wait_after_millis_id = use_memo(|| Alphanumeric.sample_string(&mut rand::rng(), 8));
+        let wait_after_millis_random_range_id =
+            use_memo(|| Alphanumeric.sample_string(&mut rand::rng(), 8));
 
         use_auto_numeric(
             wait_after_millis_id,
@@ -505,6 +604,14 @@ fn ActionItem(
             u64::MAX.to_string(),
             "ms".to_string(),
         );
+        use_auto_numeric(
+            wait_after_millis_random_range_id,
+            wait_after_move_millis_random_range.to_string(),
+            None,
+            "0".to_string(),
+            u64::MAX.to_string(),
+            "ms".to_string(),
+        );
 
         rsx! {
             div { class: DIV,
@@ -523,10 +630,32 @@ fn ActionItem(
                 span { class: KEY, "Condition" }
                 span { class: VALUE, {condition.to_string()} }
             }
+            if let Some(probability) = probability {
+                div { class: DIV,
+                    span { class: KEY, "Probability" }
+                    span { class: VALUE, "{probability * 100.0}%" }
+                }
+            }
             div { class: DIV,
                 span { class: KEY, "Wait after" }
                 span { id: wait_after_millis_id(), class: VALUE }
             }
+            div { class: DIV,
+                span { class: KEY, "Wait after random" }
+                span { id: wait_after_millis_random_range_id(), class: VALUE }
+            }
+            if linked_wait_millis > 0 {
+                div { class: DIV,
+                    span { class: KEY, "Linked wait" }
+                    span { class: VALUE, "{linked_wait_millis}ms" }
+                }
+            }
+            if let Some(key) = attack_on_arrival {
+                div { class: DIV,
+                    span { class: KEY, "Attack on arrival" }
+                    span { class: VALUE, {key.to_string()} }
+                }
+            }
         }
     }
 
@@ -537,13 +666,20 @@ fn ActionItem(
             link_key,
             count,
             position,
+            strict_position,
             condition,
             direction,
+            require_direction,
+            require_buff,
+            probability,
             with,
+            use_while_falling,
             wait_before_use_millis,
             wait_before_use_millis_random_range,
             wait_after_use_millis,
             wait_after_use_millis_random_range,
+            linked_wait_millis,
+            fire_during_move,
             queue_to_front,
         } = action;
         let wait_before_use_millis_id =
@@ -601,6 +737,10 @@ fn ActionItem(
                     span { class: KEY, "Adjust" }
                     span { class: VALUE, "{allow_adjusting}" }
                 }
+                div { class: DIV,
+                    span { class: KEY, "Strict" }
+                    span { class: VALUE, "{strict_position}" }
+                }
             }
             div { class: DIV,
                 span { class: KEY, "Key" }
@@ -624,10 +764,34 @@ fn ActionItem(
                 span { class: KEY, "Direction" }
                 span { class: VALUE, {direction.to_string()} }
             }
+            if let Some(require_direction) = require_direction {
+                div { class: DIV,
+                    span { class: KEY, "Require facing" }
+                    span { class: VALUE, {require_direction.to_string()} }
+                }
+            }
+            if let Some(require_buff) = require_buff {
+                div { class: DIV,
+                    span { class: KEY, "Require buff" }
+                    span { class: VALUE, {require_buff.to_string()} }
+                }
+            }
+            if let Some(probability) = probability {
+                div { class: DIV,
+                    span { class: KEY, "Probability" }
+                    span { class: VALUE, "{probability * 100.0}%" }
+                }
+            }
             div { class: DIV,
                 span { class: KEY, "With" }
                 span { class: VALUE, {with.to_string()} }
             }
+            if use_while_falling {
+                div { class: DIV,
+                    span { class: KEY, "Use while falling" }
+                    span { class: VALUE, "{use_while_falling}" }
+                }
+            }
             div { class: DIV,
                 span { class: KEY, "Wait before" }
                 span { id: wait_before_use_millis_id(), class: VALUE }
@@ -647,6 +811,18 @@ fn ActionItem(
                 span { class: KEY, "Wait after random" }
                 span { id: wait_after_use_millis_random_range_id(), class: VALUE }
             }
+            if linked_wait_millis > 0 {
+                div { class: DIV,
+                    span { class: KEY, "Linked wait" }
+                    span { class: VALUE, "{linked_wait_millis}ms" }
+                }
+            }
+            if fire_during_move {
+                div { class: DIV,
+                    span { class: KEY, "Fire during move" }
+                    span { class: VALUE, "{fire_during_move}" }
+                }
+            }
             if let Some(queue_to_front) = queue_to_front {
                 div { class: DIV,
                     span { class: KEY, "Queue to front" }
@@ -871,7 +1047,11 @@ fn ActionMoveInput(
     let ActionMove {
         position,
         condition,
+        probability,
         wait_after_move_millis,
+        wait_after_move_millis_random_range,
+        linked_wait_millis,
+        attack_on_arrival,
     } = value;
 
     rsx! {
@@ -892,6 +1072,44 @@ fn ActionMoveInput(
                 value: condition,
                 exclude_linked,
             }
+            ActionProbabilityInput {
+                on_input: move |probability| {
+                    on_input(Action::Move(ActionMove { probability, ..value }));
+                },
+                disabled,
+                value: probability,
+            }
+            ActionCheckbox {
+                label: "Attack on arrival",
+                disabled,
+                on_input: move |checked: bool| {
+                    on_input(
+                        Action::Move(ActionMove {
+                            attack_on_arrival: checked.then_some(KeyBinding::default()),
+                            ..value
+                        }),
+                    );
+                },
+                value: attack_on_arrival.is_some(),
+            }
+            if let Some(key) = attack_on_arrival {
+                KeyBindingInput {
+                    label: "Attack key",
+                    label_class: LABEL_CLASS,
+                    div_class: DIV_CLASS,
+                    input_class: INPUT_CLASS,
+                    disabled,
+                    on_input: move |key| {
+                        on_input(
+                            Action::Move(ActionMove {
+                                attack_on_arrival: Some(key),
+                                ..value
+                            }),
+                        );
+                    },
+                    value: key,
+                }
+            }
             ActionMillisInput {
                 label: "Wait after action",
                 disabled,
@@ -905,6 +1123,32 @@ fn ActionMoveInput(
                 },
                 value: wait_after_move_millis,
             }
+            ActionMillisInput {
+                label: "Wait after random range",
+                disabled,
+                on_input: move |wait_after_move_millis_random_range| {
+                    on_input(
+                        Action::Move(ActionMove {
+                            wait_after_move_millis_random_range,
+                            ..value
+                        }),
+                    );
+                },
+                value: wait_after_move_millis_random_range,
+            }
+            ActionMillisInput {
+                label: "Wait between linked actions",
+                disabled: disabled || matches!(condition, ActionCondition::Linked),
+                on_input: move |linked_wait_millis| {
+                    on_input(
+                        Action::Move(ActionMove {
+                            linked_wait_millis,
+                            ..value
+                        }),
+                    );
+                },
+                value: linked_wait_millis,
+            }
         }
     }
 }
@@ -925,13 +1169,20 @@ fn ActionKeyInput(
         link_key,
         count,
         position,
+        strict_position,
         condition,
         direction,
+        require_direction,
+        require_buff,
+        probability,
         with,
+        use_while_falling,
         wait_before_use_millis,
         wait_before_use_millis_random_range,
         wait_after_use_millis,
         wait_after_use_millis_random_range,
+        linked_wait_millis,
+        fire_during_move,
         queue_to_front,
     } = value;
 
@@ -972,6 +1223,14 @@ fn ActionKeyInput(
                     disabled,
                     value: position,
                 }
+                ActionCheckbox {
+                    label: "Strict position",
+                    disabled,
+                    on_input: move |strict_position: bool| {
+                        on_input(Action::Key(ActionKey { strict_position, ..value }));
+                    },
+                    value: strict_position,
+                }
             }
             KeyBindingInput {
                 label: "Key",
@@ -1071,6 +1330,69 @@ fn ActionKeyInput(
                 disabled,
                 value: direction,
             }
+            ActionCheckbox {
+                label: "Require facing direction",
+                disabled,
+                on_input: move |checked: bool| {
+                    on_input(
+                        Action::Key(ActionKey {
+                            require_direction: checked.then_some(ActionKeyDirection::default()),
+                            ..value
+                        }),
+                    );
+                },
+                value: require_direction.is_some(),
+            }
+            if let Some(require_direction) = require_direction {
+                ActionEnumSelect::<ActionKeyDirection> {
+                    label: "Facing direction",
+                    on_input: move |direction| {
+                        on_input(
+                            Action::Key(ActionKey {
+                                require_direction: Some(direction),
+                                ..value
+                            }),
+                        );
+                    },
+                    disabled,
+                    value: require_direction,
+                }
+            }
+            ActionCheckbox {
+                label: "Require active buff",
+                disabled,
+                on_input: move |checked: bool| {
+                    on_input(
+                        Action::Key(ActionKey {
+                            require_buff: checked.then_some(BuffKind::default()),
+                            ..value
+                        }),
+                    );
+                },
+                value: require_buff.is_some(),
+            }
+            if let Some(require_buff) = require_buff {
+                ActionEnumSelect::<BuffKind> {
+                    label: "Active buff",
+                    on_input: move |buff| {
+                        on_input(
+                            Action::Key(ActionKey {
+                                require_buff: Some(buff),
+                                ..value
+                            }),
+                        );
+                    },
+                    disabled,
+                    value: require_buff,
+                }
+            }
+            ActionProbabilityInput {
+                on_input: move |probability| {
+                    on_input(Action::Key(ActionKey { probability, ..value }));
+                },
+                disabled,
+                value: probability,
+            }
             ActionEnumSelect::<ActionKeyWith> {
                 label: "With",
                 on_input: move |with| {
@@ -1079,6 +1401,19 @@ fn ActionKeyInput(
                 disabled,
                 value: with,
             }
+            ActionCheckbox {
+                label: "Use while falling",
+                disabled: disabled || !matches!(with, ActionKeyWith::Any),
+                on_input: move |use_while_falling| {
+                    on_input(
+                        Action::Key(ActionKey {
+                            use_while_falling,
+                            ..value
+                        }),
+                    );
+                },
+                value: use_while_falling,
+            }
             ActionMillisInput {
                 label: "Wait before action",
                 on_input: move |wait_before_use_millis| {
@@ -1131,6 +1466,32 @@ fn ActionKeyInput(
                 disabled,
                 value: wait_after_use_millis_random_range,
             }
+            ActionMillisInput {
+                label: "Wait between linked actions",
+                on_input: move |linked_wait_millis| {
+                    on_input(
+                        Action::Key(ActionKey {
+                            linked_wait_millis,
+                            ..value
+                        }),
+                    );
+                },
+                disabled: disabled || matches!(condition, ActionCondition::Linked),
+                value: linked_wait_millis,
+            }
+            ActionCheckbox {
+                label: "Fire during next linked move",
+                disabled,
+                on_input: move |fire_during_move| {
+                    on_input(
+                        Action::Key(ActionKey {
+                            fire_during_move,
+                            ..value
+                        }),
+                    );
+                },
+                value: fire_during_move,
+            }
         }
     }
 }
@@ -1163,6 +1524,37 @@ fn ActionConditionInput(
     }
 }
 
+#[component]
+fn ActionProbabilityInput(
+    disabled: bool,
+    on_input: EventHandler<Option<f32>>,
+    value: Option<f32>,
+) -> Element {
+    rsx! {
+        ActionCheckbox {
+            label: "Has probability",
+            disabled,
+            on_input: move |checked: bool| {
+                on_input(checked.then_some(1.0));
+            },
+            value: value.is_some(),
+        }
+        if let Some(probability) = value {
+            PercentageInput {
+                label: "Probability",
+                label_class: LABEL_CLASS,
+                div_class: DIV_CLASS,
+                input_class: "{INPUT_CLASS} p-1",
+                disabled,
+                on_input: move |percentage: f32| {
+                    on_input(Some(percentage / 100.0));
+                },
+                value: probability * 100.0,
+            }
+        }
+    }
+}
+
 #[component]
 fn ActionCheckbox(
     label: String,
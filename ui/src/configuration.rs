@@ -1,8 +1,8 @@
 use std::{fmt::Display, str::FromStr};
 
 use backend::{
-    ActionConfiguration, Class, Configuration as ConfigurationData, IntoEnumIterator,
-    KeyBindingConfiguration, PotionMode,
+    ActionConfiguration, BuffKind, Class, Configuration as ConfigurationData, IntoEnumIterator,
+    KeyBinding, KeyBindingConfiguration, PotionMode,
 };
 use dioxus::prelude::*;
 use rand::distr::{Alphanumeric, SampleString};
@@ -10,7 +10,10 @@ use rand::distr::{Alphanumeric, SampleString};
 use crate::{
     AppMessage,
     icons::{CheckMarkIcon, XIcon},
-    input::{Checkbox, KeyBindingInput, MillisInput, PercentageInput, use_auto_numeric},
+    input::{
+        Checkbox, KeyBindingInput, MillisInput, NumberInputF32, NumberInputU32, PercentageInput,
+        use_auto_numeric,
+    },
     key::KeyBindingConfigurationInput,
     select::{EnumSelect, TextSelect},
     tab::Tab,
@@ -22,8 +25,10 @@ const INPUT_CLASS: &str = "w-44 rounded border border-gray-300 px-1 text-gray-70
 const ROPE_LIFT: &str = "Rope Lift";
 const TELEPORT: &str = "Teleport";
 const JUMP: &str = "Jump";
+const FLASH_JUMP: &str = "Flash Jump";
 const UP_JUMP: &str = "Up Jump";
 const INTERACT: &str = "Interact";
+const PICK_UP: &str = "Pick Up";
 const CASH_SHOP: &str = "Cash Shop";
 const FAMILIAR: &str = "Familiar Menu";
 const MAPLE_GUIDE: &str = "Maple Guide";
@@ -184,6 +189,19 @@ fn ConfigGameKeyBindings(
                 },
                 value: config_view().jump_key,
             }
+            KeyBindingConfigurationInput {
+                label: FLASH_JUMP,
+                label_active: active,
+                is_disabled: is_disabled(),
+                is_optional: true,
+                on_input: move |key| {
+                    on_config(ConfigurationData {
+                        flash_jump_key: key,
+                        ..config_view.peek().clone()
+                    });
+                },
+                value: config_view().flash_jump_key,
+            }
             KeyBindingConfigurationInput {
                 label: UP_JUMP,
                 label_active: active,
@@ -209,6 +227,18 @@ fn ConfigGameKeyBindings(
                 },
                 value: Some(config_view().interact_key),
             }
+            KeyBindingConfigurationInput {
+                label: PICK_UP,
+                label_active: active,
+                is_disabled: is_disabled(),
+                on_input: move |key: Option<KeyBindingConfiguration>| {
+                    on_config(ConfigurationData {
+                        pickup_key: key.unwrap(),
+                        ..config_view.peek().clone()
+                    });
+                },
+                value: Some(config_view().pickup_key),
+            }
             KeyBindingConfigurationInput {
                 label: CASH_SHOP,
                 label_active: active,
@@ -280,6 +310,32 @@ fn ConfigGameKeyBindings(
                     },
                     value: config_view().feed_pet_millis,
                 }
+                NumberInputU32 {
+                    label: "Pets To Feed",
+                    div_class: DIV_CLASS,
+                    label_class: LABEL_CLASS,
+                    input_class: INPUT_CLASS,
+                    disabled: is_disabled(),
+                    minimum_value: 1,
+                    on_input: move |feed_pet_count| {
+                        on_config(ConfigurationData {
+                            feed_pet_count,
+                            ..config_view.peek().clone()
+                        });
+                    },
+                    value: config_view().feed_pet_count,
+                }
+                ConfigMillisInput {
+                    label: "Spacing Milliseconds",
+                    disabled: is_disabled(),
+                    on_input: move |feed_pet_spacing_millis| {
+                        on_config(ConfigurationData {
+                            feed_pet_spacing_millis,
+                            ..config_view.peek().clone()
+                        });
+                    },
+                    value: config_view().feed_pet_spacing_millis,
+                }
             }
             KeyBindingConfigurationInput {
                 label: POTION,
@@ -362,6 +418,14 @@ fn ConfigGameKeyBindings(
                     disabled: is_disabled(),
                     selected: config_view().class,
                 }
+                button {
+                    class: "w-full button-secondary h-6",
+                    disabled: is_disabled(),
+                    onclick: move |_| {
+                        on_config(config_view.peek().clone().with_class_preset());
+                    },
+                    "Apply class preset"
+                }
             }
             div { class: "space-y-2 mt-1",
                 p { class: "font-normal italic text-xs text-gray-400 mb-1",
@@ -382,6 +446,160 @@ fn ConfigGameKeyBindings(
                     value: config_view().disable_adjusting,
                 }
             }
+            div { class: "space-y-2 mt-1",
+                p { class: "font-normal italic text-xs text-gray-400 mb-1",
+                    "Jumps when walking makes no progress for a few ticks to clear small obstacles."
+                }
+                Checkbox {
+                    label: "Jump When Adjusting Is Stuck",
+                    label_class: LABEL_CLASS,
+                    div_class: DIV_CLASS,
+                    input_class: "w-44 h-6 align-middle",
+                    disabled: is_disabled(),
+                    on_input: move |adjust_unstuck_jump| {
+                        on_config(ConfigurationData {
+                            adjust_unstuck_jump,
+                            ..config_view.peek().clone()
+                        });
+                    },
+                    value: config_view().adjust_unstuck_jump,
+                }
+            }
+            div { class: "space-y-2 mt-1",
+                p { class: "font-normal italic text-xs text-gray-400 mb-1",
+                    "Uses key while walking near the action position for classes that can attack while moving."
+                }
+                Checkbox {
+                    label: "Attack While Adjusting",
+                    label_class: LABEL_CLASS,
+                    div_class: DIV_CLASS,
+                    input_class: "w-44 h-6 align-middle",
+                    disabled: is_disabled(),
+                    on_input: move |adjust_attack_while_walking| {
+                        on_config(ConfigurationData {
+                            adjust_attack_while_walking,
+                            ..config_view.peek().clone()
+                        });
+                    },
+                    value: config_view().adjust_attack_while_walking,
+                }
+            }
+            div { class: "space-y-2 mt-1",
+                p { class: "font-normal italic text-xs text-gray-400 mb-1",
+                    "Shortens small adjusting movement near the destination and accepts the position after too many direction reversals, 0 to disable."
+                }
+                PercentageInput {
+                    label: "Adjusting Damping",
+                    div_class: DIV_CLASS,
+                    label_class: LABEL_CLASS,
+                    input_class: INPUT_CLASS,
+                    disabled: is_disabled(),
+                    on_input: move |adjust_damping_percent| {
+                        on_config(ConfigurationData {
+                            adjust_damping_percent,
+                            ..config_view.peek().clone()
+                        });
+                    },
+                    value: config_view().adjust_damping_percent,
+                }
+                NumberInputU32 {
+                    label: "Adjusting Max Corrections",
+                    div_class: DIV_CLASS,
+                    label_class: LABEL_CLASS,
+                    input_class: INPUT_CLASS,
+                    disabled: is_disabled(),
+                    minimum_value: 0,
+                    on_input: move |adjust_max_correction_cycles| {
+                        on_config(ConfigurationData {
+                            adjust_max_correction_cycles,
+                            ..config_view.peek().clone()
+                        });
+                    },
+                    value: config_view().adjust_max_correction_cycles,
+                }
+            }
+            div { class: "space-y-2 mt-1",
+                p { class: "font-normal italic text-xs text-gray-400 mb-1",
+                    "Holds the jump key for higher jumps when navigating. Set to 0 to only tap the jump key."
+                }
+                ConfigMillisInput {
+                    label: "Jump Hold Milliseconds",
+                    disabled: is_disabled(),
+                    on_input: move |jump_hold_millis| {
+                        on_config(ConfigurationData {
+                            jump_hold_millis,
+                            ..config_view.peek().clone()
+                        });
+                    },
+                    value: config_view().jump_hold_millis,
+                }
+            }
+            div { class: "space-y-2 mt-1",
+                p { class: "font-normal italic text-xs text-gray-400 mb-1",
+                    "Lower velocities wait for the character to be more stationary before using stationary skills. Stationary skill velocity allows using stationary skills with small drift, 0 to disable."
+                }
+                NumberInputF32 {
+                    label: "Near Stationary X Velocity",
+                    div_class: DIV_CLASS,
+                    label_class: LABEL_CLASS,
+                    input_class: INPUT_CLASS,
+                    disabled: is_disabled(),
+                    on_input: move |near_stationary_x_velocity_threshold| {
+                        on_config(ConfigurationData {
+                            near_stationary_x_velocity_threshold,
+                            ..config_view.peek().clone()
+                        });
+                    },
+                    value: config_view().near_stationary_x_velocity_threshold,
+                }
+                NumberInputF32 {
+                    label: "Near Stationary Y Velocity",
+                    div_class: DIV_CLASS,
+                    label_class: LABEL_CLASS,
+                    input_class: INPUT_CLASS,
+                    disabled: is_disabled(),
+                    on_input: move |near_stationary_y_velocity_threshold| {
+                        on_config(ConfigurationData {
+                            near_stationary_y_velocity_threshold,
+                            ..config_view.peek().clone()
+                        });
+                    },
+                    value: config_view().near_stationary_y_velocity_threshold,
+                }
+                NumberInputF32 {
+                    label: "Stationary Skill Velocity",
+                    div_class: DIV_CLASS,
+                    label_class: LABEL_CLASS,
+                    input_class: INPUT_CLASS,
+                    disabled: is_disabled(),
+                    on_input: move |stationary_cast_velocity_threshold| {
+                        on_config(ConfigurationData {
+                            stationary_cast_velocity_threshold,
+                            ..config_view.peek().clone()
+                        });
+                    },
+                    value: config_view().stationary_cast_velocity_threshold,
+                }
+            }
+            div { class: "space-y-2 mt-1",
+                p { class: "font-normal italic text-xs text-gray-400 mb-1",
+                    "Scales the distances used to pick between double jump, walking and jump. Increase if the character overshoots from higher movement speed, decrease if undershoots."
+                }
+                NumberInputF32 {
+                    label: "Movement Speed Factor",
+                    div_class: DIV_CLASS,
+                    label_class: LABEL_CLASS,
+                    input_class: INPUT_CLASS,
+                    disabled: is_disabled(),
+                    on_input: move |movement_speed_factor| {
+                        on_config(ConfigurationData {
+                            movement_speed_factor,
+                            ..config_view.peek().clone()
+                        });
+                    },
+                    value: config_view().movement_speed_factor,
+                }
+            }
         }
     }
 }
@@ -575,6 +793,210 @@ fn ConfigBuffKeyBindings(
             },
             value: Some(config_view().extreme_gold_potion_key),
         }
+        ConfigBuffAll { is_disabled, config_view, on_config }
+        ConfigTimerOnlyBuffs { is_disabled, config_view, on_config }
+        ConfigSkipBuffInCombat { is_disabled, config_view, on_config }
+    }
+}
+
+#[component]
+fn ConfigSkipBuffInCombat(
+    is_disabled: Memo<bool>,
+    config_view: Memo<ConfigurationData>,
+    on_config: EventHandler<ConfigurationData>,
+) -> Element {
+    rsx! {
+        div { class: "space-y-2 pt-3",
+            ConfigHeader { text: "Skip Buffs In Combat", disabled: is_disabled() }
+            p { class: "font-normal italic text-xs text-gray-400 mb-1",
+                "Waits for a lull in attacking before casting buffs, up to 30 seconds."
+            }
+            Checkbox {
+                label: "Enabled",
+                label_class: LABEL_CLASS,
+                div_class: DIV_CLASS,
+                input_class: "w-44 h-6 align-middle",
+                disabled: is_disabled(),
+                on_input: move |skip_buff_in_combat| {
+                    on_config(ConfigurationData {
+                        skip_buff_in_combat,
+                        ..config_view.peek().clone()
+                    });
+                },
+                value: config_view().skip_buff_in_combat,
+            }
+            ConfigMillisInput {
+                label: "Lull Milliseconds",
+                disabled: is_disabled() || !config_view().skip_buff_in_combat,
+                on_input: move |buff_combat_lull_millis| {
+                    on_config(ConfigurationData {
+                        buff_combat_lull_millis,
+                        ..config_view.peek().clone()
+                    });
+                },
+                value: config_view().buff_combat_lull_millis,
+            }
+        }
+    }
+}
+
+#[component]
+fn ConfigTimerOnlyBuffs(
+    is_disabled: Memo<bool>,
+    config_view: Memo<ConfigurationData>,
+    on_config: EventHandler<ConfigurationData>,
+) -> Element {
+    let buffs_view = use_memo(move || config_view().timer_only_buffs);
+
+    rsx! {
+        div { class: "space-y-2 pt-3",
+            ConfigHeader { text: "Timer Only Buffs", disabled: is_disabled() }
+            p { class: "font-normal italic text-xs text-gray-400 mb-1",
+                "Recasts the checked buffs on a timer instead of detecting them."
+            }
+            ConfigMillisInput {
+                label: "Recast Milliseconds",
+                disabled: is_disabled() || buffs_view().is_empty(),
+                on_input: move |timer_only_buff_recast_millis| {
+                    on_config(ConfigurationData {
+                        timer_only_buff_recast_millis,
+                        ..config_view.peek().clone()
+                    });
+                },
+                value: config_view().timer_only_buff_recast_millis,
+            }
+            for kind in BuffKind::iter() {
+                Checkbox {
+                    label: buff_kind_label(kind),
+                    label_class: LABEL_CLASS,
+                    div_class: DIV_CLASS,
+                    input_class: "w-44 h-6 align-middle",
+                    disabled: is_disabled(),
+                    on_input: move |checked| {
+                        let mut timer_only_buffs = buffs_view.peek().clone();
+                        timer_only_buffs.retain(|buff| *buff != kind);
+                        if checked {
+                            timer_only_buffs.push(kind);
+                        }
+                        on_config(ConfigurationData {
+                            timer_only_buffs,
+                            ..config_view.peek().clone()
+                        });
+                    },
+                    value: buffs_view().contains(&kind),
+                }
+            }
+        }
+    }
+}
+
+fn buff_kind_label(kind: BuffKind) -> &'static str {
+    match kind {
+        BuffKind::Familiar => FAMILIAR_BUFF,
+        BuffKind::SayramElixir => SAYRAM_ELIXIR,
+        BuffKind::AureliaElixir => AURELIA_ELIXIR,
+        BuffKind::ExpCouponX3 => EXP_X3,
+        BuffKind::BonusExpCoupon => BONUS_EXP,
+        BuffKind::LegionWealth => LEGION_WEALTH,
+        BuffKind::LegionLuck => LEGION_LUCK,
+        BuffKind::WealthAcquisitionPotion => WEALTH_ACQUISITION_POTION,
+        BuffKind::ExpAccumulationPotion => EXP_ACCUMULATION_POTION,
+        BuffKind::ExtremeRedPotion => EXTREME_RED_POTION,
+        BuffKind::ExtremeBluePotion => EXTREME_BLUE_POTION,
+        BuffKind::ExtremeGreenPotion => EXTREME_GREEN_POTION,
+        BuffKind::ExtremeGoldPotion => EXTREME_GOLD_POTION,
+    }
+}
+
+#[component]
+fn ConfigBuffAll(
+    is_disabled: Memo<bool>,
+    config_view: Memo<ConfigurationData>,
+    on_config: EventHandler<ConfigurationData>,
+) -> Element {
+    let keys_view = use_memo(move || config_view().buff_all_keys);
+    let keys_disabled = use_memo(move || is_disabled() || !config_view().enable_buff_all);
+
+    rsx! {
+        div { class: "space-y-2 pt-3",
+            ConfigHeader { text: "Buff All", disabled: is_disabled() }
+            p { class: "font-normal italic text-xs text-gray-400 mb-1",
+                "Presses all keys in order when any enabled buff bound to one of the keys is missing. Other buffs are cast individually."
+            }
+            Checkbox {
+                label: "Enabled",
+                label_class: LABEL_CLASS,
+                div_class: DIV_CLASS,
+                input_class: "w-44 h-6 align-middle",
+                disabled: is_disabled(),
+                on_input: move |enable_buff_all| {
+                    on_config(ConfigurationData {
+                        enable_buff_all,
+                        ..config_view.peek().clone()
+                    });
+                },
+                value: config_view().enable_buff_all,
+            }
+            ConfigMillisInput {
+                label: "Spacing Milliseconds",
+                disabled: keys_disabled(),
+                on_input: move |buff_all_spacing_millis| {
+                    on_config(ConfigurationData {
+                        buff_all_spacing_millis,
+                        ..config_view.peek().clone()
+                    });
+                },
+                value: config_view().buff_all_spacing_millis,
+            }
+            for (i , key) in keys_view().into_iter().enumerate() {
+                div { class: "flex items-center space-x-2",
+                    div { class: "flex-1",
+                        KeyBindingInput {
+                            label: format!("Key {}", i + 1),
+                            label_class: LABEL_CLASS,
+                            div_class: DIV_CLASS,
+                            input_class: INPUT_CLASS,
+                            disabled: keys_disabled(),
+                            on_input: move |key| {
+                                let mut buff_all_keys = keys_view.peek().clone();
+                                *buff_all_keys.get_mut(i).unwrap() = key;
+                                on_config(ConfigurationData {
+                                    buff_all_keys,
+                                    ..config_view.peek().clone()
+                                });
+                            },
+                            value: key,
+                        }
+                    }
+                    button {
+                        class: "w-5 h-5 border border-red-500 p-1",
+                        disabled: keys_disabled(),
+                        onclick: move |_| {
+                            let mut buff_all_keys = keys_view.peek().clone();
+                            buff_all_keys.remove(i);
+                            on_config(ConfigurationData {
+                                buff_all_keys,
+                                ..config_view.peek().clone()
+                            });
+                        },
+                        XIcon { class: "w-full h-full text-red-400 fill-current" }
+                    }
+                }
+            }
+            button {
+                class: "w-full button-secondary h-6",
+                disabled: keys_disabled(),
+                onclick: move |_| {
+                    let mut buff_all_keys = keys_view.peek().clone();
+                    buff_all_keys.push(KeyBinding::default());
+                    on_config(ConfigurationData {
+                        buff_all_keys,
+                        ..config_view.peek().clone()
+                    });
+                },
+                "Add key"
+            }
+        }
     }
 }
 
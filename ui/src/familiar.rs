@@ -1,9 +1,12 @@
-use backend::{FamiliarRarity, Familiars as FamiliarsData, Settings, SwappableFamiliars};
+use backend::{
+    FamiliarRarity, Familiars as FamiliarsData, NoSwappableCardsOutcome, Settings,
+    SwappableFamiliars,
+};
 use dioxus::prelude::*;
 
 use crate::{
     AppMessage,
-    input::MillisInput,
+    input::{MillisInput, NumberInputU32},
     settings::{SettingsCheckbox, SettingsEnumSelect},
 };
 
@@ -33,6 +36,16 @@ pub fn Familiars(
                 },
                 value: familiars_view().enable_familiars_swapping,
             }
+            SettingsCheckbox {
+                label: "Move To Map Home Position Before Swapping",
+                on_input: move |move_to_safe_spot| {
+                    on_familiars(FamiliarsData {
+                        move_to_safe_spot,
+                        ..familiars_view.peek().clone()
+                    });
+                },
+                value: familiars_view().move_to_safe_spot,
+            }
             MillisInput {
                 label: "Swap Check Every Milliseconds",
                 div_class: "flex items-center space-x-4",
@@ -47,6 +60,34 @@ pub fn Familiars(
                 },
                 value: familiars_view().swap_check_millis,
             }
+            MillisInput {
+                label: "Essence Check Every Milliseconds",
+                div_class: "flex items-center space-x-4",
+                label_class: "text-xs text-gray-700 flex-1 inline-block data-[disabled]:text-gray-400",
+                input_class: "w-44 h-7 text-xs text-gray-700 text-ellipsis border border-gray-300 rounded outline-none disabled:cursor-not-allowed disabled:text-gray-400",
+                disabled: false,
+                on_input: move |essence_check_millis| {
+                    on_familiars(FamiliarsData {
+                        essence_check_millis,
+                        ..familiars_view.peek().clone()
+                    });
+                },
+                value: familiars_view().essence_check_millis,
+            }
+            NumberInputU32 {
+                label: "Ticks To Wait Before Detecting Hovered Familiar Level",
+                div_class: "flex items-center space-x-4",
+                label_class: "text-xs text-gray-700 flex-1 inline-block data-[disabled]:text-gray-400",
+                input_class: "w-44 h-7 text-xs text-gray-700 text-ellipsis border border-gray-300 rounded outline-none disabled:cursor-not-allowed disabled:text-gray-400",
+                minimum_value: 1,
+                on_input: move |hover_settle_ticks| {
+                    on_familiars(FamiliarsData {
+                        hover_settle_ticks,
+                        ..familiars_view.peek().clone()
+                    });
+                },
+                value: familiars_view().hover_settle_ticks,
+            }
             SettingsEnumSelect::<SwappableFamiliars> {
                 label: "Swappable Slots",
                 on_select: move |swappable_familiars| {
@@ -58,6 +99,17 @@ pub fn Familiars(
                 disabled: false,
                 selected: familiars_view().swappable_familiars,
             }
+            SettingsEnumSelect::<NoSwappableCardsOutcome> {
+                label: "When No Swappable Card Is Found",
+                on_select: move |no_swappable_cards_outcome| {
+                    on_familiars(FamiliarsData {
+                        no_swappable_cards_outcome,
+                        ..familiars_view.peek().clone()
+                    });
+                },
+                disabled: false,
+                selected: familiars_view().no_swappable_cards_outcome,
+            }
             SettingsCheckbox {
                 label: "Allow Swapping Rare Familiar",
                 on_input: move |enabled| {
@@ -228,6 +228,43 @@ pub fn PercentageInput(
     }
 }
 
+#[component]
+pub fn NumberInputF32(
+    GenericInputProps {
+        label,
+        label_class,
+        div_class,
+        input_class,
+        disabled,
+        on_input,
+        value,
+    }: GenericInputProps<f32>,
+) -> Element {
+    let input_id = use_memo(|| Alphanumeric.sample_string(&mut rand::rng(), 8));
+    use_auto_numeric(
+        input_id,
+        value.to_string(),
+        Some(EventHandler::new(move |value: String| {
+            if let Ok(value) = value.parse::<f32>() {
+                on_input(value)
+            }
+        })),
+        "0".to_string(),
+        f32::MAX.to_string(),
+        String::default(),
+    );
+
+    rsx! {
+        LabeledInput {
+            label,
+            label_class,
+            div_class,
+            disabled,
+            input { id: input_id(), disabled, class: input_class }
+        }
+    }
+}
+
 // Please https://github.com/DioxusLabs/dioxus/issues/3938
 #[component]
 pub fn NumberInputU32(
@@ -254,6 +291,29 @@ pub fn NumberInputU32(
     }
 }
 
+#[component]
+pub fn NumberInputU8(
+    label: String,
+    #[props(default = String::default())] label_class: String,
+    #[props(default = String::default())] div_class: String,
+    #[props(default = String::default())] input_class: String,
+    #[props(default = false)] disabled: bool,
+    on_input: EventHandler<u8>,
+    value: u8,
+) -> Element {
+    rsx! {
+        PrimIntInput {
+            label,
+            label_class,
+            div_class,
+            input_class,
+            disabled,
+            on_input,
+            value,
+        }
+    }
+}
+
 #[component]
 pub fn NumberInputI32(
     GenericInputProps {
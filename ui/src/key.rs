@@ -157,7 +157,7 @@ pub fn KeyInput(
                 },
                 onkeydown: move |e: Event<KeyboardData>| async move {
                     e.prevent_default();
-                    if let Some(key) = map_key(e.key()) {
+                    if let Some(key) = map_key(e.key(), e.code()) {
                         if let Some(input) = input_element().as_ref() {
                             let _ = input.set_focus(false).await;
                         }
@@ -180,7 +180,11 @@ pub fn KeyInput(
     }
 }
 
-fn map_key(key: Key) -> Option<KeyBinding> {
+fn map_key(key: Key, code: Code) -> Option<KeyBinding> {
+    // Numpad keys produce the same `Key` as their main keyboard counterparts
+    if let Some(key) = map_numpad_code(code) {
+        return Some(key);
+    }
     Some(match key {
         Key::Character(s) => match s.to_lowercase().as_str() {
             "a" => KeyBinding::A,
@@ -225,6 +229,11 @@ fn map_key(key: Key) -> Option<KeyBinding> {
             "," => KeyBinding::Comma,
             "." => KeyBinding::Period,
             "/" => KeyBinding::Slash,
+            "-" => KeyBinding::Minus,
+            "=" => KeyBinding::Equal,
+            "[" => KeyBinding::LeftBracket,
+            "]" => KeyBinding::RightBracket,
+            "\\" => KeyBinding::Backslash,
             " " => KeyBinding::Space,
             _ => return None,
         },
@@ -255,6 +264,30 @@ fn map_key(key: Key) -> Option<KeyBinding> {
         Key::Shift => KeyBinding::Shift,
         Key::Control => KeyBinding::Ctrl,
         Key::Alt => KeyBinding::Alt,
+        Key::Tab => KeyBinding::Tab,
+        Key::CapsLock => KeyBinding::CapsLock,
+        Key::Backspace => KeyBinding::Backspace,
+        _ => return None,
+    })
+}
+
+fn map_numpad_code(code: Code) -> Option<KeyBinding> {
+    Some(match code {
+        Code::Numpad0 => KeyBinding::Numpad0,
+        Code::Numpad1 => KeyBinding::Numpad1,
+        Code::Numpad2 => KeyBinding::Numpad2,
+        Code::Numpad3 => KeyBinding::Numpad3,
+        Code::Numpad4 => KeyBinding::Numpad4,
+        Code::Numpad5 => KeyBinding::Numpad5,
+        Code::Numpad6 => KeyBinding::Numpad6,
+        Code::Numpad7 => KeyBinding::Numpad7,
+        Code::Numpad8 => KeyBinding::Numpad8,
+        Code::Numpad9 => KeyBinding::Numpad9,
+        Code::NumpadMultiply => KeyBinding::NumpadMultiply,
+        Code::NumpadAdd => KeyBinding::NumpadAdd,
+        Code::NumpadSubtract => KeyBinding::NumpadSubtract,
+        Code::NumpadDecimal => KeyBinding::NumpadDecimal,
+        Code::NumpadDivide => KeyBinding::NumpadDivide,
         _ => return None,
     })
 }
@@ -1,9 +1,10 @@
 use std::sync::Arc;
 
 use backend::{
-    Action, ActionKey, ActionMove, GameState, Minimap as MinimapData, RotationMode, create_minimap,
-    delete_map, minimap_frame, minimap_platforms_bound, player_state, query_maps, redetect_minimap,
-    rotate_actions, rotate_actions_halting, update_minimap, upsert_map,
+    Action, ActionKey, ActionMove, AutoMobbing, Bound, GameState, Minimap as MinimapData,
+    RotationMode, create_minimap, delete_map, minimap_frame, minimap_platforms_bound, player_state,
+    query_maps, redetect_minimap, rotate_actions, rotate_actions_halting, update_auto_mob_bound,
+    update_minimap, upsert_map,
 };
 use dioxus::{document::EvalError, prelude::*};
 use futures_util::StreamExt;
@@ -56,7 +57,7 @@ const MINIMAP_JS: &str = r#"
 const MINIMAP_ACTIONS_JS: &str = r#"
     const canvas = document.getElementById("canvas-minimap-actions");
     const canvasCtx = canvas.getContext("2d");
-    const [width, height, actions, boundEnabled, bound, platforms] = await dioxus.recv();
+    const [width, height, actions, boundEnabled, bound, polygon, platforms] = await dioxus.recv();
     canvasCtx.clearRect(0, 0, canvas.width, canvas.height);
     const anyActions = actions.filter((action) => action.condition === "Any");
     const erdaActions = actions.filter((action) => action.condition === "ErdaShowerOffCooldown");
@@ -65,7 +66,19 @@ const MINIMAP_ACTIONS_JS: &str = r#"
     canvasCtx.fillStyle = "rgb(255, 153, 128)";
     canvasCtx.strokeStyle = "rgb(255, 153, 128)";
     drawActions(canvas, canvasCtx, anyActions, true);
-    if (boundEnabled) {
+    if (boundEnabled && polygon.length > 0) {
+        canvasCtx.beginPath();
+        for (const [px, py] of polygon) {
+            canvasCtx.lineTo((px / width) * canvas.width, (py / height) * canvas.height);
+        }
+        canvasCtx.closePath();
+        if (polygon.length >= 3) {
+            canvasCtx.globalAlpha = 0.6;
+            canvasCtx.fill();
+            canvasCtx.globalAlpha = 1.0;
+        }
+        canvasCtx.stroke();
+    } else if (boundEnabled) {
         const x = (bound.x / width) * canvas.width;
         const y = (bound.y / height) * canvas.height;
         const w = (bound.width / width) * canvas.width;
@@ -125,6 +138,74 @@ const MINIMAP_ACTIONS_JS: &str = r#"
         ctx.stroke();
     }
 "#;
+const MINIMAP_BOUND_JS: &str = r#"
+    const canvas = document.getElementById("canvas-minimap-actions");
+    let start = null;
+    let moved = false;
+
+    function fraction(e) {
+        return [
+            Math.min(Math.max(e.offsetX / canvas.clientWidth, 0), 1),
+            Math.min(Math.max(e.offsetY / canvas.clientHeight, 0), 1),
+        ];
+    }
+    function bound(end) {
+        return [
+            Math.min(start[0], end[0]),
+            Math.min(start[1], end[1]),
+            Math.abs(end[0] - start[0]),
+            Math.abs(end[1] - start[1]),
+        ];
+    }
+    async function release(e) {
+        if (start === null) {
+            return;
+        }
+        const value = bound(fraction(e));
+        const wasMoved = moved;
+        start = null;
+        moved = false;
+        if (wasMoved) {
+            await dioxus.send([...value, true]);
+        }
+    }
+
+    canvas.addEventListener("mousedown", (e) => {
+        if (e.button !== 0 || e.shiftKey) {
+            return;
+        }
+        start = fraction(e);
+        moved = false;
+    });
+    canvas.addEventListener("mousemove", async (e) => {
+        if (start === null) {
+            return;
+        }
+        moved = true;
+        await dioxus.send([...bound(fraction(e)), false]);
+    });
+    canvas.addEventListener("mouseup", release);
+    canvas.addEventListener("mouseleave", release);
+    await new Promise(() => {});
+"#;
+
+const MINIMAP_BOUND_POLYGON_JS: &str = r#"
+    const canvas = document.getElementById("canvas-minimap-actions");
+
+    canvas.addEventListener("click", async (e) => {
+        if (!e.shiftKey) {
+            return;
+        }
+        const x = Math.min(Math.max(e.offsetX / canvas.clientWidth, 0), 1);
+        const y = Math.min(Math.max(e.offsetY / canvas.clientHeight, 0), 1);
+        await dioxus.send([x, y, false]);
+    });
+    canvas.addEventListener("contextmenu", async (e) => {
+        e.preventDefault();
+        await dioxus.send([0, 0, true]);
+    });
+    await new Promise(() => {});
+"#;
 
 #[derive(Clone, PartialEq, Serialize)]
 struct ActionView {
@@ -139,6 +220,7 @@ pub enum MinimapMessage {
     RedetectMinimap,
     CreateMinimap(String),
     UpdateMinimap(MinimapData, bool),
+    UpdateAutoMobBound(MinimapData, bool),
     UpdateMinimapPreset(String),
     DeleteMinimap,
 }
@@ -160,16 +242,7 @@ pub fn Minimap(
             .unwrap();
         if !minimaps.is_empty() && minimap.peek().is_none() {
             minimap.set(minimaps.first().cloned());
-            preset.set(
-                minimap
-                    .peek()
-                    .clone()
-                    .unwrap()
-                    .actions
-                    .keys()
-                    .next()
-                    .cloned(),
-            );
+            preset.set(minimap.peek().as_ref().unwrap().initial_preset());
             update_minimap(preset.peek().clone(), minimap.peek().clone().unwrap()).await;
         }
         minimaps
@@ -194,7 +267,14 @@ pub fn Minimap(
                         }
                     }
                     MinimapMessage::UpdateMinimap(mut data, save) => {
-                        if preset().is_none_or(|preset| !data.actions.contains_key(&preset)) {
+                        let is_switching = minimap
+                            .peek()
+                            .as_ref()
+                            .is_none_or(|minimap| minimap.id != data.id);
+                        if is_switching {
+                            preset.set(data.initial_preset());
+                        } else if preset().is_none_or(|preset| !data.actions.contains_key(&preset))
+                        {
                             preset.set(data.actions.keys().next().cloned());
                         }
                         minimap.set(Some(data.clone()));
@@ -208,10 +288,38 @@ pub fn Minimap(
                             minimaps.restart();
                         }
                     }
+                    MinimapMessage::UpdateAutoMobBound(mut data, save) => {
+                        let RotationMode::AutoMobbing(AutoMobbing { bound, .. }) =
+                            data.rotation_mode_of(preset().as_deref())
+                        else {
+                            continue;
+                        };
+                        minimap.set(Some(data.clone()));
+                        update_auto_mob_bound(bound).await;
+                        if save {
+                            spawn_blocking(move || {
+                                upsert_map(&mut data).unwrap();
+                            })
+                            .await
+                            .unwrap();
+                            minimaps.restart();
+                        }
+                    }
                     MinimapMessage::UpdateMinimapPreset(new_preset) => {
                         if preset().as_ref() != Some(&new_preset) {
-                            preset.set(Some(new_preset));
-                            update_minimap(preset(), minimap().unwrap()).await;
+                            let Some(mut data) = minimap() else {
+                                continue;
+                            };
+                            preset.set(Some(new_preset.clone()));
+                            data.last_preset = Some(new_preset);
+                            minimap.set(Some(data.clone()));
+                            update_minimap(preset(), data.clone()).await;
+                            spawn_blocking(move || {
+                                upsert_map(&mut data).unwrap();
+                            })
+                            .await
+                            .unwrap();
+                            minimaps.restart();
                         }
                     }
                     MinimapMessage::DeleteMinimap => {
@@ -236,7 +344,7 @@ pub fn Minimap(
         let preset = preset();
         let actions = minimap
             .clone()
-            .zip(preset)
+            .zip(preset.clone())
             .and_then(|(minimap, preset)| minimap.actions.get(&preset).cloned())
             .unwrap_or_default()
             .into_iter()
@@ -265,16 +373,25 @@ pub fn Minimap(
 
         let platforms_bound = platforms_bound();
         if let Some(minimap) = minimap {
-            let bound = match minimap.rotation_mode {
+            let platforms_bound = minimap
+                .auto_mob_platforms_bound
+                .then_some(platforms_bound)
+                .flatten();
+            let (bound, polygon) = match minimap.rotation_mode_of(preset.as_deref()) {
                 RotationMode::AutoMobbing(mobbing) => {
-                    if minimap.auto_mob_platforms_bound {
-                        platforms_bound.or(Some(mobbing.bound))
+                    if platforms_bound.is_some() {
+                        (platforms_bound, vec![])
                     } else {
-                        Some(mobbing.bound)
+                        (Some(mobbing.bound), minimap.bound_polygon.clone())
                     }
                 }
-                RotationMode::PingPong(ping_pong) => Some(ping_pong.bound),
-                RotationMode::StartToEnd | RotationMode::StartToEndThenReverse => None,
+                RotationMode::PingPong(ping_pong) => {
+                    (Some(ping_pong.bound), minimap.bound_polygon.clone())
+                }
+                RotationMode::StartToEnd
+                | RotationMode::StartToEndThenReverse
+                | RotationMode::ShuffledCycle
+                | RotationMode::HoldSpot(_) => (None, vec![]),
             };
 
             spawn(async move {
@@ -285,6 +402,7 @@ pub fn Minimap(
                         actions,
                         bound.is_some(),
                         bound.unwrap_or_default(),
+                        polygon,
                         minimap.platforms,
                     ))
                     .unwrap();
@@ -298,12 +416,103 @@ pub fn Minimap(
             }
         }
     });
+    // drag to update auto mob bound
+    use_future(move || async move {
+        let mut eval = document::eval(MINIMAP_BOUND_JS);
+        loop {
+            let Ok((x, y, width, height, save)) = eval.recv::<(f32, f32, f32, f32, bool)>().await
+            else {
+                break;
+            };
+            let Some(mut data) = minimap.peek().clone() else {
+                continue;
+            };
+            let preset = preset.peek().clone();
+            let RotationMode::AutoMobbing(auto_mobbing) = data.rotation_mode_of(preset.as_deref())
+            else {
+                continue;
+            };
+            let bound = Bound {
+                x: (x * data.width as f32) as i32,
+                y: (y * data.height as f32) as i32,
+                width: (width * data.width as f32) as i32,
+                height: (height * data.height as f32) as i32,
+            };
+            *data.rotation_mode_of_mut(preset.as_deref()) =
+                RotationMode::AutoMobbing(AutoMobbing {
+                    bound,
+                    ..auto_mobbing
+                });
+            coroutine.send(MinimapMessage::UpdateAutoMobBound(data, save));
+        }
+    });
+    // shift click to add auto mob / ping pong polygon bound point, right click to clear
+    use_future(move || async move {
+        let mut eval = document::eval(MINIMAP_BOUND_POLYGON_JS);
+        loop {
+            let Ok((x, y, clear)) = eval.recv::<(f32, f32, bool)>().await else {
+                break;
+            };
+            let Some(mut data) = minimap.peek().clone() else {
+                continue;
+            };
+            if !matches!(
+                data.rotation_mode_of(preset.peek().as_deref()),
+                RotationMode::AutoMobbing(_) | RotationMode::PingPong(_)
+            ) {
+                continue;
+            }
+            if clear {
+                if data.bound_polygon.is_empty() {
+                    continue;
+                }
+                data.bound_polygon.clear();
+            } else {
+                data.bound_polygon.push((
+                    (x * data.width as f32) as i32,
+                    (y * data.height as f32) as i32,
+                ));
+            }
+            coroutine.send(MinimapMessage::UpdateMinimap(data, true));
+        }
+    });
     // draw minimap and update states
     use_future(move || async move {
         let mut canvas = document::eval(MINIMAP_JS);
+        let mut last_minimap_id = None;
+        let mut last_minimap_preset = None;
         loop {
             let player_state = player_state().await;
             let destinations = player_state.destinations.clone();
+            // Follows the backend when it switches to another saved map by itself
+            if last_minimap_id != player_state.minimap_id {
+                last_minimap_id = player_state.minimap_id;
+                if minimap
+                    .peek()
+                    .as_ref()
+                    .is_some_and(|data| data.id != last_minimap_id)
+                    && let Some(data) = minimaps.peek().as_ref().and_then(|minimaps| {
+                        minimaps
+                            .iter()
+                            .find(|data| data.id == last_minimap_id)
+                            .cloned()
+                    })
+                {
+                    coroutine.send(MinimapMessage::UpdateMinimap(data, false));
+                }
+            }
+            // Follows the backend when it switches to another preset by itself
+            if last_minimap_preset != player_state.minimap_preset {
+                last_minimap_preset = player_state.minimap_preset.clone();
+                if minimap
+                    .peek()
+                    .as_ref()
+                    .is_some_and(|data| data.id == last_minimap_id)
+                    && *preset.peek() != last_minimap_preset
+                {
+                    preset.set(last_minimap_preset.clone());
+                }
+            }
             let is_halting = rotate_actions_halting().await;
             let bound = minimap_platforms_bound().await;
             if halting() != is_halting {
@@ -421,6 +630,20 @@ pub fn Minimap(
                                 .unwrap_or("Erda Shower: Unknown".to_string())
                         }
                     }
+                    p {
+                        {
+                            state()
+                                .map(|state| {
+                                    let status = if state.input_connected {
+                                        "Connected"
+                                    } else {
+                                        "Disconnected"
+                                    };
+                                    format!("Input: {status}")
+                                })
+                                .unwrap_or("Input: Unknown".to_string())
+                        }
+                    }
                 }
             }
             div { class: "flex w-full space-x-6 items-center justify-center items-stretch h-7",
@@ -3,7 +3,10 @@ use dioxus::prelude::*;
 
 use crate::{
     AppMessage,
-    settings::{SettingsCheckbox, SettingsTextInput},
+    input::MillisInput,
+    settings::{
+        SELECT_CLASS, SELECT_DIV_CLASS, SELECT_LABEL_CLASS, SettingsCheckbox, SettingsTextInput,
+    },
 };
 
 #[component]
@@ -42,6 +45,19 @@ pub fn Notifications(
                 },
                 value: notifications_view().discord_user_id,
             }
+            MillisInput {
+                label: "Minimum Interval Between Identical Notifications",
+                div_class: SELECT_DIV_CLASS,
+                label_class: SELECT_LABEL_CLASS,
+                input_class: SELECT_CLASS,
+                on_input: move |notification_cooldown_millis| {
+                    on_notifications(NotificationsData {
+                        notification_cooldown_millis,
+                        ..notifications_view.peek().clone()
+                    });
+                },
+                value: notifications_view().notification_cooldown_millis,
+            }
             SettingsCheckbox {
                 label: "Notify If Fails / Changes Map",
                 on_input: move |notify_on_fail_or_change_map| {
@@ -62,6 +78,96 @@ pub fn Notifications(
                 },
                 value: notifications_view().notify_on_rune_appear,
             }
+            SettingsCheckbox {
+                label: "Notify If Rune Solving Is Abandoned",
+                on_input: move |notify_on_rune_abandoned| {
+                    on_notifications(NotificationsData {
+                        notify_on_rune_abandoned,
+                        ..notifications_view.peek().clone()
+                    });
+                },
+                value: notifications_view().notify_on_rune_abandoned,
+            }
+            SettingsCheckbox {
+                label: "Notify If Rune Is Solved",
+                on_input: move |notify_on_rune_solved| {
+                    on_notifications(NotificationsData {
+                        notify_on_rune_solved,
+                        ..notifications_view.peek().clone()
+                    });
+                },
+                value: notifications_view().notify_on_rune_solved,
+            }
+            SettingsCheckbox {
+                label: "Notify If Capture Handle Is Re-selected",
+                on_input: move |notify_on_capture_handle_reselected| {
+                    on_notifications(NotificationsData {
+                        notify_on_capture_handle_reselected,
+                        ..notifications_view.peek().clone()
+                    });
+                },
+                value: notifications_view().notify_on_capture_handle_reselected,
+            }
+            SettingsCheckbox {
+                label: "Notify If Going To Town Fails",
+                on_input: move |notify_on_panic_to_town_failed| {
+                    on_notifications(NotificationsData {
+                        notify_on_panic_to_town_failed,
+                        ..notifications_view.peek().clone()
+                    });
+                },
+                value: notifications_view().notify_on_panic_to_town_failed,
+            }
+            SettingsCheckbox {
+                label: "Notify If Input Server Disconnects",
+                on_input: move |notify_on_input_disconnected| {
+                    on_notifications(NotificationsData {
+                        notify_on_input_disconnected,
+                        ..notifications_view.peek().clone()
+                    });
+                },
+                value: notifications_view().notify_on_input_disconnected,
+            }
+            SettingsCheckbox {
+                label: "Notify If Captured Frame Stays Blank",
+                on_input: move |notify_on_capture_invalid| {
+                    on_notifications(NotificationsData {
+                        notify_on_capture_invalid,
+                        ..notifications_view.peek().clone()
+                    });
+                },
+                value: notifications_view().notify_on_capture_invalid,
+            }
+            SettingsCheckbox {
+                label: "Notify If Lost Position Recovery Fails",
+                on_input: move |notify_on_lost_position_recovery_failed| {
+                    on_notifications(NotificationsData {
+                        notify_on_lost_position_recovery_failed,
+                        ..notifications_view.peek().clone()
+                    });
+                },
+                value: notifications_view().notify_on_lost_position_recovery_failed,
+            }
+            SettingsCheckbox {
+                label: "Notify If Stopped Because Stuck",
+                on_input: move |notify_on_stuck| {
+                    on_notifications(NotificationsData {
+                        notify_on_stuck,
+                        ..notifications_view.peek().clone()
+                    });
+                },
+                value: notifications_view().notify_on_stuck,
+            }
+            SettingsCheckbox {
+                label: "Notify If Stopped Because Of Too Many Channel Changes",
+                on_input: move |notify_on_channel_change_limit| {
+                    on_notifications(NotificationsData {
+                        notify_on_channel_change_limit,
+                        ..notifications_view.peek().clone()
+                    });
+                },
+                value: notifications_view().notify_on_channel_change_limit,
+            }
             SettingsCheckbox {
                 label: "Notify If Elite Boss Appears",
                 on_input: move |notify_on_elite_boss_appear| {
@@ -126,6 +126,19 @@ pub fn Platforms(
                 },
                 value: minimap().map(|data| data.auto_mob_platforms_bound).unwrap_or_default(),
             }
+            PlatformCheckbox {
+                label: "Pathing Walk Off Then Double Jump",
+                disabled: minimap().is_none(),
+                on_input: move |walk_off| {
+                    if let Some(mut minimap) = minimap.peek().clone() {
+                        minimap.platforms_pathing_walk_off_double_jump = walk_off;
+                        on_save(minimap);
+                    }
+                },
+                value: minimap()
+                    .map(|data| data.platforms_pathing_walk_off_double_jump)
+                    .unwrap_or_default(),
+            }
             div { class: "flex items-center justify-between text-xs text-gray-700 border-b border-gray-300 mt-3 mb-2 data-[disabled]:text-gray-400",
                 p { class: "w-26", "X Start" }
                 p { class: "w-26", "X End" }
@@ -1,8 +1,10 @@
-use backend::{AutoMobbing, Bound, PingPong, RotationMode};
+use backend::{
+    AutoMobbing, Bound, EmptyMapAction, HoldSpot, KeyBinding, PingPong, Position, RotationMode,
+};
 use dioxus::prelude::*;
 
 use crate::{
-    input::{Checkbox, KeyBindingInput, MillisInput, NumberInputI32, NumberInputU32},
+    input::{Checkbox, KeyBindingInput, LabeledInput, MillisInput, NumberInputI32, NumberInputU32},
     select::EnumSelect,
 };
 
@@ -15,9 +17,22 @@ pub fn Rotations(
     disabled: bool,
     on_rotation_mode: EventHandler<RotationMode>,
     on_reset_on_erda: EventHandler<bool>,
+    on_merge_distance: EventHandler<i32>,
+    on_turn_around_key: EventHandler<Option<KeyBinding>>,
+    on_home: EventHandler<(Position, u64)>,
+    on_actions_offset: EventHandler<(i32, i32)>,
     rotation_mode: RotationMode,
     reset_on_erda: bool,
+    merge_distance: i32,
+    turn_around_key: Option<KeyBinding>,
+    home: (Position, u64),
+    actions_offset: (i32, i32),
 ) -> Element {
+    let (home_position, home_idle_millis) = home;
+    let turn_around_disabled =
+        disabled || !matches!(rotation_mode, RotationMode::StartToEndThenReverse);
+    let (actions_offset_x, actions_offset_y) = actions_offset;
+
     rsx! {
         div { class: "flex flex-col space-y-2",
             ul { class: "list-disc text-xs text-gray-700 pl-4",
@@ -28,7 +43,17 @@ pub fn Rotations(
                 }
                 li { "Mob detected outside of bound is ignored" }
                 li { "Auto mobbing X,Y origin is top-left of minimap" }
+                li { "Auto mobbing bound can be updated by dragging on the minimap" }
                 li { "Overrides the below bound if auto mobbing bound by platforms enabled" }
+                li { "Polygon bound point is added by shift click and cleared by right click" }
+                li { "Polygon bound with at least 3 points overrides the below bound" }
+                li { "Key count is the maximum if scaling key count with mobs enabled" }
+                li { "Platform dwell keeps farming the busiest platform until empty that long" }
+                li { "Hold spot X,Y is the same as action position" }
+                li { "Action within merge distance of the player is used without moving" }
+                li { "Turn around key is used after reaching either end before reversing" }
+                li { "Player moves back to home X,Y after being idle for the home idle duration" }
+                li { "Actions offset X,Y is added to every action position for calibration" }
             }
             div { class: "h-2 border-b border-gray-300 mb-2" }
             EnumSelect {
@@ -50,13 +75,99 @@ pub fn Rotations(
                 disabled: disabled
                     | matches!(
                         rotation_mode,
-                        RotationMode::PingPong(_) | RotationMode::AutoMobbing(_)
+                        RotationMode::PingPong(_)
+                        | RotationMode::AutoMobbing(_)
+                        | RotationMode::HoldSpot(_)
                     ),
                 on_input: move |checked| {
                     on_reset_on_erda(checked);
                 },
                 value: reset_on_erda,
             }
+            NumberInputI32 {
+                label: "Merge Distance (0 = Disabled)",
+                div_class: DIV_CLASS,
+                label_class: LABEL_CLASS,
+                input_class: INPUT_CLASS,
+                disabled: disabled || !matches!(rotation_mode, RotationMode::StartToEndThenReverse),
+                on_input: move |distance| {
+                    on_merge_distance(distance);
+                },
+                value: merge_distance,
+            }
+            Checkbox {
+                label: "Use Key On Turn Around",
+                label_class: LABEL_CLASS,
+                div_class: DIV_CLASS,
+                input_class: "w-36 text-xs text-gray-700 text-ellipsis rounded outline-none disabled:cursor-not-allowed disabled:text-gray-400",
+                disabled: turn_around_disabled,
+                on_input: move |checked: bool| {
+                    on_turn_around_key(checked.then_some(KeyBinding::default()));
+                },
+                value: turn_around_key.is_some(),
+            }
+            if let Some(key) = turn_around_key {
+                KeyBindingInput {
+                    label: "Turn Around Key",
+                    label_class: LABEL_CLASS,
+                    div_class: DIV_CLASS,
+                    input_class: INPUT_CLASS,
+                    disabled: turn_around_disabled,
+                    on_input: move |key| {
+                        on_turn_around_key(Some(key));
+                    },
+                    value: key,
+                }
+            }
+            MillisInput {
+                label: "Home Idle Duration (0 = Disabled)",
+                div_class: DIV_CLASS,
+                label_class: LABEL_CLASS,
+                input_class: INPUT_CLASS,
+                disabled,
+                on_input: move |idle_millis| {
+                    on_home((home_position, idle_millis));
+                },
+                value: home_idle_millis,
+            }
+            NumberInputI32 {
+                label: "Home X",
+                div_class: DIV_CLASS,
+                label_class: LABEL_CLASS,
+                input_class: INPUT_CLASS,
+                disabled: disabled || home_idle_millis == 0,
+                on_input: move |x| {
+                    on_home((Position { x, ..home_position }, home_idle_millis));
+                },
+                value: home_position.x,
+            }
+            NumberInputI32 {
+                label: "Home Y",
+                div_class: DIV_CLASS,
+                label_class: LABEL_CLASS,
+                input_class: INPUT_CLASS,
+                disabled: disabled || home_idle_millis == 0,
+                on_input: move |y| {
+                    on_home((Position { y, ..home_position }, home_idle_millis));
+                },
+                value: home_position.y,
+            }
+            ActionsOffsetNudge {
+                label: "Actions Offset X",
+                disabled,
+                on_nudge: move |x| {
+                    on_actions_offset((x, actions_offset_y));
+                },
+                value: actions_offset_x,
+            }
+            ActionsOffsetNudge {
+                label: "Actions Offset Y",
+                disabled,
+                on_nudge: move |y| {
+                    on_actions_offset((actions_offset_x, y));
+                },
+                value: actions_offset_y,
+            }
             if let RotationMode::AutoMobbing(auto_mobbing) = rotation_mode {
                 AutoMobbingInput {
                     disabled,
@@ -73,6 +184,58 @@ pub fn Rotations(
                     },
                     value: ping_pong,
                 }
+            } else if let RotationMode::HoldSpot(hold_spot) = rotation_mode {
+                HoldSpotInput {
+                    disabled,
+                    on_input: move |hold_spot| {
+                        on_rotation_mode(RotationMode::HoldSpot(hold_spot));
+                    },
+                    value: hold_spot,
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ActionsOffsetNudge(
+    label: String,
+    disabled: bool,
+    on_nudge: EventHandler<i32>,
+    value: i32,
+) -> Element {
+    rsx! {
+        LabeledInput {
+            label,
+            label_class: LABEL_CLASS,
+            div_class: DIV_CLASS,
+            disabled,
+            div { class: "flex w-36 space-x-1 items-center",
+                button {
+                    class: "button-secondary w-6 h-6",
+                    disabled,
+                    onclick: move |_| {
+                        on_nudge(value - 1);
+                    },
+                    "-"
+                }
+                span { class: "flex-1 text-xs text-center text-gray-700", "{value}" }
+                button {
+                    class: "button-secondary w-6 h-6",
+                    disabled,
+                    onclick: move |_| {
+                        on_nudge(value + 1);
+                    },
+                    "+"
+                }
+                button {
+                    class: "button-tertiary h-6 px-1",
+                    disabled: disabled || value == 0,
+                    onclick: move |_| {
+                        on_nudge(0);
+                    },
+                    "Reset"
+                }
             }
         }
     }
@@ -90,6 +253,12 @@ fn AutoMobbingInput(
         key_count,
         key_wait_before_millis,
         key_wait_after_millis,
+        empty_map_action,
+        empty_map_timeout_millis,
+        scale_count_with_mobs,
+        retarget_interval_millis,
+        prefer_center,
+        platform_dwell_millis,
     } = value;
 
     rsx! {
@@ -116,6 +285,34 @@ fn AutoMobbingInput(
             },
             value: key_count,
         }
+        Checkbox {
+            label: "Scale Key Count With Mobs",
+            label_class: LABEL_CLASS,
+            div_class: DIV_CLASS,
+            input_class: "w-36 text-xs text-gray-700 text-ellipsis rounded outline-none disabled:cursor-not-allowed disabled:text-gray-400",
+            disabled,
+            on_input: move |scale_count_with_mobs| {
+                on_input(AutoMobbing {
+                    scale_count_with_mobs,
+                    ..value
+                });
+            },
+            value: scale_count_with_mobs,
+        }
+        Checkbox {
+            label: "Prefer Mobs Near Center",
+            label_class: LABEL_CLASS,
+            div_class: DIV_CLASS,
+            input_class: "w-36 text-xs text-gray-700 text-ellipsis rounded outline-none disabled:cursor-not-allowed disabled:text-gray-400",
+            disabled,
+            on_input: move |prefer_center| {
+                on_input(AutoMobbing {
+                    prefer_center,
+                    ..value
+                });
+            },
+            value: prefer_center,
+        }
         MillisInput {
             label: "Key Wait Before",
             div_class: DIV_CLASS,
@@ -144,6 +341,34 @@ fn AutoMobbingInput(
             },
             value: key_wait_after_millis,
         }
+        MillisInput {
+            label: "Min Re-target Interval",
+            div_class: DIV_CLASS,
+            label_class: LABEL_CLASS,
+            input_class: INPUT_CLASS,
+            disabled,
+            on_input: move |retarget_interval_millis| {
+                on_input(AutoMobbing {
+                    retarget_interval_millis,
+                    ..value
+                });
+            },
+            value: retarget_interval_millis,
+        }
+        MillisInput {
+            label: "Platform Dwell (0 = Disabled)",
+            div_class: DIV_CLASS,
+            label_class: LABEL_CLASS,
+            input_class: INPUT_CLASS,
+            disabled,
+            on_input: move |platform_dwell_millis| {
+                on_input(AutoMobbing {
+                    platform_dwell_millis,
+                    ..value
+                });
+            },
+            value: platform_dwell_millis,
+        }
         NumberInputI32 {
             label: "X",
             div_class: DIV_CLASS,
@@ -200,6 +425,34 @@ fn AutoMobbingInput(
             },
             value: bound.height,
         }
+        EnumSelect {
+            label: "Empty Map Action",
+            div_class: DIV_CLASS,
+            label_class: LABEL_CLASS,
+            select_class: INPUT_CLASS,
+            disabled,
+            on_select: move |empty_map_action: EmptyMapAction| {
+                on_input(AutoMobbing {
+                    empty_map_action,
+                    ..value
+                });
+            },
+            selected: empty_map_action,
+        }
+        MillisInput {
+            label: "Empty Map Timeout",
+            div_class: DIV_CLASS,
+            label_class: LABEL_CLASS,
+            input_class: INPUT_CLASS,
+            disabled: disabled || matches!(empty_map_action, EmptyMapAction::Wait),
+            on_input: move |empty_map_timeout_millis| {
+                on_input(AutoMobbing {
+                    empty_map_timeout_millis,
+                    ..value
+                });
+            },
+            value: empty_map_timeout_millis,
+        }
     }
 }
 
@@ -213,6 +466,8 @@ fn PingPongInput(disabled: bool, on_input: EventHandler<PingPong>, value: PingPo
         key_count,
         key_wait_before_millis,
         key_wait_after_millis,
+        edge_inset,
+        alternate_key,
     } = value;
 
     rsx! {
@@ -323,5 +578,167 @@ fn PingPongInput(disabled: bool, on_input: EventHandler<PingPong>, value: PingPo
             },
             value: bound.height,
         }
+        NumberInputI32 {
+            label: "Edge Inset",
+            div_class: DIV_CLASS,
+            label_class: LABEL_CLASS,
+            input_class: INPUT_CLASS,
+            disabled,
+            on_input: move |edge_inset| {
+                on_input(PingPong { edge_inset, ..value });
+            },
+            value: edge_inset,
+        }
+        Checkbox {
+            label: "Alternate Key On Left",
+            label_class: LABEL_CLASS,
+            div_class: DIV_CLASS,
+            input_class: "w-36 text-xs text-gray-700 text-ellipsis rounded outline-none disabled:cursor-not-allowed disabled:text-gray-400",
+            disabled,
+            on_input: move |enabled: bool| {
+                on_input(PingPong {
+                    alternate_key: enabled.then_some(alternate_key.unwrap_or(key)),
+                    ..value
+                });
+            },
+            value: alternate_key.is_some(),
+        }
+        KeyBindingInput {
+            label: "Alternate Key",
+            label_class: LABEL_CLASS,
+            div_class: DIV_CLASS,
+            input_class: INPUT_CLASS,
+            disabled: disabled || alternate_key.is_none(),
+            on_input: move |key| {
+                on_input(PingPong {
+                    alternate_key: Some(key),
+                    ..value
+                });
+            },
+            value: alternate_key.unwrap_or(key),
+        }
+    }
+}
+
+#[component]
+fn HoldSpotInput(disabled: bool, on_input: EventHandler<HoldSpot>, value: HoldSpot) -> Element {
+    let HoldSpot {
+        position,
+        key,
+        key_count,
+        key_wait_before_millis,
+        key_wait_after_millis,
+        reposition_threshold,
+        turn_and_attack,
+    } = value;
+
+    rsx! {
+        KeyBindingInput {
+            label: "Key",
+            label_class: LABEL_CLASS,
+            div_class: DIV_CLASS,
+            input_class: INPUT_CLASS,
+            disabled,
+            on_input: move |key| {
+                on_input(HoldSpot { key, ..value });
+            },
+            value: key,
+        }
+        NumberInputU32 {
+            label: "Key Count",
+            div_class: DIV_CLASS,
+            label_class: LABEL_CLASS,
+            input_class: INPUT_CLASS,
+            disabled,
+            minimum_value: 1,
+            on_input: move |key_count| {
+                on_input(HoldSpot { key_count, ..value });
+            },
+            value: key_count,
+        }
+        MillisInput {
+            label: "Key Wait Before",
+            div_class: DIV_CLASS,
+            label_class: LABEL_CLASS,
+            input_class: INPUT_CLASS,
+            disabled,
+            on_input: move |key_wait_before_millis| {
+                on_input(HoldSpot {
+                    key_wait_before_millis,
+                    ..value
+                });
+            },
+            value: key_wait_before_millis,
+        }
+        MillisInput {
+            label: "Key Wait After",
+            div_class: DIV_CLASS,
+            label_class: LABEL_CLASS,
+            input_class: INPUT_CLASS,
+            disabled,
+            on_input: move |key_wait_after_millis| {
+                on_input(HoldSpot {
+                    key_wait_after_millis,
+                    ..value
+                });
+            },
+            value: key_wait_after_millis,
+        }
+        NumberInputI32 {
+            label: "X",
+            div_class: DIV_CLASS,
+            label_class: LABEL_CLASS,
+            input_class: INPUT_CLASS,
+            disabled,
+            on_input: move |x| {
+                on_input(HoldSpot {
+                    position: Position { x, ..position },
+                    ..value
+                });
+            },
+            value: position.x,
+        }
+        NumberInputI32 {
+            label: "Y",
+            div_class: DIV_CLASS,
+            label_class: LABEL_CLASS,
+            input_class: INPUT_CLASS,
+            disabled,
+            on_input: move |y| {
+                on_input(HoldSpot {
+                    position: Position { y, ..position },
+                    ..value
+                });
+            },
+            value: position.y,
+        }
+        NumberInputI32 {
+            label: "Reposition Threshold",
+            div_class: DIV_CLASS,
+            label_class: LABEL_CLASS,
+            input_class: INPUT_CLASS,
+            disabled,
+            on_input: move |reposition_threshold| {
+                on_input(HoldSpot {
+                    reposition_threshold,
+                    ..value
+                });
+            },
+            value: reposition_threshold,
+        }
+        Checkbox {
+            label: "Turn And Attack",
+            label_class: LABEL_CLASS,
+            div_class: DIV_CLASS,
+            input_class: "w-36 text-xs text-gray-700 text-ellipsis rounded outline-none disabled:cursor-not-allowed disabled:text-gray-400",
+            disabled,
+            on_input: move |turn_and_attack| {
+                on_input(HoldSpot {
+                    turn_and_attack,
+                    ..value
+                });
+            },
+            value: turn_and_attack,
+        }
     }
 }
@@ -1,29 +1,40 @@
 use std::{fmt::Display, str::FromStr};
 
 use backend::{
-    CaptureMode, InputMethod, IntoEnumIterator, KeyBindingConfiguration, PanicMode,
-    Settings as SettingsData, query_capture_handles, select_capture_handle,
+    Bound, BuffsRegion, CaptureMode, ChangeChannelBlockedFallback, InputMethod, IntoEnumIterator,
+    KeyBinding, KeyBindingConfiguration, LostPositionRecovery, PanicMode, RuneApproachSide,
+    RuneArrowPressStyle, Settings as SettingsData, query_capture_handles, select_capture_handle,
 };
 #[cfg(debug_assertions)]
-use backend::{capture_image, infer_minimap, infer_rune, record_images, test_spin_rune};
+use backend::{
+    capture_image, infer_minimap, infer_rune, record_images, test_spin_rune, view_buffs_region,
+};
 use dioxus::prelude::*;
 
 use crate::{
     AppMessage,
-    input::{Checkbox, LabeledInput},
+    icons::XIcon,
+    input::{
+        Checkbox, KeyBindingInput, LabeledInput, MillisInput, NumberInputF32, NumberInputI32,
+        NumberInputU8, NumberInputU32, PercentageInput,
+    },
     key::KeyBindingConfigurationInput,
     select::{EnumSelect, Select},
 };
 
 const TOGGLE_ACTIONS: &str = "Start/Stop Actions";
+const EMERGENCY_TOWN: &str = "Emergency Go To Town";
+const TOGGLE_MODE: &str = "Toggle Auto Mobbing/Route Preset";
+const PANIC_DEFENSIVE: &str = "Panic Defensive Skill";
 const PLATFORM_START: &str = "Mark Platform Start";
 const PLATFORM_END: &str = "Mark Platform End";
 const PLATFORM_ADD: &str = "Add Platform";
+const RUNE_CLEAR_MOBS: &str = "Rune Clear Mobs Key";
 
-const SELECT_DIV_CLASS: &str = "flex items-center space-x-4";
-const SELECT_LABEL_CLASS: &str =
+pub const SELECT_DIV_CLASS: &str = "flex items-center space-x-4";
+pub const SELECT_LABEL_CLASS: &str =
     "text-xs text-gray-700 flex-1 inline-block data-[disabled]:text-gray-400";
-const SELECT_CLASS: &str = "w-44 h-7 text-xs text-gray-700 text-ellipsis border border-gray-300 rounded outline-none disabled:cursor-not-allowed disabled:text-gray-400";
+pub const SELECT_CLASS: &str = "w-44 h-7 text-xs text-gray-700 text-ellipsis border border-gray-300 rounded outline-none disabled:cursor-not-allowed disabled:text-gray-400";
 
 #[component]
 pub fn Settings(
@@ -68,6 +79,161 @@ pub fn Settings(
                     },
                     value: settings_view().enable_rune_solving,
                 }
+                MillisInput {
+                    label: "Rune Arrows Calibration Timeout",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    disabled: !settings_view().enable_rune_solving,
+                    on_input: move |rune_calibration_timeout_millis| {
+                        on_settings(SettingsData {
+                            rune_calibration_timeout_millis,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().rune_calibration_timeout_millis,
+                }
+                MillisInput {
+                    label: "Rune Solved Verification Window",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    disabled: !settings_view().enable_rune_solving,
+                    on_input: move |rune_validate_millis| {
+                        on_settings(SettingsData {
+                            rune_validate_millis,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().rune_validate_millis,
+                }
+                NumberInputU32 {
+                    label: "Rune Arrows Calibration Max Attempts",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    disabled: !settings_view().enable_rune_solving,
+                    minimum_value: 1,
+                    on_input: move |rune_calibration_max_attempts| {
+                        on_settings(SettingsData {
+                            rune_calibration_max_attempts,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().rune_calibration_max_attempts,
+                }
+                SettingsEnumSelect::<RuneArrowPressStyle> {
+                    label: "Rune Arrows Press Style",
+                    on_select: move |rune_arrow_press_style| {
+                        on_settings(SettingsData {
+                            rune_arrow_press_style,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    disabled: !settings_view().enable_rune_solving,
+                    selected: settings_view().rune_arrow_press_style,
+                }
+                MillisInput {
+                    label: "Rune Arrows Hold Milliseconds",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    disabled: !settings_view().enable_rune_solving
+                        || matches!(
+                            settings_view().rune_arrow_press_style,
+                            RuneArrowPressStyle::Tap
+                        ),
+                    on_input: move |rune_arrow_hold_millis| {
+                        on_settings(SettingsData {
+                            rune_arrow_hold_millis,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().rune_arrow_hold_millis,
+                }
+                SettingsEnumSelect::<RuneApproachSide> {
+                    label: "Rune Approach Side",
+                    on_select: move |rune_approach_side| {
+                        on_settings(SettingsData {
+                            rune_approach_side,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    disabled: !settings_view().enable_rune_solving,
+                    selected: settings_view().rune_approach_side,
+                }
+                SettingsCheckbox {
+                    label: "Clear Mobs Before Solving Rune",
+                    on_input: move |enable_rune_clear_mobs| {
+                        on_settings(SettingsData {
+                            enable_rune_clear_mobs,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().enable_rune_clear_mobs,
+                }
+                KeyBindingConfigurationInput {
+                    label: RUNE_CLEAR_MOBS,
+                    label_active: active,
+                    is_toggleable: true,
+                    is_disabled: !settings_view().enable_rune_clear_mobs,
+                    on_input: move |key: Option<KeyBindingConfiguration>| {
+                        on_settings(SettingsData {
+                            rune_clear_mobs_key: key.unwrap(),
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: Some(settings_view().rune_clear_mobs_key),
+                }
+                MillisInput {
+                    label: "Clear Mobs Milliseconds",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    disabled: !settings_view().enable_rune_clear_mobs,
+                    on_input: move |rune_clear_mobs_millis| {
+                        on_settings(SettingsData {
+                            rune_clear_mobs_millis,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().rune_clear_mobs_millis,
+                }
+                NumberInputU32 {
+                    label: "Max Runes Solved Per Minute (0 = Unlimited)",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    disabled: !settings_view().enable_rune_solving,
+                    minimum_value: 0,
+                    on_input: move |max_runes_solved_per_minute| {
+                        on_settings(SettingsData {
+                            max_runes_solved_per_minute,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().max_runes_solved_per_minute,
+                }
+                SettingsCheckbox {
+                    label: "Focus Rune Solving",
+                    on_input: move |enable_focus_rune_solving| {
+                        on_settings(SettingsData {
+                            enable_focus_rune_solving,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().enable_focus_rune_solving,
+                }
+                SettingsCheckbox {
+                    label: "Solve Runes Regardless Of Buff",
+                    on_input: move |enable_rune_solving_ignore_buff| {
+                        on_settings(SettingsData {
+                            enable_rune_solving_ignore_buff,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().enable_rune_solving_ignore_buff,
+                }
                 SettingsCheckbox {
                     label: "Enable Change Channel On Elite Boss",
                     on_input: move |enable_change_channel_on_elite_boss_appear| {
@@ -78,6 +244,31 @@ pub fn Settings(
                     },
                     value: settings_view().enable_change_channel_on_elite_boss_appear,
                 }
+                SettingsCheckbox {
+                    label: "Enable Panic On Elite Boss Low Health",
+                    on_input: move |enable_panic_on_elite_boss_low_health| {
+                        on_settings(SettingsData {
+                            enable_panic_on_elite_boss_low_health,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().enable_panic_on_elite_boss_low_health,
+                }
+                PercentageInput {
+                    label: "Elite Boss Low Health Percent",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    disabled: !settings_view().enable_panic_on_elite_boss_low_health,
+                    on_input: move |elite_boss_low_health_percent| {
+                        on_settings(SettingsData {
+                            elite_boss_low_health_percent,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().elite_boss_low_health_percent,
+                }
+                SettingsEliteBossConsumableKeys { app_coroutine, settings_view }
                 SettingsCheckbox {
                     label: "Enable Panic Mode",
                     on_input: move |enable_panic_mode| {
@@ -99,6 +290,188 @@ pub fn Settings(
                     disabled: false,
                     selected: settings_view().panic_mode,
                 }
+                KeyBindingConfigurationInput {
+                    label: PANIC_DEFENSIVE,
+                    label_active: active,
+                    is_toggleable: false,
+                    is_disabled: !matches!(settings_view().panic_mode, PanicMode::Defensive),
+                    on_input: move |key: Option<KeyBindingConfiguration>| {
+                        on_settings(SettingsData {
+                            panic_defensive_key: key.unwrap(),
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: Some(settings_view().panic_defensive_key),
+                }
+                SettingsCheckbox {
+                    label: "Panic On Strangers",
+                    on_input: move |panic_react_to_strangers| {
+                        on_settings(SettingsData {
+                            panic_react_to_strangers,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().panic_react_to_strangers,
+                }
+                SettingsCheckbox {
+                    label: "Panic On Guildies",
+                    on_input: move |panic_react_to_guildies| {
+                        on_settings(SettingsData {
+                            panic_react_to_guildies,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().panic_react_to_guildies,
+                }
+                SettingsCheckbox {
+                    label: "Panic On Friends",
+                    on_input: move |panic_react_to_friends| {
+                        on_settings(SettingsData {
+                            panic_react_to_friends,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().panic_react_to_friends,
+                }
+                MillisInput {
+                    label: "Change Channel Menu Wait Milliseconds",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    on_input: move |change_channel_menu_wait_millis| {
+                        on_settings(SettingsData {
+                            change_channel_menu_wait_millis,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().change_channel_menu_wait_millis,
+                }
+                NumberInputU32 {
+                    label: "Change Channel Menu Max Retries",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    minimum_value: 1,
+                    on_input: move |change_channel_menu_max_retries| {
+                        on_settings(SettingsData {
+                            change_channel_menu_max_retries,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().change_channel_menu_max_retries,
+                }
+                SettingsEnumSelect::<ChangeChannelBlockedFallback> {
+                    label: "Change Channel Blocked Fallback",
+                    on_select: move |change_channel_blocked_fallback| {
+                        on_settings(SettingsData {
+                            change_channel_blocked_fallback,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    disabled: false,
+                    selected: settings_view().change_channel_blocked_fallback,
+                }
+                SettingsCheckbox {
+                    label: "Enable Collect Drops",
+                    on_input: move |enable_collect_drops| {
+                        on_settings(SettingsData {
+                            enable_collect_drops,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().enable_collect_drops,
+                }
+                MillisInput {
+                    label: "Collect Drops Every Milliseconds",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    disabled: !settings_view().enable_collect_drops,
+                    on_input: move |collect_drops_millis| {
+                        on_settings(SettingsData {
+                            collect_drops_millis,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().collect_drops_millis,
+                }
+                SettingsCheckbox {
+                    label: "Enable Loot Objects",
+                    on_input: move |enable_loot_objects| {
+                        on_settings(SettingsData {
+                            enable_loot_objects,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().enable_loot_objects,
+                }
+                SettingsTextInput {
+                    label: "Loot Object Templates (Separated By ;)",
+                    on_input: move |paths: String| {
+                        on_settings(SettingsData {
+                            loot_object_template_paths: paths
+                                .split(';')
+                                .map(|path| path.trim().to_string())
+                                .filter(|path| !path.is_empty())
+                                .collect(),
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().loot_object_template_paths.join(";"),
+                }
+                MillisInput {
+                    label: "Check Loot Objects Every Milliseconds",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    disabled: !settings_view().enable_loot_objects,
+                    on_input: move |loot_object_check_millis| {
+                        on_settings(SettingsData {
+                            loot_object_check_millis,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().loot_object_check_millis,
+                }
+                MillisInput {
+                    label: "Auto Mob Buffs Grace Milliseconds",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    on_input: move |auto_mob_priority_grace_millis| {
+                        on_settings(SettingsData {
+                            auto_mob_priority_grace_millis,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().auto_mob_priority_grace_millis,
+                }
+                MillisInput {
+                    label: "Cash Shop Stay Milliseconds",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    on_input: move |cash_shop_dwell_millis| {
+                        on_settings(SettingsData {
+                            cash_shop_dwell_millis,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().cash_shop_dwell_millis,
+                }
+                MillisInput {
+                    label: "Cash Shop Wait Before Exit Milliseconds",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    on_input: move |cash_shop_exit_wait_millis| {
+                        on_settings(SettingsData {
+                            cash_shop_exit_wait_millis,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().cash_shop_exit_wait_millis,
+                }
                 SettingsCheckbox {
                     label: "Stop Actions If Fails / Changes Map",
                     on_input: move |stop_on_fail_or_change_map| {
@@ -109,6 +482,293 @@ pub fn Settings(
                     },
                     value: settings_view().stop_on_fail_or_change_map,
                 }
+                SettingsCheckbox {
+                    label: "Only Treat Unsaved Maps As Map Change",
+                    on_input: move |enable_match_saved_maps| {
+                        on_settings(SettingsData {
+                            enable_match_saved_maps,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().enable_match_saved_maps,
+                }
+                SettingsCheckbox {
+                    label: "Switch To Matched Saved Map",
+                    on_input: move |switch_to_matched_map| {
+                        on_settings(SettingsData {
+                            switch_to_matched_map,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().switch_to_matched_map,
+                }
+                SettingsCheckbox {
+                    label: "Stop Actions If Health Is Depleted",
+                    on_input: move |stop_on_health_depleted| {
+                        on_settings(SettingsData {
+                            stop_on_health_depleted,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().stop_on_health_depleted,
+                }
+                SettingsTextInput {
+                    label: "Extra Death Templates (Separated By ;)",
+                    on_input: move |paths: String| {
+                        on_settings(SettingsData {
+                            death_template_paths: paths
+                                .split(';')
+                                .map(|path| path.trim().to_string())
+                                .filter(|path| !path.is_empty())
+                                .collect(),
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().death_template_paths.join(";"),
+                }
+                SettingsCheckbox {
+                    label: "Fidget While Idle",
+                    on_input: move |enable_idle_fidget| {
+                        on_settings(SettingsData {
+                            enable_idle_fidget,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().enable_idle_fidget,
+                }
+                SettingsCheckbox {
+                    label: "Wait For Ground Before Next Action",
+                    on_input: move |wait_for_ground| {
+                        on_settings(SettingsData {
+                            wait_for_ground,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().wait_for_ground,
+                }
+                SettingsCheckbox {
+                    label: "Close Opened Menus On Start",
+                    on_input: move |enable_clean_ui_on_start| {
+                        on_settings(SettingsData {
+                            enable_clean_ui_on_start,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().enable_clean_ui_on_start,
+                }
+                SettingsCheckbox {
+                    label: "Abort Strict Position Action On Timeout",
+                    on_input: move |abort_strict_position_on_timeout| {
+                        on_settings(SettingsData {
+                            abort_strict_position_on_timeout,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().abort_strict_position_on_timeout,
+                }
+                NumberInputU32 {
+                    label: "Ticks To Wait After Changing Map / Channel",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    minimum_value: 0,
+                    on_input: move |transition_grace_ticks| {
+                        on_settings(SettingsData {
+                            transition_grace_ticks,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().transition_grace_ticks,
+                }
+                MillisInput {
+                    label: "Initial Delay On Map Load",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    on_input: move |initial_map_delay_millis| {
+                        on_settings(SettingsData {
+                            initial_map_delay_millis,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().initial_map_delay_millis,
+                }
+                MillisInput {
+                    label: "Stop If Stuck For (0 To Disable)",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    on_input: move |stuck_timeout_millis| {
+                        on_settings(SettingsData {
+                            stuck_timeout_millis,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().stuck_timeout_millis,
+                }
+                NumberInputU32 {
+                    label: "Stop After Consecutive Channel Changes (0 To Disable)",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    minimum_value: 0,
+                    on_input: move |max_consecutive_channel_changes| {
+                        on_settings(SettingsData {
+                            max_consecutive_channel_changes,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().max_consecutive_channel_changes,
+                }
+                NumberInputI32 {
+                    label: "Destination Arrival Tolerance",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    on_input: move |arrival_tolerance: i32| {
+                        on_settings(SettingsData {
+                            arrival_tolerance: arrival_tolerance.max(0),
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().arrival_tolerance,
+                }
+                NumberInputI32 {
+                    label: "Double Jump Edge Safety Margin",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    on_input: move |edge_safety_margin: i32| {
+                        on_settings(SettingsData {
+                            edge_safety_margin: edge_safety_margin.max(0),
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().edge_safety_margin,
+                }
+                SettingsCheckbox {
+                    label: "Re-detect Minimap On Repeated Unstuck",
+                    on_input: move |enable_redetect_minimap_on_unstuck| {
+                        on_settings(SettingsData {
+                            enable_redetect_minimap_on_unstuck,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().enable_redetect_minimap_on_unstuck,
+                }
+                NumberInputU32 {
+                    label: "Unstuck Count Before Re-detecting Minimap",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    disabled: !settings_view().enable_redetect_minimap_on_unstuck,
+                    minimum_value: 1,
+                    on_input: move |redetect_minimap_unstuck_count| {
+                        on_settings(SettingsData {
+                            redetect_minimap_unstuck_count,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().redetect_minimap_unstuck_count,
+                }
+                SettingsCheckbox {
+                    label: "Recover Lost Player Position",
+                    on_input: move |enable_lost_position_recovery| {
+                        on_settings(SettingsData {
+                            enable_lost_position_recovery,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().enable_lost_position_recovery,
+                }
+                MillisInput {
+                    label: "Lost Position Milliseconds Before Recovery",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    disabled: !settings_view().enable_lost_position_recovery,
+                    on_input: move |lost_position_recovery_millis| {
+                        on_settings(SettingsData {
+                            lost_position_recovery_millis,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().lost_position_recovery_millis,
+                }
+                SettingsEnumSelect::<LostPositionRecovery> {
+                    label: "Lost Position Recovery",
+                    on_select: move |lost_position_recovery| {
+                        on_settings(SettingsData {
+                            lost_position_recovery,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    disabled: !settings_view().enable_lost_position_recovery,
+                    selected: settings_view().lost_position_recovery,
+                }
+                NumberInputU8 {
+                    label: "Minimap Border Whiteness Threshold",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    on_input: move |minimap_border_whiteness_threshold| {
+                        on_settings(SettingsData {
+                            minimap_border_whiteness_threshold,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().minimap_border_whiteness_threshold,
+                }
+                SettingsCheckbox {
+                    label: "Remember Minimap Position Across Restarts",
+                    on_input: move |remember_minimap_position| {
+                        on_settings(SettingsData {
+                            remember_minimap_position,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().remember_minimap_position,
+                }
+                MillisInput {
+                    label: "Buffs Detection Interval",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    on_input: move |buffs_detection_millis| {
+                        on_settings(SettingsData {
+                            buffs_detection_millis,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().buffs_detection_millis,
+                }
+                MillisInput {
+                    label: "Elite Boss Detection Interval",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    on_input: move |elite_boss_detection_millis| {
+                        on_settings(SettingsData {
+                            elite_boss_detection_millis,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().elite_boss_detection_millis,
+                }
+                NumberInputU32 {
+                    label: "Rune Stable Detections Required",
+                    div_class: SELECT_DIV_CLASS,
+                    label_class: SELECT_LABEL_CLASS,
+                    input_class: SELECT_CLASS,
+                    minimum_value: 1,
+                    on_input: move |rune_stable_detection_count| {
+                        on_settings(SettingsData {
+                            rune_stable_detection_count,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().rune_stable_detection_count,
+                }
                 SettingsEnumSelect::<CaptureMode> {
                     label: "Capture Mode",
                     on_select: move |capture_mode| {
@@ -120,7 +780,19 @@ pub fn Settings(
                     disabled: false,
                     selected: settings_view().capture_mode,
                 }
+                SettingsMonitorRegionInput { app_coroutine, settings_view }
                 SettingsCaptureHandleSelect { settings_view }
+                SettingsCheckbox {
+                    label: "Re-select Capture Handle On Window Close",
+                    on_input: move |enable_capture_handle_reselection| {
+                        on_settings(SettingsData {
+                            enable_capture_handle_reselection,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: settings_view().enable_capture_handle_reselection,
+                }
+                SettingsBuffsRegionInput { app_coroutine, settings_view }
                 SettingsInputMethodSelect { app_coroutine, settings_view }
                 KeyBindingConfigurationInput {
                     label: TOGGLE_ACTIONS,
@@ -135,6 +807,32 @@ pub fn Settings(
                     },
                     value: Some(settings_view().toggle_actions_key),
                 }
+                KeyBindingConfigurationInput {
+                    label: EMERGENCY_TOWN,
+                    label_active: active,
+                    is_toggleable: true,
+                    is_disabled: false,
+                    on_input: move |key: Option<KeyBindingConfiguration>| {
+                        on_settings(SettingsData {
+                            emergency_town_key: key.unwrap(),
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: Some(settings_view().emergency_town_key),
+                }
+                KeyBindingConfigurationInput {
+                    label: TOGGLE_MODE,
+                    label_active: active,
+                    is_toggleable: true,
+                    is_disabled: false,
+                    on_input: move |key: Option<KeyBindingConfiguration>| {
+                        on_settings(SettingsData {
+                            toggle_mode_key: key.unwrap(),
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    value: Some(settings_view().toggle_mode_key),
+                }
                 KeyBindingConfigurationInput {
                     label: PLATFORM_START,
                     label_active: active,
@@ -177,6 +875,41 @@ pub fn Settings(
                 {
                     #[cfg(debug_assertions)]
                     rsx! {
+                        SettingsCheckbox {
+                            label: "Use Fixed Random Seed",
+                            on_input: move |enable_fixed_rng_seed| {
+                                on_settings(SettingsData {
+                                    enable_fixed_rng_seed,
+                                    ..settings_view.peek().clone()
+                                });
+                            },
+                            value: settings_view().enable_fixed_rng_seed,
+                        }
+                        NumberInputU32 {
+                            label: "Fixed Random Seed",
+                            div_class: SELECT_DIV_CLASS,
+                            label_class: SELECT_LABEL_CLASS,
+                            input_class: SELECT_CLASS,
+                            disabled: !settings_view().enable_fixed_rng_seed,
+                            minimum_value: 0,
+                            on_input: move |fixed_rng_seed| {
+                                on_settings(SettingsData {
+                                    fixed_rng_seed,
+                                    ..settings_view.peek().clone()
+                                });
+                            },
+                            value: settings_view().fixed_rng_seed,
+                        }
+                        SettingsCheckbox {
+                            label: "Play Audio Cues On Actions",
+                            on_input: move |enable_debug_audio_cues| {
+                                on_settings(SettingsData {
+                                    enable_debug_audio_cues,
+                                    ..settings_view.peek().clone()
+                                });
+                            },
+                            value: settings_view().enable_debug_audio_cues,
+                        }
                         SettingsDebugButton {
                             label: "Capture Color Image",
                             on_click: move |_| async {
@@ -215,6 +948,12 @@ pub fn Settings(
                                 test_spin_rune().await;
                             },
                         }
+                        SettingsDebugButton {
+                            label: "View Buffs Region",
+                            on_click: move |_| async {
+                                view_buffs_region().await;
+                            },
+                        }
                     }
                 }
             }
@@ -314,6 +1053,44 @@ fn SettingsInputMethodSelect(
                 },
                 value: settings_view().input_method_rpc_server_url,
             }
+            MillisInput {
+                label: "Request Timeout Milliseconds",
+                div_class: SELECT_DIV_CLASS,
+                label_class: SELECT_LABEL_CLASS,
+                input_class: SELECT_CLASS,
+                on_input: move |input_method_rpc_timeout_millis| {
+                    on_settings(SettingsData {
+                        input_method_rpc_timeout_millis,
+                        ..settings_view.peek().clone()
+                    });
+                },
+                value: settings_view().input_method_rpc_timeout_millis,
+            }
+        } else {
+            SettingsCheckbox {
+                label: "Pause On Window Focus Loss",
+                on_input: move |input_method_pause_on_focus_loss| {
+                    on_settings(SettingsData {
+                        input_method_pause_on_focus_loss,
+                        ..settings_view.peek().clone()
+                    });
+                },
+                value: settings_view().input_method_pause_on_focus_loss,
+            }
+        }
+        NumberInputU32 {
+            label: "Max Keys Per Minute (0 = Unlimited)",
+            div_class: SELECT_DIV_CLASS,
+            label_class: SELECT_LABEL_CLASS,
+            input_class: SELECT_CLASS,
+            minimum_value: 0,
+            on_input: move |max_apm| {
+                on_settings(SettingsData {
+                    max_apm,
+                    ..settings_view.peek().clone()
+                });
+            },
+            value: settings_view().max_apm,
         }
     }
 }
@@ -341,6 +1118,201 @@ pub fn SettingsEnumSelect<T: 'static + Clone + PartialEq + Display + FromStr + I
     }
 }
 
+#[component]
+fn SettingsMonitorRegionInput(
+    app_coroutine: Coroutine<AppMessage>,
+    settings_view: Memo<SettingsData>,
+) -> Element {
+    let on_settings = move |updated| {
+        app_coroutine.send(AppMessage::UpdateSettings(updated));
+    };
+    let region = use_memo(move || settings_view().capture_monitor_region);
+    let on_region = move |capture_monitor_region: Bound| {
+        on_settings(SettingsData {
+            capture_monitor_region,
+            ..settings_view.peek().clone()
+        });
+    };
+
+    rsx! {
+        if matches!(settings_view().capture_mode, CaptureMode::MonitorRegion) {
+            NumberInputU32 {
+                label: "Monitor Index",
+                div_class: SELECT_DIV_CLASS,
+                label_class: SELECT_LABEL_CLASS,
+                input_class: SELECT_CLASS,
+                minimum_value: 0,
+                on_input: move |capture_monitor_index| {
+                    on_settings(SettingsData {
+                        capture_monitor_index,
+                        ..settings_view.peek().clone()
+                    });
+                },
+                value: settings_view().capture_monitor_index,
+            }
+            NumberInputI32 {
+                label: "Monitor Region X",
+                div_class: SELECT_DIV_CLASS,
+                label_class: SELECT_LABEL_CLASS,
+                input_class: SELECT_CLASS,
+                on_input: move |x| {
+                    on_region(Bound { x, ..*region.peek() });
+                },
+                value: region().x,
+            }
+            NumberInputI32 {
+                label: "Monitor Region Y",
+                div_class: SELECT_DIV_CLASS,
+                label_class: SELECT_LABEL_CLASS,
+                input_class: SELECT_CLASS,
+                on_input: move |y| {
+                    on_region(Bound { y, ..*region.peek() });
+                },
+                value: region().y,
+            }
+            NumberInputI32 {
+                label: "Monitor Region Width",
+                div_class: SELECT_DIV_CLASS,
+                label_class: SELECT_LABEL_CLASS,
+                input_class: SELECT_CLASS,
+                on_input: move |width| {
+                    on_region(Bound { width, ..*region.peek() });
+                },
+                value: region().width,
+            }
+            NumberInputI32 {
+                label: "Monitor Region Height",
+                div_class: SELECT_DIV_CLASS,
+                label_class: SELECT_LABEL_CLASS,
+                input_class: SELECT_CLASS,
+                on_input: move |height| {
+                    on_region(Bound { height, ..*region.peek() });
+                },
+                value: region().height,
+            }
+        }
+    }
+}
+
+#[component]
+fn SettingsBuffsRegionInput(
+    app_coroutine: Coroutine<AppMessage>,
+    settings_view: Memo<SettingsData>,
+) -> Element {
+    let region = use_memo(move || settings_view().buffs_region);
+    let on_region = move |buffs_region: BuffsRegion| {
+        app_coroutine.send(AppMessage::UpdateSettings(SettingsData {
+            buffs_region,
+            ..settings_view.peek().clone()
+        }));
+    };
+
+    rsx! {
+        NumberInputI32 {
+            label: "Buffs Region Offset X",
+            div_class: SELECT_DIV_CLASS,
+            label_class: SELECT_LABEL_CLASS,
+            input_class: SELECT_CLASS,
+            on_input: move |offset_x| {
+                on_region(BuffsRegion {
+                    offset_x,
+                    ..*region.peek()
+                });
+            },
+            value: region().offset_x,
+        }
+        NumberInputI32 {
+            label: "Buffs Region Offset Y",
+            div_class: SELECT_DIV_CLASS,
+            label_class: SELECT_LABEL_CLASS,
+            input_class: SELECT_CLASS,
+            on_input: move |offset_y| {
+                on_region(BuffsRegion {
+                    offset_y,
+                    ..*region.peek()
+                });
+            },
+            value: region().offset_y,
+        }
+        NumberInputF32 {
+            label: "Buffs Region Scale",
+            div_class: SELECT_DIV_CLASS,
+            label_class: SELECT_LABEL_CLASS,
+            input_class: SELECT_CLASS,
+            on_input: move |scale| {
+                on_region(BuffsRegion {
+                    scale,
+                    ..*region.peek()
+                });
+            },
+            value: region().scale,
+        }
+    }
+}
+
+#[component]
+fn SettingsEliteBossConsumableKeys(
+    app_coroutine: Coroutine<AppMessage>,
+    settings_view: Memo<SettingsData>,
+) -> Element {
+    let on_settings = move |updated| {
+        app_coroutine.send(AppMessage::UpdateSettings(updated));
+    };
+    let keys_view = use_memo(move || settings_view().elite_boss_consumable_keys);
+
+    rsx! {
+        p { class: "font-normal italic text-xs text-gray-400",
+            "Presses all consumable keys in order once when an elite boss appears."
+        }
+        for (i , key) in keys_view().into_iter().enumerate() {
+            div { class: "flex items-center space-x-2",
+                div { class: "flex-1",
+                    KeyBindingInput {
+                        label: format!("Elite Boss Consumable Key {}", i + 1),
+                        label_class: SELECT_LABEL_CLASS,
+                        div_class: SELECT_DIV_CLASS,
+                        input_class: SELECT_CLASS,
+                        disabled: false,
+                        on_input: move |key| {
+                            let mut elite_boss_consumable_keys = keys_view.peek().clone();
+                            *elite_boss_consumable_keys.get_mut(i).unwrap() = key;
+                            on_settings(SettingsData {
+                                elite_boss_consumable_keys,
+                                ..settings_view.peek().clone()
+                            });
+                        },
+                        value: key,
+                    }
+                }
+                button {
+                    class: "w-5 h-5 border border-red-500 p-1",
+                    onclick: move |_| {
+                        let mut elite_boss_consumable_keys = keys_view.peek().clone();
+                        elite_boss_consumable_keys.remove(i);
+                        on_settings(SettingsData {
+                            elite_boss_consumable_keys,
+                            ..settings_view.peek().clone()
+                        });
+                    },
+                    XIcon { class: "w-full h-full text-red-400 fill-current" }
+                }
+            }
+        }
+        button {
+            class: "w-full button-secondary h-6",
+            onclick: move |_| {
+                let mut elite_boss_consumable_keys = keys_view.peek().clone();
+                elite_boss_consumable_keys.push(KeyBinding::default());
+                on_settings(SettingsData {
+                    elite_boss_consumable_keys,
+                    ..settings_view.peek().clone()
+                });
+            },
+            "Add elite boss consumable key"
+        }
+    }
+}
+
 #[component]
 fn SettingsCaptureHandleSelect(settings_view: Memo<SettingsData>) -> Element {
     const HANDLE_NOT_SELECTED: usize = usize::MAX;
@@ -376,7 +1348,10 @@ fn SettingsCaptureHandleSelect(settings_view: Memo<SettingsData>) -> Element {
                 }
                 None => vec![],
             },
-            disabled: matches!(settings_view().capture_mode, CaptureMode::BitBltArea),
+            disabled: matches!(
+                settings_view().capture_mode,
+                CaptureMode::BitBltArea | CaptureMode::MonitorRegion
+            ),
             on_select: move |(_, i)| {
                 if i == HANDLE_NOT_SELECTED {
                     selected_capture_handle.set(None);